@@ -0,0 +1,153 @@
+//! # Bar module
+//!
+//! This module turns a [`HyprlandState`](crate::state::HyprlandState) into a feed of
+//! [`BarState`](crate::bar::BarState)s, one json object per line, written whenever it changes.
+//! That's what bar modules that run a command and read its output expect,
+//! like eww's `deflisten` or a waybar custom module with a script that reshapes it
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::bar::feed_blocking;
+//! use hyprland::state::HyprlandState;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     // {"workspaces":[{"id":1,"name":"1","monitor":"DP-1","windows":2,...}],"title":"...",...}
+//!     feed_blocking(&HyprlandState::new()?, std::io::stdout())
+//! }
+//! ```
+
+use crate::shared::{HyprResult, WorkspaceId, WorkspaceType};
+use crate::state::HyprlandState;
+use serde::Serialize;
+
+/// This struct holds what a bar usually shows
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BarState {
+    /// The workspaces, the regular ones by id, then the named ones and then the special ones by name
+    pub workspaces: Vec<BarWorkspace>,
+    /// The title of the active window, empty if no window is focused
+    pub title: String,
+    /// The class of the active window, empty if no window is focused
+    pub class: String,
+    /// The active submap, empty if it's the default one
+    pub submap: String,
+}
+
+/// This struct holds a workspace, as a bar shows it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BarWorkspace {
+    /// The id of the workspace, `None` if it's a named or special workspace
+    pub id: Option<WorkspaceId>,
+    /// The name of the workspace
+    pub name: String,
+    /// The monitor the workspace is on
+    pub monitor: String,
+    /// The amount of windows in the workspace
+    pub windows: u8,
+    /// If it's a special workspace
+    pub special: bool,
+    /// If it's shown on its monitor
+    pub active: bool,
+    /// If it's shown on the focused monitor
+    pub focused: bool,
+    /// If a window in it wants attention
+    pub urgent: bool,
+    /// If a window in it is fullscreen
+    pub fullscreen: bool,
+}
+
+impl BarState {
+    /// This method returns what the bar shows, out of the state
+    pub fn of(state: &HyprlandState) -> Self {
+        let monitors = state.monitors();
+        let urgent = state.urgent_workspaces();
+        let mut workspaces: Vec<BarWorkspace> = state
+            .workspaces()
+            .into_iter()
+            .map(|workspace| {
+                let shown_on = monitors
+                    .iter()
+                    .find(|monitor| monitor.active_workspace.name == workspace.name);
+                BarWorkspace {
+                    id: match workspace.id {
+                        WorkspaceType::Regular(id) => Some(id),
+                        _ => None,
+                    },
+                    special: workspace.id == WorkspaceType::Special,
+                    active: shown_on.is_some(),
+                    focused: shown_on.is_some_and(|monitor| monitor.focused),
+                    urgent: urgent.contains(&workspace.name),
+                    fullscreen: workspace.fullscreen,
+                    windows: workspace.windows,
+                    monitor: workspace.monitor,
+                    name: workspace.name,
+                }
+            })
+            .collect();
+        workspaces.sort_by(|a, b| {
+            let order = |workspace: &BarWorkspace| (workspace.special, workspace.id.is_none());
+            order(a)
+                .cmp(&order(b))
+                .then(a.id.cmp(&b.id))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let window = state.active_window();
+        BarState {
+            workspaces,
+            title: window.as_ref().map(|w| w.title.clone()).unwrap_or_default(),
+            class: window.map(|w| w.class).unwrap_or_default(),
+            submap: state.submap(),
+        }
+    }
+
+    /// Returns the state as a line of json
+    fn line(&self) -> HyprResult<Vec<u8>> {
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+}
+
+/// This function writes the state to `out` as a line of json, and again every time it changes (blocking)
+///
+/// It only returns when writing fails, like when the bar closes the pipe
+pub fn feed_blocking(state: &HyprlandState, mut out: impl std::io::Write) -> HyprResult<()> {
+    let mut last = None;
+    loop {
+        let seen = state.update_count();
+        let current = BarState::of(state);
+        if last.as_ref() != Some(&current) {
+            out.write_all(&current.line()?)?;
+            out.flush()?;
+            last = Some(current);
+        }
+        state.wait_for_update(seen);
+    }
+}
+
+/// This function writes the state to `out` as a line of json, and again every time it changes (async)
+///
+/// It only returns when writing fails, like when the bar closes the pipe
+#[cfg(feature = "tokio")]
+pub async fn feed(
+    state: &HyprlandState,
+    mut out: impl tokio::io::AsyncWrite + Unpin,
+) -> HyprResult<()> {
+    use tokio::io::AsyncWriteExt;
+    let mut updates = state.watch_updates();
+    let mut last = None;
+    loop {
+        updates.borrow_and_update();
+        let current = BarState::of(state);
+        if last.as_ref() != Some(&current) {
+            out.write_all(&current.line()?).await?;
+            out.flush().await?;
+            last = Some(current);
+        }
+        if updates.changed().await.is_err() {
+            // the state can't be dropped while it's borrowed, so this doesn't happen
+            return Ok(());
+        }
+    }
+}
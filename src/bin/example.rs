@@ -4,7 +4,7 @@ use hyprland::dispatch::{dispatch_blocking, Corner, DispatchType};
 use hyprland::event_listener::EventListenerMutable as EventListener;
 use hyprland::shared::WorkspaceType;
 
-fn main() -> std::io::Result<()> {
+fn main() -> hyprland::shared::HyprResult<()> {
     // We can call dispatchers with the dispatch function!
 
     // Here we are telling hyprland to open kitty!
@@ -37,7 +37,7 @@ fn main() -> std::io::Result<()> {
     println!("monitors: {monitors:#?},\nactive window: {win:#?},\nclients {clients:#?}");
 
     // Create a event listener
-    let mut event_listener = EventListener::new();
+    let mut event_listener = EventListener::new()?;
 
     // This changes the workspace to 5 if the workspace is switched to 9
     // this is a performance and mutable state test
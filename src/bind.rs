@@ -0,0 +1,284 @@
+//! # Bind module
+//!
+//! This module provides [`Binder`](crate::bind::Binder), for creating keybinds
+//! that are added and removed while Hyprland runs, or written to the config
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::bind::{BindFlag, Binder, Modifier};
+//! use hyprland::dispatch::DispatchType;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let terminal = Binder::new("Return", DispatchType::Exec("kitty".to_string()))
+//!         .modifier(Modifier::Super);
+//!     println!("{terminal}"); // bind = SUPER, Return, exec, kitty
+//!     terminal.bind()?;
+//!
+//!     let volume = Binder::new("XF86AudioRaiseVolume", DispatchType::Exec("pamixer -i 5".to_string()))
+//!         .flag(BindFlag::Repeat)
+//!         .flag(BindFlag::Locked);
+//!     volume.bind()?; // bindel = , XF86AudioRaiseVolume, exec, pamixer -i 5
+//!
+//!     terminal.unbind()
+//! }
+//! ```
+
+use crate::dispatch::DispatchType;
+use crate::shared::HyprResult;
+use std::fmt;
+
+/// This enum holds the modifier keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Modifier {
+    Super,
+    Shift,
+    Ctrl,
+    Alt,
+    Caps,
+    Mod2,
+    Mod3,
+    Mod5,
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Modifier::Super => "SUPER",
+            Modifier::Shift => "SHIFT",
+            Modifier::Ctrl => "CTRL",
+            Modifier::Alt => "ALT",
+            Modifier::Caps => "CAPS",
+            Modifier::Mod2 => "MOD2",
+            Modifier::Mod3 => "MOD3",
+            Modifier::Mod5 => "MOD5",
+        })
+    }
+}
+
+/// This enum holds the flags that change how a bind behaves (the letters after `bind`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindFlag {
+    /// Repeats while the key is held (`e`)
+    Repeat,
+    /// Works while a input inhibitor (like a lockscreen) is active (`l`)
+    Locked,
+    /// A mouse bind, see [`Binder::mouse`] (`m`)
+    Mouse,
+    /// Triggers when the key is released (`r`)
+    Release,
+    /// Doesn't consume the key, so other binds of it trigger too (`t`)
+    Transparent,
+    /// Triggers when the key is held for a while (`o`)
+    LongPress,
+}
+
+impl BindFlag {
+    fn letter(&self) -> char {
+        match self {
+            BindFlag::Repeat => 'e',
+            BindFlag::Locked => 'l',
+            BindFlag::Mouse => 'm',
+            BindFlag::Release => 'r',
+            BindFlag::Transparent => 't',
+            BindFlag::LongPress => 'o',
+        }
+    }
+}
+
+/// This enum holds what a mouse bind does while the button is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    /// Moves the window under the cursor
+    MoveWindow,
+    /// Resizes the window under the cursor
+    ResizeWindow,
+}
+
+/// This struct holds a keybind, which can be bound (and unbound) while Hyprland runs
+/// with the `bind` keywords, or written to the config with [`Display`](fmt::Display)
+///
+/// Binds added at runtime last until the config is reloaded
+#[derive(Debug, Clone)]
+pub struct Binder {
+    modifiers: Vec<Modifier>,
+    key: String,
+    flags: Vec<BindFlag>,
+    /// The dispatcher and its arguments, as written in a bind
+    dispatcher: (String, String),
+}
+
+impl Binder {
+    /// This method creates a bind of the key (like `Q`, `Return` or `code:24`),
+    /// which calls the dispatcher
+    ///
+    /// The commands that aren't dispatchers ([`DispatchType::Keyword`] and [`DispatchType::SetCursor`])
+    /// are ran with `hyprctl`
+    pub fn new(key: impl Into<String>, dispatcher: DispatchType) -> Self {
+        let command = dispatcher.to_string();
+        let dispatcher = match command.strip_prefix("dispatch ") {
+            Some(dispatch) => match dispatch.split_once(' ') {
+                Some((name, args)) => (name.to_string(), args.to_string()),
+                None => (dispatch.to_string(), String::new()),
+            },
+            None => ("exec".to_string(), format!("hyprctl {command}")),
+        };
+        Binder {
+            modifiers: vec![],
+            key: key.into(),
+            flags: vec![],
+            dispatcher,
+        }
+    }
+
+    /// This method creates a mouse bind of the button (like `mouse:272`, the left button)
+    ///
+    /// ```rust
+    /// use hyprland::bind::{Binder, Modifier, MouseAction};
+    /// let bind = Binder::mouse("mouse:272", MouseAction::MoveWindow).modifier(Modifier::Super);
+    /// assert_eq!(bind.to_string(), "bindm = SUPER, mouse:272, movewindow");
+    /// ```
+    pub fn mouse(button: impl Into<String>, action: MouseAction) -> Self {
+        let dispatcher = match action {
+            MouseAction::MoveWindow => "movewindow",
+            MouseAction::ResizeWindow => "resizewindow",
+        };
+        Binder {
+            modifiers: vec![],
+            key: button.into(),
+            flags: vec![BindFlag::Mouse],
+            dispatcher: (dispatcher.to_string(), String::new()),
+        }
+    }
+
+    /// This method adds a modifier that has to be held
+    pub fn modifier(mut self, modifier: Modifier) -> Self {
+        if !self.modifiers.contains(&modifier) {
+            self.modifiers.push(modifier);
+        }
+        self
+    }
+
+    /// This method adds a flag
+    pub fn flag(mut self, flag: BindFlag) -> Self {
+        if !self.flags.contains(&flag) {
+            self.flags.push(flag);
+        }
+        self
+    }
+
+    /// This method returns the keyword of the bind, `bind` followed by the flags (like `bindel`)
+    pub fn keyword(&self) -> String {
+        let letters: String = self.flags.iter().map(BindFlag::letter).collect();
+        format!("bind{letters}")
+    }
+
+    /// Returns the modifiers and the key, as written in binds (like `SUPER SHIFT, Q`)
+    fn keys(&self) -> String {
+        let modifiers: Vec<String> = self.modifiers.iter().map(Modifier::to_string).collect();
+        format!("{}, {}", modifiers.join(" "), self.key)
+    }
+
+    /// This method returns the value of the bind keyword (like `SUPER, Q, exec, kitty`)
+    pub fn value(&self) -> String {
+        let (dispatcher, args) = &self.dispatcher;
+        match (self.flags.contains(&BindFlag::Mouse), args.is_empty()) {
+            (true, _) => format!("{}, {dispatcher}", self.keys()),
+            (false, true) => format!("{}, {dispatcher},", self.keys()),
+            (false, false) => format!("{}, {dispatcher}, {args}", self.keys()),
+        }
+    }
+
+    /// This method adds the bind (blocking)
+    pub fn bind(&self) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.bind_keyword())
+    }
+
+    /// This method removes the bind of the modifiers and key, whatever it does (blocking)
+    pub fn unbind(&self) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.unbind_keyword())
+    }
+
+    /// This method adds the bind (async)
+    #[cfg(feature = "async")]
+    pub async fn bind_async(&self) -> HyprResult<()> {
+        crate::dispatch::dispatch(self.bind_keyword()).await
+    }
+
+    /// This method removes the bind of the modifiers and key, whatever it does (async)
+    #[cfg(feature = "async")]
+    pub async fn unbind_async(&self) -> HyprResult<()> {
+        crate::dispatch::dispatch(self.unbind_keyword()).await
+    }
+
+    fn bind_keyword(&self) -> DispatchType {
+        DispatchType::Keyword(self.keyword(), self.value())
+    }
+
+    fn unbind_keyword(&self) -> DispatchType {
+        DispatchType::Keyword("unbind".to_string(), self.keys())
+    }
+}
+
+/// The bind is written as the config line that creates it (like `bind = SUPER, Q, exec, kitty`)
+impl fmt::Display for Binder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.keyword(), self.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_render_as_config_lines() {
+        let terminal = Binder::new("Return", DispatchType::Exec("kitty".to_string()))
+            .modifier(Modifier::Super);
+        assert_eq!(terminal.to_string(), "bind = SUPER, Return, exec, kitty");
+        let close = Binder::new("Q", DispatchType::KillActiveWindow)
+            .modifier(Modifier::Super)
+            .modifier(Modifier::Shift)
+            .modifier(Modifier::Super);
+        assert_eq!(close.to_string(), "bind = SUPER SHIFT, Q, killactive,");
+        let volume = Binder::new(
+            "XF86AudioRaiseVolume",
+            DispatchType::Exec("pamixer -i 5".to_string()),
+        )
+        .flag(BindFlag::Repeat)
+        .flag(BindFlag::Locked)
+        .flag(BindFlag::Repeat);
+        assert_eq!(volume.keyword(), "bindel");
+        assert_eq!(volume.value(), ", XF86AudioRaiseVolume, exec, pamixer -i 5");
+        let drag = Binder::mouse("mouse:272", MouseAction::MoveWindow).modifier(Modifier::Super);
+        assert_eq!(drag.to_string(), "bindm = SUPER, mouse:272, movewindow");
+    }
+
+    #[test]
+    fn commands_that_arent_dispatchers_run_hyprctl() {
+        let gaps = Binder::new(
+            "G",
+            DispatchType::Keyword("general:gaps_in".to_string(), "0".to_string()),
+        );
+        assert_eq!(
+            gaps.to_string(),
+            "bind = , G, exec, hyprctl keyword general:gaps_in 0"
+        );
+    }
+
+    #[test]
+    fn binds_are_added_and_removed_with_keywords() {
+        let terminal = Binder::new("Return", DispatchType::Exec("kitty".to_string()))
+            .modifier(Modifier::Super)
+            .flag(BindFlag::Release);
+        assert_eq!(
+            terminal.bind_keyword().to_string(),
+            "keyword bindr SUPER, Return, exec, kitty"
+        );
+        assert_eq!(
+            terminal.unbind_keyword().to_string(),
+            "keyword unbind SUPER, Return"
+        );
+    }
+}
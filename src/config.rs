@@ -0,0 +1,618 @@
+//! # Config module
+//!
+//! This module provides [`Config`](crate::config::Config), for writing a `hyprland.conf`
+//! (or a file sourced by it) from typed values, and for reading one to change some of it
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::bind::{Binder, Modifier};
+//! use hyprland::config::{Config, Section};
+//! use hyprland::dispatch::DispatchType;
+//! use hyprland::rule::{WindowRule, WindowRuleEffect};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let config = Config::new()
+//!         .comment("generated, changes are overwritten")
+//!         .variable("terminal", "kitty")
+//!         .exec_once("waybar")
+//!         .section(Section::new("general").set("gaps_in", 5).set("layout", "dwindle"))
+//!         .section(Section::new("decoration").section(Section::new("blur").set("enabled", true)))
+//!         .bind(Binder::new("Return", DispatchType::Exec("$terminal".to_string())).modifier(Modifier::Super))
+//!         .window_rule(WindowRule::new().effect(WindowRuleEffect::Float).class("pavucontrol"));
+//!     config.write("/home/user/.config/hypr/generated.conf")
+//! }
+//! ```
+//!
+//! which writes:
+//!
+//! ```text
+//! # generated, changes are overwritten
+//! $terminal = kitty
+//! exec-once = waybar
+//!
+//! general {
+//!     gaps_in = 5
+//!     layout = dwindle
+//! }
+//!
+//! decoration {
+//!     blur {
+//!         enabled = 1
+//!     }
+//! }
+//!
+//! bind = SUPER, Return, exec, $terminal
+//! windowrulev2 = float, class:^(pavucontrol)$
+//! ```
+//!
+//! Changing one option of a existing config, keeping the rest:
+//!
+//! ```rust, no_run
+//! use hyprland::config::Config;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let path = "/home/user/.config/hypr/hyprland.conf";
+//!     let mut config = Config::read(path)?;
+//!     println!("{:?}", config.get("general:gaps_in"));
+//!     config.update("general:gaps_in", 10);
+//!     config.write(path)
+//! }
+//! ```
+
+use crate::bind::Binder;
+use crate::data::{MonitorArrangement, OptionValue};
+use crate::rule::{LayerRule, WindowRule};
+use crate::shared::{HyprError, HyprResult};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How deep `source` includes are followed, so a file sourcing itself doesn't loop forever
+const MAX_SOURCE_DEPTH: usize = 16;
+
+/// Something written in the config or in a section
+enum Entry {
+    Comment(String),
+    Variable(String, String),
+    Set(String, String),
+    Section(Section),
+    /// The path as written, and the sourced config when it was read
+    Source(String, Option<Config>),
+    Bind(Binder),
+    WindowRule(WindowRule),
+    LayerRule(LayerRule),
+}
+
+/// This struct holds a section (like `general { ... }`), which holds options and other sections
+pub struct Section {
+    name: String,
+    entries: Vec<Entry>,
+}
+
+impl Section {
+    /// This method creates a empty section
+    pub fn new(name: impl Into<String>) -> Self {
+        Section {
+            name: name.into(),
+            entries: vec![],
+        }
+    }
+
+    /// This method sets a option of the section (like `gaps_in` in `general`)
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<OptionValue>) -> Self {
+        self.entries
+            .push(Entry::Set(key.into(), value.into().to_string()));
+        self
+    }
+
+    /// This method adds a section inside the section (like `blur` in `decoration`)
+    pub fn section(mut self, section: Section) -> Self {
+        self.entries.push(Entry::Section(section));
+        self
+    }
+
+    /// This method adds a comment
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.entries.push(Entry::Comment(comment.into()));
+        self
+    }
+}
+
+/// This struct holds the contents of a config file, in the order they're added
+///
+/// Nothing is checked until the config is rendered, which returns
+/// [`HyprError::InvalidConfig`] for names and values that can't be written in a config,
+/// and [`HyprError::InvalidRule`] for invalid rules
+///
+/// A parsed config keeps the comments (comments after a value are moved above it),
+/// binds and rules are kept as the keywords they're written with
+#[derive(Default)]
+pub struct Config {
+    entries: Vec<Entry>,
+}
+
+impl Config {
+    /// This method creates a empty config
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// This method adds a comment
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.entries.push(Entry::Comment(comment.into()));
+        self
+    }
+
+    /// This method defines a variable (`$name = value`), used in later lines as `$name`
+    pub fn variable(mut self, name: impl Into<String>, value: impl Into<OptionValue>) -> Self {
+        self.entries
+            .push(Entry::Variable(name.into(), value.into().to_string()));
+        self
+    }
+
+    /// This method sets a keyword, like a option with its section (`general:gaps_in`)
+    /// or a keyword such as `env`
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<OptionValue>) -> Self {
+        self.entries
+            .push(Entry::Set(key.into(), value.into().to_string()));
+        self
+    }
+
+    /// This method adds a section
+    pub fn section(mut self, section: Section) -> Self {
+        self.entries.push(Entry::Section(section));
+        self
+    }
+
+    /// This method adds a keybind
+    pub fn bind(mut self, bind: Binder) -> Self {
+        self.entries.push(Entry::Bind(bind));
+        self
+    }
+
+    /// This method adds a window rule
+    pub fn window_rule(mut self, rule: WindowRule) -> Self {
+        self.entries.push(Entry::WindowRule(rule));
+        self
+    }
+
+    /// This method adds a layer rule
+    pub fn layer_rule(mut self, rule: LayerRule) -> Self {
+        self.entries.push(Entry::LayerRule(rule));
+        self
+    }
+
+    /// This method adds the `monitor` lines of the arrangement
+    pub fn monitors(self, arrangement: &MonitorArrangement) -> Self {
+        arrangement
+            .rules()
+            .into_iter()
+            .fold(self, |config, rule| config.set("monitor", rule))
+    }
+
+    /// This method runs the command every time the config is reloaded (`exec`)
+    pub fn exec(self, command: impl Into<String>) -> Self {
+        self.set("exec", command.into())
+    }
+
+    /// This method runs the command once, when Hyprland starts (`exec-once`)
+    pub fn exec_once(self, command: impl Into<String>) -> Self {
+        self.set("exec-once", command.into())
+    }
+
+    /// This method includes another config file (`source`)
+    pub fn source(mut self, path: impl Into<String>) -> Self {
+        self.entries.push(Entry::Source(path.into(), None));
+        self
+    }
+
+    /// This method parses the contents of a config file, the `source` includes are kept
+    /// but not read
+    ///
+    /// Returns [`HyprError::InvalidConfig`] with the line number for lines that can't be parsed
+    ///
+    /// ```rust
+    /// use hyprland::config::Config;
+    /// let config = Config::parse("$gaps = 5\ngeneral {\n    gaps_in = $gaps # inner gaps\n}\n")?;
+    /// assert_eq!(config.get("general:gaps_in"), Some("5".to_string()));
+    /// # Ok::<(), hyprland::shared::HyprError>(())
+    /// ```
+    pub fn parse(text: &str) -> HyprResult<Config> {
+        // the sections that are open, the innermost last
+        let mut sections: Vec<Section> = vec![];
+        let mut entries = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let invalid = |message: &str| {
+                HyprError::InvalidConfig(format!("line {}: {message}: {line:?}", index + 1))
+            };
+            let line = line.trim();
+            let target = match sections.last_mut() {
+                Some(section) => &mut section.entries,
+                None => &mut entries,
+            };
+            if line.is_empty() {
+                continue;
+            } else if let Some(comment) = line.strip_prefix('#') {
+                let comment = comment.strip_prefix(' ').unwrap_or(comment);
+                target.push(Entry::Comment(comment.to_string()));
+            } else if line == "}" {
+                let section = sections
+                    .pop()
+                    .ok_or_else(|| invalid("no section to close"))?;
+                match sections.last_mut() {
+                    Some(parent) => parent.entries.push(Entry::Section(section)),
+                    None => entries.push(Entry::Section(section)),
+                }
+            } else if let Some((key, value)) = line.split_once('=') {
+                let (key, (value, comment)) = (key.trim(), split_comment(value));
+                if key.is_empty() {
+                    return Err(invalid("missing the name"));
+                }
+                if let Some(comment) = comment {
+                    target.push(Entry::Comment(comment));
+                }
+                target.push(match key.strip_prefix('$') {
+                    Some(name) => Entry::Variable(name.to_string(), value),
+                    None if key == "source" => Entry::Source(value, None),
+                    None => Entry::Set(key.to_string(), value),
+                });
+            } else if let Some(name) = line.strip_suffix('{') {
+                sections.push(Section::new(name.trim()));
+            } else {
+                return Err(invalid("expected a option, section or comment"));
+            }
+        }
+        match sections.last() {
+            Some(section) => Err(HyprError::InvalidConfig(format!(
+                "the section {:?} isn't closed",
+                section.name
+            ))),
+            None => Ok(Config { entries }),
+        }
+    }
+
+    /// This method reads and parses the config file, and the files it sources
+    ///
+    /// Relative paths in `source` are relative to the file sourcing them, `~` is the home directory
+    pub fn read(path: impl AsRef<Path>) -> HyprResult<Config> {
+        read(path.as_ref(), 0)
+    }
+
+    /// This method returns the value of the option (like `general:gaps_in`,
+    /// or `decoration:blur:enabled` for nested sections), with the variables in it replaced
+    ///
+    /// Like in Hyprland, the last value set wins, including in sourced files that were read,
+    /// and variables have the value they were last defined with before the line using them
+    ///
+    /// ```rust
+    /// use hyprland::config::Config;
+    /// let config = Config::parse("$gaps = 5\ngeneral:gaps_in = $gaps\n$gaps = 10\n")?;
+    /// assert_eq!(config.get("general:gaps_in"), Some("5".to_string()));
+    /// # Ok::<(), hyprland::shared::HyprError>(())
+    /// ```
+    pub fn get(&self, key: &str) -> Option<String> {
+        let (mut value, mut variables) = (None, HashMap::new());
+        find(&self.entries, "", key, &mut value, &mut variables);
+        value
+    }
+
+    /// This method changes the value of the option (where it was set last),
+    /// or adds it if it isn't set
+    ///
+    /// Only this file is changed, not the files it sources
+    pub fn update(&mut self, key: &str, value: impl Into<OptionValue>) {
+        let value = value.into().to_string();
+        match find_mut(&mut self.entries, "", key) {
+            Some(current) => *current = value,
+            None => self.entries.push(Entry::Set(key.to_string(), value)),
+        }
+    }
+
+    /// This method returns the contents of the config file
+    pub fn render(&self) -> HyprResult<String> {
+        let mut config = String::new();
+        render(&self.entries, 0, &mut config)?;
+        Ok(config)
+    }
+
+    /// This method writes the config to the file, replacing what's in it
+    pub fn write(&self, path: impl AsRef<Path>) -> HyprResult<()> {
+        Ok(std::fs::write(path, self.render()?)?)
+    }
+}
+
+/// Writes the entries, indented by `depth` levels
+fn render(entries: &[Entry], depth: usize, config: &mut String) -> HyprResult<()> {
+    let indent = "    ".repeat(depth);
+    for (index, entry) in entries.iter().enumerate() {
+        match entry {
+            Entry::Comment(comment) => {
+                for line in comment.split('\n') {
+                    config.push_str(format!("{indent}# {line}").trim_end());
+                    config.push('\n');
+                }
+            }
+            Entry::Variable(name, value) => {
+                config.push_str(&format!(
+                    "{indent}${} = {}\n",
+                    name_of(name)?,
+                    value_of(value)?
+                ));
+            }
+            Entry::Set(key, value) => {
+                config.push_str(&format!(
+                    "{indent}{} = {}\n",
+                    name_of(key)?,
+                    value_of(value)?
+                ));
+            }
+            Entry::Section(section) => {
+                // top level sections are surrounded by empty lines
+                if depth == 0 && index > 0 {
+                    config.push('\n');
+                }
+                config.push_str(&format!("{indent}{} {{\n", name_of(&section.name)?));
+                render(&section.entries, depth + 1, config)?;
+                config.push_str(&format!("{indent}}}\n"));
+                let next_is_section = matches!(entries.get(index + 1), Some(Entry::Section(_)));
+                if depth == 0 && index + 1 < entries.len() && !next_is_section {
+                    config.push('\n');
+                }
+            }
+            Entry::Source(path, _) => {
+                config.push_str(&format!("{indent}source = {}\n", value_of(path)?));
+            }
+            Entry::Bind(bind) => {
+                config.push_str(&format!("{indent}{}\n", value_of(&bind.to_string())?));
+            }
+            Entry::WindowRule(rule) => lines(&rule.to_config()?, &indent, config),
+            Entry::LayerRule(rule) => lines(&rule.to_config()?, &indent, config),
+        }
+    }
+    Ok(())
+}
+
+/// Writes the lines with the indent, escaping comment characters
+fn lines(lines: &str, indent: &str, config: &mut String) {
+    for line in lines.lines() {
+        config.push_str(&format!("{indent}{}\n", line.replace('#', "##")));
+    }
+}
+
+/// Returns the name of a option, variable or section, if it can be written in a config
+fn name_of(name: &str) -> HyprResult<&str> {
+    let invalid = |char: char| char.is_whitespace() || "=#{}$".contains(char);
+    match name.is_empty() || name.contains(invalid) {
+        true => Err(HyprError::InvalidConfig(format!("invalid name: {name:?}"))),
+        false => Ok(name),
+    }
+}
+
+/// Returns the value with comment characters escaped (`#` is written as `##`),
+/// if it can be written in a config
+fn value_of(value: &str) -> HyprResult<String> {
+    match value.contains(['\n', '\r']) {
+        true => Err(HyprError::InvalidConfig(format!(
+            "values can't contain newlines: {value:?}"
+        ))),
+        false => Ok(value.replace('#', "##")),
+    }
+}
+
+/// Splits a value from the comment after it, unescaping `##`
+fn split_comment(value: &str) -> (String, Option<String>) {
+    let mut unescaped = String::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match (char, chars.peek()) {
+            ('#', Some((_, '#'))) => {
+                chars.next();
+                unescaped.push('#');
+            }
+            ('#', _) => {
+                let comment = value[index + 1..].trim().to_string();
+                return (unescaped.trim().to_string(), Some(comment));
+            }
+            (char, _) => unescaped.push(char),
+        }
+    }
+    (unescaped.trim().to_string(), None)
+}
+
+fn read(path: &Path, depth: usize) -> HyprResult<Config> {
+    if depth > MAX_SOURCE_DEPTH {
+        return Err(HyprError::InvalidConfig(format!(
+            "{} is sourced too deeply, does it source itself?",
+            path.display()
+        )));
+    }
+    let mut config = Config::parse(&std::fs::read_to_string(path)?)?;
+    for entry in &mut config.entries {
+        if let Entry::Source(source, sourced) = entry {
+            let source = source_path(path, source);
+            *sourced = Some(read(&source, depth + 1)?);
+        }
+    }
+    Ok(config)
+}
+
+/// Returns the path of a file sourced by the config at `config`
+fn source_path(config: &Path, source: &str) -> PathBuf {
+    let source = match (source.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(source), Some(home)) => PathBuf::from(home).join(source),
+        _ => PathBuf::from(source),
+    };
+    match config.parent() {
+        Some(dir) if source.is_relative() => dir.join(source),
+        _ => source,
+    }
+}
+
+/// Finds the last value of the option, replacing the variables defined before it
+fn find(
+    entries: &[Entry],
+    prefix: &str,
+    key: &str,
+    value: &mut Option<String>,
+    variables: &mut HashMap<String, String>,
+) {
+    for entry in entries {
+        match entry {
+            Entry::Variable(name, variable) => {
+                let variable = substitute(variable, variables);
+                variables.insert(name.clone(), variable);
+            }
+            Entry::Set(option, set) if format!("{prefix}{option}") == key => {
+                *value = Some(substitute(set, variables));
+            }
+            Entry::Section(section) => {
+                let prefix = format!("{prefix}{}:", section.name);
+                find(&section.entries, &prefix, key, value, variables);
+            }
+            Entry::Source(_, Some(sourced)) => find(&sourced.entries, "", key, value, variables),
+            _ => {}
+        }
+    }
+}
+
+/// Returns the value with the variables in it replaced, unknown variables are kept as they are
+fn substitute(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut names: Vec<&String> = variables.keys().collect();
+    // longer names first, so `$terminal` isn't replaced as `$term`
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    names.into_iter().fold(value.to_string(), |value, name| {
+        value.replace(&format!("${name}"), &variables[name])
+    })
+}
+
+/// Returns the last value of the option, without looking in sourced files
+fn find_mut<'a>(entries: &'a mut [Entry], prefix: &str, key: &str) -> Option<&'a mut String> {
+    for entry in entries.iter_mut().rev() {
+        match entry {
+            Entry::Set(option, value) if format!("{prefix}{option}") == key => return Some(value),
+            Entry::Section(section) => {
+                let prefix = format!("{prefix}{}:", section.name);
+                if let Some(value) = find_mut(&mut section.entries, &prefix, key) {
+                    return Some(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bind::Modifier;
+    use crate::dispatch::DispatchType;
+    use crate::rule::WindowRuleEffect;
+
+    /// A config like a hand written one, already in the format it's rendered in
+    const CONFIG: &str = "\
+# managed by hand
+$terminal = kitty
+exec-once = waybar
+
+general {
+    gaps_in = 5
+    # the layout of new workspaces
+    layout = dwindle
+}
+
+decoration {
+    blur {
+        enabled = 1
+    }
+}
+
+bind = SUPER, Return, exec, $terminal
+source = ~/.config/hypr/monitors.conf
+";
+
+    #[test]
+    fn parsed_configs_render_as_they_were() -> HyprResult<()> {
+        assert_eq!(Config::parse(CONFIG)?.render()?, CONFIG);
+        Ok(())
+    }
+
+    #[test]
+    fn rendered_configs_parse_to_the_same_config() -> HyprResult<()> {
+        let config = Config::new()
+            .comment("generated")
+            .variable("gaps", 5)
+            .section(
+                Section::new("general")
+                    .set("gaps_in", "$gaps")
+                    .set("layout", "master"),
+            )
+            .section(Section::new("decoration").section(Section::new("blur").set("enabled", true)))
+            .bind(Binder::new("Q", DispatchType::KillActiveWindow).modifier(Modifier::Super))
+            .window_rule(
+                WindowRule::new()
+                    .effect(WindowRuleEffect::Float)
+                    .class("pavucontrol"),
+            );
+        let rendered = config.render()?;
+        let parsed = Config::parse(&rendered)?;
+        assert_eq!(parsed.render()?, rendered);
+        assert_eq!(parsed.get("general:gaps_in"), Some("5".to_string()));
+        assert_eq!(parsed.get("general:layout"), Some("master".to_string()));
+        assert_eq!(parsed.get("decoration:blur:enabled"), Some("1".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn comments_after_values() -> HyprResult<()> {
+        let config = Config::parse(
+            "general {\n    col.active_border = rgb(ff0000) ## not a comment # red\n}\n",
+        )?;
+        assert_eq!(
+            config.get("general:col.active_border"),
+            Some("rgb(ff0000) # not a comment".to_string())
+        );
+        assert_eq!(
+            config.render()?,
+            "general {\n    # red\n    col.active_border = rgb(ff0000) ## not a comment\n}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn updated_options() -> HyprResult<()> {
+        let mut config = Config::parse(CONFIG)?;
+        config.update("general:gaps_in", 10);
+        config.update("input:kb_layout", "de");
+        assert_eq!(config.get("general:gaps_in"), Some("10".to_string()));
+        assert_eq!(config.get("input:kb_layout"), Some("de".to_string()));
+        let rendered = config.render()?;
+        assert!(rendered.contains("    gaps_in = 10\n"));
+        assert!(rendered.ends_with("input:kb_layout = de\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn longer_variables_are_replaced_first() -> HyprResult<()> {
+        let config = Config::parse("$term = foot\n$terminal = kitty\nterminal = $terminal\n")?;
+        assert_eq!(config.get("terminal"), Some("kitty".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_configs() {
+        for config in ["general {\n    gaps_in = 5\n", "}\n", "= 5\n", "gaps_in\n"] {
+            assert!(
+                matches!(Config::parse(config), Err(HyprError::InvalidConfig(_))),
+                "{config:?}"
+            );
+        }
+        for config in [
+            Config::new().set("gaps in", 5),
+            Config::new().set("exec", "a\nb"),
+            Config::new().section(Section::new("general {")),
+        ] {
+            assert!(matches!(config.render(), Err(HyprError::InvalidConfig(_))));
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! # Connection module
+//!
+//! This module provides [`HyprlandConnection`](crate::connection::HyprlandConnection), a handle for sending many commands to Hyprland,
+//! and [`ConnectionPool`](crate::connection::ConnectionPool), for reusing them between tasks
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::connection::HyprlandConnection;
+//! use hyprland::dispatch::DispatchType;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let mut hyprland = HyprlandConnection::new()?;
+//!     let monitors = hyprland.get_monitors()?;
+//!     println!("{monitors:#?}");
+//!     hyprland.dispatch(DispatchType::Exec("kitty".to_string()))
+//! }
+//! ```
+
+use crate::ctl::{raw_json_request, raw_request};
+use crate::data::*;
+use crate::dispatch::{
+    batch_request, dispatch_request, Batch, DispatchType, WindowIdentifier, WindowProperty,
+};
+use crate::request::Request;
+use crate::shared::*;
+use crate::transport::{Transport, UnixSocket};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+
+/// This struct sends commands to Hyprland, keeping what can be reused between them around
+///
+/// The commands are sent with a [`Transport`], by default the [`UnixSocket`] of which
+/// the path is resolved once, and responses are read into the same buffer.
+/// Hyprland closes the command socket after answering, so every command still opens
+/// a new connection, use [`dispatch_batch`](Self::dispatch_batch) to send many at once.
+///
+/// Every method has a async version, with a `_async` suffix.
+/// The async methods are cancel safe, a command that's dropped before it finished
+/// leaves the connection usable (whether Hyprland ran it isn't known)
+#[derive(Debug, Clone)]
+pub struct HyprlandConnection<T: Transport = UnixSocket> {
+    transport: T,
+    buffer: Vec<u8>,
+}
+
+impl HyprlandConnection {
+    /// This method creates a connection to the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        Ok(HyprlandConnection::with_transport(UnixSocket::new()?))
+    }
+
+    /// This method creates a connection to a specific Hyprland instance,
+    /// which doesn't have to be the one this program runs in
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::connection::HyprlandConnection;
+    /// use hyprland::shared::Instance;
+    /// let instance = Instance::from_signature("v0.34.0_1700000000");
+    /// let mut hyprland = HyprlandConnection::for_instance(&instance);
+    /// println!("{:#?}", hyprland.get_version()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_instance(instance: &Instance) -> Self {
+        HyprlandConnection::with_transport(UnixSocket::for_instance(instance))
+    }
+
+    /// This method sets how long to wait for Hyprland to accept and to answer a command,
+    /// `None` waits forever (5 seconds by default)
+    ///
+    /// A command that takes longer returns [`HyprError::Timeout`],
+    /// whether Hyprland still runs it isn't known
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::connection::HyprlandConnection;
+    /// use std::time::Duration;
+    /// let mut hyprland = HyprlandConnection::new()?;
+    /// hyprland.set_timeout(Some(Duration::from_millis(500)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.transport.set_timeout(timeout);
+    }
+
+    /// This method makes the connection retry commands that couldn't be delivered,
+    /// like while Hyprland is restarting, `None` to not retry (the default)
+    ///
+    /// See [`Retry`] for which errors are retried
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::connection::HyprlandConnection;
+    /// use hyprland::shared::Retry;
+    /// let mut hyprland = HyprlandConnection::new()?;
+    /// hyprland.set_retry(Some(Retry::default()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_retry(&mut self, retry: Option<Retry>) {
+        self.transport.set_retry(retry);
+    }
+
+    /// This method sets when commands have to be finished by, including their retries,
+    /// `None` to not have a deadline (the default)
+    ///
+    /// Commands that would run past it return [`HyprError::DeadlineExceeded`]
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::connection::HyprlandConnection;
+    /// use std::time::{Duration, Instant};
+    /// let mut hyprland = HyprlandConnection::new()?;
+    /// hyprland.set_deadline(Some(Instant::now() + Duration::from_secs(1)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.transport.set_deadline(deadline);
+    }
+
+    /// This method sets the token that cancels the commands of the connection,
+    /// `None` to not cancel them (the default), see [`CancellationToken`]
+    pub fn set_cancellation(&mut self, cancellation: Option<CancellationToken>) {
+        self.transport.set_cancellation(cancellation);
+    }
+}
+
+impl<T: Transport> HyprlandConnection<T> {
+    /// This method creates a connection that sends commands with `transport`,
+    /// see the [`transport`](crate::transport) module
+    pub fn with_transport(transport: T) -> Self {
+        HyprlandConnection {
+            transport,
+            buffer: vec![],
+        }
+    }
+
+    /// This method returns the transport, to configure it
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    fn send_blocking<R>(&mut self, request: Request<HyprResult<R>>) -> HyprResult<R> {
+        request.send_blocking_with(&mut self.transport, &mut self.buffer)
+    }
+
+    #[cfg(feature = "async")]
+    async fn send<R>(&mut self, request: Request<HyprResult<R>>) -> HyprResult<R> {
+        request
+            .send_with(&mut self.transport, &mut self.buffer)
+            .await
+    }
+
+    /// This method sends a raw command with the json flag, and deserializes the response (blocking)
+    pub fn send_raw_json<R: DeserializeOwned>(&mut self, cmd: &str) -> HyprResult<R> {
+        self.send_blocking(raw_json_request(cmd))
+    }
+
+    /// This method sends a raw command with the json flag, and deserializes the response (async)
+    #[cfg(feature = "async")]
+    pub async fn send_raw_json_async<R: DeserializeOwned>(&mut self, cmd: &str) -> HyprResult<R> {
+        self.send(raw_json_request(cmd)).await
+    }
+
+    /// This method returns the focused monitor (blocking)
+    pub fn get_active_monitor(&mut self) -> HyprResult<Monitor> {
+        focused_monitor(self.get_monitors()?)
+    }
+
+    /// This method returns the focused monitor (async)
+    #[cfg(feature = "async")]
+    pub async fn get_active_monitor_async(&mut self) -> HyprResult<Monitor> {
+        focused_monitor(self.get_monitors_async().await?)
+    }
+
+    /// This method returns the workspace of the focused monitor (blocking)
+    pub fn get_active_workspace(&mut self) -> HyprResult<Workspace> {
+        let monitor = self.get_active_monitor()?;
+        monitor_workspace(&monitor, self.get_workspaces()?)
+    }
+
+    /// This method returns the workspace of the focused monitor (async)
+    #[cfg(feature = "async")]
+    pub async fn get_active_workspace_async(&mut self) -> HyprResult<Workspace> {
+        let monitor = self.get_active_monitor_async().await?;
+        monitor_workspace(&monitor, self.get_workspaces_async().await?)
+    }
+
+    /// This method returns the fullscreen state of the active workspace (blocking)
+    pub fn get_fullscreen_state(&mut self) -> HyprResult<bool> {
+        Ok(self.get_active_workspace()?.fullscreen)
+    }
+
+    /// This method returns the fullscreen state of the active workspace (async)
+    #[cfg(feature = "async")]
+    pub async fn get_fullscreen_state_async(&mut self) -> HyprResult<bool> {
+        Ok(self.get_active_workspace_async().await?.fullscreen)
+    }
+}
+
+macro_rules! gen_methods {
+    ($($name:ident, $async_name:ident ($($arg:ident: $ty:ty),*) -> $out:ty => $request:expr, $doc:literal;)*) => {
+        impl<T: Transport> HyprlandConnection<T> {
+            $(
+                #[doc = concat!("This method ", $doc, " (blocking)")]
+                pub fn $name(&mut self, $($arg: $ty),*) -> $out {
+                    self.send_blocking($request)
+                }
+
+                #[doc = concat!("This method ", $doc, " (async)")]
+                #[cfg(feature = "async")]
+                pub async fn $async_name(&mut self, $($arg: $ty),*) -> $out {
+                    self.send($request).await
+                }
+            )*
+        }
+    };
+}
+
+gen_methods! {
+    get_monitors, get_monitors_async () -> HyprResult<Monitors>
+        => monitors_request(), "returns all monitors";
+    get_monitors_all, get_monitors_all_async () -> HyprResult<Monitors>
+        => monitors_all_request(), "returns all monitors, including the ones that are turned off";
+    get_workspaces, get_workspaces_async () -> HyprResult<Workspaces>
+        => workspaces_request(), "returns all workspaces";
+    get_clients, get_clients_async () -> HyprResult<Clients>
+        => clients_request(), "returns all clients/windows";
+    get_active_window, get_active_window_async () -> HyprResult<ActiveWindow>
+        => active_window_request(), "returns the active window";
+    get_layers, get_layers_async () -> HyprResult<Layers>
+        => layers_request(), "returns all layer surfaces";
+    get_devices, get_devices_async () -> HyprResult<Devices>
+        => devices_request(), "returns all devices (mice, keyboards, tablets)";
+    get_version, get_version_async () -> HyprResult<Version>
+        => version_request(), "returns Hyprland version details";
+    get_binds, get_binds_async () -> HyprResult<Binds>
+        => binds_request(), "returns all keybinds";
+    get_keyword, get_keyword_async (key: String) -> HyprResult<Keyword>
+        => keyword_request(key), "returns data about a keyword";
+    get_window_property, get_window_property_async (window: WindowIdentifier, key: WindowPropertyKey)
+        -> HyprResult<WindowProperty>
+        => window_property_request(window, key), "returns the current value of a window property";
+    set_keyword, set_keyword_async (key: String, value: OptionValue) -> HyprResult<()>
+        => dispatch_request(DispatchType::Keyword(key, value.to_string()))?,
+        "sets a keyword to a new value";
+    set_keywords, set_keywords_async (keywords: &[(String, OptionValue)]) -> HyprResult<Vec<HyprResult<()>>>
+        => keyword_batch_request(keywords)?,
+        "sets multiple keywords in a single batched socket write, see [`Keyword::set_many`]";
+    dispatch, dispatch_async (dispatch_type: DispatchType) -> HyprResult<()>
+        => dispatch_request(dispatch_type)?, "calls a dispatcher";
+    dispatch_batch, dispatch_batch_async (dispatch_types: Vec<DispatchType>) -> HyprResult<()>
+        => batch_request(dispatch_types)?,
+        "calls multiple dispatchers in a single socket write, see [`dispatch_batch_blocking`](crate::dispatch::dispatch_batch_blocking)";
+    send_batch, send_batch_async (batch: Batch) -> HyprResult<Vec<HyprResult<()>>>
+        => batch.request()?, "sends a [`Batch`] of commands in a single socket write";
+    send_raw_command, send_raw_command_async (cmd: &str) -> HyprResult<String>
+        => raw_request(cmd), "sends a raw command and returns the response";
+}
+
+/// This struct holds idle connections, so their buffers are reused by the tasks that send commands
+///
+/// [`get`](Self::get) takes a idle connection, or creates one if there isn't any,
+/// and it's put back when it's dropped (up to `capacity` are kept around).
+/// Hyprland closes the command socket after every command, so there's no connection to keep open,
+/// what the pool saves is allocating the buffers responses are read into.
+/// A connection is put back with a clone of the pool's transport, so changes made to it
+/// (like [`set_timeout`](HyprlandConnection::set_timeout)) don't carry over to the next user.
+/// It doesn't limit how many commands are sent at the same time, `capacity` only limits
+/// how many idle connections are kept.
+///
+/// It's cheap to clone, and the clones share the connections
+///
+#[cfg_attr(feature = "tokio", doc = "```rust, no_run")]
+#[cfg_attr(not(feature = "tokio"), doc = "```rust, ignore")]
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::connection::ConnectionPool;
+/// let pool = ConnectionPool::new(3)?;
+/// let (mut a, mut b, mut c) = (pool.get(), pool.get(), pool.get());
+/// let (monitors, workspaces, clients) = tokio::join!(
+///     a.get_monitors_async(),
+///     b.get_workspaces_async(),
+///     c.get_clients_async(),
+/// );
+/// println!("{:#?} {:#?} {:#?}", monitors?, workspaces?, clients?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionPool<T: Transport + Clone = UnixSocket> {
+    inner: std::sync::Arc<Pool<T>>,
+}
+
+#[derive(Debug)]
+struct Pool<T: Transport> {
+    transport: T,
+    capacity: usize,
+    idle: std::sync::Mutex<Vec<HyprlandConnection<T>>>,
+}
+
+impl ConnectionPool {
+    /// This method creates a pool of connections to the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new(capacity: usize) -> HyprResult<Self> {
+        Ok(ConnectionPool::with_transport(UnixSocket::new()?, capacity))
+    }
+
+    /// This method creates a pool of connections to a specific Hyprland instance
+    pub fn for_instance(instance: &Instance, capacity: usize) -> Self {
+        ConnectionPool::with_transport(UnixSocket::for_instance(instance), capacity)
+    }
+}
+
+impl<T: Transport + Clone> ConnectionPool<T> {
+    /// This method creates a pool of connections that send commands with clones of `transport`,
+    /// configure it (like its timeout) before creating the pool
+    pub fn with_transport(transport: T, capacity: usize) -> Self {
+        ConnectionPool {
+            inner: std::sync::Arc::new(Pool {
+                transport,
+                capacity,
+                idle: std::sync::Mutex::new(Vec::with_capacity(capacity)),
+            }),
+        }
+    }
+
+    /// This method takes a connection from the pool, it's put back when it's dropped
+    pub fn get(&self) -> PooledConnection<T> {
+        let idle = self.inner.idle().pop();
+        let connection = idle
+            .unwrap_or_else(|| HyprlandConnection::with_transport(self.inner.transport.clone()));
+        PooledConnection {
+            pool: self.clone(),
+            connection: Some(connection),
+        }
+    }
+
+    /// This method returns how many idle connections the pool holds
+    pub fn idle(&self) -> usize {
+        self.inner.idle().len()
+    }
+}
+
+impl<T: Transport> Pool<T> {
+    fn idle(&self) -> std::sync::MutexGuard<'_, Vec<HyprlandConnection<T>>> {
+        self.idle
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// This struct is a connection taken from a [`ConnectionPool`],
+/// it derefs to [`HyprlandConnection`] and goes back to the pool when it's dropped
+#[derive(Debug)]
+pub struct PooledConnection<T: Transport + Clone = UnixSocket> {
+    pool: ConnectionPool<T>,
+    connection: Option<HyprlandConnection<T>>,
+}
+
+impl<T: Transport + Clone> std::ops::Deref for PooledConnection<T> {
+    type Target = HyprlandConnection<T>;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.connection {
+            Some(connection) => connection,
+            None => unreachable!("the connection is only taken when it's dropped"),
+        }
+    }
+}
+
+impl<T: Transport + Clone> std::ops::DerefMut for PooledConnection<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.connection {
+            Some(connection) => connection,
+            None => unreachable!("the connection is only taken when it's dropped"),
+        }
+    }
+}
+
+impl<T: Transport + Clone> Drop for PooledConnection<T> {
+    fn drop(&mut self) {
+        if let Some(mut connection) = self.connection.take() {
+            let mut idle = self.pool.inner.idle();
+            if idle.len() < self.pool.inner.capacity {
+                connection.transport = self.pool.inner.transport.clone();
+                idle.push(connection);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Returns a connection to a socket that answers each command with the next of `responses`,
+    /// and the thread serving it, which returns the commands it received
+    fn serve(
+        name: &str,
+        responses: Vec<&'static str>,
+    ) -> io::Result<(HyprlandConnection, JoinHandle<Vec<String>>)> {
+        let file = format!("hyprland-rs-{}-{name}.sock", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let socket_path = path.to_string_lossy().into_owned();
+        let server = thread::spawn(move || {
+            let mut received = vec![];
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    break;
+                };
+                let mut command = [0; 256];
+                let read = stream.read(&mut command).unwrap_or_default();
+                received.push(String::from_utf8_lossy(&command[..read]).into_owned());
+                let _ = stream.write_all(response.as_bytes());
+            }
+            let _ = std::fs::remove_file(path);
+            received
+        });
+        let connection = HyprlandConnection::with_transport(UnixSocket::from_path(socket_path));
+        Ok((connection, server))
+    }
+
+    #[test]
+    fn responses_are_read_into_the_same_buffer() -> HyprResult<()> {
+        let (mut hyprland, server) = serve("buffer", vec!["Window not found", "ok"])?;
+        assert_eq!(
+            hyprland.send_raw_command("dispatch focuswindow address:0x1")?,
+            "Window not found"
+        );
+        // the shorter response doesn't end with what's left of the longer one
+        assert_eq!(hyprland.send_raw_command("dispatch workspace 2")?, "ok");
+        let received = server
+            .join()
+            .map_err(|_| io::Error::other("the server panicked"))?;
+        assert_eq!(
+            received,
+            ["dispatch focuswindow address:0x1", "dispatch workspace 2"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn monitors_that_are_turned_off_are_returned() -> HyprResult<()> {
+        let monitor = r#"[{"id": 0, "name": "eDP-1", "width": 1920, "height": 1080,
+            "refreshRate": 60.0, "x": 0, "y": 0, "activeWorkspace": {"id": 1, "name": "1"},
+            "reserved": [0, 0, 0, 0], "scale": 1.0, "transform": 0, "focused": false,
+            "disabled": true}]"#;
+        let (mut hyprland, server) = serve("monitors-all", vec![monitor])?;
+        let monitors = hyprland.get_monitors_all()?;
+        assert_eq!(monitors.len(), 1);
+        assert!(monitors[0].disabled);
+        let received = server
+            .join()
+            .map_err(|_| io::Error::other("the server panicked"))?;
+        assert_eq!(received, ["j/monitors all"]);
+        Ok(())
+    }
+
+    #[test]
+    fn pooled_connections_are_put_back() -> HyprResult<()> {
+        let (connection, server) = serve("pool", vec!["ok", "ok"])?;
+        let pool = ConnectionPool::with_transport(connection.transport, 1);
+        let (mut a, b) = (pool.get(), pool.get());
+        assert_eq!(a.send_raw_command("dispatch workspace 2")?, "ok");
+        drop(a);
+        // only `capacity` connections are kept, the first one that's dropped
+        drop(b);
+        assert_eq!(pool.idle(), 1);
+        let mut connection = pool.get();
+        assert_eq!(pool.idle(), 0);
+        assert!(connection.buffer.capacity() > 0);
+        assert_eq!(connection.send_raw_command("dispatch workspace 3")?, "ok");
+        drop(connection);
+        assert_eq!(pool.clone().idle(), 1);
+        let received = server
+            .join()
+            .map_err(|_| io::Error::other("the server panicked"))?;
+        assert_eq!(received, ["dispatch workspace 2", "dispatch workspace 3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn pooled_connections_are_reset() -> HyprResult<()> {
+        let (connection, server) = serve("pool-reset", vec!["ok"])?;
+        let pool = ConnectionPool::with_transport(connection.transport, 1);
+        let mut connection = pool.get();
+        connection.set_deadline(Some(Instant::now()));
+        assert!(matches!(
+            connection.send_raw_command("dispatch workspace 2"),
+            Err(HyprError::DeadlineExceeded)
+        ));
+        drop(connection);
+        // the next user gets the pool's transport, without the deadline
+        let mut connection = pool.get();
+        assert_eq!(connection.send_raw_command("dispatch workspace 3")?, "ok");
+        let received = server
+            .join()
+            .map_err(|_| io::Error::other("the server panicked"))?;
+        assert_eq!(received, ["dispatch workspace 3"]);
+        Ok(())
+    }
+}
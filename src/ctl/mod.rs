@@ -0,0 +1,457 @@
+//! # Ctl module
+//!
+//! This module is used for calling hyprctl control commands that don't return data
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::ctl::reload_blocking;
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!    reload_blocking(false)?;
+//!
+//!    Ok(())
+//! }
+//! ```
+
+use crate::request::Request;
+use crate::shared::{Color, HyprResult};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// This module provides commands for creating and removing outputs
+pub mod output;
+
+/// This module provides commands for listing, loading and unloading plugins
+pub mod plugin;
+
+/// This function sends a raw command to the Hyprland socket and returns the response (blocking)
+///
+/// This is a escape hatch for commands that aren't covered by the rest of the crate yet,
+/// the command is written as is, the same way `hyprctl` would.
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::send_raw_command_blocking;
+/// let response = send_raw_command_blocking("dispatch exec kitty")?;
+/// assert_eq!(response, "ok");
+/// # Ok(())
+/// # }
+/// ```
+pub fn send_raw_command_blocking(cmd: &str) -> HyprResult<String> {
+    raw_request(cmd).send_blocking()
+}
+
+/// This function sends a raw command to the Hyprland socket and returns the response (async)
+///
+/// This is a escape hatch for commands that aren't covered by the rest of the crate yet,
+/// the command is written as is, the same way `hyprctl` would.
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::send_raw_command;
+/// let response = send_raw_command("dispatch exec kitty").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn send_raw_command(cmd: &str) -> HyprResult<String> {
+    raw_request(cmd).send().await
+}
+
+/// This function sends a raw command with the json flag, and deserializes the response (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::send_raw_json_blocking;
+/// let binds: serde_json::Value = send_raw_json_blocking("binds")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn send_raw_json_blocking<T: DeserializeOwned>(cmd: &str) -> HyprResult<T> {
+    raw_json_request(cmd).send_blocking()
+}
+
+/// This function sends a raw command with the json flag, and deserializes the response (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::send_raw_json;
+/// let binds: serde_json::Value = send_raw_json("binds").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn send_raw_json<T: DeserializeOwned>(cmd: &str) -> HyprResult<T> {
+    raw_json_request(cmd).send().await
+}
+
+pub(crate) fn ctl_request(cmd: String) -> Request<HyprResult<()>> {
+    Request::ok(cmd)
+}
+
+pub(crate) fn raw_request(cmd: &str) -> Request<HyprResult<String>> {
+    Request::new(cmd, |response| response)
+}
+
+pub(crate) fn raw_json_request<T: DeserializeOwned>(cmd: &str) -> Request<HyprResult<T>> {
+    Request::new(format!("j/{cmd}"), |response| {
+        Ok(serde_json::from_str(&response?)?)
+    })
+}
+
+fn gen_reload_str(config_only: bool) -> String {
+    if config_only {
+        "reload config-only".to_string()
+    } else {
+        "reload".to_string()
+    }
+}
+
+/// This function reloads the Hyprland config (blocking)
+///
+/// If `config_only` is true, only the config is reloaded
+/// and things like monitors are left alone
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::reload_blocking;
+/// reload_blocking(false)
+/// # }
+/// ```
+pub fn reload_blocking(config_only: bool) -> HyprResult<()> {
+    ctl_request(gen_reload_str(config_only)).send_blocking()
+}
+
+/// This function reloads the Hyprland config (async)
+///
+/// If `config_only` is true, only the config is reloaded
+/// and things like monitors are left alone
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::reload;
+/// reload(false).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn reload(config_only: bool) -> HyprResult<()> {
+    ctl_request(gen_reload_str(config_only)).send().await
+}
+
+/// This function enters the interactive kill mode,
+/// where the next window clicked is killed (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::kill_blocking;
+/// kill_blocking()
+/// # }
+/// ```
+pub fn kill_blocking() -> HyprResult<()> {
+    ctl_request("kill".to_string()).send_blocking()
+}
+
+/// This function enters the interactive kill mode,
+/// where the next window clicked is killed (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::kill;
+/// kill().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn kill() -> HyprResult<()> {
+    ctl_request("kill".to_string()).send().await
+}
+
+/// This function changes the cursor theme and size (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::set_cursor_blocking;
+/// set_cursor_blocking("Bibata-Modern-Classic".to_string(), 24)
+/// # }
+/// ```
+pub fn set_cursor_blocking(theme: String, size: u16) -> HyprResult<()> {
+    ctl_request(format!("setcursor {theme} {size}")).send_blocking()
+}
+
+/// This function changes the cursor theme and size (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::set_cursor;
+/// set_cursor("Bibata-Modern-Classic".to_string(), 24).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn set_cursor(theme: String, size: u16) -> HyprResult<()> {
+    ctl_request(format!("setcursor {theme} {size}"))
+        .send()
+        .await
+}
+
+/// This enum holds the layouts a keyboard can be switched to
+pub enum XkbLayoutTarget {
+    /// The next layout of the keyboard
+    Next,
+    /// The previous layout of the keyboard
+    Previous,
+    /// The layout at the specified index of the keyboard's layouts
+    Id(u8),
+}
+
+fn gen_switch_xkb_layout_str(device: String, target: XkbLayoutTarget) -> String {
+    let target = match target {
+        XkbLayoutTarget::Next => "next".to_string(),
+        XkbLayoutTarget::Previous => "prev".to_string(),
+        XkbLayoutTarget::Id(id) => id.to_string(),
+    };
+    format!("switchxkblayout {device} {target}")
+}
+
+/// This function switches the layout of a keyboard (blocking)
+///
+/// The device is the name of the keyboard, as found in
+/// [`get_devices`][crate::data::blocking::get_devices]
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::{switch_xkb_layout_blocking, XkbLayoutTarget};
+/// switch_xkb_layout_blocking("at-translated-set-2-keyboard".to_string(), XkbLayoutTarget::Next)
+/// # }
+/// ```
+pub fn switch_xkb_layout_blocking(device: String, target: XkbLayoutTarget) -> HyprResult<()> {
+    ctl_request(gen_switch_xkb_layout_str(device, target)).send_blocking()
+}
+
+/// This function switches the layout of a keyboard (async)
+///
+/// The device is the name of the keyboard, as found in
+/// [`get_devices`][crate::data::asynchronous::get_devices]
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::{switch_xkb_layout, XkbLayoutTarget};
+/// switch_xkb_layout("at-translated-set-2-keyboard".to_string(), XkbLayoutTarget::Id(0)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn switch_xkb_layout(device: String, target: XkbLayoutTarget) -> HyprResult<()> {
+    ctl_request(gen_switch_xkb_layout_str(device, target))
+        .send()
+        .await
+}
+
+/// This function shows a persistent error banner at the top of the screen (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::set_error_blocking;
+/// use hyprland::shared::Color;
+/// set_error_blocking(Color::new(255, 0, 0, 255), "Something went wrong".to_string())
+/// # }
+/// ```
+pub fn set_error_blocking(color: Color, message: String) -> HyprResult<()> {
+    ctl_request(format!("seterror {color} {message}")).send_blocking()
+}
+
+/// This function shows a persistent error banner at the top of the screen (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::set_error;
+/// use hyprland::shared::Color;
+/// set_error(Color::new(255, 0, 0, 255), "Something went wrong".to_string()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn set_error(color: Color, message: String) -> HyprResult<()> {
+    ctl_request(format!("seterror {color} {message}"))
+        .send()
+        .await
+}
+
+/// This function removes the error banner set by [`set_error_blocking`] (blocking)
+pub fn disable_error_blocking() -> HyprResult<()> {
+    ctl_request("seterror disable".to_string()).send_blocking()
+}
+
+/// This function removes the error banner set by [`set_error`] (async)
+#[cfg(feature = "async")]
+pub async fn disable_error() -> HyprResult<()> {
+    ctl_request("seterror disable".to_string()).send().await
+}
+
+fn gen_dismiss_notify_str(count: Option<u32>) -> String {
+    match count {
+        Some(count) => format!("dismissnotify {count}"),
+        None => "dismissnotify".to_string(),
+    }
+}
+
+/// This function dismisses notifications, starting from the oldest one (blocking)
+///
+/// If `count` is `None` all notifications are dismissed
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::dismiss_notify_blocking;
+/// dismiss_notify_blocking(Some(1))
+/// # }
+/// ```
+pub fn dismiss_notify_blocking(count: Option<u32>) -> HyprResult<()> {
+    ctl_request(gen_dismiss_notify_str(count)).send_blocking()
+}
+
+/// This function dismisses notifications, starting from the oldest one (async)
+///
+/// If `count` is `None` all notifications are dismissed
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::dismiss_notify;
+/// dismiss_notify(None).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn dismiss_notify(count: Option<u32>) -> HyprResult<()> {
+    ctl_request(gen_dismiss_notify_str(count)).send().await
+}
+
+/// This enum holds the icons a notification can have
+#[derive(Clone, Copy)]
+#[allow(missing_docs)]
+pub enum Icon {
+    Warning = 0,
+    Info = 1,
+    Hint = 2,
+    Error = 3,
+    Confused = 4,
+    Ok = 5,
+    NoIcon = -1,
+}
+
+fn gen_notify_str(icon: Icon, timeout: Duration, color: Option<Color>, message: String) -> String {
+    let color = match color {
+        Some(color) => color.to_string(),
+        None => "0".to_string(),
+    };
+    format!(
+        "notify {} {} {color} {message}",
+        icon as i8,
+        timeout.as_millis()
+    )
+}
+
+/// This function shows a notification (blocking)
+///
+/// If `color` is `None` the default color of the icon is used
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::{notify_blocking, Icon};
+/// use std::time::Duration;
+/// notify_blocking(Icon::Info, Duration::from_secs(5), None, "Hello!".to_string())
+/// # }
+/// ```
+pub fn notify_blocking(
+    icon: Icon,
+    timeout: Duration,
+    color: Option<Color>,
+    message: String,
+) -> HyprResult<()> {
+    ctl_request(gen_notify_str(icon, timeout, color, message)).send_blocking()
+}
+
+/// This function shows a notification (async)
+///
+/// If `color` is `None` the default color of the icon is used
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::{notify, Icon};
+/// use hyprland::shared::Color;
+/// use std::time::Duration;
+/// notify(
+///     Icon::Warning,
+///     Duration::from_secs(5),
+///     Some(Color::new(255, 165, 0, 255)),
+///     "Battery low".to_string(),
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn notify(
+    icon: Icon,
+    timeout: Duration,
+    color: Option<Color>,
+    message: String,
+) -> HyprResult<()> {
+    ctl_request(gen_notify_str(icon, timeout, color, message))
+        .send()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_commands() {
+        assert_eq!(gen_reload_str(false), "reload");
+        assert_eq!(gen_reload_str(true), "reload config-only");
+    }
+
+    #[test]
+    fn switch_xkb_layout_commands() {
+        let keyboard = || "at-translated-set-2-keyboard".to_string();
+        assert_eq!(
+            gen_switch_xkb_layout_str(keyboard(), XkbLayoutTarget::Next),
+            "switchxkblayout at-translated-set-2-keyboard next"
+        );
+        assert_eq!(
+            gen_switch_xkb_layout_str(keyboard(), XkbLayoutTarget::Previous),
+            "switchxkblayout at-translated-set-2-keyboard prev"
+        );
+        assert_eq!(
+            gen_switch_xkb_layout_str(keyboard(), XkbLayoutTarget::Id(2)),
+            "switchxkblayout at-translated-set-2-keyboard 2"
+        );
+    }
+
+    #[test]
+    fn error_and_dismiss_commands() {
+        assert_eq!(Color::new(255, 0, 0, 255).to_string(), "rgba(ff0000ff)");
+        assert_eq!(Color::new(0, 10, 200, 0).to_string(), "rgba(000ac800)");
+        assert_eq!(gen_dismiss_notify_str(Some(2)), "dismissnotify 2");
+        assert_eq!(gen_dismiss_notify_str(None), "dismissnotify");
+    }
+
+    #[test]
+    fn notify_commands() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            gen_notify_str(
+                Icon::Warning,
+                timeout,
+                Some(Color::new(255, 165, 0, 255)),
+                "Battery low".to_string()
+            ),
+            "notify 0 5000 rgba(ffa500ff) Battery low"
+        );
+        assert_eq!(
+            gen_notify_str(Icon::NoIcon, timeout, None, "a  message".to_string()),
+            "notify -1 5000 0 a  message"
+        );
+    }
+}
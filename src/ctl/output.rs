@@ -0,0 +1,85 @@
+use super::ctl_request;
+use crate::shared::HyprResult;
+
+/// This enum holds the backends an output can be created with
+pub enum OutputBackend {
+    /// A headless output, not shown anywhere
+    Headless,
+    /// The backend Hyprland picks itself
+    Auto,
+    /// A X11 window (only when Hyprland is nested in X11)
+    X11,
+    /// A Wayland window (only when Hyprland is nested in Wayland)
+    Wayland,
+}
+
+fn gen_create_str(backend: OutputBackend, name: Option<String>) -> String {
+    let backend = match backend {
+        OutputBackend::Headless => "headless",
+        OutputBackend::Auto => "auto",
+        OutputBackend::X11 => "x11",
+        OutputBackend::Wayland => "wayland",
+    };
+    match name {
+        Some(name) => format!("output create {backend} {name}"),
+        None => format!("output create {backend}"),
+    }
+}
+
+/// This function creates a new output (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::output::{create_blocking, OutputBackend};
+/// create_blocking(OutputBackend::Headless, Some("HEADLESS-VNC".to_string()))
+/// # }
+/// ```
+pub fn create_blocking(backend: OutputBackend, name: Option<String>) -> HyprResult<()> {
+    ctl_request(gen_create_str(backend, name)).send_blocking()
+}
+
+/// This function creates a new output (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::output::{create, OutputBackend};
+/// create(OutputBackend::Headless, Some("HEADLESS-VNC".to_string())).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn create(backend: OutputBackend, name: Option<String>) -> HyprResult<()> {
+    ctl_request(gen_create_str(backend, name)).send().await
+}
+
+/// This function removes a output that was created with [`create_blocking`] (blocking)
+pub fn remove_blocking(name: String) -> HyprResult<()> {
+    ctl_request(format!("output remove {name}")).send_blocking()
+}
+
+/// This function removes a output that was created with [`create`] (async)
+#[cfg(feature = "async")]
+pub async fn remove(name: String) -> HyprResult<()> {
+    ctl_request(format!("output remove {name}")).send().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_commands() {
+        assert_eq!(
+            gen_create_str(OutputBackend::Headless, Some("HEADLESS-VNC".to_string())),
+            "output create headless HEADLESS-VNC"
+        );
+        assert_eq!(
+            gen_create_str(OutputBackend::Auto, None),
+            "output create auto"
+        );
+        assert_eq!(
+            gen_create_str(OutputBackend::Wayland, None),
+            "output create wayland"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use super::ctl_request;
+use crate::request::Request;
+use crate::shared::HyprResult;
+use serde::{Deserialize, Serialize};
+
+/// This struct holds information about a loaded plugin
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Plugin {
+    /// The name of the plugin
+    pub name: String,
+    /// The author of the plugin
+    pub author: String,
+    /// The handle of the plugin (as hex)
+    pub handle: String,
+    /// The version of the plugin
+    pub version: String,
+    /// The description of the plugin
+    pub description: String,
+}
+
+/// This type provides a vector of plugins
+pub type Plugins = Vec<Plugin>;
+
+fn list_request() -> Request<HyprResult<Plugins>> {
+    Request::new("j/plugin list", |response| {
+        let deserialized: Plugins = serde_json::from_str(&response?)?;
+        Ok(deserialized)
+    })
+}
+
+/// This function returns all loaded plugins (blocking)
+pub fn list_blocking() -> HyprResult<Plugins> {
+    list_request().send_blocking()
+}
+
+/// This function returns all loaded plugins (async)
+#[cfg(feature = "async")]
+pub async fn list() -> HyprResult<Plugins> {
+    list_request().send().await
+}
+
+/// This function loads a plugin from a absolute path (blocking)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::plugin::load_blocking;
+/// load_blocking("/home/user/plugins/myplugin.so".to_string())
+/// # }
+/// ```
+pub fn load_blocking(path: String) -> HyprResult<()> {
+    ctl_request(format!("plugin load {path}")).send_blocking()
+}
+
+/// This function loads a plugin from a absolute path (async)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::ctl::plugin::load;
+/// load("/home/user/plugins/myplugin.so".to_string()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn load(path: String) -> HyprResult<()> {
+    ctl_request(format!("plugin load {path}")).send().await
+}
+
+/// This function unloads a plugin, the path must be the one it was loaded with (blocking)
+pub fn unload_blocking(path: String) -> HyprResult<()> {
+    ctl_request(format!("plugin unload {path}")).send_blocking()
+}
+
+/// This function unloads a plugin, the path must be the one it was loaded with (async)
+#[cfg(feature = "async")]
+pub async fn unload(path: String) -> HyprResult<()> {
+    ctl_request(format!("plugin unload {path}")).send().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_lists() -> serde_json::Result<()> {
+        let plugins: Plugins = serde_json::from_str(
+            r#"[{"name": "hyprexpo", "author": "Vaxry", "handle": "55d1a3b0c390",
+                 "version": "0.1", "description": "A plugin for an overview"}]"#,
+        )?;
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].name, "hyprexpo");
+        assert_eq!(plugins[0].handle, "55d1a3b0c390");
+        assert!(serde_json::from_str::<Plugins>("[]")?.is_empty());
+        Ok(())
+    }
+}
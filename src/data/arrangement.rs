@@ -0,0 +1,375 @@
+use crate::data::shared::*;
+use crate::shared::HyprResult;
+use std::fmt;
+
+/// This enum holds where a monitor is, relative to another monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Left of the other monitor
+    LeftOf,
+    /// Right of the other monitor
+    RightOf,
+    /// Above the other monitor
+    Above,
+    /// Below the other monitor
+    Below,
+}
+
+/// This struct holds the positions of the monitors, to arrange them and apply the arrangement
+///
+/// The positions and sizes are in logical pixels (scaled, and rotated by the transform),
+/// like in Hyprland's `monitor=` rules
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::data::{blocking::get_monitors, MonitorArrangement, Placement};
+/// let mut arrangement = MonitorArrangement::new(get_monitors()?);
+/// arrangement.place("HDMI-A-1", Placement::RightOf, "eDP-1");
+/// arrangement.resolve_overlaps();
+/// println!("{arrangement}"); // the `monitor=` lines for the config
+/// arrangement.apply()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MonitorArrangement {
+    monitors: Monitors,
+}
+
+impl MonitorArrangement {
+    /// This method creates a arrangement of the monitors, starting from their current positions
+    pub fn new(monitors: Monitors) -> Self {
+        MonitorArrangement { monitors }
+    }
+
+    /// This method returns the monitors, with the positions of the arrangement
+    pub fn monitors(&self) -> &Monitors {
+        &self.monitors
+    }
+
+    fn monitor(&self, name: &str) -> Option<&Monitor> {
+        self.monitors.iter().find(|monitor| monitor.name == name)
+    }
+
+    /// This method returns where the monitor is, relative to the other one
+    ///
+    /// Returns `None` if one of them doesn't exist or they overlap,
+    /// for monitors that are diagonal to each other the horizontal placement is returned
+    pub fn placement(&self, name: &str, relative_to: &str) -> Option<Placement> {
+        let monitor = Rect::of(self.monitor(name)?);
+        let other = Rect::of(self.monitor(relative_to)?);
+        if monitor.x >= other.right() {
+            Some(Placement::RightOf)
+        } else if monitor.right() <= other.x {
+            Some(Placement::LeftOf)
+        } else if monitor.bottom() <= other.y {
+            Some(Placement::Above)
+        } else if monitor.y >= other.bottom() {
+            Some(Placement::Below)
+        } else {
+            None
+        }
+    }
+
+    /// This method moves the monitor next to the other one,
+    /// aligned to its top edge (or its left edge, when above or below it)
+    ///
+    /// Returns `false` if one of them doesn't exist
+    pub fn place(&mut self, name: &str, placement: Placement, relative_to: &str) -> bool {
+        let (Some(monitor), Some(other)) = (self.monitor(name), self.monitor(relative_to)) else {
+            return false;
+        };
+        let (monitor, other) = (Rect::of(monitor), Rect::of(other));
+        let (x, y) = match placement {
+            Placement::LeftOf => (other.x - monitor.width, other.y),
+            Placement::RightOf => (other.right(), other.y),
+            Placement::Above => (other.x, other.y - monitor.height),
+            Placement::Below => (other.x, other.bottom()),
+        };
+        self.set_position(name, x, y)
+    }
+
+    /// This method moves the monitor to a position, returns `false` if it doesn't exist
+    pub fn set_position(&mut self, name: &str, x: i32, y: i32) -> bool {
+        match self
+            .monitors
+            .iter_mut()
+            .find(|monitor| monitor.name == name)
+        {
+            Some(monitor) => {
+                (monitor.x, monitor.y) = (x, y);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// This method moves monitors that overlap others to the right, until none overlap
+    ///
+    /// The monitors are handled from left to right (then top to bottom),
+    /// a monitor keeps its place if it doesn't overlap the ones before it
+    pub fn resolve_overlaps(&mut self) {
+        let mut order: Vec<usize> = (0..self.monitors.len()).collect();
+        order.sort_by_key(|&index| (self.monitors[index].x, self.monitors[index].y));
+        let mut placed: Vec<Rect> = vec![];
+        for index in order {
+            let mut rect = Rect::of(&self.monitors[index]);
+            while let Some(overlapped) = placed.iter().find(|other| other.overlaps(&rect)) {
+                rect.x = overlapped.right();
+            }
+            self.monitors[index].x = rect.x;
+            placed.push(rect);
+        }
+    }
+
+    /// This method moves all monitors so the top left one is at `0x0`,
+    /// without changing where they are relative to each other
+    pub fn normalize(&mut self) {
+        let min_x = self.monitors.iter().map(|monitor| monitor.x).min();
+        let min_y = self.monitors.iter().map(|monitor| monitor.y).min();
+        if let (Some(min_x), Some(min_y)) = (min_x, min_y) {
+            for monitor in &mut self.monitors {
+                monitor.x -= min_x;
+                monitor.y -= min_y;
+            }
+        }
+    }
+
+    /// This method returns the values of the `monitor` keyword that apply the arrangement,
+    /// one for every monitor (like `DP-1,1920x1080@60,0x0,1`)
+    pub fn rules(&self) -> Vec<String> {
+        self.monitors.iter().map(monitor_rule).collect()
+    }
+
+    fn keywords(&self) -> Vec<(String, OptionValue)> {
+        self.rules()
+            .into_iter()
+            .map(|rule| ("monitor".to_string(), OptionValue::String(rule)))
+            .collect()
+    }
+
+    /// This method applies the arrangement, by setting the `monitor` keyword for every monitor (blocking)
+    ///
+    /// The changes last until the config is reloaded, write [`Display`](fmt::Display)
+    /// of the arrangement to the config to keep them
+    pub fn apply(&self) -> HyprResult<()> {
+        Keyword::set_many(&self.keywords())?.into_iter().collect()
+    }
+
+    /// This method applies the arrangement, by setting the `monitor` keyword for every monitor (async)
+    ///
+    /// See [`apply`](Self::apply)
+    #[cfg(feature = "async")]
+    pub async fn apply_async(&self) -> HyprResult<()> {
+        Keyword::set_many_async(&self.keywords())
+            .await?
+            .into_iter()
+            .collect()
+    }
+}
+
+/// The arrangement is written as the config lines that apply it (`monitor=...`), one per line
+impl fmt::Display for MonitorArrangement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rule in self.rules() {
+            writeln!(f, "monitor={rule}")?;
+        }
+        Ok(())
+    }
+}
+
+fn monitor_rule(monitor: &Monitor) -> String {
+    let mut rule = format!(
+        "{},{}x{}@{},{}x{},{}",
+        monitor.name,
+        monitor.width,
+        monitor.height,
+        monitor.refresh_rate,
+        monitor.x,
+        monitor.y,
+        monitor.scale
+    );
+    let transform = monitor.transform.clone() as u8;
+    if transform != 0 {
+        rule.push_str(&format!(",transform,{transform}"));
+    }
+    rule
+}
+
+/// The area a monitor takes up, in logical pixels
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    fn of(monitor: &Monitor) -> Self {
+        let scale = if monitor.scale > 0.0 {
+            monitor.scale
+        } else {
+            1.0
+        };
+        let width = (f32::from(monitor.width) / scale).round() as i32;
+        let height = (f32::from(monitor.height) / scale).round() as i32;
+        // the odd transforms rotate the monitor by 90 or 270 degrees
+        let rotated = monitor.transform.clone() as u8 % 2 == 1;
+        let (width, height) = if rotated {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Rect {
+            x: monitor.x,
+            y: monitor.y,
+            width,
+            height,
+        }
+    }
+
+    fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::WorkspaceType;
+
+    /// Returns a monitor at `x`,`y` with a mode of `width`x`height`
+    fn monitor(id: u8, name: &str, (width, height): (u16, u16), (x, y): (i32, i32)) -> Monitor {
+        Monitor {
+            id,
+            name: name.to_string(),
+            width,
+            height,
+            description: String::new(),
+            make: String::new(),
+            model: String::new(),
+            serial: String::new(),
+            refresh_rate: 60.0,
+            x,
+            y,
+            active_workspace: WorkspaceBasic {
+                id: WorkspaceType::Regular(i32::from(id) + 1),
+                name: (id + 1).to_string(),
+            },
+            reserved: (0, 0, 0, 0),
+            scale: 1.0,
+            transform: Transforms::Normal,
+            focused: id == 0,
+            disabled: false,
+        }
+    }
+
+    fn positions(arrangement: &MonitorArrangement) -> Vec<(i32, i32)> {
+        let monitors = arrangement.monitors().iter();
+        monitors.map(|monitor| (monitor.x, monitor.y)).collect()
+    }
+
+    #[test]
+    fn placed_monitors() {
+        let mut arrangement = MonitorArrangement::new(vec![
+            monitor(0, "eDP-1", (1920, 1080), (0, 0)),
+            monitor(1, "DP-1", (2560, 1440), (0, 0)),
+        ]);
+        assert_eq!(arrangement.placement("DP-1", "eDP-1"), None);
+
+        assert!(arrangement.place("DP-1", Placement::RightOf, "eDP-1"));
+        assert_eq!(positions(&arrangement), [(0, 0), (1920, 0)]);
+        assert_eq!(
+            arrangement.placement("DP-1", "eDP-1"),
+            Some(Placement::RightOf)
+        );
+        assert_eq!(
+            arrangement.placement("eDP-1", "DP-1"),
+            Some(Placement::LeftOf)
+        );
+
+        assert!(arrangement.place("DP-1", Placement::Above, "eDP-1"));
+        assert_eq!(positions(&arrangement), [(0, 0), (0, -1440)]);
+        assert_eq!(
+            arrangement.placement("DP-1", "eDP-1"),
+            Some(Placement::Above)
+        );
+        assert_eq!(
+            arrangement.placement("eDP-1", "DP-1"),
+            Some(Placement::Below)
+        );
+
+        assert!(arrangement.place("DP-1", Placement::LeftOf, "eDP-1"));
+        assert_eq!(positions(&arrangement), [(0, 0), (-2560, 0)]);
+        arrangement.normalize();
+        assert_eq!(positions(&arrangement), [(2560, 0), (0, 0)]);
+
+        assert!(!arrangement.place("HDMI-A-1", Placement::Below, "eDP-1"));
+        assert!(!arrangement.place("DP-1", Placement::Below, "HDMI-A-1"));
+        assert_eq!(arrangement.placement("HDMI-A-1", "eDP-1"), None);
+    }
+
+    #[test]
+    fn scaled_and_rotated_monitors_are_placed_by_their_logical_size() {
+        let mut scaled = monitor(0, "eDP-1", (2880, 1800), (0, 0));
+        scaled.scale = 2.0;
+        let mut rotated = monitor(1, "DP-1", (2560, 1440), (0, 0));
+        rotated.transform = Transforms::Normal90;
+        let mut arrangement = MonitorArrangement::new(vec![scaled, rotated]);
+        assert!(arrangement.place("DP-1", Placement::RightOf, "eDP-1"));
+        assert_eq!(positions(&arrangement), [(0, 0), (1440, 0)]);
+        assert!(arrangement.place("eDP-1", Placement::Below, "DP-1"));
+        assert_eq!(positions(&arrangement), [(1440, 2560), (1440, 0)]);
+    }
+
+    #[test]
+    fn overlapping_monitors_are_moved_right() {
+        let mut arrangement = MonitorArrangement::new(vec![
+            monitor(0, "eDP-1", (1920, 1080), (0, 0)),
+            monitor(1, "DP-1", (1920, 1080), (100, 500)),
+            monitor(2, "DP-2", (1280, 1024), (0, 0)),
+            monitor(3, "HDMI-A-1", (1920, 1080), (0, 2000)),
+        ]);
+        arrangement.resolve_overlaps();
+        // DP-2 is handled before DP-1, as it's further left, so DP-1 is moved past both
+        assert_eq!(
+            positions(&arrangement),
+            [(0, 0), (3200, 500), (1920, 0), (0, 2000)]
+        );
+        let rects: Vec<Rect> = arrangement.monitors().iter().map(Rect::of).collect();
+        for (index, rect) in rects.iter().enumerate() {
+            assert!(!rects[index + 1..].iter().any(|other| other.overlaps(rect)));
+        }
+    }
+
+    #[test]
+    fn monitor_rules() {
+        let mut rotated = monitor(1, "DP-1", (2560, 1440), (1920, 0));
+        rotated.transform = Transforms::Normal270;
+        rotated.scale = 1.5;
+        let arrangement =
+            MonitorArrangement::new(vec![monitor(0, "eDP-1", (1920, 1080), (0, 0)), rotated]);
+        assert_eq!(
+            arrangement.rules(),
+            [
+                "eDP-1,1920x1080@60,0x0,1",
+                "DP-1,2560x1440@60,1920x0,1.5,transform,3"
+            ]
+        );
+        assert_eq!(
+            arrangement.to_string(),
+            "monitor=eDP-1,1920x1080@60,0x0,1\nmonitor=DP-1,2560x1440@60,1920x0,1.5,transform,3\n"
+        );
+    }
+}
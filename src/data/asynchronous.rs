@@ -1,171 +1,211 @@
-use crate::shared::*;
-use serde_json::Result;
-use std::io;
-
 use crate::data::shared::*;
+use crate::dispatch::{dispatch, CycleDirection, DispatchType, WindowIdentifier, WindowProperty};
+use crate::shared::{HyprResult, WorkspaceId};
 
-/// This private function is to call socket commands
-async fn call_hyprctl_data_cmd(cmd: DataCommands) -> io::Result<String> {
-    let cmd_string = match cmd {
-        DataCommands::Monitors => "monitors".to_string(),
-        DataCommands::ActiveWindow => "activewindow".to_string(),
-        DataCommands::Clients => "clients".to_string(),
-        DataCommands::Devices => "devices".to_string(),
-        DataCommands::Layers => "layers".to_string(),
-        DataCommands::Workspaces => "workspaces".to_string(),
-        DataCommands::Version => "version".to_string(),
-        DataCommands::Keyword(key) => format!("getoption {key}"),
-    };
-
-    let socket_path = get_socket_path(SocketType::Command);
-
-    write_to_socket(socket_path, format!("j/{cmd_string}").as_bytes()).await
+/// This function returns all monitors
+pub async fn get_monitors() -> HyprResult<Monitors> {
+    monitors_request().send().await
 }
 
-/// This function returns all monitors
-pub async fn get_monitors() -> Result<Monitors> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Monitors).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Monitors = serde_json::from_str(&data)?;
-    Ok(deserialized)
+/// This function returns all monitors, including the ones that are turned off
+pub async fn get_monitors_all() -> HyprResult<Monitors> {
+    monitors_all_request().send().await
 }
 
 /// This function returns all workspaces
-pub async fn get_workspaces() -> Result<Workspaces> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Workspaces).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: WorkspacesRaw = serde_json::from_str(&data)?;
-    let new = deserialized
-        .iter()
-        .map(|work| Workspace::from(work.clone()));
-    Ok(new.collect())
+pub async fn get_workspaces() -> HyprResult<Workspaces> {
+    workspaces_request().send().await
 }
 
 /// This function returns all clients/windows
-pub async fn get_clients() -> Result<Clients> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Clients).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Clients = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub async fn get_clients() -> HyprResult<Clients> {
+    clients_request().send().await
 }
 
 /// This function returns the active window
-pub async fn get_active_window() -> Result<ActiveWindow> {
-    let data = match call_hyprctl_data_cmd(DataCommands::ActiveWindow).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: ActiveWindow = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub async fn get_active_window() -> HyprResult<ActiveWindow> {
+    active_window_request().send().await
 }
 /// This function returns all layer surfaces
-pub async fn get_layers() -> Result<Layers> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Layers).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Layers = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub async fn get_layers() -> HyprResult<Layers> {
+    layers_request().send().await
 }
 
 /// This function returns all devices (mice, keyboards, tablets)
-pub async fn get_devices() -> Result<Devices> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Devices).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Devices = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub async fn get_devices() -> HyprResult<Devices> {
+    devices_request().send().await
 }
 
 /// This function returns Hyprland version details
-pub async fn get_version() -> Result<Version> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Version).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Version = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub async fn get_version() -> HyprResult<Version> {
+    version_request().send().await
+}
+
+/// This function returns all keybinds
+pub async fn get_binds() -> HyprResult<Binds> {
+    binds_request().send().await
 }
 
 /// This function returns data about a keyword
-pub async fn get_keyword(key: String) -> Result<Keyword> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Keyword(key)).await {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: OptionRaw = serde_json::from_str(&data)?;
-    let dc = deserialized.clone();
-    let keyword = Keyword {
-        option: deserialized.option,
-        value: if deserialized.int != -1 {
-            OptionValue::Int(deserialized.int)
-        } else if deserialized.float != -1.0 {
-            OptionValue::Float(deserialized.float)
-        } else if deserialized.str != *"".to_string() {
-            OptionValue::String(deserialized.str)
-        } else {
-            panic!("The option returned data that was unrecognized: {dc:#?}")
-        },
-    };
-    Ok(keyword)
+pub async fn get_keyword(key: String) -> HyprResult<Keyword> {
+    keyword_request(key).send().await
 }
 
-/// A helper function to get the current workspace
-pub async fn get_active_workspace() -> Result<Workspace> {
-    let monitor = get_active_monitor().await?;
-    let workspace_id = monitor.active_workspace.id;
-    let workspaces = get_workspaces().await?;
+/// This function returns the current value of a window property
+///
+/// The alpha override of a window is a separate property,
+/// see [`WindowPropertyKey::AlphaOverride`]
+pub async fn get_window_property(
+    window: WindowIdentifier,
+    key: WindowPropertyKey,
+) -> HyprResult<WindowProperty> {
+    window_property_request(window, key).send().await
+}
+
+impl Keyword {
+    /// This method returns data about a keyword, see [`get_keyword`]
+    pub async fn get_async(key: impl Into<String>) -> HyprResult<Self> {
+        get_keyword(key.into()).await
+    }
 
-    if let Some(work) = workspaces.iter().find(|item| item.id == workspace_id) {
-        Ok(work.clone())
-    } else {
-        panic!("No active workspace?")
+    /// This method sets a keyword to a new value
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Keyword;
+    /// Keyword::set_async("general:border_size".to_string(), 2).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_async(
+        key: impl Into<String>,
+        value: impl Into<OptionValue>,
+    ) -> HyprResult<()> {
+        dispatch(DispatchType::Keyword(key.into(), value.into().to_string())).await
+    }
+
+    /// This method sets multiple keywords in a single batched socket write
+    ///
+    /// The returned vector holds the result for each keyword, in the same order they were passed in.
+    /// A value with a `;` in it (the delimiter of the batch syntax) returns
+    /// [`HyprError::InvalidArgument`](crate::shared::HyprError::InvalidArgument) before anything is sent
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::{Keyword, OptionValue};
+    /// let results = Keyword::set_many_async(&[
+    ///     ("general:border_size".to_string(), OptionValue::Int(2)),
+    ///     ("general:layout".to_string(), OptionValue::String("master".to_string())),
+    /// ])
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_many_async(
+        keywords: &[(String, OptionValue)],
+    ) -> HyprResult<Vec<HyprResult<()>>> {
+        keyword_batch_request(keywords)?.send().await
     }
 }
 
+/// A helper function to get the current workspace
+pub async fn get_active_workspace() -> HyprResult<Workspace> {
+    let monitor = get_active_monitor().await?;
+    monitor_workspace(&monitor, get_workspaces().await?)
+}
+
 /// A helper function to get the current monitor
-pub async fn get_active_monitor() -> Result<Monitor> {
-    let monitors = get_monitors().await?;
-    if let Some(mon) = monitors.iter().find(|item| item.focused) {
-        Ok(mon.clone())
-    } else {
-        panic!("No active monitor?")
-    }
+pub async fn get_active_monitor() -> HyprResult<Monitor> {
+    focused_monitor(get_monitors().await?)
 }
 
 /// A helper function to get the current fullscreen state
-pub async fn get_fullscreen_state() -> Result<bool> {
+pub async fn get_fullscreen_state() -> HyprResult<bool> {
     let work = get_active_workspace().await?;
     Ok(work.fullscreen)
 }
+
+impl Workspace {
+    /// This method switches to the regular workspace with the lowest id that has no windows,
+    /// and returns its id
+    ///
+    /// Hyprland only keeps workspaces with windows (or that are active), so if every workspace
+    /// has windows this is the one after them, which Hyprland creates
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// let id = Workspace::first_empty_async().await?;
+    /// println!("switched to workspace {id}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn first_empty_async() -> HyprResult<WorkspaceId> {
+        let id = first_empty_workspace(&get_workspaces().await?);
+        dispatch(switch_to_workspace(id)).await?;
+        Ok(id)
+    }
+
+    /// This method switches to the next workspace with windows in the direction
+    /// (by id, on any monitor), and returns its id
+    ///
+    /// Returns `None` if there isn't one in that direction, then nothing is dispatched
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// use hyprland::dispatch::CycleDirection;
+    /// Workspace::next_occupied_async(CycleDirection::Next).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_occupied_async(direction: CycleDirection) -> HyprResult<Option<WorkspaceId>> {
+        let workspaces = get_workspaces().await?;
+        let monitors = get_monitors().await?;
+        switch_to(next_workspace(
+            monitors,
+            &workspaces,
+            &direction,
+            true,
+            false,
+        )?)
+        .await
+    }
+
+    /// This method switches to the next workspace in the direction (by id, on any monitor),
+    /// starting over from the other end after the last one, and returns its id
+    ///
+    /// With `skip_empty`, workspaces without windows are skipped.
+    /// Returns `None` if there's no other workspace, then nothing is dispatched
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// use hyprland::dispatch::CycleDirection;
+    /// Workspace::cycle_async(CycleDirection::Previous, true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn cycle_async(
+        direction: CycleDirection,
+        skip_empty: bool,
+    ) -> HyprResult<Option<WorkspaceId>> {
+        let workspaces = get_workspaces().await?;
+        let monitors = get_monitors().await?;
+        switch_to(next_workspace(
+            monitors,
+            &workspaces,
+            &direction,
+            skip_empty,
+            true,
+        )?)
+        .await
+    }
+}
+
+async fn switch_to(id: Option<WorkspaceId>) -> HyprResult<Option<WorkspaceId>> {
+    if let Some(id) = id {
+        dispatch(switch_to_workspace(id)).await?;
+    }
+    Ok(id)
+}
@@ -1,170 +1,206 @@
 use crate::data::shared::*;
-use crate::shared::*;
-use std::io;
+use crate::dispatch::{
+    dispatch_blocking, CycleDirection, DispatchType, WindowIdentifier, WindowProperty,
+};
+use crate::shared::{HyprResult, WorkspaceId};
 
-use serde_json::Result;
-
-fn call_hyprctl_data_cmd(cmd: DataCommands) -> io::Result<String> {
-    let cmd_string = match cmd {
-        DataCommands::Monitors => "monitors".to_string(),
-        DataCommands::ActiveWindow => "activewindow".to_string(),
-        DataCommands::Clients => "clients".to_string(),
-        DataCommands::Devices => "devices".to_string(),
-        DataCommands::Layers => "layers".to_string(),
-        DataCommands::Workspaces => "workspaces".to_string(),
-        DataCommands::Version => "version".to_string(),
-        DataCommands::Keyword(key) => format!("getoption {key}"),
-    };
-
-    let socket_path = get_socket_path(SocketType::Command);
-
-    write_to_socket_sync(socket_path, format!("j/{cmd_string}").as_bytes())
+/// This function returns all monitors
+pub fn get_monitors() -> HyprResult<Monitors> {
+    monitors_request().send_blocking()
 }
 
-/// This function returns all monitors
-pub fn get_monitors() -> Result<Monitors> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Monitors) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Monitors = serde_json::from_str(&data)?;
-    Ok(deserialized)
+/// This function returns all monitors, including the ones that are turned off
+pub fn get_monitors_all() -> HyprResult<Monitors> {
+    monitors_all_request().send_blocking()
 }
 
 /// This function returns all workspaces
-pub fn get_workspaces() -> Result<Workspaces> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Workspaces) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: WorkspacesRaw = serde_json::from_str(&data)?;
-    let new = deserialized
-        .iter()
-        .map(|work| Workspace::from(work.clone()));
-    Ok(new.collect())
+pub fn get_workspaces() -> HyprResult<Workspaces> {
+    workspaces_request().send_blocking()
 }
 
 /// This function returns all clients/windows
-pub fn get_clients() -> Result<Clients> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Clients) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Clients = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub fn get_clients() -> HyprResult<Clients> {
+    clients_request().send_blocking()
 }
 
 /// This function returns the active window
-pub fn get_active_window() -> Result<ActiveWindow> {
-    let data = match call_hyprctl_data_cmd(DataCommands::ActiveWindow) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: ActiveWindow = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub fn get_active_window() -> HyprResult<ActiveWindow> {
+    active_window_request().send_blocking()
 }
 /// This function returns all layer surfaces
-pub fn get_layers() -> Result<Layers> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Layers) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Layers = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub fn get_layers() -> HyprResult<Layers> {
+    layers_request().send_blocking()
 }
 
 /// This function returns all devices (mice, keyboards, tablets)
-pub fn get_devices() -> Result<Devices> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Devices) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Devices = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub fn get_devices() -> HyprResult<Devices> {
+    devices_request().send_blocking()
 }
 
 /// This function returns Hyprland version details
-pub fn get_version() -> Result<Version> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Version) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: Version = serde_json::from_str(&data)?;
-    Ok(deserialized)
+pub fn get_version() -> HyprResult<Version> {
+    version_request().send_blocking()
+}
+
+/// This function returns all keybinds
+pub fn get_binds() -> HyprResult<Binds> {
+    binds_request().send_blocking()
 }
 
 /// This function returns data about a keyword
-pub fn get_keyword(key: String) -> Result<Keyword> {
-    let data = match call_hyprctl_data_cmd(DataCommands::Keyword(key)) {
-        Ok(data) => data,
-        Err(e) => panic!(
-            "A error occured while parsing the output from the hypr socket: {:?}",
-            e
-        ),
-    };
-    let deserialized: OptionRaw = serde_json::from_str(&data)?;
-    let dc = deserialized.clone();
-    let keyword = Keyword {
-        option: deserialized.option,
-        value: if deserialized.int != -1 {
-            OptionValue::Int(deserialized.int)
-        } else if deserialized.float != -1.0 {
-            OptionValue::Float(deserialized.float)
-        } else if deserialized.str != *"".to_string() {
-            OptionValue::String(deserialized.str)
-        } else {
-            panic!("The option returned data that was unrecognized: {dc:#?}")
-        },
-    };
-    Ok(keyword)
+pub fn get_keyword(key: String) -> HyprResult<Keyword> {
+    keyword_request(key).send_blocking()
 }
 
-/// A helper function to get the current workspace
-pub fn get_active_workspace() -> Result<Workspace> {
-    let monitor = get_active_monitor()?;
-    let workspace_id = monitor.active_workspace.id;
-    let workspaces = get_workspaces()?;
+/// This function returns the current value of a window property
+///
+/// The alpha override of a window is a separate property,
+/// see [`WindowPropertyKey::AlphaOverride`]
+pub fn get_window_property(
+    window: WindowIdentifier,
+    key: WindowPropertyKey,
+) -> HyprResult<WindowProperty> {
+    window_property_request(window, key).send_blocking()
+}
+
+impl Keyword {
+    /// This method returns data about a keyword, see [`get_keyword`]
+    pub fn get(key: impl Into<String>) -> HyprResult<Self> {
+        get_keyword(key.into())
+    }
 
-    if let Some(work) = workspaces.iter().find(|item| item.id == workspace_id) {
-        Ok(work.clone())
-    } else {
-        panic!("No active workspace?")
+    /// This method sets a keyword to a new value
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::{ConfigOption, Keyword};
+    /// Keyword::set("general:border_size".to_string(), 2)?;
+    /// Keyword::set("decoration:dim_inactive".to_string(), true)?;
+    /// Keyword::set("general:layout".to_string(), "master")?;
+    /// // known options can be used instead of strings
+    /// Keyword::set(ConfigOption::DecorationRounding, 10)
+    /// # }
+    /// ```
+    pub fn set(key: impl Into<String>, value: impl Into<OptionValue>) -> HyprResult<()> {
+        dispatch_blocking(DispatchType::Keyword(key.into(), value.into().to_string()))
+    }
+
+    /// This method sets multiple keywords in a single batched socket write
+    ///
+    /// The returned vector holds the result for each keyword, in the same order they were passed in.
+    /// A value with a `;` in it (the delimiter of the batch syntax) returns
+    /// [`HyprError::InvalidArgument`](crate::shared::HyprError::InvalidArgument) before anything is sent
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::{Keyword, OptionValue};
+    /// let results = Keyword::set_many(&[
+    ///     ("general:border_size".to_string(), OptionValue::Int(2)),
+    ///     ("general:layout".to_string(), OptionValue::String("master".to_string())),
+    /// ])?;
+    /// for result in results {
+    ///     result?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_many(keywords: &[(String, OptionValue)]) -> HyprResult<Vec<HyprResult<()>>> {
+        keyword_batch_request(keywords)?.send_blocking()
     }
 }
 
+/// A helper function to get the current workspace
+pub fn get_active_workspace() -> HyprResult<Workspace> {
+    let monitor = get_active_monitor()?;
+    monitor_workspace(&monitor, get_workspaces()?)
+}
+
 /// A helper function to get the current monitor
-pub fn get_active_monitor() -> Result<Monitor> {
-    let monitors = get_monitors()?;
-    if let Some(mon) = monitors.iter().find(|item| item.focused) {
-        Ok(mon.clone())
-    } else {
-        panic!("No active monitor?")
-    }
+pub fn get_active_monitor() -> HyprResult<Monitor> {
+    focused_monitor(get_monitors()?)
 }
 
 /// A helper function to get the current fullscreen state
-pub fn get_fullscreen_state() -> Result<bool> {
+pub fn get_fullscreen_state() -> HyprResult<bool> {
     let work = get_active_workspace()?;
     Ok(work.fullscreen)
 }
+
+impl Workspace {
+    /// This method switches to the regular workspace with the lowest id that has no windows,
+    /// and returns its id
+    ///
+    /// Hyprland only keeps workspaces with windows (or that are active), so if every workspace
+    /// has windows this is the one after them, which Hyprland creates
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// let id = Workspace::first_empty()?;
+    /// println!("switched to workspace {id}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn first_empty() -> HyprResult<WorkspaceId> {
+        let id = first_empty_workspace(&get_workspaces()?);
+        dispatch_blocking(switch_to_workspace(id))?;
+        Ok(id)
+    }
+
+    /// This method switches to the next workspace with windows in the direction
+    /// (by id, on any monitor), and returns its id
+    ///
+    /// Returns `None` if there isn't one in that direction, then nothing is dispatched
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// use hyprland::dispatch::CycleDirection;
+    /// Workspace::next_occupied(CycleDirection::Next)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_occupied(direction: CycleDirection) -> HyprResult<Option<WorkspaceId>> {
+        let workspaces = get_workspaces()?;
+        switch_to(next_workspace(
+            get_monitors()?,
+            &workspaces,
+            &direction,
+            true,
+            false,
+        )?)
+    }
+
+    /// This method switches to the next workspace in the direction (by id, on any monitor),
+    /// starting over from the other end after the last one, and returns its id
+    ///
+    /// With `skip_empty`, workspaces without windows are skipped.
+    /// Returns `None` if there's no other workspace, then nothing is dispatched
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Workspace;
+    /// use hyprland::dispatch::CycleDirection;
+    /// Workspace::cycle(CycleDirection::Previous, true)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cycle(direction: CycleDirection, skip_empty: bool) -> HyprResult<Option<WorkspaceId>> {
+        let workspaces = get_workspaces()?;
+        switch_to(next_workspace(
+            get_monitors()?,
+            &workspaces,
+            &direction,
+            skip_empty,
+            true,
+        )?)
+    }
+}
+
+fn switch_to(id: Option<WorkspaceId>) -> HyprResult<Option<WorkspaceId>> {
+    if let Some(id) = id {
+        dispatch_blocking(switch_to_workspace(id))?;
+    }
+    Ok(id)
+}
@@ -5,7 +5,7 @@
 //! ## Usage
 //!
 //! here is a example of every function in use! (blocking)
-//! ```rust
+//! ```rust, no_run
 //! use hyprland::data::blocking::{
 //!     get_monitors,
 //!     get_workspaces,
@@ -15,7 +15,7 @@
 //!     get_devices
 //! };
 //!
-//! fn main() -> std::io::Result<()> {
+//! fn main() -> hyprland::shared::HyprResult<()> {
 //!     let monitors = get_monitors()?;
 //!     println!("{monitors:#?}");
 //!
@@ -42,7 +42,24 @@ mod shared;
 
 pub use crate::data::shared::*;
 
+mod options;
+
+pub use crate::data::options::*;
+
+mod arrangement;
+
+pub use crate::data::arrangement::*;
+
+mod query;
+
+pub use crate::data::query::*;
+
+mod reconcile;
+
+pub use crate::data::reconcile::*;
+
 /// This module provides async function calls
+#[cfg(feature = "async")]
 pub mod asynchronous;
 
 /// This module provides blocking function calls
@@ -0,0 +1,263 @@
+use crate::data::shared::OptionValue;
+
+/// This enum holds the types of values a config option can have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    /// A integer
+    Int,
+    /// A boolean (stored as a integer by Hyprland)
+    Bool,
+    /// A floating point
+    Float,
+    /// A string
+    String,
+    /// A color
+    Color,
+    /// A 2D vector
+    Vec2,
+    /// A gradient of colors with a optional angle
+    Gradient,
+}
+
+impl OptionType {
+    /// This method checks if a value can be used for a option of this type
+    pub fn accepts(&self, value: &OptionValue) -> bool {
+        match (self, value) {
+            (OptionType::Int, OptionValue::Int(_)) => true,
+            (OptionType::Bool, OptionValue::Int(int)) => *int == 0 || *int == 1,
+            (OptionType::Float, OptionValue::Float(_) | OptionValue::Int(_)) => true,
+            (OptionType::String, OptionValue::String(_)) => true,
+            (
+                OptionType::Color,
+                OptionValue::Color(_) | OptionValue::Int(_) | OptionValue::String(_),
+            ) => true,
+            (OptionType::Vec2, OptionValue::Vec2(_, _) | OptionValue::String(_)) => true,
+            (
+                OptionType::Gradient,
+                OptionValue::Gradient(_, _) | OptionValue::Color(_) | OptionValue::String(_),
+            ) => true,
+            _ => false,
+        }
+    }
+}
+
+macro_rules! config_options {
+    ($($variant:ident => $key:literal: $option_type:ident,)*) => {
+        /// This enum holds the config options known to the crate
+        ///
+        /// It can be used anywhere a keyword is expected,
+        /// so typos in option names are caught at compile time
+        ///
+        /// ```rust
+        /// use hyprland::data::{ConfigOption, OptionType};
+        /// let option = ConfigOption::GeneralBorderSize;
+        /// assert_eq!(option.key(), "general:border_size");
+        /// assert_eq!(option.option_type(), OptionType::Int);
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ConfigOption {
+            $(
+                #[doc = concat!("`", $key, "`")]
+                $variant,
+            )*
+        }
+
+        impl ConfigOption {
+            /// This method returns the name of the option, as used by the keyword command
+            pub fn key(&self) -> &'static str {
+                match self {
+                    $(ConfigOption::$variant => $key,)*
+                }
+            }
+
+            /// This method returns the known option with the specified name, if there is one
+            pub fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $($key => Some(ConfigOption::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// This method returns the type of value the option expects
+            pub fn option_type(&self) -> OptionType {
+                match self {
+                    $(ConfigOption::$variant => OptionType::$option_type,)*
+                }
+            }
+        }
+    };
+}
+
+impl From<ConfigOption> for String {
+    fn from(option: ConfigOption) -> Self {
+        option.key().to_string()
+    }
+}
+
+// New options go here, as `Variant => "section:option": Type,`
+config_options! {
+    GeneralBorderSize => "general:border_size": Int,
+    GeneralNoBorderOnFloating => "general:no_border_on_floating": Bool,
+    GeneralGapsIn => "general:gaps_in": Int,
+    GeneralGapsOut => "general:gaps_out": Int,
+    GeneralGapsWorkspaces => "general:gaps_workspaces": Int,
+    GeneralColInactiveBorder => "general:col.inactive_border": Gradient,
+    GeneralColActiveBorder => "general:col.active_border": Gradient,
+    GeneralColNogroupBorder => "general:col.nogroup_border": Gradient,
+    GeneralColNogroupBorderActive => "general:col.nogroup_border_active": Gradient,
+    GeneralLayout => "general:layout": String,
+    GeneralNoFocusFallback => "general:no_focus_fallback": Bool,
+    GeneralResizeOnBorder => "general:resize_on_border": Bool,
+    GeneralExtendBorderGrabArea => "general:extend_border_grab_area": Int,
+    GeneralHoverIconOnBorder => "general:hover_icon_on_border": Bool,
+    GeneralAllowTearing => "general:allow_tearing": Bool,
+    GeneralResizeCorner => "general:resize_corner": Int,
+    DecorationRounding => "decoration:rounding": Int,
+    DecorationActiveOpacity => "decoration:active_opacity": Float,
+    DecorationInactiveOpacity => "decoration:inactive_opacity": Float,
+    DecorationFullscreenOpacity => "decoration:fullscreen_opacity": Float,
+    DecorationDropShadow => "decoration:drop_shadow": Bool,
+    DecorationShadowRange => "decoration:shadow_range": Int,
+    DecorationShadowRenderPower => "decoration:shadow_render_power": Int,
+    DecorationShadowIgnoreWindow => "decoration:shadow_ignore_window": Bool,
+    DecorationColShadow => "decoration:col.shadow": Color,
+    DecorationColShadowInactive => "decoration:col.shadow_inactive": Color,
+    DecorationShadowOffset => "decoration:shadow_offset": Vec2,
+    DecorationShadowScale => "decoration:shadow_scale": Float,
+    DecorationDimInactive => "decoration:dim_inactive": Bool,
+    DecorationDimStrength => "decoration:dim_strength": Float,
+    DecorationDimSpecial => "decoration:dim_special": Float,
+    DecorationDimAround => "decoration:dim_around": Float,
+    DecorationScreenShader => "decoration:screen_shader": String,
+    DecorationBlurEnabled => "decoration:blur:enabled": Bool,
+    DecorationBlurSize => "decoration:blur:size": Int,
+    DecorationBlurPasses => "decoration:blur:passes": Int,
+    DecorationBlurIgnoreOpacity => "decoration:blur:ignore_opacity": Bool,
+    DecorationBlurNewOptimizations => "decoration:blur:new_optimizations": Bool,
+    DecorationBlurXray => "decoration:blur:xray": Bool,
+    DecorationBlurNoise => "decoration:blur:noise": Float,
+    DecorationBlurContrast => "decoration:blur:contrast": Float,
+    DecorationBlurBrightness => "decoration:blur:brightness": Float,
+    DecorationBlurVibrancy => "decoration:blur:vibrancy": Float,
+    DecorationBlurVibrancyDarkness => "decoration:blur:vibrancy_darkness": Float,
+    DecorationBlurSpecial => "decoration:blur:special": Bool,
+    DecorationBlurPopups => "decoration:blur:popups": Bool,
+    DecorationBlurPopupsIgnorealpha => "decoration:blur:popups_ignorealpha": Float,
+    AnimationsEnabled => "animations:enabled": Bool,
+    AnimationsFirstLaunchAnimation => "animations:first_launch_animation": Bool,
+    InputKbModel => "input:kb_model": String,
+    InputKbLayout => "input:kb_layout": String,
+    InputKbVariant => "input:kb_variant": String,
+    InputKbOptions => "input:kb_options": String,
+    InputKbRules => "input:kb_rules": String,
+    InputKbFile => "input:kb_file": String,
+    InputNumlockByDefault => "input:numlock_by_default": Bool,
+    InputResolveBindsBySym => "input:resolve_binds_by_sym": Bool,
+    InputRepeatRate => "input:repeat_rate": Int,
+    InputRepeatDelay => "input:repeat_delay": Int,
+    InputSensitivity => "input:sensitivity": Float,
+    InputAccelProfile => "input:accel_profile": String,
+    InputForceNoAccel => "input:force_no_accel": Bool,
+    InputLeftHanded => "input:left_handed": Bool,
+    InputScrollPoints => "input:scroll_points": String,
+    InputScrollMethod => "input:scroll_method": String,
+    InputScrollButton => "input:scroll_button": Int,
+    InputScrollButtonLock => "input:scroll_button_lock": Bool,
+    InputScrollFactor => "input:scroll_factor": Float,
+    InputNaturalScroll => "input:natural_scroll": Bool,
+    InputFollowMouse => "input:follow_mouse": Int,
+    InputMouseRefocus => "input:mouse_refocus": Bool,
+    InputFloatSwitchOverrideFocus => "input:float_switch_override_focus": Int,
+    InputSpecialFallthrough => "input:special_fallthrough": Bool,
+    InputOffWindowAxisEvents => "input:off_window_axis_events": Int,
+    InputTouchpadDisableWhileTyping => "input:touchpad:disable_while_typing": Bool,
+    InputTouchpadNaturalScroll => "input:touchpad:natural_scroll": Bool,
+    InputTouchpadScrollFactor => "input:touchpad:scroll_factor": Float,
+    InputTouchpadMiddleButtonEmulation => "input:touchpad:middle_button_emulation": Bool,
+    InputTouchpadTapButtonMap => "input:touchpad:tap_button_map": String,
+    InputTouchpadClickfingerBehavior => "input:touchpad:clickfinger_behavior": Bool,
+    InputTouchpadTapToClick => "input:touchpad:tap-to-click": Bool,
+    InputTouchpadDragLock => "input:touchpad:drag_lock": Bool,
+    InputTouchpadTapAndDrag => "input:touchpad:tap-and-drag": Bool,
+    GesturesWorkspaceSwipe => "gestures:workspace_swipe": Bool,
+    GesturesWorkspaceSwipeFingers => "gestures:workspace_swipe_fingers": Int,
+    GesturesWorkspaceSwipeDistance => "gestures:workspace_swipe_distance": Int,
+    GesturesWorkspaceSwipeInvert => "gestures:workspace_swipe_invert": Bool,
+    GesturesWorkspaceSwipeMinSpeedToForce => "gestures:workspace_swipe_min_speed_to_force": Int,
+    GesturesWorkspaceSwipeCancelRatio => "gestures:workspace_swipe_cancel_ratio": Float,
+    GesturesWorkspaceSwipeCreateNew => "gestures:workspace_swipe_create_new": Bool,
+    GesturesWorkspaceSwipeForever => "gestures:workspace_swipe_forever": Bool,
+    GroupInsertAfterCurrent => "group:insert_after_current": Bool,
+    GroupFocusRemovedWindow => "group:focus_removed_window": Bool,
+    GroupColBorderActive => "group:col.border_active": Gradient,
+    GroupColBorderInactive => "group:col.border_inactive": Gradient,
+    GroupColBorderLockedActive => "group:col.border_locked_active": Gradient,
+    GroupColBorderLockedInactive => "group:col.border_locked_inactive": Gradient,
+    MiscDisableHyprlandLogo => "misc:disable_hyprland_logo": Bool,
+    MiscDisableSplashRendering => "misc:disable_splash_rendering": Bool,
+    MiscColSplash => "misc:col.splash": Color,
+    MiscFontFamily => "misc:font_family": String,
+    MiscForceDefaultWallpaper => "misc:force_default_wallpaper": Int,
+    MiscVfr => "misc:vfr": Bool,
+    MiscVrr => "misc:vrr": Int,
+    MiscMouseMoveEnablesDpms => "misc:mouse_move_enables_dpms": Bool,
+    MiscKeyPressEnablesDpms => "misc:key_press_enables_dpms": Bool,
+    MiscAlwaysFollowOnDnd => "misc:always_follow_on_dnd": Bool,
+    MiscLayersHogKeyboardFocus => "misc:layers_hog_keyboard_focus": Bool,
+    MiscAnimateManualResizes => "misc:animate_manual_resizes": Bool,
+    MiscAnimateMouseWindowdragging => "misc:animate_mouse_windowdragging": Bool,
+    MiscDisableAutoreload => "misc:disable_autoreload": Bool,
+    MiscEnableSwallow => "misc:enable_swallow": Bool,
+    MiscSwallowRegex => "misc:swallow_regex": String,
+    MiscSwallowExceptionRegex => "misc:swallow_exception_regex": String,
+    MiscFocusOnActivate => "misc:focus_on_activate": Bool,
+    MiscMouseMoveFocusesMonitor => "misc:mouse_move_focuses_monitor": Bool,
+    MiscAllowSessionLockRestore => "misc:allow_session_lock_restore": Bool,
+    MiscBackgroundColor => "misc:background_color": Color,
+    MiscCloseSpecialOnEmpty => "misc:close_special_on_empty": Bool,
+    MiscNewWindowTakesOverFullscreen => "misc:new_window_takes_over_fullscreen": Int,
+    MiscInitialWorkspaceTracking => "misc:initial_workspace_tracking": Int,
+    BindsPassMouseWhenBound => "binds:pass_mouse_when_bound": Bool,
+    BindsScrollEventDelay => "binds:scroll_event_delay": Int,
+    BindsWorkspaceBackAndForth => "binds:workspace_back_and_forth": Bool,
+    BindsAllowWorkspaceCycles => "binds:allow_workspace_cycles": Bool,
+    BindsWorkspaceCenterOn => "binds:workspace_center_on": Int,
+    BindsFocusPreferredMethod => "binds:focus_preferred_method": Int,
+    BindsIgnoreGroupLock => "binds:ignore_group_lock": Bool,
+    BindsMovefocusCyclesFullscreen => "binds:movefocus_cycles_fullscreen": Bool,
+    XwaylandUseNearestNeighbor => "xwayland:use_nearest_neighbor": Bool,
+    XwaylandForceZeroScaling => "xwayland:force_zero_scaling": Bool,
+    OpenglNvidiaAntiFlicker => "opengl:nvidia_anti_flicker": Bool,
+    CursorNoHardwareCursors => "cursor:no_hardware_cursors": Bool,
+    CursorHideOnKeyPress => "cursor:hide_on_key_press": Bool,
+    CursorInactiveTimeout => "cursor:inactive_timeout": Int,
+    CursorNoWarps => "cursor:no_warps": Bool,
+    DebugOverlay => "debug:overlay": Bool,
+    DebugDamageBlink => "debug:damage_blink": Bool,
+    DebugDisableLogs => "debug:disable_logs": Bool,
+    DebugDisableTime => "debug:disable_time": Bool,
+    DebugDamageTracking => "debug:damage_tracking": Int,
+    DebugEnableStdoutLogs => "debug:enable_stdout_logs": Bool,
+    DwindlePseudotile => "dwindle:pseudotile": Bool,
+    DwindleForceSplit => "dwindle:force_split": Int,
+    DwindlePreserveSplit => "dwindle:preserve_split": Bool,
+    DwindleSmartSplit => "dwindle:smart_split": Bool,
+    DwindleSmartResizing => "dwindle:smart_resizing": Bool,
+    DwindlePermanentDirectionOverride => "dwindle:permanent_direction_override": Bool,
+    DwindleSpecialScaleFactor => "dwindle:special_scale_factor": Float,
+    DwindleSplitWidthMultiplier => "dwindle:split_width_multiplier": Float,
+    DwindleNoGapsWhenOnly => "dwindle:no_gaps_when_only": Int,
+    DwindleUseActiveForSplits => "dwindle:use_active_for_splits": Bool,
+    DwindleDefaultSplitRatio => "dwindle:default_split_ratio": Float,
+    MasterAllowSmallSplit => "master:allow_small_split": Bool,
+    MasterSpecialScaleFactor => "master:special_scale_factor": Float,
+    MasterMfact => "master:mfact": Float,
+    MasterNewStatus => "master:new_status": String,
+    MasterNewOnTop => "master:new_on_top": Bool,
+    MasterNoGapsWhenOnly => "master:no_gaps_when_only": Int,
+    MasterOrientation => "master:orientation": String,
+    MasterInheritFullscreen => "master:inherit_fullscreen": Bool,
+    MasterAlwaysCenterMaster => "master:always_center_master": Bool,
+    MasterSmartResizing => "master:smart_resizing": Bool,
+    MasterDropAtCursor => "master:drop_at_cursor": Bool,
+}
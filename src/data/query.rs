@@ -0,0 +1,235 @@
+use crate::data::shared::*;
+use crate::shared::*;
+
+type Predicate = Box<dyn Fn(&Client) -> bool + Send + Sync>;
+
+/// This struct filters the clients/windows, it's created with [`Client::query`]
+///
+/// Every condition added has to match, the clients are fetched when the query is run
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::data::Client;
+/// let tiled_kitty = Client::query().class("kitty").on_workspace(3).floating(false).first()?;
+/// println!("{tiled_kitty:#?}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ClientQuery {
+    predicates: Vec<Predicate>,
+    error: Option<HyprError>,
+}
+
+impl Client {
+    /// This method creates a query that matches every client, see [`ClientQuery`]
+    pub fn query() -> ClientQuery {
+        ClientQuery::default()
+    }
+}
+
+impl ClientQuery {
+    /// This method adds a condition the clients have to match
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::data::Client;
+    /// let large = Client::query().filter(|client| client.size.0 > 1000).all()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn filter(mut self, predicate: impl Fn(&Client) -> bool + Send + Sync + 'static) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// This method matches clients with exactly the class
+    pub fn class(self, class: impl Into<String>) -> Self {
+        let class = class.into();
+        self.filter(move |client| client.class == class)
+    }
+
+    /// This method matches clients with exactly the title
+    pub fn title(self, title: impl Into<String>) -> Self {
+        let title = title.into();
+        self.filter(move |client| client.title == title)
+    }
+
+    /// This method matches clients of which the title contains `text`
+    pub fn title_contains(self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        self.filter(move |client| client.title.contains(&text))
+    }
+
+    /// This method matches clients of which the class matches the regular expression
+    ///
+    /// A invalid expression is returned as [`HyprError::Regex`] when the query is run
+    #[cfg(feature = "regex")]
+    pub fn class_matches(self, regex: &str) -> Self {
+        self.regex(regex, |client| &client.class)
+    }
+
+    /// This method matches clients of which the title matches the regular expression
+    ///
+    /// A invalid expression is returned as [`HyprError::Regex`] when the query is run
+    #[cfg(feature = "regex")]
+    pub fn title_matches(self, regex: &str) -> Self {
+        self.regex(regex, |client| &client.title)
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex(mut self, regex: &str, field: fn(&Client) -> &String) -> Self {
+        match regex::Regex::new(regex) {
+            Ok(regex) => self.filter(move |client| regex.is_match(field(client))),
+            Err(error) => {
+                self.error.get_or_insert(error.into());
+                self
+            }
+        }
+    }
+
+    /// This method matches the client with the process id
+    pub fn pid(self, pid: u32) -> Self {
+        self.filter(move |client| client.pid == pid)
+    }
+
+    /// This method matches the client with the address
+    pub fn address(self, address: Address) -> Self {
+        self.filter(move |client| client.address == address)
+    }
+
+    /// This method matches clients on the regular workspace with the id
+    pub fn on_workspace(self, id: WorkspaceId) -> Self {
+        self.filter(move |client| client.workspace.id == WorkspaceType::Regular(id))
+    }
+
+    /// This method matches clients on the workspace with the name
+    /// (like `special:term` for a named special workspace)
+    pub fn on_workspace_named(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.filter(move |client| client.workspace.name == name)
+    }
+
+    /// This method matches clients on the monitor with the id
+    pub fn on_monitor(self, id: u8) -> Self {
+        self.filter(move |client| client.monitor == id)
+    }
+
+    /// This method matches floating (`true`) or tiled (`false`) clients
+    pub fn floating(self, floating: bool) -> Self {
+        self.filter(move |client| client.floating == floating)
+    }
+
+    /// This method matches clients running under XWayland (`true`) or not (`false`)
+    pub fn xwayland(self, xwayland: bool) -> Self {
+        self.filter(move |client| client.xwayland == xwayland)
+    }
+
+    /// This method matches clients with the tag
+    pub fn tagged(self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        self.filter(move |client| client.tags.contains(&tag))
+    }
+
+    /// This method returns if the client matches every condition
+    ///
+    /// A query with a invalid condition, like a invalid regular expression, matches no client,
+    /// use [`apply`](Self::apply) to get the error
+    pub fn matches(&self, client: &Client) -> bool {
+        self.error.is_none() && self.predicates.iter().all(|predicate| predicate(client))
+    }
+
+    /// This method returns the clients that match, out of `clients`
+    ///
+    /// Returns the error of a invalid condition, like a invalid regular expression
+    pub fn apply(self, clients: Clients) -> HyprResult<Clients> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(clients
+            .into_iter()
+            .filter(|client| self.matches(client))
+            .collect())
+    }
+
+    /// This method fetches the clients and returns the ones that match (blocking)
+    pub fn all(self) -> HyprResult<Clients> {
+        self.apply(crate::data::blocking::get_clients()?)
+    }
+
+    /// This method fetches the clients and returns the first one that matches (blocking)
+    pub fn first(self) -> HyprResult<Option<Client>> {
+        Ok(self.all()?.into_iter().next())
+    }
+
+    /// This method fetches the clients and returns the ones that match (async)
+    #[cfg(feature = "async")]
+    pub async fn all_async(self) -> HyprResult<Clients> {
+        self.apply(crate::data::asynchronous::get_clients().await?)
+    }
+
+    /// This method fetches the clients and returns the first one that matches (async)
+    #[cfg(feature = "async")]
+    pub async fn first_async(self) -> HyprResult<Option<Client>> {
+        Ok(self.all_async().await?.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `hyprctl clients -j`, two kitty windows on workspace 1 and firefox on workspace 2
+    const CLIENTS: &str = r#"[
+        {"address": "0x1", "at": [0, 0], "size": [1280, 1440], "workspace": {"id": 1, "name": "1"},
+         "floating": false, "monitor": 0, "class": "kitty", "title": "nvim", "pid": 100,
+         "xwayland": false, "tags": ["term"]},
+        {"address": "0x2", "at": [1280, 0], "size": [1280, 1440], "workspace": {"id": 1, "name": "1"},
+         "floating": true, "monitor": 0, "class": "kitty", "title": "htop", "pid": 101,
+         "xwayland": false},
+        {"address": "0x3", "at": [0, 0], "size": [2560, 1440], "workspace": {"id": 2, "name": "2"},
+         "floating": false, "monitor": 1, "class": "firefox", "title": "GitHub — Mozilla Firefox",
+         "pid": 102, "xwayland": true}
+    ]"#;
+
+    /// Returns the process ids of the clients the query matches
+    fn pids(query: ClientQuery) -> HyprResult<Vec<u32>> {
+        let clients: Clients = serde_json::from_str(CLIENTS)?;
+        Ok(query
+            .apply(clients)?
+            .into_iter()
+            .map(|client| client.pid)
+            .collect())
+    }
+
+    #[test]
+    fn every_condition_has_to_match() -> HyprResult<()> {
+        assert_eq!(pids(Client::query())?, [100, 101, 102]);
+        assert_eq!(pids(Client::query().class("kitty"))?, [100, 101]);
+        assert_eq!(pids(Client::query().class("kitty").floating(false))?, [100]);
+        assert_eq!(pids(Client::query().on_workspace(2).xwayland(true))?, [102]);
+        assert_eq!(
+            pids(Client::query().on_workspace_named("1").title("htop"))?,
+            [101]
+        );
+        assert_eq!(pids(Client::query().address(Address::new("2")))?, [101]);
+        assert_eq!(pids(Client::query().tagged("term"))?, [100]);
+        assert!(pids(Client::query().title_contains("Firefox").on_monitor(0))?.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn invalid_regular_expressions_are_returned() -> HyprResult<()> {
+        assert_eq!(
+            pids(Client::query().title_matches("^(nvim|htop)$"))?,
+            [100, 101]
+        );
+        assert_eq!(pids(Client::query().class_matches("^fire"))?, [102]);
+        let invalid = Client::query().class_matches("(kitty");
+        let clients: Clients = serde_json::from_str(CLIENTS)?;
+        assert!(!clients.iter().any(|client| invalid.matches(client)));
+        assert!(matches!(pids(invalid), Err(HyprError::Regex(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use crate::data::shared::*;
+use crate::shared::{Color, HyprResult};
+
+/// This struct holds the values options should have, and sets the ones that don't have them
+///
+/// The current values are fetched in a single batch, and only the options with a different value
+/// are set, in another one, so reconciling the same options again does nothing
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::data::{ConfigOption, Reconciler};
+/// let changed = Reconciler::new()
+///     .set(ConfigOption::GeneralGapsIn, 5)
+///     .set(ConfigOption::DecorationRounding, 10)
+///     .set("general:layout", "dwindle")
+///     .reconcile()?;
+/// println!("changed {changed:?}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Reconciler {
+    options: Vec<(String, OptionValue)>,
+}
+
+impl Reconciler {
+    /// This method creates a reconciler without options
+    pub fn new() -> Self {
+        Reconciler::default()
+    }
+
+    /// This method adds the value a option should have, replacing the one added before for it
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<OptionValue>) -> Self {
+        let (key, value) = (key.into(), value.into());
+        match self.options.iter_mut().find(|(option, _)| *option == key) {
+            Some((_, current)) => *current = value,
+            None => self.options.push((key, value)),
+        }
+        self
+    }
+
+    /// This method returns the options that don't have the value they should have,
+    /// out of the current values in `live`
+    ///
+    /// Options that aren't in `live` are returned too
+    pub fn changes(&self, live: &[Keyword]) -> Vec<(String, OptionValue)> {
+        self.options
+            .iter()
+            .filter(|(key, value)| {
+                !live
+                    .iter()
+                    .any(|keyword| keyword.option == *key && same_value(&keyword.value, value))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// This method fetches the current values and sets the options that changed,
+    /// returning their names (blocking)
+    pub fn reconcile(&self) -> HyprResult<Vec<String>> {
+        if self.options.is_empty() {
+            return Ok(vec![]);
+        }
+        let live = keywords_request(&self.keys())?.send_blocking()?;
+        let changes = self.changes(&live);
+        if !changes.is_empty() {
+            Keyword::set_many(&changes)?
+                .into_iter()
+                .collect::<HyprResult<()>>()?;
+        }
+        Ok(changes.into_iter().map(|(key, _)| key).collect())
+    }
+
+    /// This method fetches the current values and sets the options that changed,
+    /// returning their names (async)
+    #[cfg(feature = "async")]
+    pub async fn reconcile_async(&self) -> HyprResult<Vec<String>> {
+        if self.options.is_empty() {
+            return Ok(vec![]);
+        }
+        let live = keywords_request(&self.keys())?.send().await?;
+        let changes = self.changes(&live);
+        if !changes.is_empty() {
+            Keyword::set_many_async(&changes)
+                .await?
+                .into_iter()
+                .collect::<HyprResult<()>>()?;
+        }
+        Ok(changes.into_iter().map(|(key, _)| key).collect())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.options.iter().map(|(key, _)| key.as_str()).collect()
+    }
+}
+
+/// Returns if the values are the same, Hyprland stores floats as 32-bit
+/// and bools and colors as integers
+fn same_value(live: &OptionValue, value: &OptionValue) -> bool {
+    // compared the way Hyprland stores them, a fixed epsilon is too small for big values
+    let same_float = |a: f64, b: f64| a as f32 == b as f32;
+    match (live, value) {
+        (OptionValue::Float(live), OptionValue::Float(value)) => same_float(*live, *value),
+        (OptionValue::Float(live), OptionValue::Int(value)) => same_float(*live, *value as f64),
+        (OptionValue::Vec2(x, y), OptionValue::Vec2(value_x, value_y)) => {
+            same_float(*x, *value_x) && same_float(*y, *value_y)
+        }
+        (OptionValue::Color(live), OptionValue::Int(value)) => {
+            u32::try_from(*value).is_ok_and(|argb| *live == Color::from_argb(argb))
+        }
+        (live, value) => live.to_string() == value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floats_are_compared_as_f32() {
+        // what Hyprland returns for 12345.678 after storing it as a f32
+        let live = OptionValue::Float(f64::from(12345.678_f32));
+        assert!(same_value(&live, &OptionValue::Float(12345.678)));
+        assert!(!same_value(&OptionValue::Float(0.5), &OptionValue::Int(0)));
+        assert!(same_value(&OptionValue::Float(1.0), &OptionValue::Int(1)));
+        assert!(!same_value(&live, &OptionValue::Float(12345.68)));
+    }
+
+    fn keyword(option: &str, value: impl Into<OptionValue>) -> Keyword {
+        Keyword {
+            option: option.to_string(),
+            value: value.into(),
+        }
+    }
+
+    fn keys(changes: Vec<(String, OptionValue)>) -> Vec<String> {
+        changes.into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn changes_are_the_options_with_other_values() {
+        let reconciler = Reconciler::new()
+            .set("general:gaps_in", 5)
+            .set("general:layout", "dwindle")
+            .set("decoration:blur:enabled", true)
+            .set("general:col.active_border", 0xffff0000_i64)
+            .set("general:gaps_out", 10)
+            .set("general:gaps_out", 20);
+        let live = [
+            keyword("general:gaps_in", 5),
+            keyword("general:layout", "master"),
+            keyword("decoration:blur:enabled", 1),
+            keyword("general:col.active_border", Color::new(255, 0, 0, 255)),
+            keyword("general:gaps_out", 10),
+        ];
+        assert_eq!(
+            keys(reconciler.changes(&live)),
+            ["general:layout", "general:gaps_out"]
+        );
+        // the value that was set last is the one that's set
+        assert!(matches!(
+            reconciler.changes(&live).last(),
+            Some((_, OptionValue::Int(20)))
+        ));
+    }
+
+    #[test]
+    fn options_that_werent_fetched_are_changes() {
+        let reconciler = Reconciler::new()
+            .set("general:gaps_in", 5)
+            .set("input:sensitivity", 0.5);
+        assert_eq!(
+            keys(reconciler.changes(&[])),
+            ["general:gaps_in", "input:sensitivity"]
+        );
+        let live = [
+            keyword("input:sensitivity", 0.5),
+            keyword("general:gaps_in", 5),
+        ];
+        assert!(reconciler.changes(&live).is_empty());
+        assert!(Reconciler::new().changes(&live).is_empty());
+    }
+
+    #[test]
+    fn values_are_compared_the_way_hyprland_stores_them() {
+        let live = OptionValue::Color(Color::from_argb(0xff33ccff));
+        assert!(same_value(&live, &OptionValue::Int(0xff33ccff)));
+        assert!(!same_value(&live, &OptionValue::Int(0x33ccff)));
+        assert!(!same_value(&live, &OptionValue::Int(-1)));
+        assert!(same_value(
+            &OptionValue::Vec2(f64::from(0.1_f32), 2.0),
+            &OptionValue::Vec2(0.1, 2.0)
+        ));
+        assert!(!same_value(
+            &OptionValue::Vec2(0.1, 2.0),
+            &OptionValue::Vec2(2.0, 0.1)
+        ));
+        assert!(same_value(&OptionValue::Int(1), &OptionValue::from(true)));
+        assert!(!same_value(&OptionValue::Int(1), &OptionValue::from("1.0")));
+    }
+}
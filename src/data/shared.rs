@@ -1,31 +1,286 @@
+use crate::bind::Modifier;
+use crate::data::options::{ConfigOption, OptionType};
+use crate::dispatch::{
+    CycleDirection, DispatchType, WindowIdentifier, WindowProperty, WorkspaceIdentifierWithSpecial,
+};
+use crate::request::Request;
 use crate::shared::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::collections::HashMap;
+use std::fmt;
 
 /// This pub(crate) enum holds every socket command that returns data
-#[derive(Debug)]
 pub(crate) enum DataCommands {
     Monitors,
+    MonitorsAll,
     Workspaces,
     Clients,
     ActiveWindow,
     Layers,
     Devices,
     Version,
+    Binds,
     Keyword(String),
+    WindowProperty(WindowIdentifier, WindowPropertyKey),
+}
+
+impl fmt::Display for DataCommands {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataCommands::Monitors => f.write_str("monitors"),
+            DataCommands::MonitorsAll => f.write_str("monitors all"),
+            DataCommands::ActiveWindow => f.write_str("activewindow"),
+            DataCommands::Clients => f.write_str("clients"),
+            DataCommands::Devices => f.write_str("devices"),
+            DataCommands::Layers => f.write_str("layers"),
+            DataCommands::Workspaces => f.write_str("workspaces"),
+            DataCommands::Version => f.write_str("version"),
+            DataCommands::Binds => f.write_str("binds"),
+            DataCommands::Keyword(key) => write!(f, "getoption {key}"),
+            DataCommands::WindowProperty(win, prop) => write!(f, "getprop {win} {}", prop.as_str()),
+        }
+    }
+}
+
+/// This pub(crate) function creates the request for a data command,
+/// `parse` is called with the json Hyprland responded with
+pub(crate) fn data_request<T>(
+    cmd: DataCommands,
+    parse: impl FnOnce(&str) -> HyprResult<T> + Send + 'static,
+) -> Request<HyprResult<T>> {
+    Request::new(format!("j/{cmd}"), move |output| {
+        let parsed = parse(&output?);
+        if let Err(error) = &parsed {
+            log!(debug, "couldn't parse the response to {cmd}: {error}");
+        }
+        parsed
+    })
+}
+
+fn json_request<T: DeserializeOwned>(cmd: DataCommands) -> Request<HyprResult<T>> {
+    data_request(cmd, |data| Ok(serde_json::from_str(data)?))
+}
+
+pub(crate) fn monitors_request() -> Request<HyprResult<Monitors>> {
+    json_request(DataCommands::Monitors)
+}
+
+pub(crate) fn monitors_all_request() -> Request<HyprResult<Monitors>> {
+    json_request(DataCommands::MonitorsAll)
+}
+
+pub(crate) fn workspaces_request() -> Request<HyprResult<Workspaces>> {
+    data_request(DataCommands::Workspaces, |data| {
+        let deserialized: WorkspacesRaw = serde_json::from_str(data)?;
+        Ok(deserialized.into_iter().map(Workspace::from).collect())
+    })
+}
+
+pub(crate) fn clients_request() -> Request<HyprResult<Clients>> {
+    json_request(DataCommands::Clients)
+}
+
+pub(crate) fn active_window_request() -> Request<HyprResult<ActiveWindow>> {
+    json_request(DataCommands::ActiveWindow)
+}
+
+pub(crate) fn layers_request() -> Request<HyprResult<Layers>> {
+    json_request(DataCommands::Layers)
+}
+
+pub(crate) fn devices_request() -> Request<HyprResult<Devices>> {
+    json_request(DataCommands::Devices)
+}
+
+pub(crate) fn version_request() -> Request<HyprResult<Version>> {
+    json_request(DataCommands::Version)
+}
+
+pub(crate) fn binds_request() -> Request<HyprResult<Binds>> {
+    json_request(DataCommands::Binds)
+}
+
+pub(crate) fn keyword_request(key: String) -> Request<HyprResult<Keyword>> {
+    data_request(DataCommands::Keyword(key), parse_keyword)
+}
+
+/// This pub(crate) function creates the request that gets the keywords in a single batch
+pub(crate) fn keywords_request(keys: &[&str]) -> HyprResult<Request<HyprResult<Vec<Keyword>>>> {
+    let cmds = keys
+        .iter()
+        .map(|key| format!("j/{}", DataCommands::Keyword(key.to_string())));
+    let count = keys.len();
+    Ok(Request::new(batch_command(cmds)?, move |response| {
+        split_batch_response(&response?, count)
+            .into_iter()
+            .map(|data| parse_keyword(&data?))
+            .collect()
+    }))
+}
+
+fn parse_keyword(data: &str) -> HyprResult<Keyword> {
+    let deserialized: OptionRaw = serde_json::from_str(data)?;
+    Keyword::try_from(deserialized)
+}
+
+pub(crate) fn window_property_request(
+    window: WindowIdentifier,
+    key: WindowPropertyKey,
+) -> Request<HyprResult<WindowProperty>> {
+    let cmd = DataCommands::WindowProperty(window, key);
+    data_request(cmd, move |data| Ok(parse_window_property(key, data)?))
+}
+
+pub(crate) fn keyword_batch_request(
+    keywords: &[(String, OptionValue)],
+) -> HyprResult<Request<HyprResult<Vec<HyprResult<()>>>>> {
+    let cmds = keywords
+        .iter()
+        .map(|(key, value)| KeywordCommand(key, value));
+    let count = keywords.len();
+    Ok(Request::new(batch_command(cmds)?, move |response| {
+        Ok(batch_results(&response?, count))
+    }))
+}
+
+/// Formats setting a keyword, without cloning it into a `DispatchType`
+struct KeywordCommand<'a>(&'a str, &'a OptionValue);
+
+impl fmt::Display for KeywordCommand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "keyword {} {}", self.0, self.1)
+    }
+}
+
+/// This pub(crate) function picks the focused monitor
+pub(crate) fn focused_monitor(monitors: Monitors) -> HyprResult<Monitor> {
+    match monitors.into_iter().find(|item| item.focused) {
+        Some(mon) => Ok(mon),
+        None => Err(HyprError::UnexpectedResponse(
+            "no monitor is focused".to_string(),
+        )),
+    }
+}
+
+/// This pub(crate) function picks the workspace that is active on the monitor
+pub(crate) fn monitor_workspace(
+    monitor: &Monitor,
+    workspaces: Workspaces,
+) -> HyprResult<Workspace> {
+    let workspace_id = &monitor.active_workspace.id;
+    match workspaces.into_iter().find(|item| &item.id == workspace_id) {
+        Some(work) => Ok(work),
+        None => Err(HyprError::UnexpectedResponse(format!(
+            "the active workspace of {} ({workspace_id:?}) wasn't found",
+            monitor.name
+        ))),
+    }
+}
+
+/// This pub(crate) function picks the client with the address,
+/// a address no client has is a invalid argument
+pub(crate) fn find_client<'a>(clients: &'a Clients, address: &Address) -> HyprResult<&'a Client> {
+    clients
+        .iter()
+        .find(|client| client.address == *address)
+        .ok_or_else(|| {
+            HyprError::InvalidArgument(format!("there's no window with the address {address}"))
+        })
+}
+
+/// This pub(crate) function picks the regular workspace with the lowest id that has no windows,
+/// which doesn't have to exist yet
+pub(crate) fn first_empty_workspace(workspaces: &Workspaces) -> WorkspaceId {
+    let mut occupied: Vec<WorkspaceId> = workspaces
+        .iter()
+        .filter(|work| work.windows > 0)
+        .filter_map(|work| match work.id {
+            WorkspaceType::Regular(id) if id > 0 => Some(id),
+            _ => None,
+        })
+        .collect();
+    occupied.sort_unstable();
+    occupied.dedup();
+    let mut first = 1;
+    for id in occupied {
+        if id != first {
+            break;
+        }
+        first += 1;
+    }
+    first
+}
+
+/// This pub(crate) function picks the regular workspace that comes after the active one
+/// in the direction, `wrap` starts over from the other end when there isn't one
+pub(crate) fn next_workspace(
+    monitors: Monitors,
+    workspaces: &Workspaces,
+    direction: &CycleDirection,
+    skip_empty: bool,
+    wrap: bool,
+) -> HyprResult<Option<WorkspaceId>> {
+    let current = match focused_monitor(monitors)?.active_workspace.id {
+        WorkspaceType::Regular(id) => Some(id),
+        _ => None,
+    };
+    let mut ids: Vec<WorkspaceId> = workspaces
+        .iter()
+        .filter(|work| !skip_empty || work.windows > 0)
+        .filter_map(|work| match work.id {
+            WorkspaceType::Regular(id) => Some(id),
+            _ => None,
+        })
+        .collect();
+    ids.sort_unstable();
+    if let CycleDirection::Previous = direction {
+        ids.reverse();
+    }
+    let comes_after = |id: WorkspaceId| match (current, direction) {
+        (Some(current), CycleDirection::Next) => id > current,
+        (Some(current), CycleDirection::Previous) => id < current,
+        (None, _) => true,
+    };
+    let next = ids.iter().copied().find(|&id| comes_after(id));
+    Ok(match next {
+        None if wrap => ids.into_iter().find(|&id| Some(id) != current),
+        next => next,
+    })
+}
+
+/// This pub(crate) function returns the dispatch that switches to the workspace
+pub(crate) fn switch_to_workspace(id: WorkspaceId) -> DispatchType {
+    DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Id(id))
 }
 
 /// This struct holds a basic identifier for a workspace often used in other structs
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "WorkspaceBasicRaw")]
 pub struct WorkspaceBasic {
     /// The workspace Id
-    #[serde(deserialize_with = "de_work_id")]
     pub id: WorkspaceType,
     /// The workspace's name
     pub name: String,
 }
 
+/// The workspace identifier as Hyprland sends it, named workspaces are only told apart by name
+#[derive(Deserialize)]
+struct WorkspaceBasicRaw {
+    id: WorkspaceId,
+    name: String,
+}
+
+impl From<WorkspaceBasicRaw> for WorkspaceBasic {
+    fn from(raw: WorkspaceBasicRaw) -> Self {
+        WorkspaceBasic {
+            id: WorkspaceType::from_id_and_name(raw.id, &raw.name),
+            name: raw.name,
+        }
+    }
+}
+
 /// This enum provides the different monitor transforms
 #[derive(Serialize_repr, Deserialize_repr, Debug, Clone)]
 #[repr(u8)]
@@ -59,6 +314,18 @@ pub struct Monitor {
     pub width: u16,
     /// The monitor height (in pixels)
     pub height: u16,
+    /// The monitor's description, its make, model and serial (like `Dell Inc. DELL U2720Q 1234ABC`)
+    #[serde(default)]
+    pub description: String,
+    /// The monitor's manufacturer
+    #[serde(default)]
+    pub make: String,
+    /// The monitor's model
+    #[serde(default)]
+    pub model: String,
+    /// The monitor's serial number, empty if it doesn't report one
+    #[serde(default)]
+    pub serial: String,
     /// The monitor's refresh rate (in hertz)
     #[serde(rename = "refreshRate")]
     pub refresh_rate: f32,
@@ -77,6 +344,9 @@ pub struct Monitor {
     pub transform: Transforms,
     /// a string that identifies if the display is active
     pub focused: bool,
+    /// If the monitor is connected but turned off, these are only returned by `get_monitors_all`
+    #[serde(default)]
+    pub disabled: bool,
 }
 
 /// This type provides a vector of monitors
@@ -85,7 +355,7 @@ pub type Monitors = Vec<Monitor>;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct WorkspaceRaw {
     /// The workspace Id
-    pub id: i8,
+    pub id: WorkspaceId,
     /// The workspace's name
     pub name: String,
     /// The monitor the workspace is on
@@ -98,7 +368,7 @@ pub(crate) struct WorkspaceRaw {
 }
 
 /// This struct holds information for a workspace
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Workspace {
     /// The workspace Id
     pub id: WorkspaceType,
@@ -116,14 +386,7 @@ pub struct Workspace {
 impl From<WorkspaceRaw> for Workspace {
     fn from(raw: WorkspaceRaw) -> Self {
         Workspace {
-            id: match raw.id {
-                -99 => WorkspaceType::Special,
-                0.. => WorkspaceType::Regular(match raw.id.try_into() {
-                    Ok(num) => num,
-                    Err(e) => panic!("Issue with parsing id (i8) as u8: {e}"),
-                }),
-                _ => panic!("Unrecognised id"),
-            },
+            id: WorkspaceType::from_id_and_name(raw.id, &raw.name),
             name: raw.name,
             monitor: raw.monitor,
             windows: raw.windows,
@@ -139,7 +402,7 @@ pub type Workspaces = Vec<Workspace>;
 pub(crate) type WorkspacesRaw = Vec<WorkspaceRaw>;
 
 /// This struct holds information for a client/window
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Client {
     /// The client's [`Address`][crate::shared::Address]
     pub address: Address,
@@ -161,6 +424,57 @@ pub struct Client {
     pub pid: u32,
     /// Is this window running under XWayland?
     pub xwayland: bool,
+    /// The tags applied to the window
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The windows in the group the window is in, in the order of their tabs,
+    /// empty if it isn't in a group
+    #[serde(default)]
+    pub grouped: Vec<Address>,
+    /// The fullscreen mode of the window
+    #[serde(default)]
+    pub fullscreen: FullscreenMode,
+    /// The fullscreen mode the window is told it's in,
+    /// it's only different from [`fullscreen`](Self::fullscreen) for fake fullscreen
+    #[serde(default, rename = "fullscreenClient")]
+    pub fullscreen_client: FullscreenMode,
+    /// Is this window hidden? (like a terminal swallowed by a window it started)
+    #[serde(default)]
+    pub hidden: bool,
+    /// The window this window swallowed, `None` if it didn't swallow one
+    #[serde(default, deserialize_with = "crate::shared::de_optional_address")]
+    pub swallowing: Option<Address>,
+}
+
+/// This enum holds the fullscreen modes of a window
+#[derive(Serialize_repr, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum FullscreenMode {
+    /// Not fullscreen
+    #[default]
+    None = 0,
+    /// Filling the workspace, with the gaps, borders and bars still shown
+    Maximized = 1,
+    /// Filling the monitor
+    Fullscreen = 2,
+}
+
+impl<'de> Deserialize<'de> for FullscreenMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Aux {
+            Mode(u8),
+            // before Hyprland 0.42 it was only fullscreen or not
+            Bool(bool),
+        }
+        Ok(match Aux::deserialize(deserializer)? {
+            Aux::Mode(0) | Aux::Bool(false) => FullscreenMode::None,
+            Aux::Mode(1) => FullscreenMode::Maximized,
+            // 3 is maximized and fullscreen, which shows as fullscreen
+            Aux::Mode(_) | Aux::Bool(true) => FullscreenMode::Fullscreen,
+        })
+    }
 }
 
 /// This type provides a vector of clients
@@ -174,6 +488,12 @@ pub struct ActiveWindow(
     Option<Client>,
 );
 
+impl ActiveWindow {
+    pub(crate) fn into_client(self) -> Option<Client> {
+        self.0
+    }
+}
+
 /// This struct holds information about a layer surface/client
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LayerClient {
@@ -297,12 +617,74 @@ pub struct Version {
     pub flags: Vec<String>,
 }
 
+/// This struct holds a keybind
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Bind {
+    /// If it works while a input inhibitor (like a lockscreen) is active
+    pub locked: bool,
+    /// If it's a mouse bind
+    pub mouse: bool,
+    /// If it triggers when the key is released
+    pub release: bool,
+    /// If it repeats while the key is held
+    pub repeat: bool,
+    /// If it triggers when the key is held for a while (newer versions of Hyprland)
+    #[serde(rename = "longPress", default)]
+    pub long_press: bool,
+    /// If it doesn't consume the key, so other binds of it trigger too
+    pub non_consuming: bool,
+    /// The modifiers as a bitmask, see [`modifiers`](Self::modifiers)
+    pub modmask: u32,
+    /// The submap the bind is in, empty for the default one
+    pub submap: String,
+    /// The key, empty if the bind uses a keycode
+    pub key: String,
+    /// The keycode, 0 if the bind uses a key
+    pub keycode: u32,
+    /// If it triggers on any key (in a submap)
+    #[serde(default)]
+    pub catch_all: bool,
+    /// The description of the bind, empty if it has none (newer versions of Hyprland)
+    #[serde(default)]
+    pub description: String,
+    /// The dispatcher it calls
+    pub dispatcher: String,
+    /// The arguments of the dispatcher
+    pub arg: String,
+}
+
+/// This type provides a vector of binds
+pub type Binds = Vec<Bind>;
+
+impl Bind {
+    /// This method returns the modifiers that have to be held
+    ///
+    /// ```rust
+    /// use hyprland::bind::Modifier;
+    /// # let json = r#"{"locked":false,"mouse":false,"release":false,"repeat":false,"non_consuming":false,
+    /// #     "modmask":65,"submap":"","key":"Q","keycode":0,"catch_all":false,"dispatcher":"killactive","arg":""}"#;
+    /// let bind: hyprland::data::Bind = serde_json::from_str(json).unwrap();
+    /// assert_eq!(bind.modifiers(), vec![Modifier::Shift, Modifier::Super]);
+    /// ```
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        use Modifier::*;
+        [Shift, Caps, Ctrl, Alt, Mod2, Mod3, Super, Mod5]
+            .into_iter()
+            .enumerate()
+            .filter(|(bit, _)| self.modmask & (1 << bit) != 0)
+            .map(|(_, modifier)| modifier)
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct OptionRaw {
     pub option: String,
-    pub int: i64,
-    pub float: f64,
-    pub str: String,
+    pub int: Option<i64>,
+    pub float: Option<f64>,
+    pub str: Option<String>,
+    pub vec2: Option<(f64, f64)>,
+    pub custom: Option<String>,
 }
 
 /// This enum holds the possible values of a keyword/option
@@ -314,6 +696,79 @@ pub enum OptionValue {
     Float(f64),
     /// A string
     String(String),
+    /// A 2D vector
+    Vec2(f64, f64),
+    /// A color
+    Color(Color),
+    /// A gradient
+    Gradient(
+        /// The colors of the gradient
+        Vec<Color>,
+        /// The angle of the gradient (in degrees)
+        Option<f64>,
+    ),
+}
+
+impl From<i64> for OptionValue {
+    fn from(int: i64) -> Self {
+        OptionValue::Int(int)
+    }
+}
+
+impl From<f64> for OptionValue {
+    fn from(float: f64) -> Self {
+        OptionValue::Float(float)
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(b: bool) -> Self {
+        OptionValue::Int(b as i64)
+    }
+}
+
+impl From<&str> for OptionValue {
+    fn from(str: &str) -> Self {
+        OptionValue::String(str.to_string())
+    }
+}
+
+impl From<(f64, f64)> for OptionValue {
+    fn from((x, y): (f64, f64)) -> Self {
+        OptionValue::Vec2(x, y)
+    }
+}
+
+impl From<Color> for OptionValue {
+    fn from(color: Color) -> Self {
+        OptionValue::Color(color)
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(str: String) -> Self {
+        OptionValue::String(str)
+    }
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionValue::Int(int) => write!(f, "{int}"),
+            OptionValue::Float(float) => write!(f, "{float}"),
+            OptionValue::String(str) => write!(f, "{str}"),
+            OptionValue::Vec2(x, y) => write!(f, "{x} {y}"),
+            OptionValue::Color(color) => write!(f, "{color}"),
+            OptionValue::Gradient(colors, angle) => {
+                let colors: Vec<String> = colors.iter().map(|color| color.to_string()).collect();
+                write!(f, "{}", colors.join(" "))?;
+                match angle {
+                    Some(angle) => write!(f, " {angle}deg"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
 }
 
 /// This struct holds a keyword
@@ -324,3 +779,315 @@ pub struct Keyword {
     /// The value of the keyword/option
     pub value: OptionValue,
 }
+
+impl Keyword {
+    /// This method returns the keyword of a option in the config section of a specific device
+    /// (`device:<name>:<option>`), the name is escaped the same way Hyprland does it
+    ///
+    /// ```rust
+    /// use hyprland::data::Keyword;
+    /// let key = Keyword::device_key("Logitech G502 HERO", "sensitivity");
+    /// assert_eq!(key, "device:logitech-g502-hero:sensitivity");
+    /// ```
+    pub fn device_key(device: &str, option: &str) -> String {
+        let name = device.replace([' ', '\n'], "-").to_lowercase();
+        format!("device:{name}:{option}")
+    }
+}
+
+impl TryFrom<OptionRaw> for Keyword {
+    type Error = HyprError;
+
+    fn try_from(raw: OptionRaw) -> HyprResult<Self> {
+        let option_type = ConfigOption::from_key(&raw.option).map(|option| option.option_type());
+        let value = match raw.clone() {
+            // Older versions of Hyprland return every field, with unused ones set to -1 or ""
+            OptionRaw {
+                int: Some(int),
+                float: Some(float),
+                str: Some(str),
+                ..
+            } => {
+                if int != -1 {
+                    OptionValue::Int(int)
+                } else if float != -1.0 {
+                    OptionValue::Float(float)
+                } else if !str.is_empty() {
+                    OptionValue::String(str)
+                } else {
+                    return Err(unrecognized_option(&raw));
+                }
+            }
+            OptionRaw { int: Some(int), .. } => match option_type {
+                Some(OptionType::Color) => OptionValue::Color(Color::from_argb(int as u32)),
+                _ => OptionValue::Int(int),
+            },
+            OptionRaw {
+                float: Some(float), ..
+            } => OptionValue::Float(float),
+            OptionRaw { str: Some(str), .. } => OptionValue::String(str),
+            OptionRaw {
+                vec2: Some((x, y)), ..
+            } => OptionValue::Vec2(x, y),
+            OptionRaw {
+                custom: Some(custom),
+                ..
+            } => match option_type {
+                Some(OptionType::Gradient) => match parse_gradient(&custom) {
+                    Some((colors, angle)) => OptionValue::Gradient(colors, angle),
+                    None => OptionValue::String(custom),
+                },
+                _ => OptionValue::String(custom),
+            },
+            _ => return Err(unrecognized_option(&raw)),
+        };
+        Ok(Keyword {
+            option: raw.option,
+            value,
+        })
+    }
+}
+
+fn unrecognized_option(raw: &OptionRaw) -> HyprError {
+    HyprError::UnexpectedResponse(format!("the option returned unrecognized data: {raw:?}"))
+}
+
+/// This function parses a gradient the way Hyprland prints it (`aarrggbb aarrggbb 45deg`)
+fn parse_gradient(str: &str) -> Option<(Vec<Color>, Option<f64>)> {
+    let mut colors = vec![];
+    let mut angle = None;
+    for item in str.split_whitespace() {
+        if let Some(deg) = item.strip_suffix("deg") {
+            angle = Some(deg.parse().ok()?);
+        } else {
+            colors.push(Color::from_argb(u32::from_str_radix(item, 16).ok()?));
+        }
+    }
+    Some((colors, angle))
+}
+
+/// This enum holds the names of the window properties that can be read with `getprop`
+#[derive(Debug, Clone, Copy)]
+#[allow(missing_docs)]
+pub enum WindowPropertyKey {
+    AnimationStyle,
+    Rounding,
+    BorderSize,
+    ForceNoBlur,
+    ForceOpaque,
+    ForceOpaqueOverriden,
+    ForceAllowsInput,
+    ForceNoAnims,
+    ForceNoBorder,
+    ForceNoShadow,
+    ForceNoDim,
+    NoFocus,
+    WindowDanceCompat,
+    NoMaxSize,
+    DimAround,
+    KeepAspectRatio,
+    Immediate,
+    Xray,
+    Alpha,
+    AlphaOverride,
+    AlphaInactive,
+    AlphaInactiveOverride,
+    MaxSize,
+    MinSize,
+}
+
+impl WindowPropertyKey {
+    /// This method returns the name Hyprland uses for the property
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WindowPropertyKey::AnimationStyle => "animationstyle",
+            WindowPropertyKey::Rounding => "rounding",
+            WindowPropertyKey::BorderSize => "bordersize",
+            WindowPropertyKey::ForceNoBlur => "forcenoblur",
+            WindowPropertyKey::ForceOpaque => "forceopaque",
+            WindowPropertyKey::ForceOpaqueOverriden => "forceopaqueoverriden",
+            WindowPropertyKey::ForceAllowsInput => "forceallowsinput",
+            WindowPropertyKey::ForceNoAnims => "forcenoanims",
+            WindowPropertyKey::ForceNoBorder => "forcenoborder",
+            WindowPropertyKey::ForceNoShadow => "forcenoshadow",
+            WindowPropertyKey::ForceNoDim => "forcenodim",
+            WindowPropertyKey::NoFocus => "nofocus",
+            WindowPropertyKey::WindowDanceCompat => "windowdancecompat",
+            WindowPropertyKey::NoMaxSize => "nomaxsize",
+            WindowPropertyKey::DimAround => "dimaround",
+            WindowPropertyKey::KeepAspectRatio => "keepaspectratio",
+            WindowPropertyKey::Immediate => "immediate",
+            WindowPropertyKey::Xray => "xray",
+            WindowPropertyKey::Alpha => "alpha",
+            WindowPropertyKey::AlphaOverride => "alphaoverride",
+            WindowPropertyKey::AlphaInactive => "alphainactive",
+            WindowPropertyKey::AlphaInactiveOverride => "alphainactiveoverride",
+            WindowPropertyKey::MaxSize => "maxsize",
+            WindowPropertyKey::MinSize => "minsize",
+        }
+    }
+}
+
+/// This pub(crate) function turns the json response of `getprop` into a [`WindowProperty`]
+pub(crate) fn parse_window_property(
+    key: WindowPropertyKey,
+    data: &str,
+) -> serde_json::Result<WindowProperty> {
+    use serde::de::Error;
+    use serde_json::Value;
+
+    let mut object: HashMap<String, Value> = serde_json::from_str(data)?;
+    let value = match object.remove(key.as_str()) {
+        Some(value) => value,
+        None => {
+            return Err(serde_json::Error::custom(format!(
+                "the response has no value for {}: {data}",
+                key.as_str()
+            )))
+        }
+    };
+    let invalid = |value: &Value| {
+        serde_json::Error::custom(format!("unexpected value for {}: {value}", key.as_str()))
+    };
+    let as_bool = |value: &Value| match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Number(num) => Ok(num.as_i64() != Some(0)),
+        _ => Err(invalid(value)),
+    };
+    let as_int = |value: &Value| match value.as_i64() {
+        Some(int) => Ok(int as i32),
+        None => Err(invalid(value)),
+    };
+    let as_float = |value: &Value| match value.as_f64() {
+        Some(float) => Ok(float as f32),
+        None => Err(invalid(value)),
+    };
+    let as_size = |value: &Value| -> serde_json::Result<(u16, u16)> {
+        serde_json::from_value(value.clone()).map_err(|_| invalid(value))
+    };
+
+    Ok(match key {
+        WindowPropertyKey::AnimationStyle => match value.as_str() {
+            Some(style) => WindowProperty::AnimationStyle(style.to_string()),
+            None => return Err(invalid(&value)),
+        },
+        WindowPropertyKey::Rounding => WindowProperty::Rounding(as_int(&value)?),
+        WindowPropertyKey::BorderSize => WindowProperty::BorderSize(as_int(&value)?),
+        WindowPropertyKey::ForceNoBlur => WindowProperty::ForceNoBlur(as_bool(&value)?),
+        WindowPropertyKey::ForceOpaque => WindowProperty::ForceOpaque(as_bool(&value)?),
+        WindowPropertyKey::ForceOpaqueOverriden => {
+            WindowProperty::ForceOpaqueOverriden(as_bool(&value)?)
+        }
+        WindowPropertyKey::ForceAllowsInput => WindowProperty::ForceAllowsInput(as_bool(&value)?),
+        WindowPropertyKey::ForceNoAnims => WindowProperty::ForceNoAnims(as_bool(&value)?),
+        WindowPropertyKey::ForceNoBorder => WindowProperty::ForceNoBorder(as_bool(&value)?),
+        WindowPropertyKey::ForceNoShadow => WindowProperty::ForceNoShadow(as_bool(&value)?),
+        WindowPropertyKey::ForceNoDim => WindowProperty::ForceNoDim(as_bool(&value)?),
+        WindowPropertyKey::NoFocus => WindowProperty::NoFocus(as_bool(&value)?),
+        WindowPropertyKey::WindowDanceCompat => WindowProperty::WindowDanceCompat(as_bool(&value)?),
+        WindowPropertyKey::NoMaxSize => WindowProperty::NoMaxSize(as_bool(&value)?),
+        WindowPropertyKey::DimAround => WindowProperty::DimAround(as_bool(&value)?),
+        WindowPropertyKey::KeepAspectRatio => WindowProperty::KeepAspectRatio(as_bool(&value)?),
+        WindowPropertyKey::Immediate => WindowProperty::Immediate(as_bool(&value)?),
+        WindowPropertyKey::Xray => WindowProperty::Xray(as_bool(&value)?),
+        WindowPropertyKey::Alpha => WindowProperty::Alpha(as_float(&value)?),
+        WindowPropertyKey::AlphaOverride => WindowProperty::AlphaOverride(as_bool(&value)?),
+        WindowPropertyKey::AlphaInactive => WindowProperty::AlphaInactive(as_float(&value)?),
+        WindowPropertyKey::AlphaInactiveOverride => {
+            WindowProperty::AlphaInactiveOverride(as_bool(&value)?)
+        }
+        WindowPropertyKey::MaxSize => {
+            let (w, h) = as_size(&value)?;
+            WindowProperty::MaxSize(w, h)
+        }
+        WindowPropertyKey::MinSize => {
+            let (w, h) = as_size(&value)?;
+            WindowProperty::MinSize(w, h)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `hyprctl workspaces -j`, with a regular, a named and two special workspaces
+    const WORKSPACES: &str = r#"[
+        {"id": 1, "name": "1", "monitor": "DP-1", "monitorID": 0, "windows": 2,
+         "hasfullscreen": false, "lastwindow": "0x5581b1f5c9a0", "lastwindowtitle": "nvim"},
+        {"id": -1337, "name": "coding", "monitor": "DP-1", "monitorID": 0, "windows": 1,
+         "hasfullscreen": true, "lastwindow": "0x5581b1f5d010", "lastwindowtitle": "firefox"},
+        {"id": -99, "name": "special", "monitor": "HDMI-A-1", "monitorID": 1, "windows": 1,
+         "hasfullscreen": false, "lastwindow": "0x5581b1f5e230", "lastwindowtitle": "kitty"},
+        {"id": -98, "name": "special:scratch", "monitor": "HDMI-A-1", "monitorID": 1,
+         "windows": 0, "hasfullscreen": false, "lastwindow": "0x0", "lastwindowtitle": ""}
+    ]"#;
+
+    #[test]
+    fn workspaces_with_negative_ids() -> HyprResult<()> {
+        let raw: WorkspacesRaw = serde_json::from_str(WORKSPACES)?;
+        let ids: Vec<WorkspaceType> = raw.into_iter().map(|raw| Workspace::from(raw).id).collect();
+        assert_eq!(
+            ids,
+            [
+                WorkspaceType::Regular(1),
+                WorkspaceType::Named("coding".to_string()),
+                WorkspaceType::Special,
+                WorkspaceType::Special,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn basic_workspaces_with_negative_ids() -> HyprResult<()> {
+        let basic = |json: &str| serde_json::from_str::<WorkspaceBasic>(json).map(|work| work.id);
+        assert_eq!(
+            basic(r#"{"id": 300, "name": "300"}"#)?,
+            WorkspaceType::Regular(300)
+        );
+        assert_eq!(
+            basic(r#"{"id": -1337, "name": "coding"}"#)?,
+            WorkspaceType::Named("coding".to_string())
+        );
+        assert_eq!(
+            basic(r#"{"id": -97, "name": "special:music"}"#)?,
+            WorkspaceType::Special
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn first_empty_workspace_skips_the_ones_with_windows() -> HyprResult<()> {
+        let workspaces = |raw: &str| -> HyprResult<Workspaces> {
+            let raw: WorkspacesRaw = serde_json::from_str(raw)?;
+            Ok(raw.into_iter().map(Workspace::from).collect())
+        };
+        // only 1 is a regular workspace with windows
+        assert_eq!(first_empty_workspace(&workspaces(WORKSPACES)?), 2);
+        let workspace = |id: i32, windows: u8| {
+            format!(
+                r#"{{"id": {id}, "name": "{id}", "monitor": "DP-1", "monitorID": 0,
+                    "windows": {windows}, "hasfullscreen": false, "lastwindow": "0x0",
+                    "lastwindowtitle": ""}}"#
+            )
+        };
+        let raw = [
+            workspace(2, 1),
+            workspace(1, 3),
+            workspace(3, 0),
+            workspace(4, 1),
+        ];
+        assert_eq!(
+            first_empty_workspace(&workspaces(&format!("[{}]", raw.join(",")))?),
+            3
+        );
+        let raw = [workspace(2, 1), workspace(1, 3)];
+        assert_eq!(
+            first_empty_workspace(&workspaces(&format!("[{}]", raw.join(",")))?),
+            3
+        );
+        assert_eq!(first_empty_workspace(&workspaces("[]")?), 1);
+        Ok(())
+    }
+}
@@ -4,19 +4,19 @@
 //!
 //! ## Usage
 //!
-//! ```rust
+//! ```rust, no_run
 //! use hyprland::dispatch::{dispatch_blocking, DispatchType};
-//! fn main() -> std::io::Result<()> {
+//! fn main() -> hyprland::shared::HyprResult<()> {
 //!    dispatch_blocking(DispatchType::Exec("kitty".to_string()))?;
 //!
 //!    Ok(())
 //! }
 //! ````
 
-use crate::shared::{
-    get_socket_path, write_to_socket, write_to_socket_sync, Address, SocketType, WorkspaceId,
-};
-use std::io;
+use crate::data::{FullscreenMode, OptionValue};
+use crate::request::Request;
+use crate::shared::{batch_command, batch_results, Address, HyprResult, WorkspaceId};
+use std::fmt;
 
 /// This enum is for identifying a window
 #[derive(Clone)]
@@ -87,6 +87,84 @@ pub enum Corner {
     BottomLeft,
 }
 
+/// This enum holds the positions a window can be moved to in the stacking order
+#[allow(missing_docs)]
+pub enum ZOrder {
+    Top,
+    Bottom,
+}
+
+/// This enum holds the operations that can be done on a window tag
+pub enum Tag {
+    /// Adds the tag to the window (`+tag`)
+    Set(String),
+    /// Removes the tag from the window (`-tag`)
+    Unset(String),
+    /// Adds the tag if the window doesn't have it, otherwise removes it
+    Toggle(String),
+}
+
+/// This enum holds the window properties that can be changed with the `setprop` dispatcher
+#[derive(Clone)]
+pub enum WindowProperty {
+    /// The animation style of the window
+    AnimationStyle(String),
+    /// The rounding of the window corners (in pixels)
+    Rounding(i32),
+    /// The size of the window border (in pixels)
+    BorderSize(i32),
+    /// Forces the window to not be blurred
+    ForceNoBlur(bool),
+    /// Forces the window to be opaque
+    ForceOpaque(bool),
+    /// Makes [`ForceOpaque`][WindowProperty::ForceOpaque] take priority over toggleopaque
+    ForceOpaqueOverriden(bool),
+    /// Forces the window to accept input focus
+    ForceAllowsInput(bool),
+    /// Forces the window to have no animations
+    ForceNoAnims(bool),
+    /// Forces the window to have no border
+    ForceNoBorder(bool),
+    /// Forces the window to have no shadow
+    ForceNoShadow(bool),
+    /// Forces the window to not be dimmed when inactive
+    ForceNoDim(bool),
+    /// Disables focus for the window
+    NoFocus(bool),
+    /// Fixes focus stealing for some java apps
+    WindowDanceCompat(bool),
+    /// Removes the max size limitation of the window
+    NoMaxSize(bool),
+    /// Dims everything behind the window
+    DimAround(bool),
+    /// Keeps the aspect ratio of the window when resizing
+    KeepAspectRatio(bool),
+    /// Allows tearing for the window
+    Immediate(bool),
+    /// Makes the window blur only the wallpaper behind it
+    Xray(bool),
+    /// The opacity of the window (0.0 - 1.0)
+    Alpha(f32),
+    /// Makes [`Alpha`][WindowProperty::Alpha] override the opacity instead of multiplying it
+    AlphaOverride(bool),
+    /// The opacity of the window when it is inactive (0.0 - 1.0)
+    AlphaInactive(f32),
+    /// Makes [`AlphaInactive`][WindowProperty::AlphaInactive] override the opacity instead of multiplying it
+    AlphaInactiveOverride(bool),
+    /// The maximum size of the window
+    MaxSize(u16, u16),
+    /// The minimum size of the window
+    MinSize(u16, u16),
+}
+
+/// This enum holds the states a key can be sent in
+#[allow(missing_docs)]
+pub enum KeyState {
+    Down,
+    Up,
+    Repeat,
+}
+
 /// This enum holds options that are applied to the current workspace
 pub enum WorkspaceOptions {
     /// Makes all windows pseudo tiled
@@ -112,6 +190,8 @@ pub enum WorkspaceIdentifierWithSpecial {
     Name(String),
     /// The special workspace
     Special,
+    /// A named special workspace (`special:name`)
+    NamedSpecial(String),
 }
 
 /// This enum is for identifying workspaces
@@ -139,6 +219,90 @@ pub enum WindowMove {
     Direction(Direction),
 }
 
+/// This enum holds the orientations of the master layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum MasterOrientation {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Center,
+}
+
+/// This enum holds the messages accepted by the dwindle layout
+#[derive(Clone)]
+pub enum DwindleLayoutMsg {
+    /// Toggles the split direction of the current window
+    ToggleSplit,
+    /// Swaps the two halves of the split of the current window
+    SwapSplit,
+    /// Sets the direction the next window will be opened in
+    Preselect(Direction),
+    /// Moves the current window to the root of its workspace tree
+    MoveToRoot,
+}
+
+/// This enum holds the messages accepted by the master layout
+#[derive(Clone)]
+pub enum MasterLayoutMsg {
+    /// Swaps the current window with the master window
+    SwapWithMaster,
+    /// Focuses the master window
+    FocusMaster,
+    /// Focuses the next window in the layout
+    CycleNext,
+    /// Focuses the previous window in the layout
+    CyclePrev,
+    /// Swaps the current window with the next one
+    SwapNext,
+    /// Swaps the current window with the previous one
+    SwapPrev,
+    /// Adds a master window to the current workspace
+    AddMaster,
+    /// Removes a master window from the current workspace
+    RemoveMaster,
+    /// Sets the orientation of the master area for the current workspace
+    Orientation(MasterOrientation),
+    /// Switches to the next orientation for the current workspace
+    OrientationNext,
+    /// Switches to the previous orientation for the current workspace
+    OrientationPrev,
+    /// Cycles through the orientations for the current workspace
+    OrientationCycle,
+    /// Changes the master factor (size of the master area) by a delta
+    Mfact(f32),
+    /// Sets the master factor (size of the master area) to an exact value
+    MfactExact(f32),
+}
+
+/// This enum holds which window of the group to make active
+pub enum GroupActive {
+    /// The window in the tab after the active one
+    Next,
+    /// The window in the tab before the active one
+    Previous,
+    /// The window in the tab at the index (from 1)
+    Index(u8),
+}
+
+/// This enum holds what to do with a lock
+#[allow(missing_docs)]
+pub enum LockAction {
+    Lock,
+    Unlock,
+    Toggle,
+}
+
+/// This enum holds the messages that can be sent to the active layout
+#[derive(Clone)]
+pub enum LayoutMsg {
+    /// A message for the dwindle layout
+    Dwindle(DwindleLayoutMsg),
+    /// A message for the master layout
+    Master(MasterLayoutMsg),
+}
+
 /// This enum holds every dispatcher
 pub enum DispatchType {
     /// This dispatcher changes a keyword
@@ -159,6 +323,8 @@ pub enum DispatchType {
     Exec(String),
     /// This dispatcher kills the active window/client
     KillActiveWindow,
+    /// This dispatcher closes a specified window/client
+    CloseWindow(WindowIdentifier),
     /// This dispatcher changes the current workspace
     Workspace(WorkspaceIdentifierWithSpecial),
     /// This dispatcher moves the focused window to a specified workspace, and
@@ -167,10 +333,16 @@ pub enum DispatchType {
     /// This dispatcher moves the focused window to a specified workspace, and
     /// does not change workspaces
     MoveFocusedWindowToWorkspaceSilent(WorkspaceIdentifier),
+    /// This dispatcher moves a specified window to a workspace, and
+    /// does not change workspaces
+    MoveWindowToWorkspaceSilent(WorkspaceIdentifierWithSpecial, WindowIdentifier),
     /// This dispatcher floats the current window
     ToggleFloating,
     /// This toggles the current window fullscreen state
     ToggleFullscreen(FullscreenType),
+    /// This dispatcher sets the fullscreen mode of the current window, and the one the window
+    /// is told it's in (which differ for fake fullscreen), `None` keeps the current one
+    FullscreenState(Option<FullscreenMode>, Option<FullscreenMode>),
     /// This dispatcher toggles pseudo tiling for the current window
     TogglePseudo,
     /// This dispatcher moves the window focus in a specified direction
@@ -205,221 +377,602 @@ pub enum DispatchType {
     MoveWorkspaceToMonitor(WorkspaceIdentifier, MonitorIdentifier),
     /// This toggles the special workspace (AKA scratchpad)
     ToggleSpecialWorkspace,
+    /// This toggles a named special workspace, see [`Scratchpad`](crate::scratchpad::Scratchpad)
+    ToggleNamedSpecialWorkspace(String),
+    /// This dispatcher sends a message to the active layout
+    LayoutMsg(LayoutMsg),
+    /// This dispatcher changes the stacking order of a floating window,
+    /// if no window is specified the active one is used
+    AlterZOrder(ZOrder, Option<WindowIdentifier>),
+    /// This dispatcher applies a tag to a window,
+    /// if no window is specified the active one is used
+    TagWindow(Tag, Option<WindowIdentifier>),
+    /// This dispatcher sets a property of a window
+    SetProp(
+        /// The window to change
+        WindowIdentifier,
+        /// The property and its value
+        WindowProperty,
+        /// Locks the property so window rules and focus changes can't change it
+        bool,
+    ),
+    /// This dispatcher toggles swallowing for the active window
+    ToggleSwallow,
+    /// This dispatcher emits a custom event on the event socket (`custom>>data`)
+    Event(String),
+    /// This dispatcher makes the active window a group, or ungroups the group it's in
+    ToggleGroup,
+    /// This dispatcher changes the active window of the active group
+    ChangeGroupActive(GroupActive),
+    /// This dispatcher moves the active window into the group in the direction
+    MoveIntoGroup(Direction),
+    /// This dispatcher moves a window out of its group,
+    /// if no window is specified the active one is used
+    MoveOutOfGroup(Option<WindowIdentifier>),
+    /// This dispatcher swaps the active window with the one in the next or previous tab of its group
+    MoveGroupWindow(CycleDirection),
+    /// This dispatcher locks or unlocks all groups, so windows can't be moved into or out of them
+    LockGroups(LockAction),
+    /// This dispatcher locks or unlocks the active group
+    LockActiveGroup(LockAction),
+    /// This dispatcher sends a key in a specific state to a window,
+    /// the window must keep focus for the key events to continue
+    SendKeyState(
+        /// The modifiers (e.g. `SUPER SHIFT`)
+        String,
+        /// The key
+        String,
+        /// The state of the key
+        KeyState,
+        /// The window to send the key to
+        WindowIdentifier,
+    ),
+}
+
+impl fmt::Display for WorkspaceIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkspaceIdentifier::Id(id) => write!(f, "{id}"),
+            WorkspaceIdentifier::Name(name) => write!(f, "name:{name}"),
+            WorkspaceIdentifier::PositiveRelative(int) => write!(f, "+{int}"),
+            WorkspaceIdentifier::PositiveRelativeMonitor(int) => write!(f, "m+{int}"),
+            WorkspaceIdentifier::NegativeRelative(int) => write!(f, "-{int}"),
+            WorkspaceIdentifier::NegativeRelativeMonitor(int) => write!(f, "m-{int}"),
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceIdentifierWithSpecial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkspaceIdentifierWithSpecial::Id(id) => write!(f, "{id}"),
+            WorkspaceIdentifierWithSpecial::Name(name) => write!(f, "name:{name}"),
+            WorkspaceIdentifierWithSpecial::PositiveRelative(int) => write!(f, "+{int}"),
+            WorkspaceIdentifierWithSpecial::PositiveRelativeMonitor(int) => write!(f, "m+{int}"),
+            WorkspaceIdentifierWithSpecial::NegativeRelative(int) => write!(f, "-{int}"),
+            WorkspaceIdentifierWithSpecial::NegativeRelativeMonitor(int) => write!(f, "m-{int}"),
+            WorkspaceIdentifierWithSpecial::Special => f.write_str("special"),
+            WorkspaceIdentifierWithSpecial::NamedSpecial(name) => write!(f, "special:{name}"),
+        }
+    }
 }
 
-fn match_workspace_identifier(identifier: WorkspaceIdentifier) -> String {
-    match identifier {
-        WorkspaceIdentifier::Id(id) => format!("{id}"),
-        WorkspaceIdentifier::Name(name) => format!("name:{name}"),
-        WorkspaceIdentifier::PositiveRelative(int) => format!("+{int}"),
-        WorkspaceIdentifier::PositiveRelativeMonitor(int) => format!("m+{int}"),
-        WorkspaceIdentifier::NegativeRelative(int) => format!("-{int}"),
-        WorkspaceIdentifier::NegativeRelativeMonitor(int) => format!("m-{int}"),
+impl fmt::Display for MonitorIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MonitorIdentifier::Direction(dir) => write!(f, "{dir}"),
+            MonitorIdentifier::Id(id) => write!(f, "{id}"),
+            MonitorIdentifier::Name(name) => f.write_str(name),
+        }
     }
 }
 
-fn match_workspace_identifier_special(identifier: WorkspaceIdentifierWithSpecial) -> String {
-    match identifier {
-        WorkspaceIdentifierWithSpecial::Id(id) => format!("{id}"),
-        WorkspaceIdentifierWithSpecial::Name(name) => format!("name:{name}"),
-        WorkspaceIdentifierWithSpecial::PositiveRelative(int) => format!("+{int}"),
-        WorkspaceIdentifierWithSpecial::PositiveRelativeMonitor(int) => format!("m+{int}"),
-        WorkspaceIdentifierWithSpecial::NegativeRelative(int) => format!("-{int}"),
-        WorkspaceIdentifierWithSpecial::NegativeRelativeMonitor(int) => format!("m-{int}"),
-        WorkspaceIdentifierWithSpecial::Special => "special".to_string(),
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Left => "l",
+            Direction::Right => "r",
+            Direction::Down => "d",
+            Direction::Up => "u",
+        })
     }
 }
 
-fn match_mon_indentifier(identifier: MonitorIdentifier) -> String {
-    match identifier {
-        MonitorIdentifier::Direction(dir) => match_dir(dir),
-        MonitorIdentifier::Id(id) => id.to_string(),
-        MonitorIdentifier::Name(name) => name,
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Position::Delta(x, y) => write!(f, "{x},{y}"),
+            Position::Exact(w, h) => write!(f, "exact {w} {h}"),
+        }
     }
 }
 
-fn match_dir(dir: Direction) -> String {
-    match dir {
-        Direction::Left => "l",
-        Direction::Right => "r",
-        Direction::Down => "d",
-        Direction::Up => "u",
+impl fmt::Display for WindowIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowIdentifier::Address(addr) => write!(f, "address:{addr}"),
+            WindowIdentifier::ProcessId(id) => write!(f, "pid:{id}"),
+            WindowIdentifier::ClassRegularExpression(regex) => f.write_str(regex),
+            WindowIdentifier::Title(title) => write!(f, "title:{title}"),
+        }
     }
-    .to_string()
 }
 
-fn position_to_string(pos: Position) -> String {
-    match pos {
-        Position::Delta(x, y) => format!("{x},{y}"),
-        Position::Exact(w, h) => format!("exact {w} {h}"),
+impl fmt::Display for LockAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LockAction::Lock => "lock",
+            LockAction::Unlock => "unlock",
+            LockAction::Toggle => "toggle",
+        })
     }
 }
 
-fn match_window_identifier(iden: WindowIdentifier) -> String {
-    match iden {
-        WindowIdentifier::Address(addr) => format!("address:{}", addr),
-        WindowIdentifier::ProcessId(id) => format!("pid:{}", id),
-        WindowIdentifier::ClassRegularExpression(regex) => regex,
-        WindowIdentifier::Title(title) => format!("title:{}", title),
+impl fmt::Display for MasterOrientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            MasterOrientation::Left => "left",
+            MasterOrientation::Right => "right",
+            MasterOrientation::Top => "top",
+            MasterOrientation::Bottom => "bottom",
+            MasterOrientation::Center => "center",
+        })
     }
 }
 
-fn gen_dispatch_str(cmd: DispatchType) -> io::Result<String> {
-    let string_to_pass = match &cmd {
-        DispatchType::Exec(sh) => format!("exec {sh}"),
-        DispatchType::KillActiveWindow => "killactive".to_string(),
-        DispatchType::Workspace(identifier) => format!(
-            "workspace {}",
-            match_workspace_identifier_special(identifier.clone())
-        ),
-        DispatchType::MoveFocusedWindowToWorkspace(identifier) => {
-            format!(
-                "workspace {}",
-                match_workspace_identifier(identifier.clone())
-            )
+impl fmt::Display for LayoutMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutMsg::Dwindle(msg) => match msg {
+                DwindleLayoutMsg::ToggleSplit => f.write_str("togglesplit"),
+                DwindleLayoutMsg::SwapSplit => f.write_str("swapsplit"),
+                DwindleLayoutMsg::Preselect(dir) => write!(f, "preselect {dir}"),
+                DwindleLayoutMsg::MoveToRoot => f.write_str("movetoroot"),
+            },
+            LayoutMsg::Master(msg) => match msg {
+                MasterLayoutMsg::SwapWithMaster => f.write_str("swapwithmaster"),
+                MasterLayoutMsg::FocusMaster => f.write_str("focusmaster"),
+                MasterLayoutMsg::CycleNext => f.write_str("cyclenext"),
+                MasterLayoutMsg::CyclePrev => f.write_str("cycleprev"),
+                MasterLayoutMsg::SwapNext => f.write_str("swapnext"),
+                MasterLayoutMsg::SwapPrev => f.write_str("swapprev"),
+                MasterLayoutMsg::AddMaster => f.write_str("addmaster"),
+                MasterLayoutMsg::RemoveMaster => f.write_str("removemaster"),
+                MasterLayoutMsg::Orientation(orientation) => write!(f, "orientation{orientation}"),
+                MasterLayoutMsg::OrientationNext => f.write_str("orientationnext"),
+                MasterLayoutMsg::OrientationPrev => f.write_str("orientationprev"),
+                MasterLayoutMsg::OrientationCycle => f.write_str("orientationcycle"),
+                MasterLayoutMsg::Mfact(delta) => write!(f, "mfact {delta}"),
+                MasterLayoutMsg::MfactExact(value) => write!(f, "mfact exact {value}"),
+            },
         }
-        DispatchType::MoveFocusedWindowToWorkspaceSilent(identifier) => {
-            format!(
-                "workspace {}",
-                match_workspace_identifier(identifier.clone())
-            )
+    }
+}
+
+impl fmt::Display for WindowProperty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn bool_to_string(value: bool) -> &'static str {
+            if value {
+                "1"
+            } else {
+                "0"
+            }
         }
-        DispatchType::ToggleFloating => "togglefloating".to_string(),
-        DispatchType::ToggleFullscreen(fullscreen_type) => format!(
-            "fullscreen {}",
-            match fullscreen_type {
-                FullscreenType::Real => "0",
-                FullscreenType::Maximize => "1",
-                FullscreenType::NoParam => "",
+        match *self {
+            WindowProperty::AnimationStyle(ref style) => write!(f, "animationstyle {style}"),
+            WindowProperty::Rounding(int) => write!(f, "rounding {int}"),
+            WindowProperty::BorderSize(int) => write!(f, "bordersize {int}"),
+            WindowProperty::ForceNoBlur(b) => write!(f, "forcenoblur {}", bool_to_string(b)),
+            WindowProperty::ForceOpaque(b) => write!(f, "forceopaque {}", bool_to_string(b)),
+            WindowProperty::ForceOpaqueOverriden(b) => {
+                write!(f, "forceopaqueoverriden {}", bool_to_string(b))
             }
-        ),
-        DispatchType::TogglePseudo => "pseudo".to_string(),
-        DispatchType::MoveFocus(dir) => format!(
-            "movefocus {}",
-            match dir {
-                Direction::Down => "d",
-                Direction::Up => "u",
-                Direction::Right => "r",
-                Direction::Left => "l",
+            WindowProperty::ForceAllowsInput(b) => {
+                write!(f, "forceallowsinput {}", bool_to_string(b))
             }
-        ),
-        DispatchType::MoveWindow(iden) => format!(
-            "movewindow {}",
-            match iden {
-                WindowMove::Direction(dir) => match_dir(dir.clone()),
-                WindowMove::Monitor(mon) => format!("mon:{}", match_mon_indentifier(mon.clone())),
+            WindowProperty::ForceNoAnims(b) => write!(f, "forcenoanims {}", bool_to_string(b)),
+            WindowProperty::ForceNoBorder(b) => write!(f, "forcenoborder {}", bool_to_string(b)),
+            WindowProperty::ForceNoShadow(b) => write!(f, "forcenoshadow {}", bool_to_string(b)),
+            WindowProperty::ForceNoDim(b) => write!(f, "forcenodim {}", bool_to_string(b)),
+            WindowProperty::NoFocus(b) => write!(f, "nofocus {}", bool_to_string(b)),
+            WindowProperty::WindowDanceCompat(b) => {
+                write!(f, "windowdancecompat {}", bool_to_string(b))
             }
-        ),
-        DispatchType::ResizeActive(pos) => {
-            format!("resizeactive {}", position_to_string(pos.clone()))
-        }
-        DispatchType::MoveActive(pos) => format!("moveactive {}", position_to_string(pos.clone())),
-        DispatchType::CycleWindow(dir) => format!(
-            "cyclenext {}",
-            match dir {
-                CycleDirection::Next => "",
-                CycleDirection::Previous => "prev",
+            WindowProperty::NoMaxSize(b) => write!(f, "nomaxsize {}", bool_to_string(b)),
+            WindowProperty::DimAround(b) => write!(f, "dimaround {}", bool_to_string(b)),
+            WindowProperty::KeepAspectRatio(b) => {
+                write!(f, "keepaspectratio {}", bool_to_string(b))
+            }
+            WindowProperty::Immediate(b) => write!(f, "immediate {}", bool_to_string(b)),
+            WindowProperty::Xray(b) => write!(f, "xray {}", bool_to_string(b)),
+            WindowProperty::Alpha(float) => write!(f, "alpha {float}"),
+            WindowProperty::AlphaOverride(b) => write!(f, "alphaoverride {}", bool_to_string(b)),
+            WindowProperty::AlphaInactive(float) => write!(f, "alphainactive {float}"),
+            WindowProperty::AlphaInactiveOverride(b) => {
+                write!(f, "alphainactiveoverride {}", bool_to_string(b))
             }
-        ),
-        DispatchType::FocusWindow(win) => {
-            format!("focuswindow {}", match_window_identifier(win.clone()))
+            WindowProperty::MaxSize(w, h) => write!(f, "maxsize {w} {h}"),
+            WindowProperty::MinSize(w, h) => write!(f, "minsize {w} {h}"),
         }
-        DispatchType::FocusMonitor(mon) => {
-            format!("focusmonitor {}", match_mon_indentifier(mon.clone()))
+    }
+}
+
+/// Formats the dispatcher as the command sent to Hyprland (like `dispatch exec kitty`),
+/// which can be written into a reused buffer with [`write!`]
+///
+/// ```rust
+/// use hyprland::dispatch::DispatchType;
+/// use std::fmt::Write;
+/// let mut command = String::new();
+/// write!(command, "{}", DispatchType::Exec("kitty".to_string()))?;
+/// assert_eq!(command, "dispatch exec kitty");
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+impl fmt::Display for DispatchType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DispatchType::Keyword(key, val) => return write!(f, "keyword {key} {val}"),
+            DispatchType::SetCursor(theme, size) => return write!(f, "setcursor {theme} {size}"),
+            _ => f.write_str("dispatch ")?,
         }
-        DispatchType::ChangeSplitRatio(ratio) => format!("splitratio {}", ratio),
-        DispatchType::ToggleOpaque => "toggleopaque".to_string(),
-        DispatchType::MoveCursorToCorner(corner) => format!(
-            "movecursortocorner {}",
-            match corner {
-                Corner::BottomLeft => "0",
-                Corner::BottomRight => "1",
-                Corner::TopRight => "2",
-                Corner::TopLeft => "3",
+        match self {
+            DispatchType::Exec(sh) => write!(f, "exec {sh}"),
+            DispatchType::KillActiveWindow => f.write_str("killactive"),
+            DispatchType::CloseWindow(win) => write!(f, "closewindow {win}"),
+            DispatchType::Workspace(identifier) => write!(f, "workspace {identifier}"),
+            DispatchType::MoveFocusedWindowToWorkspace(identifier) => {
+                write!(f, "movetoworkspace {identifier}")
             }
-        ),
-        DispatchType::WorkspaceOption(opt) => format!(
-            "workspaceopt {}",
-            match opt {
-                WorkspaceOptions::AllFloat => "allfloat",
-                WorkspaceOptions::AllPseudo => "allpseudo",
+            DispatchType::MoveFocusedWindowToWorkspaceSilent(identifier) => {
+                write!(f, "movetoworkspacesilent {identifier}")
             }
-        ),
-        DispatchType::Exit => "exit".to_string(),
-        DispatchType::ForceRendererReload => "forcerendererreload".to_string(),
-        DispatchType::MoveCurrentWorkspaceToMonitor(mon) => {
-            format!(
-                "movecurrentworkspacetomonitor {}",
-                match_mon_indentifier(mon.clone())
-            )
-        }
-        DispatchType::MoveWorkspaceToMonitor(work, mon) => format!(
-            "movecurrentworkspacetomonitor {} {}",
-            match_workspace_identifier(work.clone()),
-            match_mon_indentifier(mon.clone())
-        ),
-        DispatchType::ToggleSpecialWorkspace => "togglespecialworkspace".to_string(),
-        DispatchType::Keyword(key, val) => {
-            format!("{key} {val}", key = key.clone(), val = val.clone())
+            DispatchType::MoveWindowToWorkspaceSilent(work, win) => {
+                write!(f, "movetoworkspacesilent {work},{win}")
+            }
+            DispatchType::ToggleFloating => f.write_str("togglefloating"),
+            DispatchType::ToggleFullscreen(fullscreen_type) => write!(
+                f,
+                "fullscreen {}",
+                match fullscreen_type {
+                    FullscreenType::Real => "0",
+                    FullscreenType::Maximize => "1",
+                    FullscreenType::NoParam => "",
+                }
+            ),
+            DispatchType::FullscreenState(internal, client) => {
+                let mode = |mode: &Option<FullscreenMode>| mode.map_or(-1, |mode| mode as i8);
+                write!(f, "fullscreenstate {} {}", mode(internal), mode(client))
+            }
+            DispatchType::TogglePseudo => f.write_str("pseudo"),
+            DispatchType::MoveFocus(dir) => write!(f, "movefocus {dir}"),
+            DispatchType::MoveWindow(iden) => match iden {
+                WindowMove::Direction(dir) => write!(f, "movewindow {dir}"),
+                WindowMove::Monitor(mon) => write!(f, "movewindow mon:{mon}"),
+            },
+            DispatchType::ResizeActive(pos) => write!(f, "resizeactive {pos}"),
+            DispatchType::MoveActive(pos) => write!(f, "moveactive {pos}"),
+            DispatchType::CycleWindow(dir) => write!(
+                f,
+                "cyclenext {}",
+                match dir {
+                    CycleDirection::Next => "",
+                    CycleDirection::Previous => "prev",
+                }
+            ),
+            DispatchType::FocusWindow(win) => write!(f, "focuswindow {win}"),
+            DispatchType::FocusMonitor(mon) => write!(f, "focusmonitor {mon}"),
+            DispatchType::ChangeSplitRatio(ratio) => write!(f, "splitratio {ratio}"),
+            DispatchType::ToggleOpaque => f.write_str("toggleopaque"),
+            DispatchType::MoveCursorToCorner(corner) => write!(
+                f,
+                "movecursortocorner {}",
+                match corner {
+                    Corner::BottomLeft => "0",
+                    Corner::BottomRight => "1",
+                    Corner::TopRight => "2",
+                    Corner::TopLeft => "3",
+                }
+            ),
+            DispatchType::WorkspaceOption(opt) => write!(
+                f,
+                "workspaceopt {}",
+                match opt {
+                    WorkspaceOptions::AllFloat => "allfloat",
+                    WorkspaceOptions::AllPseudo => "allpseudo",
+                }
+            ),
+            DispatchType::Exit => f.write_str("exit"),
+            DispatchType::ForceRendererReload => f.write_str("forcerendererreload"),
+            DispatchType::MoveCurrentWorkspaceToMonitor(mon) => {
+                write!(f, "movecurrentworkspacetomonitor {mon}")
+            }
+            DispatchType::MoveWorkspaceToMonitor(work, mon) => {
+                write!(f, "moveworkspacetomonitor {work} {mon}")
+            }
+            DispatchType::ToggleSpecialWorkspace => f.write_str("togglespecialworkspace"),
+            DispatchType::ToggleNamedSpecialWorkspace(name) => {
+                write!(f, "togglespecialworkspace {name}")
+            }
+            DispatchType::LayoutMsg(msg) => write!(f, "layoutmsg {msg}"),
+            DispatchType::AlterZOrder(zorder, win) => {
+                f.write_str(match zorder {
+                    ZOrder::Top => "alterzorder top",
+                    ZOrder::Bottom => "alterzorder bottom",
+                })?;
+                match win {
+                    Some(win) => write!(f, ",{win}"),
+                    None => Ok(()),
+                }
+            }
+            DispatchType::TagWindow(tag, win) => {
+                match tag {
+                    Tag::Set(tag) => write!(f, "tagwindow +{tag}")?,
+                    Tag::Unset(tag) => write!(f, "tagwindow -{tag}")?,
+                    Tag::Toggle(tag) => write!(f, "tagwindow {tag}")?,
+                }
+                match win {
+                    Some(win) => write!(f, " {win}"),
+                    None => Ok(()),
+                }
+            }
+            DispatchType::SetProp(win, prop, lock) => write!(
+                f,
+                "setprop {win} {prop}{}",
+                if *lock { " lock" } else { "" }
+            ),
+            DispatchType::ToggleSwallow => f.write_str("toggleswallow"),
+            DispatchType::Event(data) => write!(f, "event {data}"),
+            DispatchType::ToggleGroup => f.write_str("togglegroup"),
+            DispatchType::ChangeGroupActive(active) => match active {
+                GroupActive::Next => f.write_str("changegroupactive f"),
+                GroupActive::Previous => f.write_str("changegroupactive b"),
+                GroupActive::Index(index) => write!(f, "changegroupactive {index}"),
+            },
+            DispatchType::MoveIntoGroup(dir) => write!(f, "moveintogroup {dir}"),
+            DispatchType::MoveOutOfGroup(win) => match win {
+                Some(win) => write!(f, "moveoutofgroup {win}"),
+                None => f.write_str("moveoutofgroup"),
+            },
+            DispatchType::MoveGroupWindow(dir) => write!(
+                f,
+                "movegroupwindow {}",
+                match dir {
+                    CycleDirection::Next => "f",
+                    CycleDirection::Previous => "b",
+                }
+            ),
+            DispatchType::LockGroups(action) => write!(f, "lockgroups {action}"),
+            DispatchType::LockActiveGroup(action) => write!(f, "lockactivegroup {action}"),
+            DispatchType::SendKeyState(mods, key, state, win) => write!(
+                f,
+                "sendkeystate {mods}, {key}, {}, {win}",
+                match state {
+                    KeyState::Down => "down",
+                    KeyState::Up => "up",
+                    KeyState::Repeat => "repeat",
+                }
+            ),
+            DispatchType::Keyword(..) | DispatchType::SetCursor(..) => Ok(()),
         }
-        DispatchType::SetCursor(theme, size) => {
-            format!("{theme} {size}", theme = theme.clone(), size = *size)
+    }
+}
+
+fn gen_dispatch_str(cmd: DispatchType) -> HyprResult<String> {
+    Ok(cmd.to_string())
+}
+
+fn gen_batch_str(cmds: Vec<DispatchType>) -> HyprResult<String> {
+    batch_command(&cmds)
+}
+
+pub(crate) fn dispatch_request(dispatch_type: DispatchType) -> HyprResult<Request<HyprResult<()>>> {
+    Ok(Request::ok(gen_dispatch_str(dispatch_type)?))
+}
+
+pub(crate) fn batch_request(
+    dispatch_types: Vec<DispatchType>,
+) -> HyprResult<Request<HyprResult<()>>> {
+    let count = dispatch_types.len();
+    Ok(Request::new(
+        gen_batch_str(dispatch_types)?,
+        move |output| batch_results(&output?, count).into_iter().collect(),
+    ))
+}
+
+enum BatchCommand {
+    Dispatch(DispatchType),
+    Command(String),
+}
+
+impl fmt::Display for BatchCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchCommand::Dispatch(dispatch_type) => write!(f, "{dispatch_type}"),
+            BatchCommand::Command(cmd) => f.write_str(cmd),
         }
-    };
-    if let DispatchType::Keyword(_, _) = cmd {
-        Ok(format!("keyword {string_to_pass}"))
-    } else if let DispatchType::SetCursor(_, _) = cmd {
-        Ok(format!("setcursor {string_to_pass}"))
-    } else {
-        Ok(format!("dispatch {string_to_pass}"))
+    }
+}
+
+/// This struct holds commands of any kind, which are sent in a single socket write
+///
+/// Using Hyprland's batch syntax, keywords, dispatchers and other commands can be mixed,
+/// a command with a `;` in it returns [`HyprError::InvalidArgument`](crate::shared::HyprError::InvalidArgument)
+/// before anything is sent. Every command is expected to return `ok`,
+/// the results are returned in the same order the commands were added
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::dispatch::{Batch, DispatchType};
+/// let results = Batch::new()
+///     .keyword("general:border_size", 2)
+///     .dispatch(DispatchType::Exec("kitty".to_string()))
+///     .dispatch(DispatchType::ToggleFloating)
+///     .send()?;
+/// for result in results {
+///     result?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Batch {
+    commands: Vec<BatchCommand>,
+}
+
+impl Batch {
+    /// This method creates a empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This method adds a dispatcher to the batch
+    pub fn dispatch(mut self, dispatch_type: DispatchType) -> Self {
+        self.commands.push(BatchCommand::Dispatch(dispatch_type));
+        self
+    }
+
+    /// This method adds setting a keyword to the batch
+    pub fn keyword(mut self, key: impl Into<String>, value: impl Into<OptionValue>) -> Self {
+        let keyword = DispatchType::Keyword(key.into(), value.into().to_string());
+        self.commands.push(BatchCommand::Dispatch(keyword));
+        self
+    }
+
+    /// This method adds a raw command to the batch, written as is the same way `hyprctl` would,
+    /// like `reload` or `seterror disable`
+    pub fn command(mut self, cmd: impl Into<String>) -> Self {
+        self.commands.push(BatchCommand::Command(cmd.into()));
+        self
+    }
+
+    /// This method returns how many commands are in the batch
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// This method returns `true` if no commands were added
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub(crate) fn request(self) -> HyprResult<Request<HyprResult<Vec<HyprResult<()>>>>> {
+        let count = self.commands.len();
+        Ok(Request::new(
+            batch_command(&self.commands)?,
+            move |response| Ok(batch_results(&response?, count)),
+        ))
+    }
+
+    /// This method sends the batch, and returns the result of each command (blocking)
+    pub fn send(self) -> HyprResult<Vec<HyprResult<()>>> {
+        self.request()?.send_blocking()
+    }
+
+    /// This method sends the batch, and returns the result of each command (async)
+    #[cfg(feature = "async")]
+    pub async fn send_async(self) -> HyprResult<Vec<HyprResult<()>>> {
+        self.request()?.send().await
     }
 }
 
 /// This function calls a specified dispatcher (blocking)
 ///
-/// ```rust
-/// # fn main() -> std::io::Result<()> {
+/// If Hyprland can't run the dispatcher, its message is returned as [`HyprError::Command`](crate::shared::HyprError::Command)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
 /// use hyprland::dispatch::{DispatchType,dispatch_blocking};
 /// // This is an example of just one dispatcher, there are many more!
 /// dispatch_blocking(DispatchType::Exec("something".to_string()))
 /// # }
 /// ```
-pub fn dispatch_blocking(dispatch_type: DispatchType) -> io::Result<()> {
-    let socket_path = get_socket_path(SocketType::Command);
-    let output = write_to_socket_sync(socket_path, gen_dispatch_str(dispatch_type)?.as_bytes());
-
-    match output {
-        Ok(msg) => match msg.as_str() {
-            "ok" => Ok(()),
-            msg => panic!(
-                "Hyprland returned a non `ok` value to the dispatcher, this is usually a error, output:({msg})"
-            ),
-        },
-        Err(error) => panic!("A error occured when running the dispatcher: {error:#?}"),
-    }
+pub fn dispatch_blocking(dispatch_type: DispatchType) -> HyprResult<()> {
+    dispatch_request(dispatch_type)?.send_blocking()
 }
 
 /// This function calls a specified dispatcher (async)
 ///
+/// If Hyprland can't run the dispatcher, its message is returned as [`HyprError::Command`](crate::shared::HyprError::Command)
+///
 /// ```rust
-/// # async fn function() -> std::io::Result<()> {
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
 /// use hyprland::dispatch::{DispatchType,dispatch};
 /// // This is an example of just one dispatcher, there are many more!
 /// dispatch(DispatchType::Exec("kitty".to_string())).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn dispatch(dispatch_type: DispatchType) -> io::Result<()> {
-    let socket_path = get_socket_path(SocketType::Command);
-    let output = write_to_socket(socket_path, gen_dispatch_str(dispatch_type)?.as_bytes()).await;
-
-    match output {
-        Ok(msg) => match msg.as_str() {
-            "ok" => Ok(()),
-            msg => panic!(
-                "Hyprland returned a non `ok` value to the dispatcher, this is usually a error, output:({msg})"
-            ),
-        },
-        Err(error) => panic!("A error occured when running the dispatcher: {error:#?}"),
+#[cfg(feature = "async")]
+pub async fn dispatch(dispatch_type: DispatchType) -> HyprResult<()> {
+    dispatch_request(dispatch_type)?.send().await
+}
+
+/// This function calls multiple dispatchers in a single socket write (blocking)
+///
+/// The dispatchers are joined using Hyprland's batch syntax,
+/// so a argument with a `;` in it returns [`HyprError::InvalidArgument`](crate::shared::HyprError::InvalidArgument)
+/// before anything is sent.
+/// The first message of a dispatcher Hyprland couldn't run is returned as [`HyprError::Command`](crate::shared::HyprError::Command)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::dispatch::{dispatch_batch_blocking, DispatchType};
+/// dispatch_batch_blocking(vec![
+///     DispatchType::Exec("kitty".to_string()),
+///     DispatchType::ToggleFloating,
+/// ])
+/// # }
+/// ```
+pub fn dispatch_batch_blocking(dispatch_types: Vec<DispatchType>) -> HyprResult<()> {
+    batch_request(dispatch_types)?.send_blocking()
+}
+
+/// This function calls multiple dispatchers in a single socket write (async)
+///
+/// The dispatchers are joined using Hyprland's batch syntax,
+/// so a argument with a `;` in it returns [`HyprError::InvalidArgument`](crate::shared::HyprError::InvalidArgument)
+/// before anything is sent.
+/// The first message of a dispatcher Hyprland couldn't run is returned as [`HyprError::Command`](crate::shared::HyprError::Command)
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::dispatch::{dispatch_batch, DispatchType};
+/// dispatch_batch(vec![
+///     DispatchType::Exec("kitty".to_string()),
+///     DispatchType::ToggleFloating,
+/// ])
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn dispatch_batch(dispatch_types: Vec<DispatchType>) -> HyprResult<()> {
+    batch_request(dispatch_types)?.send().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_the_focused_window_to_a_workspace() {
+        let workspace = WorkspaceIdentifier::Name("music".to_string());
+        assert_eq!(
+            DispatchType::MoveFocusedWindowToWorkspace(WorkspaceIdentifier::Id(2)).to_string(),
+            "dispatch movetoworkspace 2"
+        );
+        assert_eq!(
+            DispatchType::MoveFocusedWindowToWorkspaceSilent(workspace).to_string(),
+            "dispatch movetoworkspacesilent name:music"
+        );
+    }
+
+    #[test]
+    fn moving_a_workspace_to_a_monitor() {
+        let monitor = MonitorIdentifier::Name("DP-1".to_string());
+        assert_eq!(
+            DispatchType::MoveWorkspaceToMonitor(WorkspaceIdentifier::Id(3), monitor).to_string(),
+            "dispatch moveworkspacetomonitor 3 DP-1"
+        );
     }
 }
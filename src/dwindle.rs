@@ -0,0 +1,273 @@
+//! # Dwindle module
+//!
+//! This module provides functions for the dwindle layout, for the layout messages it takes
+//! (so they don't have to be written as `layoutmsg` strings) and [`DwindleOptions`](crate::dwindle::DwindleOptions)
+//! for reading its options
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::dispatch::Direction;
+//! use hyprland::dwindle::{preselect_blocking, toggle_split_blocking, DwindleOptions};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let options = DwindleOptions::get()?;
+//!     if !options.preserve_split {
+//!         println!("the splits change when the windows are resized");
+//!     }
+//!     // the next window opens below the active one
+//!     preselect_blocking(Direction::Down)?;
+//!     toggle_split_blocking()
+//! }
+//! ```
+
+use crate::data::{ConfigOption, Keyword, OptionValue};
+use crate::dispatch::{Direction, DispatchType, DwindleLayoutMsg, LayoutMsg};
+use crate::shared::*;
+
+/// This enum holds where new windows are put when a window is split, `dwindle:force_split`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForceSplit {
+    /// The side of the window the cursor is on
+    Mouse,
+    /// Left of the window, or above it when it's split top and bottom
+    LeftOrTop,
+    /// Right of the window, or below it when it's split top and bottom
+    RightOrBottom,
+}
+
+/// This struct holds the options of the dwindle layout, as they were when they were read
+#[derive(Debug, Clone, PartialEq)]
+pub struct DwindleOptions {
+    /// New windows are pseudotiled, `dwindle:pseudotile`
+    pub pseudotile: bool,
+    /// Where new windows are put when a window is split, `dwindle:force_split`
+    pub force_split: ForceSplit,
+    /// Splits stay the way they are when the windows are resized, `dwindle:preserve_split`
+    pub preserve_split: bool,
+    /// Windows are split towards the edge the cursor is closest to, `dwindle:smart_split`
+    pub smart_split: bool,
+    /// Resizing moves the edge closest to the cursor, `dwindle:smart_resizing`
+    pub smart_resizing: bool,
+    /// The direction of a preselect stays until it's changed,
+    /// instead of only being used for the next window, `dwindle:permanent_direction_override`
+    pub permanent_direction_override: bool,
+    /// The active window is split instead of the one under the cursor, `dwindle:use_active_for_splits`
+    pub use_active_for_splits: bool,
+    /// The part of a split the new window takes up (0.1 to 1.9, 1 is half),
+    /// `dwindle:default_split_ratio`
+    pub default_split_ratio: f32,
+    /// How much wider a window can be before it's split left and right instead of top and bottom,
+    /// `dwindle:split_width_multiplier`
+    pub split_width_multiplier: f32,
+}
+
+impl DwindleOptions {
+    /// This method reads the options of the dwindle layout (blocking)
+    pub fn get() -> HyprResult<Self> {
+        Ok(DwindleOptions {
+            pseudotile: as_bool(Keyword::get(ConfigOption::DwindlePseudotile)?)?,
+            force_split: as_force_split(Keyword::get(ConfigOption::DwindleForceSplit)?)?,
+            preserve_split: as_bool(Keyword::get(ConfigOption::DwindlePreserveSplit)?)?,
+            smart_split: as_bool(Keyword::get(ConfigOption::DwindleSmartSplit)?)?,
+            smart_resizing: as_bool(Keyword::get(ConfigOption::DwindleSmartResizing)?)?,
+            permanent_direction_override: as_bool(Keyword::get(
+                ConfigOption::DwindlePermanentDirectionOverride,
+            )?)?,
+            use_active_for_splits: as_bool(Keyword::get(ConfigOption::DwindleUseActiveForSplits)?)?,
+            default_split_ratio: as_float(Keyword::get(ConfigOption::DwindleDefaultSplitRatio)?)?,
+            split_width_multiplier: as_float(Keyword::get(
+                ConfigOption::DwindleSplitWidthMultiplier,
+            )?)?,
+        })
+    }
+
+    /// This method reads the options of the dwindle layout (async)
+    #[cfg(feature = "async")]
+    pub async fn get_async() -> HyprResult<Self> {
+        Ok(DwindleOptions {
+            pseudotile: as_bool(Keyword::get_async(ConfigOption::DwindlePseudotile).await?)?,
+            force_split: as_force_split(
+                Keyword::get_async(ConfigOption::DwindleForceSplit).await?,
+            )?,
+            preserve_split: as_bool(Keyword::get_async(ConfigOption::DwindlePreserveSplit).await?)?,
+            smart_split: as_bool(Keyword::get_async(ConfigOption::DwindleSmartSplit).await?)?,
+            smart_resizing: as_bool(Keyword::get_async(ConfigOption::DwindleSmartResizing).await?)?,
+            permanent_direction_override: as_bool(
+                Keyword::get_async(ConfigOption::DwindlePermanentDirectionOverride).await?,
+            )?,
+            use_active_for_splits: as_bool(
+                Keyword::get_async(ConfigOption::DwindleUseActiveForSplits).await?,
+            )?,
+            default_split_ratio: as_float(
+                Keyword::get_async(ConfigOption::DwindleDefaultSplitRatio).await?,
+            )?,
+            split_width_multiplier: as_float(
+                Keyword::get_async(ConfigOption::DwindleSplitWidthMultiplier).await?,
+            )?,
+        })
+    }
+}
+
+/// This function returns if the dwindle layout is the layout (blocking)
+pub fn is_active_blocking() -> HyprResult<bool> {
+    is_dwindle(Keyword::get(ConfigOption::GeneralLayout)?)
+}
+
+/// This function returns if the dwindle layout is the layout (async)
+#[cfg(feature = "async")]
+pub async fn is_active() -> HyprResult<bool> {
+    is_dwindle(Keyword::get_async(ConfigOption::GeneralLayout).await?)
+}
+
+/// This function sets the side of the active window the next window is opened on (blocking)
+///
+/// It's only used for the next window, unless `dwindle:permanent_direction_override` is set
+pub fn preselect_blocking(direction: Direction) -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(DwindleLayoutMsg::Preselect(direction)))
+}
+
+/// This function sets the side of the active window the next window is opened on (async)
+///
+/// It's only used for the next window, unless `dwindle:permanent_direction_override` is set
+#[cfg(feature = "async")]
+pub async fn preselect(direction: Direction) -> HyprResult<()> {
+    crate::dispatch::dispatch(message(DwindleLayoutMsg::Preselect(direction))).await
+}
+
+/// This function switches the split of the active window between left and right
+/// and top and bottom (blocking)
+pub fn toggle_split_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(DwindleLayoutMsg::ToggleSplit))
+}
+
+/// This function switches the split of the active window between left and right
+/// and top and bottom (async)
+#[cfg(feature = "async")]
+pub async fn toggle_split() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(DwindleLayoutMsg::ToggleSplit)).await
+}
+
+/// This function swaps the two sides of the split of the active window (blocking)
+pub fn swap_split_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(DwindleLayoutMsg::SwapSplit))
+}
+
+/// This function swaps the two sides of the split of the active window (async)
+#[cfg(feature = "async")]
+pub async fn swap_split() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(DwindleLayoutMsg::SwapSplit)).await
+}
+
+/// This function moves the active window to the root of its workspace's tree, so it's split
+/// with all the others (blocking)
+pub fn move_to_root_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(DwindleLayoutMsg::MoveToRoot))
+}
+
+/// This function moves the active window to the root of its workspace's tree, so it's split
+/// with all the others (async)
+#[cfg(feature = "async")]
+pub async fn move_to_root() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(DwindleLayoutMsg::MoveToRoot)).await
+}
+
+/// This function toggles pseudotiling for the active window, which keeps the size it asks for
+/// inside its tile (blocking)
+pub fn toggle_pseudotile_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(DispatchType::TogglePseudo)
+}
+
+/// This function toggles pseudotiling for the active window, which keeps the size it asks for
+/// inside its tile (async)
+#[cfg(feature = "async")]
+pub async fn toggle_pseudotile() -> HyprResult<()> {
+    crate::dispatch::dispatch(DispatchType::TogglePseudo).await
+}
+
+fn message(msg: DwindleLayoutMsg) -> DispatchType {
+    DispatchType::LayoutMsg(LayoutMsg::Dwindle(msg))
+}
+
+fn is_dwindle(keyword: Keyword) -> HyprResult<bool> {
+    match keyword.value {
+        OptionValue::String(layout) => Ok(layout == "dwindle"),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn as_bool(keyword: Keyword) -> HyprResult<bool> {
+    match keyword.value {
+        OptionValue::Int(int @ (0 | 1)) => Ok(int == 1),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn as_float(keyword: Keyword) -> HyprResult<f32> {
+    match keyword.value {
+        OptionValue::Float(float) => Ok(float as f32),
+        OptionValue::Int(int) => Ok(int as f32),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn as_force_split(keyword: Keyword) -> HyprResult<ForceSplit> {
+    match keyword.value {
+        OptionValue::Int(0) => Ok(ForceSplit::Mouse),
+        OptionValue::Int(1) => Ok(ForceSplit::LeftOrTop),
+        OptionValue::Int(2) => Ok(ForceSplit::RightOrBottom),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn unexpected(option: &str, value: &OptionValue) -> HyprError {
+    HyprError::UnexpectedResponse(format!("{option} has the unexpected value {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword(option: &str, value: impl Into<OptionValue>) -> Keyword {
+        Keyword {
+            option: option.to_string(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn layout_messages() {
+        assert_eq!(
+            message(DwindleLayoutMsg::Preselect(Direction::Left)).to_string(),
+            "dispatch layoutmsg preselect l"
+        );
+        assert_eq!(
+            message(DwindleLayoutMsg::ToggleSplit).to_string(),
+            "dispatch layoutmsg togglesplit"
+        );
+        assert_eq!(
+            message(DwindleLayoutMsg::MoveToRoot).to_string(),
+            "dispatch layoutmsg movetoroot"
+        );
+    }
+
+    #[test]
+    fn options_are_parsed() -> HyprResult<()> {
+        assert!(is_dwindle(keyword("general:layout", "dwindle"))?);
+        assert!(!is_dwindle(keyword("general:layout", "master"))?);
+        assert!(as_bool(keyword("dwindle:pseudotile", 1))?);
+        assert!(!as_bool(keyword("dwindle:pseudotile", 0))?);
+        assert!(matches!(
+            as_bool(keyword("dwindle:pseudotile", 2)),
+            Err(HyprError::UnexpectedResponse(_))
+        ));
+        assert_eq!(as_float(keyword("dwindle:default_split_ratio", 1.5))?, 1.5);
+        assert_eq!(as_float(keyword("dwindle:split_width_multiplier", 1))?, 1.0);
+        assert_eq!(
+            as_force_split(keyword("dwindle:force_split", 2))?,
+            ForceSplit::RightOrBottom
+        );
+        assert!(as_force_split(keyword("dwindle:force_split", 3)).is_err());
+        Ok(())
+    }
+}
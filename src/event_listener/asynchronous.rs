@@ -0,0 +1,393 @@
+use crate::shared::*;
+use std::time::{Duration, Instant};
+
+use crate::event_listener::replay::{Recording, ReplayTiming};
+use crate::event_listener::shared::*;
+
+/// This struct is used for adding async event handlers and executing them on events
+/// # The Async Event Listener
+///
+/// This struct holds what you need to create a event listener with async handlers
+///
+/// ## Usage
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::event_listener::AsyncEventListener;
+/// let mut listener = AsyncEventListener::new(); // creates a new listener
+/// // add a event handler which will be ran when this event happens
+/// listener.add_workspace_change_handler(|data| async move { println!("{:#?}", data) });
+/// listener.start_listener().await
+/// # }
+/// ```
+///
+/// ## State
+///
+/// Async handlers have to be `Fn + Send + Sync` and by default run concurrently,
+/// so state shared between them goes in a `Arc<Mutex<_>>` that is cloned into each future
+///
+#[cfg_attr(feature = "tokio", doc = "```rust, no_run")]
+#[cfg_attr(not(feature = "tokio"), doc = "```rust, ignore")]
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::event_listener::AsyncEventListener;
+/// use std::sync::Arc;
+/// use tokio::sync::Mutex;
+/// let mut listener = AsyncEventListener::new();
+///
+/// let windows = Arc::new(Mutex::new(vec![]));
+/// let opened = windows.clone();
+/// listener.add_window_open_handler(move |data| {
+///     let opened = opened.clone();
+///     async move { opened.lock().await.push(data.address) }
+/// });
+/// listener.add_window_close_handler(move |address| {
+///     let windows = windows.clone();
+///     async move { windows.lock().await.retain(|a| a != &address) }
+/// });
+/// listener.start_listener().await
+/// # }
+/// ```
+pub struct EventListener {
+    pub(crate) events: AsyncEvents,
+    pub(crate) filter: EventFilter,
+    pub(crate) reconnect: Option<Reconnect>,
+    pub(crate) handle: ListenerHandle,
+    pub(crate) execution: Execution,
+    pub(crate) coalescer: Coalescer,
+    pub(crate) instance: Option<Instance>,
+    pub(crate) tasks: Tasks,
+}
+
+impl Default for EventListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventListener {
+    /// This method creates a new AsyncEventListener instance
+    ///
+    /// ```rust
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// let mut listener = AsyncEventListener::new();
+    /// ```
+    pub fn new() -> EventListener {
+        EventListener {
+            events: AsyncEvents::default(),
+            filter: None,
+            reconnect: None,
+            handle: ListenerHandle::default(),
+            execution: Execution::default(),
+            coalescer: Coalescer::default(),
+            instance: None,
+            tasks: Tasks::default(),
+        }
+    }
+
+    /// This method adds a handler which executes on every event,
+    /// with the parsed event and the line Hyprland sent for it
+    ///
+    /// Only events the listener delivers are passed to it (see `set_event_filter`),
+    /// the synthetic [`Event::Reconnected`](crate::event_listener::Event::Reconnected) isn't
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_raw_event_handler(|raw| async move { println!("{} was parsed as {:?}", raw.line, raw.event) });
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn add_raw_event_handler<Fut: AsyncHandlerFuture>(
+        &mut self,
+        f: impl Fn(RawEvent) -> Fut + Send + Sync + 'static,
+    ) -> HandlerId {
+        let id = HandlerId::next();
+        self.events
+            .raw_events
+            .push((id, Box::new(move |data| Box::pin(f(data)))));
+        id
+    }
+
+    /// This method removes a handler, using the id returned when it was added
+    ///
+    /// Returns `false` if the listener has no handler with that id.
+    /// To remove handlers while the listener is running use [`handle`](Self::handle)
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// let mut listener = AsyncEventListener::new();
+    /// let id = listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// listener.remove_handler(id);
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn remove_handler(&mut self, id: HandlerId) -> bool {
+        self.events.remove(id)
+    }
+
+    /// This method returns a handle that can control the listener while it's running,
+    /// like removing handlers from another thread or from inside a handler
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// let mut listener = AsyncEventListener::new();
+    /// let handle = listener.handle();
+    /// let id = listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// // later, from anywhere
+    /// handle.remove_handler(id);
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn handle(&self) -> ListenerHandle {
+        self.handle.clone()
+    }
+
+    /// This method sets which events the listener delivers,
+    /// every other event is skipped without being parsed
+    ///
+    /// By default every event is delivered
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{AsyncEventListener, EventKind};
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// listener.set_event_filter([EventKind::WorkspaceChanged]);
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn set_event_filter(&mut self, kinds: impl IntoIterator<Item = EventKind>) {
+        self.filter = Some(kinds.into_iter().collect());
+    }
+
+    /// This method makes the listener reconnect when the event socket is closed or fails,
+    /// instead of returning
+    ///
+    /// Every reconnect is delivered as [`Event::Reconnected`](crate::event_listener::Event::Reconnected),
+    /// events sent while the listener was disconnected are lost.
+    /// The socket path is resolved again on every attempt
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{AsyncEventListener, Reconnect};
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_reconnect_handler(|_| async { println!("reconnected") });
+    /// listener.set_auto_reconnect(Reconnect::default());
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn set_auto_reconnect(&mut self, options: Reconnect) {
+        self.reconnect = Some(options);
+    }
+
+    /// This method makes the listener listen to the events of a specific Hyprland instance,
+    /// instead of the one this program runs in
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// use hyprland::shared::Instance;
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.set_instance(Instance::from_signature("v0.34.0_1700000000"));
+    /// listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn set_instance(&mut self, instance: Instance) {
+        self.instance = Some(instance);
+    }
+
+    /// This method sets how the handlers are ran, by default they run concurrently
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{AsyncEventListener, Execution};
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// // handlers run one after another, in the order the events arrived
+    /// listener.set_execution(Execution::Sequential);
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn set_execution(&mut self, execution: Execution) {
+        self.execution = execution;
+    }
+
+    /// This method makes the listener coalesce bursts of events of the given kinds,
+    /// so only the latest event of a kind within `window` is delivered
+    ///
+    /// The first event of a burst is held back for `window` and replaced by newer events of its kind,
+    /// so coalesced events are delivered up to `window` late, possibly after events of other kinds
+    /// that came in later. The raw handlers only see the delivered events
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{AsyncEventListener, EventKind};
+    /// use std::time::Duration;
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_active_window_change_handler(|data| async move { println!("{data:?}") });
+    /// listener.set_coalescing(Duration::from_millis(50), [EventKind::ActiveWindowChanged]);
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub fn set_coalescing(&mut self, window: Duration, kinds: impl IntoIterator<Item = EventKind>) {
+        self.coalescer = Coalescer::new(window, kinds.into_iter().collect());
+    }
+
+    /// This method replays a recording through the handlers, instead of listening to Hyprland
+    ///
+    /// Events are filtered, coalesced and delivered the same way as by `start_listener`,
+    /// using the recorded times, but a line that can't be parsed returns a error
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{AsyncEventListener, Recording, ReplayTiming};
+    /// let recording = Recording::open("events.log")?;
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// listener.replay(&recording, ReplayTiming::Recorded).await
+    /// # }
+    /// ```
+    pub async fn replay(&mut self, recording: &Recording, timing: ReplayTiming) -> HyprResult<()> {
+        let start = Instant::now();
+        for recorded in recording.lines() {
+            let at = start + recorded.offset;
+            while let Some(deadline) = self.coalescer.next_deadline().filter(|due| *due <= at) {
+                if let Some(wait) = timing.delay(deadline) {
+                    self.tasks.alongside(crate::runtime::sleep(wait)).await;
+                }
+                self.deliver(None, deadline).await;
+            }
+            if let Some(wait) = timing.delay(at) {
+                self.tasks.alongside(crate::runtime::sleep(wait)).await;
+            }
+            let parsed = parse_event(&recorded.line, &self.filter)?;
+            self.deliver(parsed.map(|event| (recorded.line.clone(), event)), at)
+                .await;
+        }
+        for (line, event) in self.coalescer.take_all() {
+            self.event_executor(Some(&line), &event).await;
+        }
+        self.tasks.finish().await;
+        Ok(())
+    }
+
+    /// Runs the handlers for the coalesced events that are due and for the received event,
+    /// unless it's held back to be coalesced
+    async fn deliver(&mut self, received: Option<(String, Event)>, now: Instant) {
+        let mut ready = self.coalescer.take_due(now);
+        if let Some((line, event)) = received {
+            ready.extend(self.coalescer.push(line, event, now));
+        }
+        for (line, event) in ready {
+            self.event_executor(Some(&line), &event).await;
+        }
+    }
+
+    /// This method starts the event listener
+    ///
+    /// This should be ran after all of your handlers are defined,
+    /// how the handlers are ran is chosen with [`set_execution`](Self::set_execution)
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::AsyncEventListener;
+    /// let mut listener = AsyncEventListener::new();
+    /// listener.add_workspace_change_handler(|id| async move { println!("changed workspace to {id:?}") });
+    /// listener.start_listener().await
+    /// # }
+    /// ```
+    pub async fn start_listener(&mut self) -> HyprResult<()> {
+        let socket_path = instance_socket_path(self.instance.as_ref(), SocketType::Listener)?;
+
+        let mut stream = crate::runtime::connect(&socket_path)
+            .await
+            .map_err(|error| connect_error(&socket_path, error))?;
+
+        loop {
+            let mut lines = crate::runtime::Lines::new(stream);
+
+            loop {
+                let deadline = self.coalescer.next_deadline();
+                let wake = next_wake(&self.handle, deadline, &mut lines);
+                let received = match self.tasks.alongside(wake).await {
+                    Wake::Stopped => break,
+                    Wake::Due => None,
+                    Wake::Line(Ok(Some(line))) => {
+                        parse_event_or_skip(&line, &self.filter).map(|event| (line, event))
+                    }
+                    Wake::Line(Ok(None)) => break,
+                    Wake::Line(Err(_)) if self.reconnect.is_some() => break,
+                    Wake::Line(Err(error)) => {
+                        self.tasks.finish().await;
+                        return Err(error.into());
+                    }
+                };
+                self.deliver(received, Instant::now()).await;
+            }
+
+            if self.handle.take_stop() {
+                self.tasks.finish().await;
+                return Ok(());
+            }
+            for (line, event) in self.coalescer.take_all() {
+                self.event_executor(Some(&line), &event).await;
+            }
+            let reconnected = match self.reconnect {
+                Some(options) => reconnect(options, self.instance.as_ref()),
+                None => {
+                    self.tasks.finish().await;
+                    return Ok(());
+                }
+            };
+            stream = self.tasks.alongside(reconnected).await?;
+            if filter_allows(&self.filter, EventKind::Reconnected) {
+                self.event_executor(None, &Event::Reconnected).await;
+            }
+        }
+    }
+}
+
+macro_rules! gen_listener {
+    ($($field:ident: $ty:ty => $method:ident, $pat:pat => $val:expr, $when:literal;)*) => {
+        impl EventListener {
+            $(
+                #[doc = concat!(
+                    "This method adds a event to the listener which executes ", $when, "\n\n",
+                    "```rust, no_run\n",
+                    "# async fn function() -> hyprland::shared::HyprResult<()> {\n",
+                    "use hyprland::event_listener::AsyncEventListener;\n",
+                    "let mut listener = AsyncEventListener::new();\n",
+                    "listener.", stringify!($method), "(|data| async move { println!(\"{data:?}\") });\n",
+                    "listener.start_listener().await\n",
+                    "# }\n",
+                    "```",
+                )]
+                pub fn $method<Fut: AsyncHandlerFuture>(
+                    &mut self,
+                    f: impl Fn($ty) -> Fut + Send + Sync + 'static,
+                ) -> HandlerId {
+                    let id = HandlerId::next();
+                    self.events.$field.push((id, Box::new(move |data| Box::pin(f(data)))));
+                    id
+                }
+            )*
+
+            async fn event_executor(&mut self, line: Option<&str>, event: &Event) {
+                for id in self.handle.take_removed() {
+                    self.events.remove(id);
+                }
+                if let Some(line) = line {
+                    for (_, item) in self.events.raw_events.iter_mut() {
+                        self.tasks.run(self.execution, item(raw_event(line, event))).await;
+                    }
+                }
+                #[allow(unreachable_patterns)]
+                match event {
+                    $($pat => {
+                        for (_, item) in self.events.$field.iter() {
+                            self.tasks.run(self.execution, item($val)).await;
+                        }
+                    })*
+                    // events without handlers (yet)
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+event_handlers!(gen_listener);
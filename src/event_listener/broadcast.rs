@@ -0,0 +1,151 @@
+use futures_util::StreamExt;
+#[cfg(feature = "tokio")]
+use tokio::sync::broadcast::{self, Receiver, Sender};
+#[cfg(feature = "tokio")]
+use tokio::task::JoinHandle;
+
+use crate::event_listener::shared::*;
+use crate::event_listener::stream::event_stream;
+use crate::shared::HyprResult;
+
+/// This struct shares one event socket connection between any number of consumers
+///
+/// Every subscriber gets its own copy of every event, through a broadcast channel.
+/// If a subscriber falls more than `capacity` events behind, it skips the oldest ones
+/// and gets a error saying how many it skipped
+///
+/// With tokio the channel is a `tokio::sync::broadcast` channel (the error is `Lagged`)
+/// and the events are forwarded by a task. With async-io it's a `async_broadcast` channel
+/// (the error is `Overflowed`) and the events are forwarded by a thread of the broadcaster
+///
+#[cfg_attr(feature = "tokio", doc = "```rust, no_run")]
+#[cfg_attr(not(feature = "tokio"), doc = "```rust, ignore")]
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::event_listener::{Event, EventBroadcaster};
+/// let broadcaster = EventBroadcaster::new(64).await?;
+///
+/// let mut workspaces = broadcaster.subscribe();
+/// tokio::spawn(async move {
+///     while let Ok(event) = workspaces.recv().await {
+///         if let Event::WorkspaceChanged(id) = event {
+///             println!("changed workspace to {id:?}");
+///         }
+///     }
+/// });
+///
+/// let mut windows = broadcaster.subscribe();
+/// while let Ok(event) = windows.recv().await {
+///     if let Event::ActiveWindowChanged(data) = event {
+///         println!("active window changed: {data:?}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventBroadcaster {
+    #[cfg(feature = "tokio")]
+    sender: Sender<Event>,
+    #[cfg(feature = "tokio")]
+    task: JoinHandle<HyprResult<()>>,
+    /// Keeps the channel open while there are no subscribers
+    #[cfg(not(feature = "tokio"))]
+    receiver: async_broadcast::InactiveReceiver<Event>,
+    #[cfg(not(feature = "tokio"))]
+    thread: std::thread::JoinHandle<HyprResult<()>>,
+    /// Stops the thread forwarding the events
+    #[cfg(not(feature = "tokio"))]
+    stop: ListenerHandle,
+}
+
+#[cfg(feature = "tokio")]
+impl EventBroadcaster {
+    /// This method connects to the event socket and starts forwarding its events,
+    /// `capacity` is how many events are kept for subscribers that fall behind
+    ///
+    /// Dropping the broadcaster closes the connection
+    pub async fn new(capacity: usize) -> HyprResult<Self> {
+        let (sender, _) = broadcast::channel(capacity);
+        let events = event_stream().await?;
+
+        let task_sender = sender.clone();
+        let task = tokio::spawn(async move {
+            let mut events = Box::pin(events);
+            while let Some(event) = events.next().await {
+                // sending only fails when there are no subscribers right now, which is fine
+                let _ = task_sender.send(event?);
+            }
+            Ok(())
+        });
+
+        Ok(EventBroadcaster { sender, task })
+    }
+
+    /// This method returns a new receiver, which gets every event sent after it was created
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    /// This method returns if the event socket was closed (or failed)
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl EventBroadcaster {
+    /// This method connects to the event socket and starts forwarding its events,
+    /// `capacity` is how many events are kept for subscribers that fall behind
+    ///
+    /// Dropping the broadcaster closes the connection
+    pub async fn new(capacity: usize) -> HyprResult<Self> {
+        let (mut sender, receiver) = async_broadcast::broadcast(capacity);
+        sender.set_overflow(true);
+        sender.set_await_active(false);
+        let events = event_stream().await?;
+
+        let stop = ListenerHandle::default();
+        let thread_stop = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name("hyprland-events".to_string())
+            .spawn(move || {
+                let forward = async move {
+                    let mut events = Box::pin(events);
+                    while let Some(event) = events.next().await {
+                        // sending only fails when there are no subscribers right now, which is fine
+                        let _ = sender.try_broadcast(event?);
+                    }
+                    Ok(())
+                };
+                let stopped = async move {
+                    thread_stop.stopped().await;
+                    Ok(())
+                };
+                async_io::block_on(futures_lite::future::or(forward, stopped))
+            })?;
+
+        Ok(EventBroadcaster {
+            receiver: receiver.deactivate(),
+            thread,
+            stop,
+        })
+    }
+
+    /// This method returns a new receiver, which gets every event sent after it was created
+    pub fn subscribe(&self) -> async_broadcast::Receiver<Event> {
+        self.receiver.activate_cloned()
+    }
+
+    /// This method returns if the event socket was closed (or failed)
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+}
+
+impl Drop for EventBroadcaster {
+    fn drop(&mut self) {
+        #[cfg(feature = "tokio")]
+        self.task.abort();
+        #[cfg(not(feature = "tokio"))]
+        self.stop.stop();
+    }
+}
@@ -1,6 +1,8 @@
 use crate::shared::*;
 use std::io;
+use std::time::{Duration, Instant};
 
+use crate::event_listener::replay::{Recording, ReplayTiming};
 use crate::event_listener::shared::*;
 
 /// This struct is used for adding event handlers and executing them on events
@@ -17,8 +19,36 @@ use crate::event_listener::shared::*;
 /// listener.add_workspace_change_handler(|data| println!("{:#?}", data));
 /// listener.start_listener_blocking(); // or `.start_listener().await` if async
 /// ```
+///
+/// ## State
+///
+/// Handlers are `FnMut`, so they can own and change the values they capture,
+/// to share state between handlers capture a `Rc<RefCell<_>>`
+///
+/// ```rust, no_run
+/// use hyprland::event_listener::EventListener;
+/// use std::{cell::RefCell, rc::Rc};
+/// let mut listener = EventListener::new();
+///
+/// let mut changes = 0;
+/// listener.add_workspace_change_handler(move |_| {
+///     changes += 1;
+///     println!("the workspace was changed {changes} times");
+/// });
+///
+/// let windows = Rc::new(RefCell::new(vec![]));
+/// let opened = windows.clone();
+/// listener.add_window_open_handler(move |data| opened.borrow_mut().push(data.address));
+/// listener.add_window_close_handler(move |address| windows.borrow_mut().retain(|a| a != &address));
+/// listener.start_listener_blocking();
+/// ```
 pub struct EventListener {
     pub(crate) events: Events,
+    pub(crate) filter: EventFilter,
+    pub(crate) reconnect: Option<Reconnect>,
+    pub(crate) handle: ListenerHandle,
+    pub(crate) coalescer: Coalescer,
+    pub(crate) instance: Option<Instance>,
 }
 
 impl Default for EventListener {
@@ -36,190 +66,173 @@ impl EventListener {
     /// ```
     pub fn new() -> EventListener {
         EventListener {
-            events: Events {
-                workspace_changed_events: vec![],
-                workspace_added_events: vec![],
-                workspace_destroyed_events: vec![],
-                active_monitor_changed_events: vec![],
-                active_window_changed_events: vec![],
-                fullscreen_state_changed_events: vec![],
-                monitor_removed_events: vec![],
-                monitor_added_events: vec![],
-            },
+            events: Events::default(),
+            filter: None,
+            reconnect: None,
+            handle: ListenerHandle::default(),
+            coalescer: Coalescer::default(),
+            instance: None,
         }
     }
 
-    /// This method adds a event to the listener which executes on workspace change
+    /// This method adds a handler which executes on every event,
+    /// with the parsed event and the line Hyprland sent for it
     ///
+    /// Only events the listener delivers are passed to it (see `set_event_filter`),
+    /// the synthetic [`Event::Reconnected`](crate::event_listener::Event::Reconnected) isn't
     /// ```rust, no_run
     /// use hyprland::event_listener::EventListener;
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+    /// listener.add_raw_event_handler(|raw| println!("{} was parsed as {:?}", raw.line, raw.event));
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_workspace_change_handler(&mut self, f: impl Fn(WorkspaceType) + 'static) {
+    pub fn add_raw_event_handler(&mut self, f: impl FnMut(RawEvent) + 'static) -> HandlerId {
+        let id = HandlerId::next();
         self.events
-            .workspace_changed_events
-            .push(EventTypes::Regular(Box::new(f)));
+            .raw_events
+            .push((id, EventTypes::Regular(Box::new(f))));
+        id
     }
 
-    /// This method add a event to the listener which executes when a new workspace is created
+    /// This method removes a handler, using the id returned when it was added
     ///
+    /// Returns `false` if the listener has no handler with that id.
+    /// To remove handlers while the listener is running use [`handle`](Self::handle)
     /// ```rust, no_run
     /// use hyprland::event_listener::EventListener;
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_added_handler(|id| println!("workspace {id:?} was added"));
+    /// let id = listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+    /// listener.remove_handler(id);
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_workspace_added_handler(&mut self, f: impl Fn(WorkspaceType) + 'static) {
-        self.events
-            .workspace_added_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn remove_handler(&mut self, id: HandlerId) -> bool {
+        self.events.remove(id)
     }
 
-    /// This method add a event to the listener which executes when a new workspace is created
-    ///
+    /// This method returns a handle that can control the listener while it's running,
+    /// like removing handlers from another thread or from inside a handler
     /// ```rust, no_run
     /// use hyprland::event_listener::EventListener;
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_destroy_handler(|id| println!("workspace {id:?} was destroyed"));
+    /// let handle = listener.handle();
+    /// let id = listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+    /// // later, from anywhere
+    /// handle.remove_handler(id);
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_workspace_destroy_handler(&mut self, f: impl Fn(WorkspaceType) + 'static) {
-        self.events
-            .workspace_destroyed_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn handle(&self) -> ListenerHandle {
+        self.handle.clone()
     }
 
-    /// This method add a event to the listener which executes when the active monitor is changed
+    /// This method sets which events the listener delivers,
+    /// every other event is skipped without being parsed
     ///
+    /// By default every event is delivered
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListener;
+    /// use hyprland::event_listener::{EventKind, EventListener};
     /// let mut listener = EventListener::new();
-    /// listener.add_active_monitor_change_handler(|data| println!("Active Monitor changed: {data:#?}"));
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+    /// listener.set_event_filter([EventKind::WorkspaceChanged]);
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_active_monitor_change_handler(&mut self, f: impl Fn(MonitorEventData) + 'static) {
-        self.events
-            .active_monitor_changed_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn set_event_filter(&mut self, kinds: impl IntoIterator<Item = EventKind>) {
+        self.filter = Some(kinds.into_iter().collect());
     }
 
-    /// This method add a event to the listener which executes when the active window is changed
+    /// This method makes the listener reconnect when the event socket is closed or fails,
+    /// instead of returning
     ///
+    /// Every reconnect is delivered as [`Event::Reconnected`](crate::event_listener::Event::Reconnected),
+    /// events sent while the listener was disconnected are lost.
+    /// The socket path is resolved again on every attempt
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListener;
+    /// use hyprland::event_listener::{EventListener, Reconnect};
     /// let mut listener = EventListener::new();
-    /// listener.add_active_window_change_handler(|data| println!("Active window changed: {data:#?}"));
+    /// listener.add_reconnect_handler(|_| println!("reconnected"));
+    /// listener.set_auto_reconnect(Reconnect::default());
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_active_window_change_handler(
-        &mut self,
-        f: impl Fn(Option<WindowEventData>) + 'static,
-    ) {
-        self.events
-            .active_window_changed_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn set_auto_reconnect(&mut self, options: Reconnect) {
+        self.reconnect = Some(options);
     }
 
-    /// This method add a event to the listener which executes when the active monitor is changed
-    ///
+    /// This method makes the listener listen to the events of a specific Hyprland instance,
+    /// instead of the one this program runs in
     /// ```rust, no_run
     /// use hyprland::event_listener::EventListener;
+    /// use hyprland::shared::Instance;
     /// let mut listener = EventListener::new();
-    /// listener.add_fullscreen_state_change_handler(|state| println!("Fullscreen is on: {state}"));
+    /// listener.set_instance(Instance::from_signature("v0.34.0_1700000000"));
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_fullscreen_state_change_handler(&mut self, f: impl Fn(bool) + 'static) {
-        self.events
-            .fullscreen_state_changed_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn set_instance(&mut self, instance: Instance) {
+        self.instance = Some(instance);
     }
 
-    /// This method add a event to the listener which executes when a new monitor is added
+    /// This method makes the listener coalesce bursts of events of the given kinds,
+    /// so only the latest event of a kind within `window` is delivered
     ///
+    /// The first event of a burst is held back for `window` and replaced by newer events of its kind,
+    /// so coalesced events are delivered up to `window` late, possibly after events of other kinds
+    /// that came in later. The raw handlers only see the delivered events
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListener;
+    /// use hyprland::event_listener::{EventKind, EventListener};
+    /// use std::time::Duration;
     /// let mut listener = EventListener::new();
-    /// listener.add_monitor_added_handler(|data| println!("Monitor added: {data}"));
+    /// listener.add_active_window_change_handler(|data| println!("{data:?}"));
+    /// listener.set_coalescing(Duration::from_millis(50), [EventKind::ActiveWindowChanged]);
     /// listener.start_listener_blocking();
     /// ```
-    pub fn add_monitor_added_handler(&mut self, f: impl Fn(String) + 'static) {
-        self.events
-            .monitor_added_events
-            .push(EventTypes::Regular(Box::new(f)));
+    pub fn set_coalescing(&mut self, window: Duration, kinds: impl IntoIterator<Item = EventKind>) {
+        self.coalescer = Coalescer::new(window, kinds.into_iter().collect());
     }
 
-    /// This method add a event to the listener which executes when a monitor is removed
+    /// This method replays a recording through the handlers, instead of listening to Hyprland
     ///
-    /// ```rust, no_run
-    /// use hyprland::event_listener::EventListener;
+    /// Events are filtered, coalesced and delivered the same way as by `start_listener`,
+    /// using the recorded times, but a line that can't be parsed returns a error
+    /// ```rust
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{EventListener, Recording, ReplayTiming};
+    /// let recording = Recording::from_reader("0\tworkspace>>2\n".as_bytes())?;
     /// let mut listener = EventListener::new();
-    /// listener.add_monitor_removed_handler(|data| println!("Monitor removed: {data}"));
-    /// listener.start_listener_blocking();
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+    /// listener.replay(&recording, ReplayTiming::Instant)?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn add_monitor_removed_handler(&mut self, f: impl Fn(String) + 'static) {
-        self.events
-            .monitor_removed_events
-            .push(EventTypes::Regular(Box::new(f)));
-    }
-
-    fn event_executor(&self, event: &Event) {
-        match event {
-            Event::WorkspaceChanged(id) => {
-                let events = &self.events.workspace_changed_events;
-                for item in events.iter() {
-                    execute_closure(item, id.clone());
+    pub fn replay(&mut self, recording: &Recording, timing: ReplayTiming) -> HyprResult<()> {
+        let start = Instant::now();
+        for recorded in recording.lines() {
+            let at = start + recorded.offset;
+            while let Some(deadline) = self.coalescer.next_deadline().filter(|due| *due <= at) {
+                if let Some(wait) = timing.delay(deadline) {
+                    std::thread::sleep(wait);
                 }
+                self.deliver(None, deadline);
             }
-            Event::WorkspaceAdded(id) => {
-                let events = &self.events.workspace_added_events;
-                for item in events.iter() {
-                    execute_closure(item, id.clone());
-                }
-            }
-            Event::WorkspaceDeleted(id) => {
-                let events = &self.events.workspace_destroyed_events;
-                for item in events.iter() {
-                    execute_closure(item, id.clone());
-                }
-            }
-            Event::ActiveMonitorChanged(MonitorEventData(monitor, id)) => {
-                let events = &self.events.active_monitor_changed_events;
-                for item in events.iter() {
-                    execute_closure(item, MonitorEventData(monitor.clone(), id.clone()));
-                }
-            }
-            Event::ActiveWindowChanged(Some(WindowEventData(class, title))) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    execute_closure(item, Some(WindowEventData(class.clone(), title.clone())));
-                }
-            }
-            Event::ActiveWindowChanged(None) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    execute_closure(item, None);
-                }
-            }
-            Event::FullscreenStateChanged(bool) => {
-                let events = &self.events.fullscreen_state_changed_events;
-                for item in events.iter() {
-                    execute_closure(item, *bool);
-                }
-            }
-            Event::MonitorAdded(monitor) => {
-                let events = &self.events.monitor_added_events;
-                for item in events.iter() {
-                    execute_closure(item, monitor.clone());
-                }
-            }
-            Event::MonitorRemoved(monitor) => {
-                let events = &self.events.monitor_removed_events;
-                for item in events.iter() {
-                    execute_closure(item, monitor.clone());
-                }
+            if let Some(wait) = timing.delay(at) {
+                std::thread::sleep(wait);
             }
+            let parsed = parse_event(&recorded.line, &self.filter)?;
+            self.deliver(parsed.map(|event| (recorded.line.clone(), event)), at);
+        }
+        for (line, event) in self.coalescer.take_all() {
+            self.event_executor(Some(&line), &event);
+        }
+        Ok(())
+    }
+
+    /// Runs the handlers for the coalesced events that are due and for the received event,
+    /// unless it's held back to be coalesced
+    fn deliver(&mut self, received: Option<(String, Event)>, now: Instant) {
+        let mut ready = self.coalescer.take_due(now);
+        if let Some((line, event)) = received {
+            ready.extend(self.coalescer.push(line, event, now));
+        }
+        for (line, event) in ready {
+            self.event_executor(Some(&line), &event);
         }
     }
 
@@ -227,7 +240,7 @@ impl EventListener {
     ///
     /// This should be ran after all of your handlers are defined
     /// ```rust, no_run
-    /// # async fn function() -> std::io::Result<()> {
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListener;
     /// let mut listener = EventListener::new();
     /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
@@ -235,39 +248,46 @@ impl EventListener {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn start_listener(&self) -> io::Result<()> {
-        use tokio::io::AsyncReadExt;
-        use tokio::net::UnixStream;
-
-        let socket_path = get_socket_path(SocketType::Listener);
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    pub async fn start_listener(&mut self) -> HyprResult<()> {
+        let socket_path = instance_socket_path(self.instance.as_ref(), SocketType::Listener)?;
 
-        let mut stream = UnixStream::connect(socket_path).await?;
+        let mut stream = crate::runtime::connect(&socket_path)
+            .await
+            .map_err(|error| connect_error(&socket_path, error))?;
 
         loop {
-            let mut buf = [0; 2048];
+            let mut lines = crate::runtime::Lines::new(stream);
 
-            let num_read = stream.read(&mut buf).await?;
-            if num_read == 0 {
-                break;
+            loop {
+                let deadline = self.coalescer.next_deadline();
+                let received = match next_wake(&self.handle, deadline, &mut lines).await {
+                    Wake::Stopped => break,
+                    Wake::Due => None,
+                    Wake::Line(Ok(Some(line))) => {
+                        parse_event_or_skip(&line, &self.filter).map(|event| (line, event))
+                    }
+                    Wake::Line(Ok(None)) => break,
+                    Wake::Line(Err(_)) if self.reconnect.is_some() => break,
+                    Wake::Line(Err(error)) => return Err(error.into()),
+                };
+                self.deliver(received, Instant::now());
             }
-            let buf = &buf[..num_read];
 
-            let string = match String::from_utf8(buf.to_vec()) {
-                Ok(str) => str,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
-
-            let parsed: Vec<Event> = match event_parser(string) {
-                Ok(vec) => vec,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
-
-            for event in parsed.iter() {
-                self.event_executor(event);
+            if self.handle.take_stop() {
+                return Ok(());
+            }
+            for (line, event) in self.coalescer.take_all() {
+                self.event_executor(Some(&line), &event);
+            }
+            match self.reconnect {
+                Some(options) => stream = reconnect(options, self.instance.as_ref()).await?,
+                None => return Ok(()),
+            }
+            if filter_allows(&self.filter, EventKind::Reconnected) {
+                self.event_executor(None, &Event::Reconnected);
             }
         }
-
-        Ok(())
     }
 
     /// This method starts the event listener (blocking)
@@ -279,38 +299,92 @@ impl EventListener {
     /// listener.add_workspace_change_handler(&|id| println!("changed workspace to {id:?}"));
     /// listener.start_listener_blocking();
     /// ```
-    pub fn start_listener_blocking(self) -> io::Result<()> {
-        use io::prelude::*;
+    pub fn start_listener_blocking(mut self) -> HyprResult<()> {
         use std::os::unix::net::UnixStream;
 
-        let socket_path = get_socket_path(SocketType::Listener);
+        let socket_path = instance_socket_path(self.instance.as_ref(), SocketType::Listener)?;
 
-        let mut stream = UnixStream::connect(socket_path)?;
+        let mut stream = UnixStream::connect(&socket_path)
+            .map_err(|error| connect_error(&socket_path, error))?;
 
         loop {
-            let mut buf = [0; 2048];
-
-            let num_read = stream.read(&mut buf)?;
-            if num_read == 0 {
-                break;
+            self.handle.set_socket(stream.try_clone().ok());
+            let mut reader = io::BufReader::new(stream);
+            let mut buf = vec![];
+            while !self.handle.is_stopped() {
+                let deadline = self.coalescer.next_deadline();
+                let received = match read_line_blocking(&mut reader, &mut buf, deadline) {
+                    Ok(NextLine::Line(line)) => {
+                        parse_event_or_skip(&line, &self.filter).map(|event| (line, event))
+                    }
+                    Ok(NextLine::TimedOut) => None,
+                    Ok(NextLine::Closed) => break,
+                    Err(_) if self.reconnect.is_some() => break,
+                    Err(error) => return Err(error.into()),
+                };
+                self.deliver(received, Instant::now());
             }
-            let buf = &buf[..num_read];
+            self.handle.set_socket(None);
 
-            let string = match String::from_utf8(buf.to_vec()) {
-                Ok(str) => str,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+            if self.handle.take_stop() {
+                return Ok(());
+            }
+            for (line, event) in self.coalescer.take_all() {
+                self.event_executor(Some(&line), &event);
+            }
+            match self.reconnect {
+                Some(options) => stream = reconnect_blocking(options, self.instance.as_ref())?,
+                None => return Ok(()),
+            }
+            if filter_allows(&self.filter, EventKind::Reconnected) {
+                self.event_executor(None, &Event::Reconnected);
+            }
+        }
+    }
+}
 
-            let parsed: Vec<Event> = match event_parser(string) {
-                Ok(vec) => vec,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+macro_rules! gen_listener {
+    ($($field:ident: $ty:ty => $method:ident, $pat:pat => $val:expr, $when:literal;)*) => {
+        impl EventListener {
+            $(
+                #[doc = concat!(
+                    "This method adds a event to the listener which executes ", $when, "\n\n",
+                    "```rust, no_run\n",
+                    "use hyprland::event_listener::EventListener;\n",
+                    "let mut listener = EventListener::new();\n",
+                    "listener.", stringify!($method), "(|data| println!(\"{data:?}\"));\n",
+                    "listener.start_listener_blocking();\n",
+                    "```",
+                )]
+                pub fn $method(&mut self, f: impl FnMut($ty) + 'static) -> HandlerId {
+                    let id = HandlerId::next();
+                    self.events.$field.push((id, EventTypes::Regular(Box::new(f))));
+                    id
+                }
+            )*
 
-            for event in parsed.iter() {
-                self.event_executor(event);
+            fn event_executor(&mut self, line: Option<&str>, event: &Event) {
+                for id in self.handle.take_removed() {
+                    self.events.remove(id);
+                }
+                if let Some(line) = line {
+                    for (_, item) in self.events.raw_events.iter_mut() {
+                        execute_closure(item, raw_event(line, event));
+                    }
+                }
+                #[allow(unreachable_patterns)]
+                match event {
+                    $($pat => {
+                        for (_, item) in self.events.$field.iter_mut() {
+                            execute_closure(item, $val);
+                        }
+                    })*
+                    // events without handlers (yet)
+                    _ => {}
+                }
             }
         }
-
-        Ok(())
-    }
+    };
 }
+
+event_handlers!(gen_listener);
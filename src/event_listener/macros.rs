@@ -0,0 +1,143 @@
+/// This internal macro holds the table of every event handler
+///
+/// Every entry is `field: Type => add_method, Pattern => value, "when it executes";`,
+/// where `Pattern` matches a `&Event` and `value` is what the handlers are called with.
+/// The table is passed to `$callback`, which generates the handler storage or listener methods
+macro_rules! event_handlers {
+    ($callback:ident) => {
+        $callback! {
+            workspace_changed_events: WorkspaceType => add_workspace_change_handler,
+                Event::WorkspaceChanged(data) => data.clone(),
+                "on workspace change";
+            workspace_added_events: WorkspaceType => add_workspace_added_handler,
+                Event::WorkspaceAdded(data) => data.clone(),
+                "when a new workspace is created";
+            workspace_destroyed_events: WorkspaceType => add_workspace_destroy_handler,
+                Event::WorkspaceDeleted(data) => data.clone(),
+                "when a workspace is destroyed";
+            workspace_moved_events: MonitorEventData => add_workspace_moved_handler,
+                Event::WorkspaceMoved(data) => data.clone(),
+                "when a workspace is moved to another monitor";
+            workspace_changed_v2_events: WorkspaceV2EventData => add_workspace_change_v2_handler,
+                Event::WorkspaceChangedV2(data) => data.clone(),
+                "on workspace change, with the workspace id and name";
+            workspace_added_v2_events: WorkspaceV2EventData => add_workspace_added_v2_handler,
+                Event::WorkspaceAddedV2(data) => data.clone(),
+                "when a new workspace is created, with the workspace id and name";
+            workspace_destroyed_v2_events: WorkspaceV2EventData => add_workspace_destroy_v2_handler,
+                Event::WorkspaceDeletedV2(data) => data.clone(),
+                "when a workspace is destroyed, with the workspace id and name";
+            workspace_moved_v2_events: WorkspaceMovedV2EventData => add_workspace_moved_v2_handler,
+                Event::WorkspaceMovedV2(data) => data.clone(),
+                "when a workspace is moved to another monitor, with the workspace id and name";
+            workspace_renamed_events: WorkspaceV2EventData => add_workspace_rename_handler,
+                Event::WorkspaceRenamed(data) => data.clone(),
+                "when a workspace is renamed, with the workspace id and new name";
+            active_special_changed_events: ActiveSpecialEventData => add_active_special_change_handler,
+                Event::ActiveSpecialChanged(data) => data.clone(),
+                "when a special workspace is shown or hidden, with its name and monitor";
+            active_monitor_changed_events: MonitorEventData => add_active_monitor_change_handler,
+                Event::ActiveMonitorChanged(data) => data.clone(),
+                "when the active monitor is changed";
+            active_monitor_changed_v2_events: MonitorFocusV2EventData => add_active_monitor_change_v2_handler,
+                Event::ActiveMonitorChangedV2(data) => data.clone(),
+                "when the active monitor is changed, with the id of its workspace";
+            active_window_changed_events: Option<WindowEventData> => add_active_window_change_handler,
+                Event::ActiveWindowChanged(data) => data.clone(),
+                "when the active window is changed";
+            active_window_changed_v2_events: Option<Address> => add_active_window_change_v2_handler,
+                Event::ActiveWindowChangedV2(data) => data.clone(),
+                "when the active window is changed, with the address of the new window";
+            fullscreen_state_changed_events: bool => add_fullscreen_state_change_handler,
+                Event::FullscreenStateChanged(data) => *data,
+                "when a window enters or exits fullscreen, with `true` if it entered fullscreen";
+            monitor_added_events: String => add_monitor_added_handler,
+                Event::MonitorAdded(data) => data.clone(),
+                "when a new monitor is added";
+            monitor_added_v2_events: MonitorV2EventData => add_monitor_added_v2_handler,
+                Event::MonitorAddedV2(data) => data.clone(),
+                "when a new monitor is added, with its id and description";
+            monitor_removed_events: String => add_monitor_removed_handler,
+                Event::MonitorRemoved(data) => data.clone(),
+                "when a monitor is removed";
+            monitor_removed_v2_events: MonitorV2EventData => add_monitor_removed_v2_handler,
+                Event::MonitorRemovedV2(data) => data.clone(),
+                "when a monitor is removed, with its id and description";
+            layout_changed_events: LayoutEventData => add_layout_change_handler,
+                Event::LayoutChanged(data) => data.clone(),
+                "when the layout of a keyboard is changed, with the keyboard and layout names";
+            window_open_events: WindowOpenEventData => add_window_open_handler,
+                Event::WindowOpened(data) => data.clone(),
+                "when a new window is opened";
+            window_close_events: Address => add_window_close_handler,
+                Event::WindowClosed(data) => data.clone(),
+                "when a window is closed";
+            window_moved_events: WindowMoveEventData => add_window_moved_handler,
+                Event::WindowMoved(data) => data.clone(),
+                "when a window is moved to another workspace";
+            window_moved_v2_events: WindowMoveV2EventData => add_window_moved_v2_handler,
+                Event::WindowMovedV2(data) => data.clone(),
+                "when a window is moved to another workspace, with the workspace id and name";
+            layer_open_events: String => add_layer_open_handler,
+                Event::LayerOpened(data) => data.clone(),
+                "when a layer surface is opened, with its namespace";
+            layer_closed_events: String => add_layer_closed_handler,
+                Event::LayerClosed(data) => data.clone(),
+                "when a layer surface is closed, with its namespace";
+            sub_map_changed_events: String => add_sub_map_change_handler,
+                Event::SubmapChanged(data) => data.clone(),
+                "when the submap is changed, with its name (empty if the submap was reset)";
+            urgent_state_events: Address => add_urgent_state_handler,
+                Event::Urgent(data) => data.clone(),
+                "when a window requests attention, with its address";
+            minimize_events: MinimizeEventData => add_minimize_handler,
+                Event::Minimize(data) => data.clone(),
+                "when a window asks to be minimized or restored, with its address";
+            screencast_events: ScreencastEventData => add_screencast_handler,
+                Event::Screencast(data) => data.clone(),
+                "when a screencast starts or stops, with what is being shared";
+            window_title_changed_events: Address => add_window_title_change_handler,
+                Event::WindowTitleChanged(data) => data.clone(),
+                "when the title of a window is changed, with the window address";
+            window_title_changed_v2_events: WindowTitleEventData => add_window_title_change_v2_handler,
+                Event::WindowTitleChangedV2(data) => data.clone(),
+                "when the title of a window is changed, with the window address and the new title";
+            group_toggled_events: GroupToggleEventData => add_group_toggled_handler,
+                Event::GroupToggled(data) => data.clone(),
+                "when a group is created or destroyed, with the addresses of its windows";
+            window_moved_into_group_events: Address => add_window_moved_into_group_handler,
+                Event::WindowMovedIntoGroup(data) => data.clone(),
+                "when a window is moved into a group, with the window address";
+            window_moved_out_of_group_events: Address => add_window_moved_out_of_group_handler,
+                Event::WindowMovedOutOfGroup(data) => data.clone(),
+                "when a window is moved out of a group, with the window address";
+            groups_lock_toggled_events: bool => add_groups_lock_toggled_handler,
+                Event::GroupsLockToggled(data) => *data,
+                "when groups are locked or unlocked, with `true` if they are now locked";
+            ignore_group_lock_toggled_events: bool => add_ignore_group_lock_toggled_handler,
+                Event::IgnoreGroupLockToggled(data) => *data,
+                "when `ignoregrouplock` is toggled, with `true` if group locks are now ignored";
+            config_reloaded_events: () => add_config_reload_handler,
+                Event::ConfigReloaded => (),
+                "when the config is reloaded";
+            window_pin_events: WindowPinEventData => add_window_pin_handler,
+                Event::WindowPinned(data) => data.clone(),
+                "when a window is pinned or unpinned, with its address and new pin state";
+            float_state_events: WindowFloatEventData => add_float_state_handler,
+                Event::FloatStateChanged(data) => data.clone(),
+                "when a window is made floating or tiled, with its address and new floating state";
+            custom_events: String => add_custom_event_handler,
+                Event::Custom(data) => data.clone(),
+                "on custom events sent with the `event` dispatcher, with their data";
+            bell_events: Option<Address> => add_bell_handler,
+                Event::Bell(data) => data.clone(),
+                "when a window rings the bell, with its address (`None` if it isn't known)";
+            unknown_events: UnknownEventData => add_unknown_event_handler,
+                Event::Unknown(data) => data.clone(),
+                "on events this crate does not know about, with the raw event name and data";
+            reconnected_events: () => add_reconnect_handler,
+                Event::Reconnected => (),
+                "when the listener reconnected to the event socket (see `set_auto_reconnect`)";
+        }
+    };
+}
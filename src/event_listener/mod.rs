@@ -1,3 +1,6 @@
+#[macro_use]
+mod macros;
+
 mod shared;
 pub use crate::event_listener::shared::*;
 
@@ -6,3 +9,21 @@ pub use crate::event_listener::immutable::EventListener;
 
 mod immutable;
 pub use crate::event_listener::mutable::EventListener as EventListenerMutable;
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod asynchronous;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub use crate::event_listener::asynchronous::EventListener as AsyncEventListener;
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod stream;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub use crate::event_listener::stream::{event_stream, raw_event_stream};
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod broadcast;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub use crate::event_listener::broadcast::EventBroadcaster;
+
+mod replay;
+pub use crate::event_listener::replay::{EventRecorder, RecordedLine, Recording, ReplayTiming};
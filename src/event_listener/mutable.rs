@@ -1,8 +1,8 @@
 use crate::shared::*;
 use std::io;
-use tokio::io::AsyncReadExt;
-use tokio::net::UnixStream;
+use std::time::{Duration, Instant};
 
+use crate::event_listener::replay::{Recording, ReplayTiming};
 use crate::event_listener::shared::*;
 
 /// This struct is used for adding event handlers and executing them on events
@@ -13,353 +13,273 @@ use crate::event_listener::shared::*;
 /// ## Usage
 ///
 /// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
 /// use hyprland::event_listener::EventListenerMutable as EventListener;
-/// let mut listener = EventListener::new(); // creates a new listener
+/// let mut listener = EventListener::new()?; // creates a new listener
 /// // add a event handler which will be ran when this event happens
 /// listener.add_workspace_change_handler(|data, _| println!("{:#?}", data));
-/// listener.start_listener_blocking(); // or `.start_listener().await` if async
+/// listener.start_listener_blocking() // or `.start_listener().await` if async
+/// # }
 /// ```
 pub struct EventListener {
     pub(crate) events: Events,
+    pub(crate) filter: EventFilter,
+    pub(crate) reconnect: Option<Reconnect>,
+    pub(crate) handle: ListenerHandle,
+    pub(crate) coalescer: Coalescer,
+    pub(crate) instance: Option<Instance>,
     /// The state of some of the events
     pub state: State,
 }
 
-impl Default for EventListener {
-    fn default() -> Self {
-        Self::new()
+impl EventListener {
+    /// This method creates a new EventListener instance,
+    /// with the current state fetched from Hyprland
+    ///
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::EventListenerMutable as EventListener;
+    /// let mut listener = EventListener::new()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new() -> HyprResult<EventListener> {
+        EventListener::for_instance(&Instance::current()?)
     }
-}
 
-impl EventListener {
-    /// This method creates a new EventListener instance
+    /// This method creates a new EventListener instance that listens to the events
+    /// of a specific Hyprland instance, with the current state fetched from it
     ///
-    /// ```rust
+    /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
+    /// use hyprland::shared::Instance;
+    /// let instance = Instance::from_signature("v0.34.0_1700000000");
+    /// let mut listener = EventListener::for_instance(&instance)?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn new() -> EventListener {
-        use crate::data::blocking::{get_active_workspace, get_fullscreen_state, get_monitors};
-        EventListener {
-            events: Events {
-                workspace_changed_events: vec![],
-                workspace_added_events: vec![],
-                workspace_destroyed_events: vec![],
-                active_monitor_changed_events: vec![],
-                active_window_changed_events: vec![],
-                fullscreen_state_changed_events: vec![],
-                monitor_removed_events: vec![],
-                monitor_added_events: vec![],
-            },
+    pub fn for_instance(instance: &Instance) -> HyprResult<EventListener> {
+        let mut hyprland = crate::connection::HyprlandConnection::for_instance(instance);
+        let workspace = hyprland.get_active_workspace()?;
+        Ok(EventListener {
+            events: Events::default(),
+            filter: None,
+            reconnect: None,
+            handle: ListenerHandle::default(),
+            coalescer: Coalescer::default(),
+            instance: Some(instance.clone()),
             state: State {
-                active_workspace: match get_active_workspace() {
-                    Ok(work) => work.id,
-                    Err(e) => panic!("Error parsing data whith serde: {e}"),
-                },
-                active_monitor: match get_monitors() {
-                    Ok(monitors) => match monitors.iter().find(|item| item.focused) {
-                        Some(mon) => mon.name.clone(),
-                        None => panic!("No active monitor?"),
-                    },
-                    Err(e) => panic!("A error occured when parsing json with serde {e}"),
-                },
-                fullscreen_state: match get_fullscreen_state() {
-                    Ok(fstate) => fstate,
-                    Err(e) => panic!("Error parsing data whith serde: {e}"),
-                },
+                active_workspace: workspace.id,
+                active_monitor: hyprland.get_active_monitor()?.name,
+                fullscreen_state: workspace.fullscreen,
             },
+        })
+    }
+
+    /// Updates the tracked state with the new values from an event
+    fn update_state(&mut self, event: &Event) {
+        match event {
+            Event::WorkspaceChanged(id) => self.state.active_workspace = id.clone(),
+            Event::ActiveMonitorChanged(MonitorEventData(monitor, _)) => {
+                self.state.active_monitor = monitor.clone()
+            }
+            Event::FullscreenStateChanged(bool) => self.state.fullscreen_state = *bool,
+            _ => {}
         }
     }
 
-    /// This method adds a event to the listener which executes on workspace change
+    /// This method adds a handler which executes on every event,
+    /// with the parsed event and the line Hyprland sent for it
     ///
+    /// Only events the listener delivers are passed to it (see `set_event_filter`),
+    /// the synthetic [`Event::Reconnected`](crate::event_listener::Event::Reconnected) isn't
     /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
-    /// listener.start_listener_blocking();
+    /// let mut listener = EventListener::new()?;
+    /// listener.add_raw_event_handler(|raw, _| println!("{} was parsed as {:?}", raw.line, raw.event));
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_workspace_change_handler(
+    pub fn add_raw_event_handler(
         &mut self,
-        f: impl Fn(WorkspaceType, &mut State) + 'static,
-    ) {
+        f: impl FnMut(RawEvent, &mut State) + 'static,
+    ) -> HandlerId {
+        let id = HandlerId::next();
         self.events
-            .workspace_changed_events
-            .push(EventTypes::MutableState(Box::new(f)));
+            .raw_events
+            .push((id, EventTypes::MutableState(Box::new(f))));
+        id
     }
 
-    /// This method add a event to the listener which executes when a new workspace is created
+    /// This method removes a handler, using the id returned when it was added
     ///
+    /// Returns `false` if the listener has no handler with that id.
+    /// To remove handlers while the listener is running use [`handle`](Self::handle)
     /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_workspace_added_handler(|id, _| println!("workspace {id:?} was added"));
-    /// listener.start_listener_blocking();
+    /// let mut listener = EventListener::new()?;
+    /// let id = listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
+    /// listener.remove_handler(id);
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_workspace_added_handler(&mut self, f: impl Fn(WorkspaceType, &mut State) + 'static) {
-        self.events
-            .workspace_added_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn remove_handler(&mut self, id: HandlerId) -> bool {
+        self.events.remove(id)
     }
 
-    /// This method add a event to the listener which executes when a new workspace is created
-    ///
+    /// This method returns a handle that can control the listener while it's running,
+    /// like removing handlers from another thread or from inside a handler
     /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_workspace_destroy_handler(|id, _| println!("workspace {id:?} was destroyed"));
-    /// listener.start_listener_blocking();
+    /// let mut listener = EventListener::new()?;
+    /// let handle = listener.handle();
+    /// let id = listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
+    /// // later, from anywhere
+    /// handle.remove_handler(id);
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_workspace_destroy_handler(
-        &mut self,
-        f: impl Fn(WorkspaceType, &mut State) + 'static,
-    ) {
-        self.events
-            .workspace_destroyed_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn handle(&self) -> ListenerHandle {
+        self.handle.clone()
     }
 
-    /// This method add a event to the listener which executes when the active monitor is changed
+    /// This method sets which events the listener delivers,
+    /// every other event is skipped without being parsed
     ///
+    /// By default every event is delivered
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_active_monitor_change_handler(|data, _| println!("Active Monitor changed: {data:#?}"));
-    /// listener.start_listener_blocking();
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{EventKind, EventListenerMutable as EventListener};
+    /// let mut listener = EventListener::new()?;
+    /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
+    /// listener.set_event_filter([EventKind::WorkspaceChanged]);
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_active_monitor_change_handler(
-        &mut self,
-        f: impl Fn(MonitorEventData, &mut State) + 'static,
-    ) {
-        self.events
-            .active_monitor_changed_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn set_event_filter(&mut self, kinds: impl IntoIterator<Item = EventKind>) {
+        self.filter = Some(kinds.into_iter().collect());
     }
 
-    /// This method add a event to the listener which executes when the active window is changed
+    /// This method makes the listener reconnect when the event socket is closed or fails,
+    /// instead of returning
     ///
+    /// Every reconnect is delivered as [`Event::Reconnected`](crate::event_listener::Event::Reconnected),
+    /// events sent while the listener was disconnected are lost.
+    /// The socket path is resolved again on every attempt
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_active_window_change_handler(|data, _| println!("Active window changed: {data:#?}"));
-    /// listener.start_listener_blocking();
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{EventListenerMutable as EventListener, Reconnect};
+    /// let mut listener = EventListener::new()?;
+    /// listener.add_reconnect_handler(|_, _| println!("reconnected"));
+    /// listener.set_auto_reconnect(Reconnect::default());
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_active_window_change_handler(
-        &mut self,
-        f: impl Fn(Option<WindowEventData>, &mut State) + 'static,
-    ) {
-        self.events
-            .active_window_changed_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn set_auto_reconnect(&mut self, options: Reconnect) {
+        self.reconnect = Some(options);
     }
 
-    /// This method add a event to the listener which executes when the active monitor is changed
+    /// This method makes the listener listen to the events of a specific Hyprland instance,
+    /// instead of the one it was created for
     ///
+    /// The tracked state isn't fetched again, use [`for_instance`](Self::for_instance)
+    /// to start with the state of the instance
     /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_fullscreen_state_change_handler(|state, _| println!("Fullscreen is on: {state}"));
-    /// listener.start_listener_blocking();
+    /// use hyprland::shared::Instance;
+    /// let mut listener = EventListener::new()?;
+    /// listener.set_instance(Instance::from_signature("v0.34.0_1700000000"));
+    /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_fullscreen_state_change_handler(&mut self, f: impl Fn(bool, &mut State) + 'static) {
-        self.events
-            .fullscreen_state_changed_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn set_instance(&mut self, instance: Instance) {
+        self.instance = Some(instance);
     }
 
-    /// This method add a event to the listener which executes when a new monitor is added
+    /// This method makes the listener coalesce bursts of events of the given kinds,
+    /// so only the latest event of a kind within `window` is delivered
     ///
+    /// The first event of a burst is held back for `window` and replaced by newer events of its kind,
+    /// so coalesced events are delivered up to `window` late, possibly after events of other kinds
+    /// that came in later. The raw handlers only see the delivered events
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_monitor_added_handler(|data, _| println!("Monitor added: {data}"));
-    /// listener.start_listener_blocking();
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{EventKind, EventListenerMutable as EventListener};
+    /// use std::time::Duration;
+    /// let mut listener = EventListener::new()?;
+    /// listener.add_active_window_change_handler(|data, _| println!("{data:?}"));
+    /// listener.set_coalescing(Duration::from_millis(50), [EventKind::ActiveWindowChanged]);
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn add_monitor_added_handler(&mut self, f: impl Fn(String, &mut State) + 'static) {
-        self.events
-            .monitor_added_events
-            .push(EventTypes::MutableState(Box::new(f)));
+    pub fn set_coalescing(&mut self, window: Duration, kinds: impl IntoIterator<Item = EventKind>) {
+        self.coalescer = Coalescer::new(window, kinds.into_iter().collect());
     }
 
-    /// This method add a event to the listener which executes when a monitor is removed
+    /// This method replays a recording through the handlers, instead of listening to Hyprland
     ///
+    /// Events are filtered, coalesced and delivered the same way as by `start_listener`,
+    /// using the recorded times, but a line that can't be parsed returns a error.
+    /// The state starts from what the listener has, so it should match the start of the recording
     /// ```rust, no_run
-    /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
-    /// listener.add_monitor_removed_handler(|data, _| println!("Monitor removed: {data}"));
-    /// listener.start_listener_blocking();
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::{EventListenerMutable as EventListener, Recording, ReplayTiming};
+    /// let recording = Recording::from_reader("0\tworkspace>>2\n".as_bytes())?;
+    /// let mut listener = EventListener::new()?;
+    /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
+    /// listener.replay(&recording, ReplayTiming::Instant)?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn add_monitor_removed_handler(&mut self, f: impl Fn(String, &mut State) + 'static) {
-        self.events
-            .monitor_removed_events
-            .push(EventTypes::MutableState(Box::new(f)));
-    }
-
-    async fn event_executor(&mut self, event: &Event) -> io::Result<()> {
-        match event {
-            Event::WorkspaceChanged(id) => {
-                let handlers = &self.events.workspace_changed_events;
-                self.state.active_workspace = id.clone();
-                for item in handlers.iter() {
-                    let new_state =
-                        execute_closure_mut(self.state.clone(), item, id.clone()).await?;
-                    self.state = new_state;
-                }
-            }
-            Event::WorkspaceAdded(id) => {
-                let events = &self.events.workspace_added_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut(self.state.clone(), item, id.clone()).await?;
-                    self.state = new_state;
-                }
-            }
-            Event::WorkspaceDeleted(id) => {
-                let events = &self.events.workspace_destroyed_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut(self.state.clone(), item, id.clone()).await?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveMonitorChanged(MonitorEventData(monitor, id)) => {
-                let events = &self.events.active_monitor_changed_events;
-                self.state.active_monitor = monitor.clone();
-                for item in events.iter() {
-                    let new_state = execute_closure_mut(
-                        self.state.clone(),
-                        item,
-                        MonitorEventData(monitor.clone(), id.clone()),
-                    )
-                    .await?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveWindowChanged(Some(WindowEventData(class, title))) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut(
-                        self.state.clone(),
-                        item,
-                        Some(WindowEventData(class.clone(), title.clone())),
-                    )
-                    .await?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveWindowChanged(None) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut(self.state.clone(), item, None).await?;
-                    self.state = new_state;
-                }
-            }
-            Event::FullscreenStateChanged(bool) => {
-                let events = &self.events.fullscreen_state_changed_events;
-                self.state.fullscreen_state = *bool;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut(self.state.clone(), item, *bool).await?;
-                    self.state = new_state;
-                }
-            }
-            Event::MonitorAdded(monitor) => {
-                let events = &self.events.monitor_added_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut(self.state.clone(), item, monitor.clone()).await?;
-                    self.state = new_state;
+    pub fn replay(&mut self, recording: &Recording, timing: ReplayTiming) -> HyprResult<()> {
+        let start = Instant::now();
+        for recorded in recording.lines() {
+            let at = start + recorded.offset;
+            while let Some(deadline) = self.coalescer.next_deadline().filter(|due| *due <= at) {
+                if let Some(wait) = timing.delay(deadline) {
+                    std::thread::sleep(wait);
                 }
+                self.deliver_sync(None, deadline)?;
             }
-            Event::MonitorRemoved(monitor) => {
-                let events = &self.events.monitor_removed_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut(self.state.clone(), item, monitor.clone()).await?;
-                    self.state = new_state;
-                }
+            if let Some(wait) = timing.delay(at) {
+                std::thread::sleep(wait);
             }
+            let parsed = parse_event(&recorded.line, &self.filter)?;
+            self.deliver_sync(parsed.map(|event| (recorded.line.clone(), event)), at)?;
+        }
+        for (line, event) in self.coalescer.take_all() {
+            self.event_executor_sync(Some(&line), &event)?;
         }
         Ok(())
     }
 
-    fn event_executor_sync(&mut self, event: &Event) -> io::Result<()> {
-        match event {
-            Event::WorkspaceChanged(id) => {
-                let handlers = &self.events.workspace_changed_events;
-                self.state.active_workspace = id.clone();
-                for item in handlers.iter() {
-                    let new_state = execute_closure_mut_sync(self.state.clone(), item, id.clone())?;
-                    self.state = new_state;
-                }
-            }
-            Event::WorkspaceAdded(id) => {
-                let events = &self.events.workspace_added_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(self.state.clone(), item, id.clone())?;
-                    self.state = new_state;
-                }
-            }
-            Event::WorkspaceDeleted(id) => {
-                let events = &self.events.workspace_destroyed_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(self.state.clone(), item, id.clone())?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveMonitorChanged(MonitorEventData(monitor, id)) => {
-                let events = &self.events.active_monitor_changed_events;
-                self.state.active_monitor = monitor.clone();
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(
-                        self.state.clone(),
-                        item,
-                        MonitorEventData(monitor.clone(), id.clone()),
-                    )?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveWindowChanged(Some(WindowEventData(class, title))) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(
-                        self.state.clone(),
-                        item,
-                        Some(WindowEventData(class.clone(), title.clone())),
-                    )?;
-                    self.state = new_state;
-                }
-            }
-            Event::ActiveWindowChanged(None) => {
-                let events = &self.events.active_window_changed_events;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(self.state.clone(), item, None)?;
-                    self.state = new_state;
-                }
-            }
-            Event::FullscreenStateChanged(bool) => {
-                let events = &self.events.fullscreen_state_changed_events;
-                self.state.fullscreen_state = *bool;
-                for item in events.iter() {
-                    let new_state = execute_closure_mut_sync(self.state.clone(), item, *bool)?;
-                    self.state = new_state;
-                }
-            }
-            Event::MonitorAdded(monitor) => {
-                let events = &self.events.monitor_added_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut_sync(self.state.clone(), item, monitor.clone())?;
-                    self.state = new_state;
-                }
-            }
-            Event::MonitorRemoved(monitor) => {
-                let events = &self.events.monitor_removed_events;
-                for item in events.iter() {
-                    let new_state =
-                        execute_closure_mut_sync(self.state.clone(), item, monitor.clone())?;
-                    self.state = new_state;
-                }
-            }
+    /// Runs the handlers for the coalesced events that are due and for the received event,
+    /// unless it's held back to be coalesced
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    async fn deliver(&mut self, received: Option<(String, Event)>, now: Instant) -> HyprResult<()> {
+        let mut ready = self.coalescer.take_due(now);
+        if let Some((line, event)) = received {
+            ready.extend(self.coalescer.push(line, event, now));
+        }
+        for (line, event) in ready {
+            self.event_executor(Some(&line), &event).await?;
+        }
+        Ok(())
+    }
+
+    fn deliver_sync(&mut self, received: Option<(String, Event)>, now: Instant) -> HyprResult<()> {
+        let mut ready = self.coalescer.take_due(now);
+        if let Some((line, event)) = received {
+            ready.extend(self.coalescer.push(line, event, now));
+        }
+        for (line, event) in ready {
+            self.event_executor_sync(Some(&line), &event)?;
         }
         Ok(())
     }
@@ -368,89 +288,226 @@ impl EventListener {
     ///
     /// This should be ran after all of your handlers are defined
     /// ```rust, no_run
-    /// # async fn function() -> std::io::Result<()> {
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
+    /// let mut listener = EventListener::new()?;
     /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
     /// listener.start_listener().await;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn start_listener(&mut self) -> io::Result<()> {
-        let socket_path = get_socket_path(SocketType::Listener);
-
-        let mut stream = UnixStream::connect(socket_path).await?;
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    pub async fn start_listener(&mut self) -> HyprResult<()> {
+        let socket_path = instance_socket_path(self.instance.as_ref(), SocketType::Listener)?;
 
-        let mut buf = [0; 4096];
+        let mut stream = crate::runtime::connect(&socket_path)
+            .await
+            .map_err(|error| connect_error(&socket_path, error))?;
 
         loop {
-            stream.readable().await?;
-            let num_read = stream.read(&mut buf).await?;
-            if num_read == 0 {
-                break;
-            }
-            let buf = &buf[..num_read];
-
-            let string = match String::from_utf8(buf.to_vec()) {
-                Ok(str) => str,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+            let mut lines = crate::runtime::Lines::new(stream);
 
-            let parsed: Vec<Event> = match event_parser(string) {
-                Ok(vec) => vec,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+            loop {
+                let deadline = self.coalescer.next_deadline();
+                let received = match next_wake(&self.handle, deadline, &mut lines).await {
+                    Wake::Stopped => break,
+                    Wake::Due => None,
+                    Wake::Line(Ok(Some(line))) => {
+                        parse_event_or_skip(&line, &self.filter).map(|event| (line, event))
+                    }
+                    Wake::Line(Ok(None)) => break,
+                    Wake::Line(Err(_)) if self.reconnect.is_some() => break,
+                    Wake::Line(Err(error)) => return Err(error.into()),
+                };
+                self.deliver(received, Instant::now()).await?;
+            }
 
-            for event in parsed.iter() {
-                self.event_executor(event).await?;
+            if self.handle.take_stop() {
+                return Ok(());
+            }
+            for (line, event) in self.coalescer.take_all() {
+                self.event_executor(Some(&line), &event).await?;
+            }
+            match self.reconnect {
+                Some(options) => stream = reconnect(options, self.instance.as_ref()).await?,
+                None => return Ok(()),
+            }
+            if filter_allows(&self.filter, EventKind::Reconnected) {
+                self.event_executor(None, &Event::Reconnected).await?;
             }
         }
-
-        Ok(())
     }
 
     /// This method starts the event listener (blocking)
     ///
     /// This should be ran after all of your handlers are defined
     /// ```rust, no_run
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
     /// use hyprland::event_listener::EventListenerMutable as EventListener;
-    /// let mut listener = EventListener::new();
+    /// let mut listener = EventListener::new()?;
     /// listener.add_workspace_change_handler(|id, _| println!("changed workspace to {id:?}"));
-    /// listener.start_listener_blocking();
+    /// listener.start_listener_blocking()
+    /// # }
     /// ```
-    pub fn start_listener_blocking(mut self) -> io::Result<()> {
-        use io::prelude::*;
+    pub fn start_listener_blocking(mut self) -> HyprResult<()> {
         use std::os::unix::net::UnixStream;
 
-        let socket_path = get_socket_path(SocketType::Listener);
-
-        let mut stream = UnixStream::connect(socket_path)?;
+        let socket_path = instance_socket_path(self.instance.as_ref(), SocketType::Listener)?;
 
-        let mut buf = [0; 4096];
+        let mut stream = UnixStream::connect(&socket_path)
+            .map_err(|error| connect_error(&socket_path, error))?;
 
         loop {
-            //stream.readable()?;
-            let num_read = stream.read(&mut buf)?;
-            if num_read == 0 {
-                break;
+            self.handle.set_socket(stream.try_clone().ok());
+            let mut reader = io::BufReader::new(stream);
+            let mut buf = vec![];
+            while !self.handle.is_stopped() {
+                let deadline = self.coalescer.next_deadline();
+                let received = match read_line_blocking(&mut reader, &mut buf, deadline) {
+                    Ok(NextLine::Line(line)) => {
+                        parse_event_or_skip(&line, &self.filter).map(|event| (line, event))
+                    }
+                    Ok(NextLine::TimedOut) => None,
+                    Ok(NextLine::Closed) => break,
+                    Err(_) if self.reconnect.is_some() => break,
+                    Err(error) => return Err(error.into()),
+                };
+                self.deliver_sync(received, Instant::now())?;
             }
-            let buf = &buf[..num_read];
+            self.handle.set_socket(None);
 
-            let string = match String::from_utf8(buf.to_vec()) {
-                Ok(str) => str,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+            if self.handle.take_stop() {
+                return Ok(());
+            }
+            for (line, event) in self.coalescer.take_all() {
+                self.event_executor_sync(Some(&line), &event)?;
+            }
+            match self.reconnect {
+                Some(options) => stream = reconnect_blocking(options, self.instance.as_ref())?,
+                None => return Ok(()),
+            }
+            if filter_allows(&self.filter, EventKind::Reconnected) {
+                self.event_executor_sync(None, &Event::Reconnected)?;
+            }
+        }
+    }
+}
 
-            let parsed: Vec<Event> = match event_parser(string) {
-                Ok(vec) => vec,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
+macro_rules! gen_listener {
+    ($($field:ident: $ty:ty => $method:ident, $pat:pat => $val:expr, $when:literal;)*) => {
+        impl EventListener {
+            $(
+                #[doc = concat!(
+                    "This method adds a event to the listener which executes ", $when, "\n\n",
+                    "```rust, no_run\n",
+                    "# fn main() -> hyprland::shared::HyprResult<()> {\n",
+                    "use hyprland::event_listener::EventListenerMutable as EventListener;\n",
+                    "let mut listener = EventListener::new()?;\n",
+                    "listener.", stringify!($method), "(|data, _| println!(\"{data:?}\"));\n",
+                    "listener.start_listener_blocking()\n",
+                    "# }\n",
+                    "```",
+                )]
+                pub fn $method(&mut self, f: impl FnMut($ty, &mut State) + 'static) -> HandlerId {
+                    let id = HandlerId::next();
+                    self.events.$field.push((id, EventTypes::MutableState(Box::new(f))));
+                    id
+                }
+            )*
 
-            for event in parsed.iter() {
-                self.event_executor_sync(event)?;
+            #[cfg(any(feature = "tokio", feature = "async-io"))]
+            async fn event_executor(&mut self, line: Option<&str>, event: &Event) -> HyprResult<()> {
+                for id in self.handle.take_removed() {
+                    self.events.remove(id);
+                }
+                if let Some(line) = line {
+                    for (_, item) in self.events.raw_events.iter_mut() {
+                        self.state = execute_closure_mut(self.state.clone(), item, raw_event(line, event)).await?;
+                    }
+                }
+                self.update_state(event);
+                #[allow(unreachable_patterns)]
+                match event {
+                    $($pat => {
+                        for (_, item) in self.events.$field.iter_mut() {
+                            self.state = execute_closure_mut(self.state.clone(), item, $val).await?;
+                        }
+                    })*
+                    // events without handlers (yet)
+                    _ => {}
+                }
+                Ok(())
+            }
+
+            fn event_executor_sync(&mut self, line: Option<&str>, event: &Event) -> HyprResult<()> {
+                for id in self.handle.take_removed() {
+                    self.events.remove(id);
+                }
+                if let Some(line) = line {
+                    for (_, item) in self.events.raw_events.iter_mut() {
+                        self.state = execute_closure_mut_sync(self.state.clone(), item, raw_event(line, event))?;
+                    }
+                }
+                self.update_state(event);
+                #[allow(unreachable_patterns)]
+                match event {
+                    $($pat => {
+                        for (_, item) in self.events.$field.iter_mut() {
+                            self.state = execute_closure_mut_sync(self.state.clone(), item, $val)?;
+                        }
+                    })*
+                    // events without handlers (yet)
+                    _ => {}
+                }
+                Ok(())
             }
         }
+    };
+}
+
+event_handlers!(gen_listener);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::WorkspaceType;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn listener() -> EventListener {
+        EventListener {
+            events: Events::default(),
+            filter: None,
+            reconnect: None,
+            handle: ListenerHandle::default(),
+            coalescer: Coalescer::default(),
+            instance: None,
+            state: State {
+                active_workspace: WorkspaceType::Regular(1),
+                active_monitor: "DP-1".into(),
+                fullscreen_state: false,
+            },
+        }
+    }
 
+    #[test]
+    fn replay_updates_the_state() -> HyprResult<()> {
+        let recording =
+            Recording::from_reader("0\tworkspace>>2\n5\tfocusedmon>>HDMI-A-1,3\n".as_bytes())?;
+        let mut listener = listener();
+        let seen = Rc::new(RefCell::new(vec![]));
+        let workspaces = seen.clone();
+        listener.add_workspace_change_handler(move |id, state| {
+            workspaces
+                .borrow_mut()
+                .push(format!("{id:?} on {}", state.active_monitor));
+        });
+        listener.replay(&recording, ReplayTiming::Instant)?;
+        assert_eq!(*seen.borrow(), ["Regular(2) on DP-1"]);
+        assert_eq!(listener.state.active_monitor, "HDMI-A-1");
+        assert_eq!(listener.state.active_workspace, WorkspaceType::Regular(2));
+        let malformed = Recording::from_reader("0\topenwindow>>5581b1f5d010\n".as_bytes())?;
+        assert!(listener.replay(&malformed, ReplayTiming::Instant).is_err());
         Ok(())
     }
 }
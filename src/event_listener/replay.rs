@@ -0,0 +1,158 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::event_listener::shared::*;
+
+/// This struct writes raw event lines to a file (or any writer), with the time they were recorded
+///
+/// Every line is written as `<milliseconds since the recorder was created>\t<event line>`,
+/// the result can be loaded with [`Recording`] and replayed through a listener
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::event_listener::{EventListener, EventRecorder};
+/// let mut recorder = EventRecorder::create("events.log")?;
+/// let mut listener = EventListener::new();
+/// listener.add_raw_event_handler(move |raw| {
+///     if let Err(error) = recorder.record(&raw.line) {
+///         eprintln!("failed to record event: {error}");
+///     }
+/// });
+/// listener.start_listener_blocking()
+/// # }
+/// ```
+pub struct EventRecorder<W: Write = BufWriter<File>> {
+    writer: W,
+    start: Instant,
+}
+
+impl EventRecorder {
+    /// This method creates a recorder that writes to a new file (truncating it if it exists)
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(EventRecorder::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// This method creates a recorder that writes to the writer
+    pub fn new(writer: W) -> Self {
+        EventRecorder {
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    /// This method records a raw event line, it's flushed right away
+    pub fn record(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.writer, "{}\t{line}", self.start.elapsed().as_millis())?;
+        self.writer.flush()
+    }
+
+    /// This method returns the writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// This struct holds a recorded event line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedLine {
+    /// When the line was recorded, relative to the start of the recording
+    pub offset: Duration,
+    /// The event line
+    pub line: String,
+}
+
+/// This struct holds a event recording made with [`EventRecorder`]
+///
+/// It can be replayed through the handlers of a listener (see `EventListener::replay`),
+/// which makes it possible to test event driven code without a running compositor
+///
+/// ```rust
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::event_listener::{EventListener, Recording, ReplayTiming};
+/// let recording = Recording::from_reader("0\tworkspace>>2\n15\tworkspace>>3\n".as_bytes())?;
+/// let mut listener = EventListener::new();
+/// listener.add_workspace_change_handler(|id| println!("changed workspace to {id:?}"));
+/// listener.replay(&recording, ReplayTiming::Instant)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    lines: Vec<RecordedLine>,
+}
+
+impl Recording {
+    /// This method loads a recording from a file
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Recording::from_reader(io::BufReader::new(File::open(path)?))
+    }
+
+    /// This method loads a recording from a reader, empty lines are skipped
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut lines = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let (offset, event_line) = match line.split_once('\t') {
+                Some((offset, event_line)) => match offset.parse::<u64>() {
+                    Ok(millis) => (Duration::from_millis(millis), event_line),
+                    Err(_) => return Err(invalid_recording(&line)),
+                },
+                None => return Err(invalid_recording(&line)),
+            };
+            lines.push(RecordedLine {
+                offset,
+                line: event_line.to_string(),
+            });
+        }
+        Ok(Recording { lines })
+    }
+
+    /// This method returns the recorded lines
+    pub fn lines(&self) -> &[RecordedLine] {
+        &self.lines
+    }
+
+    /// This method parses the recorded lines, lines that aren't a event are skipped
+    pub fn events(&self) -> impl Iterator<Item = io::Result<RawEvent>> + '_ {
+        self.lines
+            .iter()
+            .filter_map(|recorded| match parse_event(&recorded.line, &None) {
+                Ok(Some(event)) => Some(Ok(raw_event(&recorded.line, &event))),
+                Ok(None) => None,
+                Err(error) => Some(Err(error)),
+            })
+    }
+}
+
+fn invalid_recording(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid recorded line: {line:?}"),
+    )
+}
+
+/// This enum sets how fast a recording is replayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Every event is delivered right away
+    Instant,
+    /// The events are delivered with the delays they were recorded with
+    Recorded,
+}
+
+impl ReplayTiming {
+    /// Returns how long to wait before delivering something replayed at `at`, if at all
+    pub(crate) fn delay(&self, at: Instant) -> Option<Duration> {
+        match self {
+            ReplayTiming::Instant => None,
+            ReplayTiming::Recorded => at.checked_duration_since(Instant::now()),
+        }
+    }
+}
@@ -1,27 +1,174 @@
 use crate::shared::*;
-use regex::{Error as RegexError, Regex, RegexSet};
+use std::collections::HashSet;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+use std::future::Future;
 use std::io;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 pub(crate) enum EventTypes<T: ?Sized, U: ?Sized> {
     MutableState(Box<U>),
     Regular(Box<T>),
 }
 
-pub(crate) type Closure<T> = EventTypes<dyn Fn(T), dyn Fn(T, &mut State)>;
-pub(crate) type Closures<T> = Vec<Closure<T>>;
+pub(crate) type Closure<T> = EventTypes<dyn FnMut(T), dyn FnMut(T, &mut State)>;
+pub(crate) type Closures<T> = Vec<(HandlerId, Closure<T>)>;
 
-#[allow(clippy::type_complexity)]
-pub(crate) struct Events {
-    pub(crate) workspace_changed_events: Closures<WorkspaceType>,
-    pub(crate) workspace_added_events: Closures<WorkspaceType>,
-    pub(crate) workspace_destroyed_events: Closures<WorkspaceType>,
-    pub(crate) active_monitor_changed_events: Closures<MonitorEventData>,
-    pub(crate) active_window_changed_events: Closures<Option<WindowEventData>>,
-    pub(crate) fullscreen_state_changed_events: Closures<bool>,
-    pub(crate) monitor_removed_events: Closures<String>,
-    pub(crate) monitor_added_events: Closures<String>,
+/// This trait is implemented by every future that can be returned by a async handler
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub trait AsyncHandlerFuture: Future<Output = ()> + Send + 'static {}
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+impl<T: Future<Output = ()> + Send + 'static> AsyncHandlerFuture for T {}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) type VoidFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) type AsyncClosure<T> = Box<dyn Fn(T) -> VoidFuture + Send + Sync>;
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) type AsyncClosures<T> = Vec<(HandlerId, AsyncClosure<T>)>;
+
+/// This struct identifies a event handler, it's returned when a handler is added
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandlerId(u64);
+
+impl HandlerId {
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        HandlerId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// This struct controls a event listener from outside of it, even while the listener is running
+///
+/// It's cheap to clone and can be sent to other threads or moved into handlers
+#[derive(Debug, Clone, Default)]
+pub struct ListenerHandle {
+    removed: Arc<Mutex<Vec<HandlerId>>>,
+    stop: Arc<StopSignal>,
+}
+
+#[derive(Debug, Default)]
+struct StopSignal {
+    stopped: AtomicBool,
+    /// The async listeners waiting to be stopped
+    wakers: Wakers,
+    /// A clone of the socket used by blocking listeners, shut down to interrupt their read
+    socket: Mutex<Option<std::os::unix::net::UnixStream>>,
+}
+
+impl ListenerHandle {
+    /// This method removes a handler from the listener,
+    /// the handler is removed before the next event is delivered
+    pub fn remove_handler(&self, id: HandlerId) {
+        lock(&self.removed).push(id);
+    }
+
+    pub(crate) fn take_removed(&self) -> Vec<HandlerId> {
+        std::mem::take(&mut *lock(&self.removed))
+    }
+
+    /// This method stops the listener, its `start_listener` method returns `Ok(())`
+    /// without waiting for the next event
+    ///
+    /// Handlers that are running when this is called are finished first.
+    /// If the listener isn't running, the next time it's started it stops right away
+    ///
+    /// ```rust, no_run
+    /// use hyprland::event_listener::EventListener;
+    /// let mut listener = EventListener::new();
+    /// let handle = listener.handle();
+    /// listener.add_workspace_change_handler(move |_| handle.stop());
+    /// listener.start_listener_blocking(); // returns after the first workspace change
+    /// ```
+    pub fn stop(&self) {
+        self.stop.stopped.store(true, Ordering::SeqCst);
+        self.stop.wakers.wake_all();
+        if let Some(socket) = lock(&self.stop.socket).as_ref() {
+            // the listener sees this as the socket being closed
+            let _ = socket.shutdown(std::net::Shutdown::Both);
+        }
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stop.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Returns if the listener was stopped, and resets it so it can be started again
+    pub(crate) fn take_stop(&self) -> bool {
+        self.stop.stopped.swap(false, Ordering::SeqCst)
+    }
+
+    /// Completes once the listener is stopped
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    pub(crate) async fn stopped(&self) {
+        self.stop.wakers.wait_until(|| self.is_stopped()).await
+    }
+
+    pub(crate) fn set_socket(&self, socket: Option<std::os::unix::net::UnixStream>) {
+        *lock(&self.stop.socket) = socket;
+    }
+}
+
+macro_rules! gen_events {
+    ($($field:ident: $ty:ty => $method:ident, $pat:pat => $val:expr, $when:literal;)*) => {
+        #[derive(Default)]
+        pub(crate) struct Events {
+            pub(crate) raw_events: Closures<RawEvent>,
+            $(pub(crate) $field: Closures<$ty>,)*
+        }
+
+        #[cfg(any(feature = "tokio", feature = "async-io"))]
+        #[derive(Default)]
+        pub(crate) struct AsyncEvents {
+            pub(crate) raw_events: AsyncClosures<RawEvent>,
+            $(pub(crate) $field: AsyncClosures<$ty>,)*
+        }
+
+        impl Events {
+            /// Removes the handler with the id, returns `false` if there is none
+            pub(crate) fn remove(&mut self, id: HandlerId) -> bool {
+                let len = self.raw_events.len();
+                self.raw_events.retain(|(handler, _)| *handler != id);
+                let mut removed = self.raw_events.len() != len;
+                $(
+                    let len = self.$field.len();
+                    self.$field.retain(|(handler, _)| *handler != id);
+                    removed |= self.$field.len() != len;
+                )*
+                removed
+            }
+        }
+
+        #[cfg(any(feature = "tokio", feature = "async-io"))]
+        impl AsyncEvents {
+            /// Removes the handler with the id, returns `false` if there is none
+            pub(crate) fn remove(&mut self, id: HandlerId) -> bool {
+                let len = self.raw_events.len();
+                self.raw_events.retain(|(handler, _)| *handler != id);
+                let mut removed = self.raw_events.len() != len;
+                $(
+                    let len = self.$field.len();
+                    self.$field.retain(|(handler, _)| *handler != id);
+                    removed |= self.$field.len() != len;
+                )*
+                removed
+            }
+        }
+    };
 }
 
+event_handlers!(gen_events);
+
 /// The mutable state available to Closures
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct State {
@@ -35,7 +182,8 @@ pub struct State {
 
 impl State {
     /// Execute changes in state
-    pub async fn execute_state(self, old: State) -> io::Result<Self> {
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    pub async fn execute_state(self, old: State) -> HyprResult<Self> {
         let state = self.clone();
         if self != old {
             use crate::dispatch::{dispatch, DispatchType};
@@ -45,10 +193,13 @@ impl State {
             }
             if old.active_workspace != state.active_workspace {
                 use crate::dispatch::WorkspaceIdentifierWithSpecial;
-                dispatch(DispatchType::Workspace(match state.active_workspace {
-                    WorkspaceType::Regular(id) => WorkspaceIdentifierWithSpecial::Id(id),
-                    WorkspaceType::Special => WorkspaceIdentifierWithSpecial::Special,
-                }))
+                dispatch(DispatchType::Workspace(
+                    match state.active_workspace.clone() {
+                        WorkspaceType::Regular(id) => WorkspaceIdentifierWithSpecial::Id(id),
+                        WorkspaceType::Special => WorkspaceIdentifierWithSpecial::Special,
+                        WorkspaceType::Named(name) => WorkspaceIdentifierWithSpecial::Name(name),
+                    },
+                ))
                 .await?;
             }
             if old.active_monitor != state.active_monitor {
@@ -62,7 +213,7 @@ impl State {
         Ok(state)
     }
     /// Execute changes in state
-    pub fn execute_state_sync(self, old: State) -> io::Result<Self> {
+    pub fn execute_state_sync(self, old: State) -> HyprResult<Self> {
         let state = self.clone();
         if self != old {
             use crate::dispatch::{dispatch_blocking, DispatchType};
@@ -72,10 +223,13 @@ impl State {
             }
             if old.active_workspace != state.active_workspace {
                 use crate::dispatch::WorkspaceIdentifierWithSpecial;
-                dispatch_blocking(DispatchType::Workspace(match state.active_workspace {
-                    WorkspaceType::Regular(id) => WorkspaceIdentifierWithSpecial::Id(id),
-                    WorkspaceType::Special => WorkspaceIdentifierWithSpecial::Special,
-                }))?;
+                dispatch_blocking(DispatchType::Workspace(
+                    match state.active_workspace.clone() {
+                        WorkspaceType::Regular(id) => WorkspaceIdentifierWithSpecial::Id(id),
+                        WorkspaceType::Special => WorkspaceIdentifierWithSpecial::Special,
+                        WorkspaceType::Named(name) => WorkspaceIdentifierWithSpecial::Name(name),
+                    },
+                ))?;
             }
             if old.active_monitor != state.active_monitor {
                 use crate::dispatch::MonitorIdentifier;
@@ -88,18 +242,187 @@ impl State {
     }
 }
 
-pub(crate) fn execute_closure<T>(f: &Closure<T>, val: T) {
+/// This enum sets how the async listener runs its handlers
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Execution {
+    /// Every handler is spawned as its own task, so a slow handler doesn't stall the listener,
+    /// operations of handlers can interleave and finish in any order
+    ///
+    /// With async-io the handlers run inside the listener instead, which waits for them to finish
+    /// before it returns
+    #[default]
+    Concurrent,
+    /// Handlers are awaited one after another, in the order the events arrived
+    /// and the order the handlers were added
+    Sequential,
+}
+
+/// This pub(crate) struct runs the handlers of a async listener that run concurrently
+///
+/// With tokio they're spawned as tasks. With async-io there's no executor to spawn them on,
+/// so they run inside the listener while it waits for events,
+/// and the ones still running when it returns are finished first
+#[derive(Default)]
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) struct Tasks {
+    #[cfg(not(feature = "tokio"))]
+    running: Vec<VoidFuture>,
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+impl Tasks {
+    /// Runs the handler the way `execution` says
+    pub(crate) async fn run(&mut self, execution: Execution, future: VoidFuture) {
+        match execution {
+            #[cfg(feature = "tokio")]
+            Execution::Concurrent => {
+                tokio::spawn(future);
+            }
+            #[cfg(not(feature = "tokio"))]
+            Execution::Concurrent => self.running.push(future),
+            Execution::Sequential => future.await,
+        }
+    }
+
+    /// Waits for `future`, running the handlers while it's waited on
+    pub(crate) async fn alongside<T>(&mut self, future: impl Future<Output = T>) -> T {
+        #[cfg(feature = "tokio")]
+        {
+            future.await
+        }
+        #[cfg(not(feature = "tokio"))]
+        {
+            let mut future = std::pin::pin!(future);
+            std::future::poll_fn(|cx| {
+                self.poll_running(cx);
+                future.as_mut().poll(cx)
+            })
+            .await
+        }
+    }
+
+    /// Waits for the handlers that are still running
+    pub(crate) async fn finish(&mut self) {
+        #[cfg(not(feature = "tokio"))]
+        std::future::poll_fn(|cx| {
+            self.poll_running(cx);
+            match self.running.is_empty() {
+                true => std::task::Poll::Ready(()),
+                false => std::task::Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Polls the handlers, the finished ones are dropped
+    #[cfg(not(feature = "tokio"))]
+    fn poll_running(&mut self, cx: &mut std::task::Context) {
+        self.running
+            .retain_mut(|future| future.as_mut().poll(cx).is_pending());
+    }
+}
+
+/// This struct configures how a listener reconnects when the event socket is closed,
+/// like when Hyprland restarts
+///
+/// After every failed attempt the delay doubles, up to `max_delay`
+#[derive(Debug, Clone, Copy)]
+pub struct Reconnect {
+    /// The delay before the first attempt
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts
+    pub max_delay: Duration,
+    /// How many failed attempts in a row to give up after, `None` to never give up
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Reconnect {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            max_attempts: None,
+        }
+    }
+}
+
+impl Reconnect {
+    fn next_delay(&self, delay: Duration) -> Duration {
+        (delay * 2).min(self.max_delay)
+    }
+
+    fn gave_up(&self, attempts: u32) -> bool {
+        self.max_attempts.is_some_and(|max| attempts >= max)
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) async fn reconnect(
+    options: Reconnect,
+    instance: Option<&Instance>,
+) -> HyprResult<crate::runtime::UnixStream> {
+    let mut delay = options.initial_delay;
+    let mut attempts = 0;
+    loop {
+        crate::runtime::sleep(delay).await;
+        let socket_path = instance_socket_path(instance, SocketType::Listener)?;
+        match crate::runtime::connect(&socket_path).await {
+            Ok(stream) => {
+                log!(debug, "reconnected to {socket_path}");
+                return Ok(stream);
+            }
+            Err(error) => {
+                attempts += 1;
+                if options.gave_up(attempts) {
+                    return Err(connect_error(&socket_path, error));
+                }
+                delay = options.next_delay(delay);
+            }
+        }
+    }
+}
+
+pub(crate) fn reconnect_blocking(
+    options: Reconnect,
+    instance: Option<&Instance>,
+) -> HyprResult<std::os::unix::net::UnixStream> {
+    let mut delay = options.initial_delay;
+    let mut attempts = 0;
+    loop {
+        std::thread::sleep(delay);
+        let socket_path = instance_socket_path(instance, SocketType::Listener)?;
+        match std::os::unix::net::UnixStream::connect(&socket_path) {
+            Ok(stream) => {
+                log!(debug, "reconnected to {socket_path}");
+                return Ok(stream);
+            }
+            Err(error) => {
+                attempts += 1;
+                if options.gave_up(attempts) {
+                    return Err(connect_error(&socket_path, error));
+                }
+                delay = options.next_delay(delay);
+            }
+        }
+    }
+}
+
+pub(crate) fn execute_closure<T>(f: &mut Closure<T>, val: T) {
     match f {
-        EventTypes::MutableState(_) => panic!("Using mutable handler with immutable listener"),
+        EventTypes::MutableState(_) => {
+            unreachable!("mutable handlers are only added to the mutable listener")
+        }
         EventTypes::Regular(fun) => fun(val),
     }
 }
 
+#[cfg(any(feature = "tokio", feature = "async-io"))]
 pub(crate) async fn execute_closure_mut<T>(
     state: State,
-    f: &Closure<T>,
+    f: &mut Closure<T>,
     val: T,
-) -> io::Result<State> {
+) -> HyprResult<State> {
     let old_state = state.clone();
     let mut new_state = state.clone();
     match f {
@@ -114,9 +437,9 @@ pub(crate) async fn execute_closure_mut<T>(
 #[allow(clippy::redundant_clone)]
 pub(crate) fn execute_closure_mut_sync<T>(
     state: State,
-    f: &Closure<T>,
+    f: &mut Closure<T>,
     val: T,
-) -> io::Result<State> {
+) -> HyprResult<State> {
     let old_state = state.clone();
     let mut new_state = state.clone();
     match f {
@@ -146,156 +469,1077 @@ pub struct MonitorEventData(
     pub WorkspaceType,
 );
 
+/// This struct holds the data of workspace events that carry both the id and name
+#[derive(Debug, Clone)]
+pub struct WorkspaceV2EventData {
+    /// The workspace id
+    pub id: i32,
+    /// The workspace name
+    pub name: String,
+}
+
+/// This struct holds the data of the workspace moved (v2) event
+#[derive(Debug, Clone)]
+pub struct WorkspaceMovedV2EventData {
+    /// The workspace id
+    pub id: i32,
+    /// The workspace name
+    pub name: String,
+    /// The name of the monitor the workspace was moved to
+    pub monitor: String,
+}
+
+/// This struct holds the data of the active monitor changed (v2) event
+#[derive(Debug, Clone)]
+pub struct MonitorFocusV2EventData {
+    /// The monitor name
+    pub monitor: String,
+    /// The id of the workspace on that monitor
+    pub workspace_id: i32,
+}
+
+/// This struct holds the data of the v2 monitor events
+#[derive(Debug, Clone)]
+pub struct MonitorV2EventData {
+    /// The monitor id
+    pub id: u8,
+    /// The monitor name
+    pub name: String,
+    /// The monitor description
+    pub description: String,
+}
+
+/// This struct holds the data of the active special workspace changed event
+#[derive(Debug, Clone)]
+pub struct ActiveSpecialEventData {
+    /// The special workspace name, `None` if the special workspace was closed
+    pub workspace_name: Option<String>,
+    /// The monitor the special workspace is on
+    pub monitor: String,
+}
+
+/// This struct holds the data of the keyboard layout changed event
+#[derive(Debug, Clone)]
+pub struct LayoutEventData {
+    /// The keyboard name
+    pub keyboard_name: String,
+    /// The name of the new layout
+    pub layout_name: String,
+}
+
+/// This struct holds the data of the window opened event
+#[derive(Debug, Clone)]
+pub struct WindowOpenEventData {
+    /// The window address
+    pub address: Address,
+    /// The workspace the window was opened on
+    pub workspace: WorkspaceType,
+    /// The name of the workspace the window was opened on
+    pub workspace_name: String,
+    /// The window class
+    pub class: String,
+    /// The window title
+    pub title: String,
+}
+
+/// This struct holds the data of the window moved event
+#[derive(Debug, Clone)]
+pub struct WindowMoveEventData {
+    /// The window address
+    pub address: Address,
+    /// The workspace the window was moved to
+    pub workspace: WorkspaceType,
+}
+
+/// This struct holds the data of the window moved (v2) event
+#[derive(Debug, Clone)]
+pub struct WindowMoveV2EventData {
+    /// The window address
+    pub address: Address,
+    /// The id of the workspace the window was moved to
+    pub workspace_id: i32,
+    /// The name of the workspace the window was moved to
+    pub workspace_name: String,
+}
+
+/// This struct holds the data of the floating state changed event
+#[derive(Debug, Clone)]
+pub struct WindowFloatEventData {
+    /// The window address
+    pub address: Address,
+    /// If the window is now floating
+    pub floating: bool,
+}
+
+/// This struct holds the data of the minimize event
+#[derive(Debug, Clone)]
+pub struct MinimizeEventData {
+    /// The window address
+    pub address: Address,
+    /// If the window was minimized
+    pub minimized: bool,
+}
+
+/// This enum holds what is being screencast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreencastOwner {
+    /// A whole monitor is being shared
+    Monitor,
+    /// A single window is being shared
+    Window,
+}
+
+/// This struct holds the data of the screencast event
+#[derive(Debug, Clone)]
+pub struct ScreencastEventData {
+    /// If the screencast started
+    pub active: bool,
+    /// What is being shared
+    pub owner: ScreencastOwner,
+}
+
+/// This struct holds the data of the window title changed (v2) event
+#[derive(Debug, Clone)]
+pub struct WindowTitleEventData {
+    /// The window address
+    pub address: Address,
+    /// The new window title
+    pub title: String,
+}
+
+/// This struct holds the data of the group toggled event
+#[derive(Debug, Clone)]
+pub struct GroupToggleEventData {
+    /// If the group was created (`false` if it was destroyed)
+    pub opened: bool,
+    /// The addresses of the windows in the group
+    pub addresses: Vec<Address>,
+}
+
+/// This struct holds the data of the window pinned event
+#[derive(Debug, Clone)]
+pub struct WindowPinEventData {
+    /// The window address
+    pub address: Address,
+    /// If the window is now pinned
+    pub pinned: bool,
+}
+
+/// This struct holds a parsed event together with the line Hyprland sent for it
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    /// The line exactly as it was read from the socket (without the newline)
+    pub line: String,
+    /// The parsed event
+    pub event: Event,
+}
+
+pub(crate) fn raw_event(line: &str, event: &Event) -> RawEvent {
+    RawEvent {
+        line: line.to_string(),
+        event: event.clone(),
+    }
+}
+
+/// This struct holds a event that isn't known by this crate
+#[derive(Debug, Clone)]
+pub struct UnknownEventData {
+    /// The event name (the part before `>>`)
+    pub name: String,
+    /// The unparsed event data (the part after `>>`)
+    pub data: String,
+}
+
 /// This enum holds every event type
 #[derive(Debug, Clone)]
-pub(crate) enum Event {
+pub enum Event {
+    /// The active workspace was changed
     WorkspaceChanged(WorkspaceType),
+    /// The active workspace was changed (with the workspace id)
+    WorkspaceChangedV2(WorkspaceV2EventData),
+    /// A workspace was destroyed
     WorkspaceDeleted(WorkspaceType),
+    /// A workspace was destroyed (with the workspace id)
+    WorkspaceDeletedV2(WorkspaceV2EventData),
+    /// A workspace was created
     WorkspaceAdded(WorkspaceType),
+    /// A workspace was created (with the workspace id)
+    WorkspaceAddedV2(WorkspaceV2EventData),
+    /// A workspace was moved to another monitor
+    WorkspaceMoved(MonitorEventData),
+    /// A workspace was moved to another monitor (with the workspace id)
+    WorkspaceMovedV2(WorkspaceMovedV2EventData),
+    /// A workspace was renamed
+    WorkspaceRenamed(WorkspaceV2EventData),
+    /// The special workspace was opened or closed on a monitor
+    ActiveSpecialChanged(ActiveSpecialEventData),
+    /// The active window was changed, `None` if no window is focused
     ActiveWindowChanged(Option<WindowEventData>),
+    /// The active window was changed (with the window address), `None` if no window is focused
+    ActiveWindowChangedV2(Option<Address>),
+    /// The active monitor was changed
     ActiveMonitorChanged(MonitorEventData),
+    /// The active monitor was changed (with the workspace id)
+    ActiveMonitorChangedV2(MonitorFocusV2EventData),
+    /// A window entered (`true`) or exited (`false`) fullscreen
     FullscreenStateChanged(bool),
+    /// A monitor was added
     MonitorAdded(String),
+    /// A monitor was added (with the monitor id and description)
+    MonitorAddedV2(MonitorV2EventData),
+    /// A monitor was removed
     MonitorRemoved(String),
+    /// A monitor was removed (with the monitor id and description)
+    MonitorRemovedV2(MonitorV2EventData),
+    /// The keyboard layout was changed
+    LayoutChanged(LayoutEventData),
+    /// A window was opened
+    WindowOpened(WindowOpenEventData),
+    /// A window was closed
+    WindowClosed(Address),
+    /// A window was moved to another workspace
+    WindowMoved(WindowMoveEventData),
+    /// A window was moved to another workspace (with the workspace id)
+    WindowMovedV2(WindowMoveV2EventData),
+    /// A layer surface was opened, holds its namespace
+    LayerOpened(String),
+    /// A layer surface was closed, holds its namespace
+    LayerClosed(String),
+    /// The submap was changed, empty if it was reset
+    SubmapChanged(String),
+    /// A window was made floating or tiled
+    FloatStateChanged(WindowFloatEventData),
+    /// A window requested attention
+    Urgent(Address),
+    /// A window requested to be minimized
+    Minimize(MinimizeEventData),
+    /// A screencast was started or stopped
+    Screencast(ScreencastEventData),
+    /// The title of a window was changed
+    WindowTitleChanged(Address),
+    /// The title of a window was changed (with the new title)
+    WindowTitleChangedV2(WindowTitleEventData),
+    /// A group was created or destroyed
+    GroupToggled(GroupToggleEventData),
+    /// A window was moved into a group
+    WindowMovedIntoGroup(Address),
+    /// A window was moved out of a group
+    WindowMovedOutOfGroup(Address),
+    /// The `ignoregrouplock` setting was toggled
+    IgnoreGroupLockToggled(bool),
+    /// Groups were locked or unlocked
+    GroupsLockToggled(bool),
+    /// The config was reloaded
+    ConfigReloaded,
+    /// A window was pinned or unpinned
+    WindowPinned(WindowPinEventData),
+    /// A custom event sent with the `event` dispatcher
+    Custom(String),
+    /// A window rang the bell, `None` if the window isn't known
+    Bell(Option<Address>),
+    /// A event this crate doesn't know about, like new or plugin events
+    Unknown(UnknownEventData),
+    /// Not sent by Hyprland, the listener reconnected to the event socket after it was closed
+    /// (only with [`Reconnect`](crate::event_listener::Reconnect) enabled)
+    Reconnected,
 }
 
-fn check_for_regex_error(val: Result<Regex, RegexError>) -> Regex {
-    match val {
-        Ok(value) => value,
-        Err(RegexError::Syntax(str)) => panic!("syntax error: {str}"),
-        Err(RegexError::CompiledTooBig(size)) => {
-            panic!("The compiled regex size is too big ({size})")
+macro_rules! event_kinds {
+    ($($kind:ident => $name:literal,)*) => {
+        /// This enum holds the kind of every event, without its data
+        ///
+        /// It's used for choosing which events a listener delivers
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum EventKind {
+            $(
+                #[doc = concat!("The `", $name, "` event ([`Event::", stringify!($kind), "`])")]
+                $kind,
+            )*
+            /// Any event this crate doesn't know about ([`Event::Unknown`])
+            Unknown,
+            /// The listener reconnected to the event socket ([`Event::Reconnected`])
+            Reconnected,
+        }
+
+        impl EventKind {
+            /// This method returns the kind of a event from the name Hyprland uses for it
+            pub fn from_name(name: &str) -> EventKind {
+                match name {
+                    $($name => EventKind::$kind,)*
+                    _ => EventKind::Unknown,
+                }
+            }
+        }
+
+        impl Event {
+            /// This method returns the kind of the event
+            pub fn kind(&self) -> EventKind {
+                match self {
+                    $(Event::$kind { .. } => EventKind::$kind,)*
+                    Event::Unknown(_) => EventKind::Unknown,
+                    Event::Reconnected => EventKind::Reconnected,
+                }
+            }
+        }
+    };
+}
+
+event_kinds! {
+    WorkspaceChanged => "workspace",
+    WorkspaceChangedV2 => "workspacev2",
+    WorkspaceDeleted => "destroyworkspace",
+    WorkspaceDeletedV2 => "destroyworkspacev2",
+    WorkspaceAdded => "createworkspace",
+    WorkspaceAddedV2 => "createworkspacev2",
+    WorkspaceMoved => "moveworkspace",
+    WorkspaceMovedV2 => "moveworkspacev2",
+    WorkspaceRenamed => "renameworkspace",
+    ActiveSpecialChanged => "activespecial",
+    ActiveWindowChanged => "activewindow",
+    ActiveWindowChangedV2 => "activewindowv2",
+    ActiveMonitorChanged => "focusedmon",
+    ActiveMonitorChangedV2 => "focusedmonv2",
+    FullscreenStateChanged => "fullscreen",
+    MonitorAdded => "monitoradded",
+    MonitorAddedV2 => "monitoraddedv2",
+    MonitorRemoved => "monitorremoved",
+    MonitorRemovedV2 => "monitorremovedv2",
+    LayoutChanged => "activelayout",
+    WindowOpened => "openwindow",
+    WindowClosed => "closewindow",
+    WindowMoved => "movewindow",
+    WindowMovedV2 => "movewindowv2",
+    LayerOpened => "openlayer",
+    LayerClosed => "closelayer",
+    SubmapChanged => "submap",
+    FloatStateChanged => "changefloatingmode",
+    Urgent => "urgent",
+    Minimize => "minimized",
+    Screencast => "screencast",
+    WindowTitleChanged => "windowtitle",
+    WindowTitleChangedV2 => "windowtitlev2",
+    GroupToggled => "togglegroup",
+    WindowMovedIntoGroup => "moveintogroup",
+    WindowMovedOutOfGroup => "moveoutofgroup",
+    IgnoreGroupLockToggled => "ignoregrouplock",
+    GroupsLockToggled => "lockgroups",
+    ConfigReloaded => "configreloaded",
+    WindowPinned => "pin",
+    Custom => "custom",
+    Bell => "bell",
+}
+
+/// The set of event kinds a listener delivers, `None` delivers every event
+pub(crate) type EventFilter = Option<HashSet<EventKind>>;
+
+pub(crate) fn filter_allows(filter: &EventFilter, kind: EventKind) -> bool {
+    match filter {
+        Some(kinds) => kinds.contains(&kind),
+        None => true,
+    }
+}
+
+/// Holds back events of the coalesced kinds, so only the latest one of a burst is delivered
+#[derive(Default)]
+pub(crate) struct Coalescer {
+    window: Duration,
+    kinds: HashSet<EventKind>,
+    pending: Vec<PendingEvent>,
+}
+
+struct PendingEvent {
+    deadline: Instant,
+    line: String,
+    event: Event,
+}
+
+impl Coalescer {
+    pub(crate) fn new(window: Duration, kinds: HashSet<EventKind>) -> Self {
+        Coalescer {
+            window,
+            kinds,
+            pending: vec![],
+        }
+    }
+
+    /// Returns the event back if it isn't coalesced, otherwise holds it back,
+    /// replacing the pending event of the same kind
+    pub(crate) fn push(
+        &mut self,
+        line: String,
+        event: Event,
+        now: Instant,
+    ) -> Option<(String, Event)> {
+        let kind = event.kind();
+        if !self.kinds.contains(&kind) {
+            return Some((line, event));
         }
-        Err(RegexError::__Nonexhaustive) => unreachable!(),
+        match self
+            .pending
+            .iter_mut()
+            .find(|pending| pending.event.kind() == kind)
+        {
+            Some(pending) => {
+                pending.line = line;
+                pending.event = event;
+            }
+            None => self.pending.push(PendingEvent {
+                deadline: now + self.window,
+                line,
+                event,
+            }),
+        }
+        None
+    }
+
+    /// When the next pending event is due
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|pending| pending.deadline).min()
+    }
+
+    /// Takes the pending events that are due, oldest first
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<(String, Event)> {
+        let (mut due, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|pending| pending.deadline <= now);
+        self.pending = pending;
+        due.sort_by_key(|pending| pending.deadline);
+        due.into_iter()
+            .map(|pending| (pending.line, pending.event))
+            .collect()
+    }
+
+    /// Takes every pending event, oldest first
+    pub(crate) fn take_all(&mut self) -> Vec<(String, Event)> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.sort_by_key(|pending| pending.deadline);
+        pending
+            .into_iter()
+            .map(|pending| (pending.line, pending.event))
+            .collect()
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => crate::runtime::sleep_until(deadline).await,
+        None => std::future::pending().await,
     }
 }
 
-fn check_for_regex_set_error(val: Result<RegexSet, RegexError>) -> RegexSet {
-    match val {
-        Ok(value) => value,
-        Err(RegexError::Syntax(str)) => panic!("syntax error: {str}"),
-        Err(RegexError::CompiledTooBig(size)) => {
-            panic!("The compiled regex size is too big ({size})")
+/// This pub(crate) enum holds what a async listener was woken up for
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) enum Wake {
+    /// The listener was stopped
+    Stopped,
+    /// A coalesced event is due
+    Due,
+    /// The next line, `None` if the socket was closed
+    Line(io::Result<Option<String>>),
+}
+
+/// This pub(crate) function waits for the listener to be stopped, the coalesced events
+/// to be due at `deadline` or the next line, checked in that order
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) async fn next_wake(
+    handle: &ListenerHandle,
+    deadline: Option<Instant>,
+    lines: &mut crate::runtime::Lines,
+) -> Wake {
+    use std::task::Poll;
+    let mut stopped = std::pin::pin!(handle.stopped());
+    let mut due = std::pin::pin!(sleep_until(deadline));
+    let mut line = std::pin::pin!(lines.next_line());
+    std::future::poll_fn(|cx| {
+        if stopped.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Wake::Stopped);
         }
-        Err(RegexError::__Nonexhaustive) => unreachable!(),
+        if due.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Wake::Due);
+        }
+        line.as_mut().poll(cx).map(Wake::Line)
+    })
+    .await
+}
+
+pub(crate) enum NextLine {
+    Line(String),
+    TimedOut,
+    Closed,
+}
+
+/// Reads the next line from the event socket, waiting no longer than until `deadline`
+pub(crate) fn read_line_blocking(
+    reader: &mut io::BufReader<std::os::unix::net::UnixStream>,
+    buf: &mut Vec<u8>,
+    deadline: Option<Instant>,
+) -> io::Result<NextLine> {
+    use io::BufRead;
+
+    // a zero timeout is rejected, so wait at least a millisecond
+    let timeout = deadline.map(|deadline| {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1))
+    });
+    reader.get_ref().set_read_timeout(timeout)?;
+    match reader.read_until(b'\n', buf) {
+        Ok(0) if buf.is_empty() => Ok(NextLine::Closed),
+        Ok(_) => {
+            if buf.last() == Some(&b'\n') {
+                buf.pop();
+            }
+            match String::from_utf8(std::mem::take(buf)) {
+                Ok(line) => Ok(NextLine::Line(line)),
+                Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            }
+        }
+        Err(error)
+            if matches!(
+                error.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(NextLine::TimedOut)
+        }
+        Err(error) => Err(error),
     }
 }
 
-fn parse_string_as_work(str: String) -> WorkspaceType {
-    if str == "special" {
+fn invalid_data(event: &str, data: &str) -> io::Error {
+    log!(debug, "invalid data for event {event}: {data:?}");
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid data for event {event}: {data:?}"),
+    )
+}
+
+fn parse_string_as_work(str: &str) -> WorkspaceType {
+    if str == "special" || str.starts_with("special:") {
         WorkspaceType::Special
     } else {
-        match str.parse::<i8>() {
-            Ok(num) => WorkspaceType::from(num),
-            Err(e) => panic!("error parsing string as u8:\nthe string:{str}\nerror message: {e}"),
-        }
-    }
-}
-
-/// This internal function parses event strings
-pub(crate) fn event_parser(event: String) -> io::Result<Vec<Event>> {
-    lazy_static! {
-        static ref EVENT_SET: RegexSet = check_for_regex_set_error(RegexSet::new(&[
-            r"\bworkspace>>(?P<workspace>special|[0-9]{1,2}|)",
-            r"destroyworkspace>>(?P<workspace>special|[0-9]{1,2})",
-            r"createworkspace>>(?P<workspace>special|[0-9]{1,2})",
-            r"focusedmon>>(?P<monitor>.*),(?P<workspace>[0-9]{1,2})",
-            r"activewindow>>(?P<class>.*),(?P<title>.*)",
-            r"fullscreen>>(?P<state>0|1)",
-            r"monitorremoved>>(?P<monitor>.*)",
-            r"monitoradded>>(?P<monitor>.*)"
-        ]));
-        static ref EVENT_REGEXES: Vec<Regex> = EVENT_SET
-            .patterns()
-            .iter()
-            .map(|pat| check_for_regex_error(Regex::new(pat)))
-            .collect();
-    }
-
-    let event_iter = event.trim().split('\n');
-
-    let mut events: Vec<Event> = vec![];
-
-    for item in event_iter {
-        let matches = EVENT_SET.matches(item);
-        let matches_event: Vec<_> = matches.into_iter().collect();
-        let captures = if !EVENT_REGEXES.is_empty() && !matches_event.is_empty() {
-            match EVENT_REGEXES[matches_event[0]].captures(item) {
-                Some(captures) => captures,
-                None => panic!("Regex has no captures"),
-            }
+        match str.parse::<WorkspaceId>() {
+            Ok(num) if num >= 0 => WorkspaceType::Regular(num),
+            _ => WorkspaceType::Named(str.to_string()),
+        }
+    }
+}
+
+fn parse_bool(event: &str, str: &str) -> io::Result<bool> {
+    match str {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(invalid_data(event, str)),
+    }
+}
+
+fn parse_int<T: std::str::FromStr>(event: &str, str: &str) -> io::Result<T> {
+    str.parse::<T>().map_err(|_| invalid_data(event, str))
+}
+
+fn parse_opt_address(str: &str) -> Option<Address> {
+    match str.trim_matches(',') {
+        "" => None,
+        addr => Some(Address::new(addr)),
+    }
+}
+
+/// Splits the event data into exactly `N` comma separated arguments,
+/// the last argument keeps any remaining commas
+fn args<'a, const N: usize>(event: &str, data: &'a str) -> io::Result<[&'a str; N]> {
+    let args: Vec<&str> = data.splitn(N, ',').collect();
+    args.try_into().map_err(|_| invalid_data(event, data))
+}
+
+/// Splits the event data on its last comma, for events where only the first argument can hold commas
+fn rargs<'a>(event: &str, data: &'a str) -> io::Result<(&'a str, &'a str)> {
+    data.rsplit_once(',')
+        .ok_or_else(|| invalid_data(event, data))
+}
+
+/// This internal function parses a single event line (`EVENT>>DATA`)
+///
+/// Returns `None` for lines that aren't a event and for events not in the filter,
+/// which are skipped before their data is parsed.
+/// Events with a unknown name are returned as [`Event::Unknown`]
+pub(crate) fn parse_event(line: &str, filter: &EventFilter) -> io::Result<Option<Event>> {
+    let (name, data) = match line.split_once(">>") {
+        Some(split) => split,
+        None => return Ok(None),
+    };
+    if !filter_allows(filter, EventKind::from_name(name)) {
+        return Ok(None);
+    }
+    #[cfg(feature = "tracing")]
+    let _entered = tracing::trace_span!("parse_event", name).entered();
+
+    let event = match name {
+        "workspace" => Event::WorkspaceChanged(if data.is_empty() {
+            WorkspaceType::Regular(1)
         } else {
-            panic!("something has went down -{:#?}-", matches_event)
-        };
-
-        if matches_event.len() == 1 {
-            match matches_event[0] {
-                0 => {
-                    // WorkspaceChanged
-                    let captured = &captures["workspace"];
-                    let workspace = if !captured.is_empty() {
-                        parse_string_as_work(captured.to_string())
-                    } else {
-                        WorkspaceType::Regular(1)
-                    };
-                    events.push(Event::WorkspaceChanged(workspace));
-                }
-                1 => {
-                    // destroyworkspace
-                    let workspace = parse_string_as_work(captures["workspace"].to_string());
-                    events.push(Event::WorkspaceDeleted(workspace));
-                }
-                2 => {
-                    // WorkspaceAdded
-                    let workspace = parse_string_as_work(captures["workspace"].to_string());
-                    events.push(Event::WorkspaceAdded(workspace));
-                }
-                3 => {
-                    // ActiveMonitorChanged
-                    let monitor = &captures["monitor"];
-                    let workspace = match captures["workspace"].parse::<i8>() {
-                        Ok(num) => num,
-                        Err(e) => panic!("error parsing string as u8: {e}"),
-                    };
-                    events.push(Event::ActiveMonitorChanged(MonitorEventData(
-                        monitor.to_string(),
-                        WorkspaceType::from(workspace),
-                    )));
-                }
-                4 => {
-                    // ActiveWindowChanged
-                    let class = &captures["class"];
-                    let title = &captures["title"];
-                    if !class.is_empty() && !title.is_empty() {
-                        events.push(Event::ActiveWindowChanged(Some(WindowEventData(
-                            class.to_string(),
-                            title.to_string(),
-                        ))));
-                    } else {
-                        events.push(Event::ActiveWindowChanged(None));
-                    }
-                }
-                5 => {
-                    // FullscreenStateChanged
-                    let state = &captures["state"] != "0";
-                    events.push(Event::FullscreenStateChanged(state))
-                }
-                6 => {
-                    // MonitorRemoved
-                    let monitor = &captures["monitor"];
-                    events.push(Event::MonitorRemoved(monitor.to_string()));
-                }
-                7 => {
-                    // MonitorAdded
-                    let monitor = &captures["monitor"];
-                    events.push(Event::MonitorAdded(monitor.to_string()));
-                }
-                _ => panic!("How did this happen?"),
+            parse_string_as_work(data)
+        }),
+        "workspacev2" => {
+            let [id, ws_name] = args(name, data)?;
+            Event::WorkspaceChangedV2(WorkspaceV2EventData {
+                id: parse_int(name, id)?,
+                name: ws_name.to_string(),
+            })
+        }
+        "destroyworkspace" => Event::WorkspaceDeleted(parse_string_as_work(data)),
+        "destroyworkspacev2" => {
+            let [id, ws_name] = args(name, data)?;
+            Event::WorkspaceDeletedV2(WorkspaceV2EventData {
+                id: parse_int(name, id)?,
+                name: ws_name.to_string(),
+            })
+        }
+        "createworkspace" => Event::WorkspaceAdded(parse_string_as_work(data)),
+        "createworkspacev2" => {
+            let [id, ws_name] = args(name, data)?;
+            Event::WorkspaceAddedV2(WorkspaceV2EventData {
+                id: parse_int(name, id)?,
+                name: ws_name.to_string(),
+            })
+        }
+        "moveworkspace" => {
+            let (workspace, monitor) = rargs(name, data)?;
+            Event::WorkspaceMoved(MonitorEventData(
+                monitor.to_string(),
+                parse_string_as_work(workspace),
+            ))
+        }
+        "moveworkspacev2" => {
+            let [id, rest] = args(name, data)?;
+            let (ws_name, monitor) = rargs(name, rest)?;
+            Event::WorkspaceMovedV2(WorkspaceMovedV2EventData {
+                id: parse_int(name, id)?,
+                name: ws_name.to_string(),
+                monitor: monitor.to_string(),
+            })
+        }
+        "renameworkspace" => {
+            let [id, new_name] = args(name, data)?;
+            Event::WorkspaceRenamed(WorkspaceV2EventData {
+                id: parse_int(name, id)?,
+                name: new_name.to_string(),
+            })
+        }
+        "activespecial" => {
+            let (workspace, monitor) = rargs(name, data)?;
+            Event::ActiveSpecialChanged(ActiveSpecialEventData {
+                workspace_name: match workspace {
+                    "" => None,
+                    workspace => Some(workspace.to_string()),
+                },
+                monitor: monitor.to_string(),
+            })
+        }
+        "focusedmon" => {
+            let [monitor, workspace] = args(name, data)?;
+            Event::ActiveMonitorChanged(MonitorEventData(
+                monitor.to_string(),
+                parse_string_as_work(workspace),
+            ))
+        }
+        "focusedmonv2" => {
+            let [monitor, workspace_id] = args(name, data)?;
+            Event::ActiveMonitorChangedV2(MonitorFocusV2EventData {
+                monitor: monitor.to_string(),
+                workspace_id: parse_int(name, workspace_id)?,
+            })
+        }
+        "activewindow" => {
+            let [class, title] = args(name, data)?;
+            if class.is_empty() && title.is_empty() {
+                Event::ActiveWindowChanged(None)
+            } else {
+                Event::ActiveWindowChanged(Some(WindowEventData(
+                    class.to_string(),
+                    title.to_string(),
+                )))
             }
-        } else {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Unknown event"));
+        }
+        "activewindowv2" => Event::ActiveWindowChangedV2(parse_opt_address(data)),
+        "fullscreen" => Event::FullscreenStateChanged(parse_bool(name, data)?),
+        "monitoradded" => Event::MonitorAdded(data.to_string()),
+        "monitoraddedv2" => {
+            let [id, mon_name, description] = args(name, data)?;
+            Event::MonitorAddedV2(MonitorV2EventData {
+                id: parse_int(name, id)?,
+                name: mon_name.to_string(),
+                description: description.to_string(),
+            })
+        }
+        "monitorremoved" => Event::MonitorRemoved(data.to_string()),
+        "monitorremovedv2" => {
+            let [id, mon_name, description] = args(name, data)?;
+            Event::MonitorRemovedV2(MonitorV2EventData {
+                id: parse_int(name, id)?,
+                name: mon_name.to_string(),
+                description: description.to_string(),
+            })
+        }
+        "activelayout" => {
+            let [keyboard_name, layout_name] = args(name, data)?;
+            Event::LayoutChanged(LayoutEventData {
+                keyboard_name: keyboard_name.to_string(),
+                layout_name: layout_name.to_string(),
+            })
+        }
+        "openwindow" => {
+            let [address, workspace, class, title] = args(name, data)?;
+            Event::WindowOpened(WindowOpenEventData {
+                address: Address::new(address),
+                workspace: parse_string_as_work(workspace),
+                workspace_name: workspace.to_string(),
+                class: class.to_string(),
+                title: title.to_string(),
+            })
+        }
+        "closewindow" => Event::WindowClosed(Address::new(data)),
+        "movewindow" => {
+            let [address, workspace] = args(name, data)?;
+            Event::WindowMoved(WindowMoveEventData {
+                address: Address::new(address),
+                workspace: parse_string_as_work(workspace),
+            })
+        }
+        "movewindowv2" => {
+            let [address, workspace_id, workspace_name] = args(name, data)?;
+            Event::WindowMovedV2(WindowMoveV2EventData {
+                address: Address::new(address),
+                workspace_id: parse_int(name, workspace_id)?,
+                workspace_name: workspace_name.to_string(),
+            })
+        }
+        "openlayer" => Event::LayerOpened(data.to_string()),
+        "closelayer" => Event::LayerClosed(data.to_string()),
+        "submap" => Event::SubmapChanged(data.to_string()),
+        "changefloatingmode" => {
+            let [address, floating] = args(name, data)?;
+            Event::FloatStateChanged(WindowFloatEventData {
+                address: Address::new(address),
+                floating: parse_bool(name, floating)?,
+            })
+        }
+        "urgent" => Event::Urgent(Address::new(data)),
+        "minimized" => {
+            let [address, minimized] = args(name, data)?;
+            Event::Minimize(MinimizeEventData {
+                address: Address::new(address),
+                minimized: parse_bool(name, minimized)?,
+            })
+        }
+        "screencast" => {
+            let [active, owner] = args(name, data)?;
+            Event::Screencast(ScreencastEventData {
+                active: parse_bool(name, active)?,
+                owner: match owner {
+                    "0" => ScreencastOwner::Monitor,
+                    "1" => ScreencastOwner::Window,
+                    _ => return Err(invalid_data(name, data)),
+                },
+            })
+        }
+        "windowtitle" => Event::WindowTitleChanged(Address::new(data)),
+        "windowtitlev2" => {
+            let [address, title] = args(name, data)?;
+            Event::WindowTitleChangedV2(WindowTitleEventData {
+                address: Address::new(address),
+                title: title.to_string(),
+            })
+        }
+        "togglegroup" => {
+            let (opened, addresses) = data.split_once(',').unwrap_or((data, ""));
+            Event::GroupToggled(GroupToggleEventData {
+                opened: parse_bool(name, opened)?,
+                addresses: addresses
+                    .split(',')
+                    .filter(|addr| !addr.is_empty())
+                    .map(Address::new)
+                    .collect(),
+            })
+        }
+        "moveintogroup" => Event::WindowMovedIntoGroup(Address::new(data)),
+        "moveoutofgroup" => Event::WindowMovedOutOfGroup(Address::new(data)),
+        "ignoregrouplock" => Event::IgnoreGroupLockToggled(parse_bool(name, data)?),
+        "lockgroups" => Event::GroupsLockToggled(parse_bool(name, data)?),
+        "configreloaded" => Event::ConfigReloaded,
+        "pin" => {
+            let [address, pinned] = args(name, data)?;
+            Event::WindowPinned(WindowPinEventData {
+                address: Address::new(address),
+                pinned: parse_bool(name, pinned)?,
+            })
+        }
+        "custom" => Event::Custom(data.to_string()),
+        "bell" => Event::Bell(parse_opt_address(data)),
+        // Hyprland (or a plugin) sends events this crate doesn't know about (yet)
+        _ => Event::Unknown(UnknownEventData {
+            name: name.to_string(),
+            data: data.to_string(),
+        }),
+    };
+
+    Ok(Some(event))
+}
+
+/// This internal function parses a event line like [`parse_event`], but logs a line it can't parse
+/// and skips it, so a single malformed event doesn't stop the listener
+pub(crate) fn parse_event_or_skip(line: &str, filter: &EventFilter) -> Option<Event> {
+    match parse_event(line, filter) {
+        Ok(event) => event,
+        Err(error) => {
+            log!(warn, "skipped the event {line:?}: {error}");
+            None
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(events)
+    /// A line of every event this crate knows about
+    const LINES: &[&str] = &[
+        "workspace>>2",
+        "workspacev2>>2,2",
+        "destroyworkspace>>coding",
+        "destroyworkspacev2>>-1337,coding",
+        "createworkspace>>special:scratch",
+        "createworkspacev2>>-98,special:scratch",
+        "moveworkspace>>3,DP-1",
+        "moveworkspacev2>>3,3,DP-1",
+        "renameworkspace>>3,music",
+        "activespecial>>special:scratch,DP-1",
+        "activewindow>>kitty,nvim",
+        "activewindowv2>>5581b1f5c9a0",
+        "focusedmon>>DP-1,2",
+        "focusedmonv2>>DP-1,2",
+        "fullscreen>>1",
+        "monitoradded>>HDMI-A-1",
+        "monitoraddedv2>>1,HDMI-A-1,Dell Inc. DELL U2720Q",
+        "monitorremoved>>HDMI-A-1",
+        "monitorremovedv2>>1,HDMI-A-1,Dell Inc. DELL U2720Q",
+        "activelayout>>at-translated-set-2-keyboard,English (US)",
+        "openwindow>>5581b1f5c9a0,2,kitty,nvim",
+        "closewindow>>5581b1f5c9a0",
+        "movewindow>>5581b1f5c9a0,3",
+        "movewindowv2>>5581b1f5c9a0,3,3",
+        "openlayer>>waybar",
+        "closelayer>>waybar",
+        "submap>>resize",
+        "changefloatingmode>>5581b1f5c9a0,1",
+        "urgent>>5581b1f5c9a0",
+        "minimized>>5581b1f5c9a0,1",
+        "screencast>>1,0",
+        "windowtitle>>5581b1f5c9a0",
+        "windowtitlev2>>5581b1f5c9a0,nvim",
+        "togglegroup>>1,5581b1f5c9a0,5581b1f5d010",
+        "moveintogroup>>5581b1f5c9a0",
+        "moveoutofgroup>>5581b1f5c9a0",
+        "ignoregrouplock>>1",
+        "lockgroups>>0",
+        "configreloaded>>",
+        "pin>>5581b1f5c9a0,1",
+        "custom>>hello",
+        "bell>>5581b1f5c9a0",
+    ];
+
+    fn parse(line: &str) -> Option<Event> {
+        parse_event(line, &None).ok().flatten()
+    }
+
+    #[test]
+    fn every_event_is_parsed_as_its_kind() {
+        for line in LINES {
+            let name = line.split_once(">>").map_or("", |(name, _)| name);
+            let event = parse(line);
+            assert_ne!(EventKind::from_name(name), EventKind::Unknown, "{line:?}");
+            assert_eq!(
+                event.as_ref().map(Event::kind),
+                Some(EventKind::from_name(name)),
+                "{line:?} was parsed as {event:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn workspace_events() {
+        assert!(matches!(
+            parse("workspace>>2"),
+            Some(Event::WorkspaceChanged(WorkspaceType::Regular(2)))
+        ));
+        assert!(matches!(
+            parse("workspace>>"),
+            Some(Event::WorkspaceChanged(WorkspaceType::Regular(1)))
+        ));
+        assert!(matches!(
+            parse("destroyworkspace>>-1"),
+            Some(Event::WorkspaceDeleted(WorkspaceType::Named(name))) if name == "-1"
+        ));
+        assert!(matches!(
+            parse("createworkspace>>special:scratch"),
+            Some(Event::WorkspaceAdded(WorkspaceType::Special))
+        ));
+        assert!(matches!(
+            parse("workspacev2>>-1337,a,b"),
+            Some(Event::WorkspaceChangedV2(data)) if data.id == -1337 && data.name == "a,b"
+        ));
+        assert!(matches!(
+            parse("moveworkspace>>a,b,DP-1"),
+            Some(Event::WorkspaceMoved(MonitorEventData(monitor, WorkspaceType::Named(name))))
+                if monitor == "DP-1" && name == "a,b"
+        ));
+        assert!(matches!(
+            parse("moveworkspacev2>>-1337,a,b,DP-1"),
+            Some(Event::WorkspaceMovedV2(data))
+                if data.id == -1337 && data.name == "a,b" && data.monitor == "DP-1"
+        ));
+        assert!(matches!(
+            parse("renameworkspace>>3,a,b"),
+            Some(Event::WorkspaceRenamed(data)) if data.id == 3 && data.name == "a,b"
+        ));
+        assert!(matches!(
+            parse("activespecial>>,DP-1"),
+            Some(Event::ActiveSpecialChanged(data))
+                if data.workspace_name.is_none() && data.monitor == "DP-1"
+        ));
+    }
+
+    #[test]
+    fn window_events() {
+        let address = Address::new("5581b1f5c9a0");
+        assert!(matches!(
+            parse("activewindow>>kitty,vim, but a title, with commas"),
+            Some(Event::ActiveWindowChanged(Some(WindowEventData(class, title))))
+                if class == "kitty" && title == "vim, but a title, with commas"
+        ));
+        assert!(matches!(
+            parse("activewindow>>,"),
+            Some(Event::ActiveWindowChanged(None))
+        ));
+        assert!(matches!(
+            parse("activewindowv2>>5581b1f5c9a0"),
+            Some(Event::ActiveWindowChangedV2(Some(active))) if active == address
+        ));
+        assert!(matches!(
+            parse("activewindowv2>>,"),
+            Some(Event::ActiveWindowChangedV2(None))
+        ));
+        assert!(matches!(
+            parse("openwindow>>5581b1f5c9a0,special:scratch,kitty,a, b"),
+            Some(Event::WindowOpened(data)) if data.address == address
+                && data.workspace == WorkspaceType::Special
+                && data.workspace_name == "special:scratch"
+                && data.class == "kitty"
+                && data.title == "a, b"
+        ));
+        assert!(matches!(
+            parse("movewindowv2>>5581b1f5c9a0,-1337,a,b"),
+            Some(Event::WindowMovedV2(data)) if data.address == address
+                && data.workspace_id == -1337
+                && data.workspace_name == "a,b"
+        ));
+        assert!(matches!(
+            parse("changefloatingmode>>5581b1f5c9a0,0"),
+            Some(Event::FloatStateChanged(data)) if data.address == address && !data.floating
+        ));
+        assert!(matches!(
+            parse("windowtitlev2>>5581b1f5c9a0,a, b"),
+            Some(Event::WindowTitleChangedV2(data)) if data.address == address && data.title == "a, b"
+        ));
+        assert!(matches!(
+            parse("togglegroup>>0,5581b1f5c9a0,5581b1f5d010"),
+            Some(Event::GroupToggled(data)) if !data.opened
+                && data.addresses == [address.clone(), Address::new("5581b1f5d010")]
+        ));
+        assert!(matches!(
+            parse("togglegroup>>1"),
+            Some(Event::GroupToggled(data)) if data.opened && data.addresses.is_empty()
+        ));
+        assert!(matches!(
+            parse("pin>>5581b1f5c9a0,1"),
+            Some(Event::WindowPinned(data)) if data.address == address && data.pinned
+        ));
+        assert!(matches!(parse("bell>>"), Some(Event::Bell(None))));
+    }
+
+    #[test]
+    fn other_events() {
+        assert!(matches!(
+            parse("focusedmonv2>>DP-1,-1337"),
+            Some(Event::ActiveMonitorChangedV2(data))
+                if data.monitor == "DP-1" && data.workspace_id == -1337
+        ));
+        assert!(matches!(
+            parse("monitoraddedv2>>1,HDMI-A-1,Dell Inc. DELL U2720Q, 27\""),
+            Some(Event::MonitorAddedV2(data)) if data.id == 1
+                && data.name == "HDMI-A-1"
+                && data.description == "Dell Inc. DELL U2720Q, 27\""
+        ));
+        assert!(matches!(
+            parse("activelayout>>keyboard,English (US)"),
+            Some(Event::LayoutChanged(data))
+                if data.keyboard_name == "keyboard" && data.layout_name == "English (US)"
+        ));
+        assert!(matches!(
+            parse("screencast>>0,1"),
+            Some(Event::Screencast(data)) if !data.active && data.owner == ScreencastOwner::Window
+        ));
+        assert!(matches!(
+            parse("submap>>"),
+            Some(Event::SubmapChanged(submap)) if submap.is_empty()
+        ));
+        assert!(matches!(
+            parse("custom>>a>>b"),
+            Some(Event::Custom(data)) if data == "a>>b"
+        ));
+        assert!(matches!(
+            parse("pluginevent>>a,b"),
+            Some(Event::Unknown(data)) if data.name == "pluginevent" && data.data == "a,b"
+        ));
+    }
+
+    #[test]
+    fn malformed_events_are_skipped() {
+        let malformed = [
+            "workspacev2>>two,2",
+            "workspacev2>>2",
+            "fullscreen>>yes",
+            "monitoraddedv2>>1,HDMI-A-1",
+            "monitoraddedv2>>300,HDMI-A-1,Dell",
+            "screencast>>1,2",
+            "movewindowv2>>5581b1f5c9a0,3",
+            "togglegroup>>,5581b1f5c9a0",
+        ];
+        for line in malformed {
+            let error = parse_event(line, &None).err();
+            assert_eq!(
+                error.map(|error| error.kind()),
+                Some(io::ErrorKind::InvalidData),
+                "{line:?}"
+            );
+            assert!(parse_event_or_skip(line, &None).is_none());
+        }
+        assert!(matches!(parse_event("not a event", &None), Ok(None)));
+        assert!(parse_event_or_skip("fullscreen>>1", &None).is_some());
+    }
+
+    #[test]
+    fn filtered_events_are_skipped() {
+        let filter = Some(HashSet::from([EventKind::WindowClosed, EventKind::Unknown]));
+        assert!(matches!(
+            parse_event("closewindow>>5581b1f5c9a0", &filter),
+            Ok(Some(Event::WindowClosed(_)))
+        ));
+        // the data isn't parsed, so it can't fail
+        assert!(matches!(parse_event("fullscreen>>yes", &filter), Ok(None)));
+        assert!(matches!(
+            parse_event("pluginevent>>", &filter),
+            Ok(Some(Event::Unknown(_)))
+        ));
+    }
 }
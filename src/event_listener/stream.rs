@@ -0,0 +1,68 @@
+use crate::shared::*;
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::event_listener::shared::*;
+
+/// This function connects to the event socket and returns the events as a [`Stream`]
+///
+/// This is a alternative to the event listeners, for when stream combinators are more convenient
+/// than handlers, the events are parsed the same way
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use futures_util::StreamExt;
+/// use hyprland::event_listener::{event_stream, Event};
+///
+/// let mut events = Box::pin(event_stream().await?);
+/// while let Some(event) = events.next().await {
+///     if let Event::WorkspaceChanged(id) = event? {
+///         println!("changed workspace to {id:?}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn event_stream() -> HyprResult<impl Stream<Item = HyprResult<Event>>> {
+    let raw_events = raw_event_stream().await?;
+    Ok(raw_events.map(|raw| raw.map(|raw| raw.event)))
+}
+
+/// This function connects to the event socket and returns the events as a [`Stream`],
+/// together with the lines Hyprland sent for them
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use futures_util::StreamExt;
+/// use hyprland::event_listener::raw_event_stream;
+///
+/// let mut events = Box::pin(raw_event_stream().await?);
+/// while let Some(raw) = events.next().await {
+///     let raw = raw?;
+///     println!("{} was parsed as {:?}", raw.line, raw.event);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn raw_event_stream() -> HyprResult<impl Stream<Item = HyprResult<RawEvent>>> {
+    let socket_path = get_socket_path(SocketType::Listener)?;
+
+    let stream = crate::runtime::connect(&socket_path)
+        .await
+        .map_err(|error| connect_error(&socket_path, error))?;
+    let lines = crate::runtime::Lines::new(stream);
+
+    Ok(stream::unfold(lines, |mut lines| async move {
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => return None,
+                Err(error) => return Some((Err(error.into()), lines)),
+            };
+            match parse_event(&line, &None) {
+                Ok(Some(event)) => return Some((Ok(RawEvent { line, event }), lines)),
+                Ok(None) => {}
+                Err(error) => return Some((Err(error.into()), lines)),
+            }
+        }
+    }))
+}
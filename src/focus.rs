@@ -0,0 +1,318 @@
+//! # Focus module
+//!
+//! This module provides [`FocusHistory`](crate::focus::FocusHistory), which knows the order
+//! windows were focused in (most recently used first) for switching between them like alt-tab,
+//! which Hyprland's `cyclenext` doesn't do
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::dispatch::CycleDirection;
+//! use hyprland::focus::FocusHistory;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let history = FocusHistory::new()?;
+//!     // every press while alt is held goes one window further back
+//!     history.cycle_mru(CycleDirection::Next)?;
+//!     history.cycle_mru(CycleDirection::Next)?;
+//!     // alt was released, the next press starts from the window that's focused now
+//!     history.end_cycle();
+//!     // go back to the window that was focused before
+//!     history.focus_previous()?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::dispatch::{CycleDirection, DispatchType, WindowIdentifier};
+use crate::shared::*;
+use crate::state::HyprlandState;
+use std::sync::{Mutex, PoisonError};
+
+/// This struct follows the focused window with a [`HyprlandState`], and focuses windows in the order
+/// they were focused in
+///
+/// While cycling, the order is kept as it was when the cycle started, so focusing windows with
+/// [`cycle_mru`](Self::cycle_mru) doesn't change which window comes next.
+/// The cycle ends with [`end_cycle`](Self::end_cycle), or when another window is focused
+pub struct FocusHistory {
+    state: HyprlandState,
+    cycle: Mutex<Option<Cycle>>,
+}
+
+/// The order of a cycle, and where it's at
+struct Cycle {
+    order: Vec<Address>,
+    index: usize,
+    /// The window that was focused before the last step, focused until the step's event arrives
+    from: Option<Address>,
+}
+
+impl FocusHistory {
+    /// This method starts following the focus of the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        FocusHistory::for_instance(&Instance::current()?)
+    }
+
+    /// This method starts following the focus of a specific Hyprland instance
+    pub fn for_instance(instance: &Instance) -> HyprResult<Self> {
+        Ok(FocusHistory::with_state(HyprlandState::for_instance(
+            instance,
+        )?))
+    }
+
+    /// This method follows the focus with a state that already exists
+    pub fn with_state(state: HyprlandState) -> Self {
+        FocusHistory {
+            state,
+            cycle: Mutex::new(None),
+        }
+    }
+
+    /// This method returns the state the focus is followed with
+    pub fn state(&self) -> &HyprlandState {
+        &self.state
+    }
+
+    /// This method returns the addresses of all windows, the most recently focused first,
+    /// see [`HyprlandState::focus_history`]
+    pub fn history(&self) -> Vec<Address> {
+        self.state.focus_history()
+    }
+
+    /// This method returns the window that was focused before the active one
+    pub fn previous(&self) -> Option<Address> {
+        let history = self.history();
+        let active = self.state.active_window().map(|client| client.address);
+        history
+            .into_iter()
+            .find(|address| Some(address) != active.as_ref())
+    }
+
+    /// This method focuses the window that was focused before the active one,
+    /// and returns it, `None` if there's no other window (blocking)
+    ///
+    /// It ends the cycle, if there's one
+    pub fn focus_previous(&self) -> HyprResult<Option<Address>> {
+        self.end_cycle();
+        let previous = self.previous();
+        if let Some(address) = &previous {
+            crate::dispatch::dispatch_blocking(focus_dispatch(address))?;
+        }
+        Ok(previous)
+    }
+
+    /// This method focuses the window that was focused before the active one,
+    /// and returns it, `None` if there's no other window (async)
+    ///
+    /// It ends the cycle, if there's one
+    #[cfg(feature = "async")]
+    pub async fn focus_previous_async(&self) -> HyprResult<Option<Address>> {
+        self.end_cycle();
+        let previous = self.previous();
+        if let Some(address) = &previous {
+            crate::dispatch::dispatch(focus_dispatch(address)).await?;
+        }
+        Ok(previous)
+    }
+
+    /// This method focuses the next window in the cycle, [`CycleDirection::Next`] being the one
+    /// focused longer ago, and returns it, `None` if there's no other window (blocking)
+    ///
+    /// It starts a cycle if there isn't one
+    pub fn cycle_mru(&self, direction: CycleDirection) -> HyprResult<Option<Address>> {
+        let next = self.step(direction);
+        if let Some(address) = &next {
+            crate::dispatch::dispatch_blocking(focus_dispatch(address))?;
+        }
+        Ok(next)
+    }
+
+    /// This method focuses the next window in the cycle, [`CycleDirection::Next`] being the one
+    /// focused longer ago, and returns it, `None` if there's no other window (async)
+    ///
+    /// It starts a cycle if there isn't one
+    #[cfg(feature = "async")]
+    pub async fn cycle_mru_async(&self, direction: CycleDirection) -> HyprResult<Option<Address>> {
+        let next = self.step(direction);
+        if let Some(address) = &next {
+            crate::dispatch::dispatch(focus_dispatch(address)).await?;
+        }
+        Ok(next)
+    }
+
+    /// This method ends the cycle, so the next one starts from the window that's focused then,
+    /// like when the modifier of a alt-tab bind is released
+    pub fn end_cycle(&self) {
+        *self.cycle.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Returns the window to focus next in the cycle, starting a new one if another window
+    /// was focused since the last step
+    fn step(&self, direction: CycleDirection) -> Option<Address> {
+        let active = self.state.active_window().map(|client| client.address);
+        let mut cycle = self.cycle.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = cycle.take().filter(|cycle| {
+            active.as_ref() == cycle.order.get(cycle.index) || active == cycle.from
+        });
+        let mut current = current.unwrap_or_else(|| {
+            let order = self.history();
+            // the active window is first in the history, without one the cycle starts at either end
+            let index = order
+                .iter()
+                .position(|address| Some(address) == active.as_ref())
+                .unwrap_or(match direction {
+                    CycleDirection::Next => order.len().saturating_sub(1),
+                    CycleDirection::Previous => 0,
+                });
+            Cycle {
+                order,
+                index,
+                from: None,
+            }
+        });
+        let len = current.order.len();
+        // closed windows stay in the order until the cycle ends, they're skipped
+        let index = (1..=len)
+            .map(|step| match direction {
+                CycleDirection::Next => (current.index + step) % len,
+                CycleDirection::Previous => (current.index + len - step) % len,
+            })
+            .find(|index| {
+                let address = &current.order[*index];
+                Some(address) != active.as_ref() && self.state.client(address).is_some()
+            })?;
+        current.index = index;
+        let next = current.order[index].clone();
+        current.from = active;
+        *cycle = Some(current);
+        Some(next)
+    }
+}
+
+fn focus_dispatch(address: &Address) -> DispatchType {
+    DispatchType::FocusWindow(WindowIdentifier::Address(address.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_listener::{Event, WindowOpenEventData};
+
+    /// Returns the history of a state with the windows `a`, `b` and `c`, focused in that order
+    fn history() -> FocusHistory {
+        let state = HyprlandState::empty();
+        for window in ["a", "b", "c"] {
+            state.apply(&Event::WindowOpened(WindowOpenEventData {
+                address: Address::new(window),
+                workspace: WorkspaceType::Regular(1),
+                workspace_name: "1".to_string(),
+                class: "kitty".to_string(),
+                title: window.to_string(),
+            }));
+            focus(&state, window);
+        }
+        FocusHistory::with_state(state)
+    }
+
+    /// Sends the event of the window being focused
+    fn focus(state: &HyprlandState, window: &str) {
+        state.apply(&Event::ActiveWindowChangedV2(Some(Address::new(window))));
+    }
+
+    /// Steps and focuses the window that was stepped to, returns its address without `0x`
+    fn step(history: &FocusHistory, direction: CycleDirection) -> Option<String> {
+        let next = history.step(direction)?.to_string();
+        focus(history.state(), &next);
+        next.strip_prefix("0x").map(str::to_string)
+    }
+
+    #[test]
+    fn cycles_keep_their_order() {
+        let history = history();
+        assert_eq!(history.previous(), Some(Address::new("b")));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("b"));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("a"));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+        assert_eq!(
+            step(&history, CycleDirection::Previous).as_deref(),
+            Some("a")
+        );
+        // the history changed while cycling, the cycle didn't
+        assert_eq!(
+            history.history(),
+            [Address::new("a"), Address::new("c"), Address::new("b")]
+        );
+
+        history.end_cycle();
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+        assert_eq!(
+            step(&history, CycleDirection::Previous).as_deref(),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn steps_before_the_focus_event_continue_the_cycle() {
+        let history = history();
+        assert_eq!(history.step(CycleDirection::Next), Some(Address::new("b")));
+        assert_eq!(history.step(CycleDirection::Next), Some(Address::new("a")));
+        // the events of both steps arrive
+        focus(history.state(), "b");
+        focus(history.state(), "a");
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn focusing_another_window_starts_a_new_cycle() {
+        let history = history();
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("b"));
+        // clicked on a, so the history is a, b, c
+        focus(history.state(), "a");
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("b"));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn closed_windows_are_skipped() {
+        let history = history();
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("b"));
+        history
+            .state()
+            .apply(&Event::WindowClosed(Address::new("a")));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn cycles_without_other_windows() {
+        let history = FocusHistory::with_state(HyprlandState::empty());
+        assert_eq!(history.step(CycleDirection::Next), None);
+        assert_eq!(history.previous(), None);
+
+        let history = self::history();
+        for window in ["a", "b"] {
+            history
+                .state()
+                .apply(&Event::WindowClosed(Address::new(window)));
+        }
+        assert_eq!(history.step(CycleDirection::Next), None);
+        assert_eq!(history.step(CycleDirection::Previous), None);
+        assert_eq!(history.previous(), None);
+    }
+
+    #[test]
+    fn cycles_without_a_active_window_start_at_either_end() {
+        let history = history();
+        history.state().apply(&Event::ActiveWindowChangedV2(None));
+        // the history is c, b, a
+        assert_eq!(step(&history, CycleDirection::Next).as_deref(), Some("c"));
+        history.end_cycle();
+        history.state().apply(&Event::ActiveWindowChangedV2(None));
+        assert_eq!(
+            step(&history, CycleDirection::Previous).as_deref(),
+            Some("a")
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! # Fullscreen module
+//!
+//! This module provides [`toggle_fullscreen_blocking`](crate::fullscreen::toggle_fullscreen_blocking)
+//! and [`set_fullscreen_blocking`](crate::fullscreen::set_fullscreen_blocking), which look at the
+//! fullscreen mode of the active window and send the one dispatcher that gets it to the
+//! [`FullscreenTarget`](crate::fullscreen::FullscreenTarget), so switching between maximized,
+//! fullscreen and fake fullscreen doesn't have to go through not being fullscreen
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::fullscreen::{toggle_fullscreen_blocking, FullscreenTarget};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     // maximized goes straight to fullscreen, fullscreen goes back to not being fullscreen
+//!     toggle_fullscreen_blocking(FullscreenTarget::Fullscreen)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::data::{Client, FullscreenMode};
+use crate::dispatch::{DispatchType, FullscreenType};
+use crate::shared::HyprResult;
+
+/// This enum holds the fullscreen modes a window can be put in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FullscreenTarget {
+    /// Filling the workspace, with the gaps, borders and bars still shown
+    Maximized,
+    /// Filling the monitor
+    Fullscreen,
+    /// The window is told it's fullscreen, but it stays the size it is
+    Fake,
+}
+
+impl FullscreenTarget {
+    /// This method returns the fullscreen mode the client is in, `None` if it isn't fullscreen
+    pub fn of(client: &Client) -> Option<FullscreenTarget> {
+        match (client.fullscreen, client.fullscreen_client) {
+            (FullscreenMode::Fullscreen, _) => Some(FullscreenTarget::Fullscreen),
+            (FullscreenMode::Maximized, _) => Some(FullscreenTarget::Maximized),
+            (FullscreenMode::None, FullscreenMode::Fullscreen) => Some(FullscreenTarget::Fake),
+            (FullscreenMode::None, _) => None,
+        }
+    }
+
+    /// Returns the fullscreen mode of the window, and the one it's told it's in
+    fn modes(target: Option<FullscreenTarget>) -> (FullscreenMode, FullscreenMode) {
+        match target {
+            None => (FullscreenMode::None, FullscreenMode::None),
+            Some(FullscreenTarget::Maximized) => {
+                (FullscreenMode::Maximized, FullscreenMode::Maximized)
+            }
+            Some(FullscreenTarget::Fullscreen) => {
+                (FullscreenMode::Fullscreen, FullscreenMode::Fullscreen)
+            }
+            Some(FullscreenTarget::Fake) => (FullscreenMode::None, FullscreenMode::Fullscreen),
+        }
+    }
+}
+
+/// This function returns the dispatcher that puts the client in the fullscreen mode,
+/// or takes it out of fullscreen if the target is `None`, and `None` if it's already in it
+///
+/// The dispatcher acts on the active window, so the client should be the active one
+///
+/// ```rust
+/// use hyprland::fullscreen::{fullscreen_dispatch, FullscreenTarget};
+/// # let client: hyprland::data::Client = serde_json::from_str(r#"{"address":"0x1","at":[0,0],
+/// #     "size":[10,10],"workspace":{"id":1,"name":"1"},"floating":false,"monitor":0,"class":"",
+/// #     "title":"","pid":1,"xwayland":false,"fullscreen":1,"fullscreenClient":1}"#).unwrap();
+/// // the client is maximized
+/// let dispatch = fullscreen_dispatch(&client, Some(FullscreenTarget::Fullscreen));
+/// assert_eq!(dispatch.unwrap().to_string(), "dispatch fullscreenstate 2 2");
+/// let dispatch = fullscreen_dispatch(&client, None);
+/// assert_eq!(dispatch.unwrap().to_string(), "dispatch fullscreen 1");
+/// ```
+pub fn fullscreen_dispatch(
+    client: &Client,
+    target: Option<FullscreenTarget>,
+) -> Option<DispatchType> {
+    let current = (client.fullscreen, client.fullscreen_client);
+    let (internal, told) = FullscreenTarget::modes(target);
+    if current == (internal, told) {
+        return None;
+    }
+    // the toggles go between not being fullscreen and the mode, and work on older versions too
+    let toggle = |target| match target {
+        FullscreenTarget::Maximized => Some(FullscreenType::Maximize),
+        FullscreenTarget::Fullscreen => Some(FullscreenType::Real),
+        FullscreenTarget::Fake => None,
+    };
+    let toggled = match (FullscreenTarget::of(client), target) {
+        (None, Some(target)) if current == FullscreenTarget::modes(None) => toggle(target),
+        (Some(from), None) if current == FullscreenTarget::modes(Some(from)) => toggle(from),
+        _ => None,
+    };
+    if let Some(toggled) = toggled {
+        return Some(DispatchType::ToggleFullscreen(toggled));
+    }
+    let changed = |now: FullscreenMode, then: FullscreenMode| (now != then).then_some(then);
+    Some(DispatchType::FullscreenState(
+        changed(current.0, internal),
+        changed(current.1, told),
+    ))
+}
+
+/// This function puts the active window in the fullscreen mode, or takes it out of fullscreen
+/// if the target is `None` (blocking)
+///
+/// Returns `false` if it was already in it, or no window is focused
+pub fn set_fullscreen_blocking(target: Option<FullscreenTarget>) -> HyprResult<bool> {
+    let active = crate::data::blocking::get_active_window()?.into_client();
+    match active.and_then(|client| fullscreen_dispatch(&client, target)) {
+        Some(dispatch) => crate::dispatch::dispatch_blocking(dispatch).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// This function puts the active window in the fullscreen mode, or takes it out of fullscreen
+/// if the target is `None` (async)
+///
+/// Returns `false` if it was already in it, or no window is focused
+#[cfg(feature = "async")]
+pub async fn set_fullscreen(target: Option<FullscreenTarget>) -> HyprResult<bool> {
+    let active = crate::data::asynchronous::get_active_window()
+        .await?
+        .into_client();
+    match active.and_then(|client| fullscreen_dispatch(&client, target)) {
+        Some(dispatch) => crate::dispatch::dispatch(dispatch).await.map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// This function puts the active window in the fullscreen mode, or takes it out of fullscreen
+/// if it's already in it (blocking)
+///
+/// Returns `false` if no window is focused
+pub fn toggle_fullscreen_blocking(target: FullscreenTarget) -> HyprResult<bool> {
+    let active = crate::data::blocking::get_active_window()?.into_client();
+    match active.and_then(|client| toggle_dispatch(&client, target)) {
+        Some(dispatch) => crate::dispatch::dispatch_blocking(dispatch).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// This function puts the active window in the fullscreen mode, or takes it out of fullscreen
+/// if it's already in it (async)
+///
+/// Returns `false` if no window is focused
+#[cfg(feature = "async")]
+pub async fn toggle_fullscreen(target: FullscreenTarget) -> HyprResult<bool> {
+    let active = crate::data::asynchronous::get_active_window()
+        .await?
+        .into_client();
+    match active.and_then(|client| toggle_dispatch(&client, target)) {
+        Some(dispatch) => crate::dispatch::dispatch(dispatch).await.map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn toggle_dispatch(client: &Client, target: FullscreenTarget) -> Option<DispatchType> {
+    let target = match FullscreenTarget::of(client) {
+        Some(current) if current == target => None,
+        _ => Some(target),
+    };
+    fullscreen_dispatch(client, target)
+}
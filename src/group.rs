@@ -0,0 +1,339 @@
+//! # Group module
+//!
+//! This module provides [`Group`](crate::group::Group), a group of windows shown as tabs,
+//! with methods for what a tab bar does with it, so it doesn't have to send the group dispatchers itself
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::group::Group;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     if let Some(group) = Group::active()? {
+//!         println!("the group has {} tabs", group.windows().len());
+//!         // a click on the second tab
+//!         group.focus_nth(1)?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::data::{find_client, Client, Clients};
+use crate::dispatch::{Direction, DispatchType, LockAction, WindowIdentifier};
+use crate::shared::*;
+
+/// This struct holds the windows of a group, as they were when it was fetched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    windows: Vec<Address>,
+}
+
+impl Group {
+    /// This method returns the group the client is in, `None` if it isn't in one
+    pub fn of(client: &Client) -> Option<Group> {
+        if client.grouped.is_empty() {
+            return None;
+        }
+        Some(Group {
+            windows: client.grouped.clone(),
+        })
+    }
+
+    /// This method returns the group the active window is in,
+    /// `None` if it isn't in one or no window is focused (blocking)
+    pub fn active() -> HyprResult<Option<Group>> {
+        let window = crate::data::blocking::get_active_window()?.into_client();
+        Ok(window.as_ref().and_then(Group::of))
+    }
+
+    /// This method returns the group the active window is in,
+    /// `None` if it isn't in one or no window is focused (async)
+    #[cfg(feature = "async")]
+    pub async fn active_async() -> HyprResult<Option<Group>> {
+        let window = crate::data::asynchronous::get_active_window()
+            .await?
+            .into_client();
+        Ok(window.as_ref().and_then(Group::of))
+    }
+
+    /// This method returns all groups (blocking)
+    pub fn all() -> HyprResult<Vec<Group>> {
+        Ok(groups_of(&crate::data::blocking::get_clients()?))
+    }
+
+    /// This method returns all groups (async)
+    #[cfg(feature = "async")]
+    pub async fn all_async() -> HyprResult<Vec<Group>> {
+        Ok(groups_of(&crate::data::asynchronous::get_clients().await?))
+    }
+
+    /// This method returns the addresses of the windows, in the order of their tabs
+    pub fn windows(&self) -> &[Address] {
+        &self.windows
+    }
+
+    /// This method returns if the window is in the group
+    pub fn contains(&self, window: &Address) -> bool {
+        self.windows.contains(window)
+    }
+
+    /// This method focuses the window in the tab at `n` (from 0), which shows it in the group (blocking)
+    ///
+    /// Returns `false` if the group doesn't have `n + 1` tabs
+    pub fn focus_nth(&self, n: usize) -> HyprResult<bool> {
+        match self.windows.get(n) {
+            Some(window) => crate::dispatch::dispatch_blocking(focus(window)).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// This method focuses the window in the tab at `n` (from 0), which shows it in the group (async)
+    ///
+    /// Returns `false` if the group doesn't have `n + 1` tabs
+    #[cfg(feature = "async")]
+    pub async fn focus_nth_async(&self, n: usize) -> HyprResult<bool> {
+        match self.windows.get(n) {
+            Some(window) => crate::dispatch::dispatch(focus(window)).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// This method moves the window into the group, and focuses it (blocking)
+    ///
+    /// Hyprland only moves windows into the group next to them,
+    /// so the window has to be next to it on the same workspace
+    pub fn add(&self, window: &Address) -> HyprResult<()> {
+        if self.contains(window) {
+            return Ok(());
+        }
+        let clients = crate::data::blocking::get_clients()?;
+        crate::dispatch::dispatch_batch_blocking(self.add_dispatches(&clients, window)?)
+    }
+
+    /// This method moves the window into the group, and focuses it (async)
+    ///
+    /// See [`add`](Self::add)
+    #[cfg(feature = "async")]
+    pub async fn add_async(&self, window: &Address) -> HyprResult<()> {
+        if self.contains(window) {
+            return Ok(());
+        }
+        let clients = crate::data::asynchronous::get_clients().await?;
+        crate::dispatch::dispatch_batch(self.add_dispatches(&clients, window)?).await
+    }
+
+    /// This method moves the window out of the group (blocking)
+    ///
+    /// Returns `false` if the window isn't in the group
+    pub fn remove(&self, window: &Address) -> HyprResult<bool> {
+        if !self.contains(window) {
+            return Ok(false);
+        }
+        crate::dispatch::dispatch_blocking(remove(window)).map(|_| true)
+    }
+
+    /// This method moves the window out of the group (async)
+    ///
+    /// Returns `false` if the window isn't in the group
+    #[cfg(feature = "async")]
+    pub async fn remove_async(&self, window: &Address) -> HyprResult<bool> {
+        if !self.contains(window) {
+            return Ok(false);
+        }
+        crate::dispatch::dispatch(remove(window))
+            .await
+            .map(|_| true)
+    }
+
+    /// This method locks the group, so windows can't be moved into or out of it (blocking)
+    ///
+    /// Only the active group can be locked, so the group is focused first if it isn't
+    pub fn lock(&self) -> HyprResult<()> {
+        self.set_lock(LockAction::Lock)
+    }
+
+    /// This method unlocks the group (blocking)
+    ///
+    /// Only the active group can be unlocked, so the group is focused first if it isn't
+    pub fn unlock(&self) -> HyprResult<()> {
+        self.set_lock(LockAction::Unlock)
+    }
+
+    /// This method locks the group, so windows can't be moved into or out of it (async)
+    ///
+    /// Only the active group can be locked, so the group is focused first if it isn't
+    #[cfg(feature = "async")]
+    pub async fn lock_async(&self) -> HyprResult<()> {
+        self.set_lock_async(LockAction::Lock).await
+    }
+
+    /// This method unlocks the group (async)
+    ///
+    /// Only the active group can be unlocked, so the group is focused first if it isn't
+    #[cfg(feature = "async")]
+    pub async fn unlock_async(&self) -> HyprResult<()> {
+        self.set_lock_async(LockAction::Unlock).await
+    }
+
+    fn set_lock(&self, action: LockAction) -> HyprResult<()> {
+        let active = crate::data::blocking::get_active_window()?.into_client();
+        crate::dispatch::dispatch_batch_blocking(self.lock_dispatches(active, action))
+    }
+
+    #[cfg(feature = "async")]
+    async fn set_lock_async(&self, action: LockAction) -> HyprResult<()> {
+        let active = crate::data::asynchronous::get_active_window()
+            .await?
+            .into_client();
+        crate::dispatch::dispatch_batch(self.lock_dispatches(active, action)).await
+    }
+
+    fn lock_dispatches(&self, active: Option<Client>, action: LockAction) -> Vec<DispatchType> {
+        let mut dispatches = vec![];
+        let is_active = active.is_some_and(|client| self.contains(&client.address));
+        if let Some(window) = self.windows.first().filter(|_| !is_active) {
+            dispatches.push(focus(window));
+        }
+        dispatches.push(DispatchType::LockActiveGroup(action));
+        dispatches
+    }
+
+    /// Returns the dispatchers that focus the window and move it into the group,
+    /// in the direction the group is in from the window
+    fn add_dispatches(&self, clients: &Clients, window: &Address) -> HyprResult<Vec<DispatchType>> {
+        let find = |address: &Address| clients.iter().find(|client| client.address == *address);
+        let client = find_client(clients, window)?;
+        let member = self.windows.iter().find_map(find).ok_or_else(|| {
+            HyprError::UnexpectedResponse("none of the group's windows are open".to_string())
+        })?;
+        let center = |client: &Client| {
+            (
+                i32::from(client.at.0) + i32::from(client.size.0) / 2,
+                i32::from(client.at.1) + i32::from(client.size.1) / 2,
+            )
+        };
+        let ((x, y), (group_x, group_y)) = (center(client), center(member));
+        let (dx, dy) = (group_x - x, group_y - y);
+        let direction = if dx.abs() >= dy.abs() {
+            if dx < 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        } else if dy < 0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+        Ok(vec![focus(window), DispatchType::MoveIntoGroup(direction)])
+    }
+}
+
+fn focus(window: &Address) -> DispatchType {
+    DispatchType::FocusWindow(WindowIdentifier::Address(window.clone()))
+}
+
+fn remove(window: &Address) -> DispatchType {
+    DispatchType::MoveOutOfGroup(Some(WindowIdentifier::Address(window.clone())))
+}
+
+/// Returns the groups the clients are in, every group once
+fn groups_of(clients: &Clients) -> Vec<Group> {
+    let mut groups: Vec<Group> = vec![];
+    for group in clients.iter().filter_map(Group::of) {
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns clients where 0x1 and 0x2 are a group on the left,
+    /// 0x3 is on the right of it and 0x4 is below it
+    fn clients() -> HyprResult<Clients> {
+        let client = |address: &str, at: (i16, i16), size: (u16, u16), grouped: &str| {
+            format!(
+                r#"{{"address": "{address}", "at": [{}, {}], "size": [{}, {}],
+                    "workspace": {{"id": 1, "name": "1"}}, "floating": false, "monitor": 0,
+                    "class": "kitty", "title": "", "pid": 1, "xwayland": false,
+                    "grouped": [{grouped}]}}"#,
+                at.0, at.1, size.0, size.1
+            )
+        };
+        let group = r#""0x1", "0x2""#;
+        let clients = [
+            client("0x1", (0, 0), (1280, 1080), group),
+            client("0x2", (0, 0), (1280, 1080), group),
+            client("0x3", (1280, 0), (1280, 1440), ""),
+            client("0x4", (0, 1080), (1280, 360), ""),
+        ];
+        Ok(serde_json::from_str(&format!("[{}]", clients.join(", ")))?)
+    }
+
+    #[test]
+    fn groups_are_found_once() -> HyprResult<()> {
+        let clients = clients()?;
+        let groups = groups_of(&clients);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].windows(),
+            [Address::new("0x1"), Address::new("0x2")]
+        );
+        assert!(groups[0].contains(&Address::new("0x2")));
+        assert_eq!(Group::of(&clients[0]).as_ref(), groups.first());
+        assert_eq!(Group::of(&clients[2]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn windows_are_moved_into_the_group_from_their_side() -> HyprResult<()> {
+        let clients = clients()?;
+        let group = groups_of(&clients).remove(0);
+        let add = |window: &str| -> HyprResult<Vec<String>> {
+            let dispatches = group.add_dispatches(&clients, &Address::new(window))?;
+            Ok(dispatches.iter().map(ToString::to_string).collect())
+        };
+        assert_eq!(
+            add("0x3")?,
+            [
+                "dispatch focuswindow address:0x3",
+                "dispatch moveintogroup l"
+            ]
+        );
+        assert_eq!(
+            add("0x4")?,
+            [
+                "dispatch focuswindow address:0x4",
+                "dispatch moveintogroup u"
+            ]
+        );
+        assert!(matches!(add("0x5"), Err(HyprError::InvalidArgument(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn the_group_is_focused_before_locking_it() -> HyprResult<()> {
+        let mut clients = clients()?;
+        let group = groups_of(&clients).remove(0);
+        let lock = |active: Option<Client>, action| -> Vec<String> {
+            let dispatches = group.lock_dispatches(active, action);
+            dispatches.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            lock(Some(clients.remove(1)), LockAction::Lock),
+            ["dispatch lockactivegroup lock"]
+        );
+        assert_eq!(
+            lock(clients.pop(), LockAction::Unlock),
+            [
+                "dispatch focuswindow address:0x1",
+                "dispatch lockactivegroup unlock"
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! # Hyprpaper module
+//!
+//! This module provides [`Hyprpaper`](crate::hyprpaper::Hyprpaper), a client for the control socket
+//! of hyprpaper (the wallpaper utility), for what `hyprctl hyprpaper` does
+//!
+//! hyprpaper has to be started with `ipc = on` in its config (the default)
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::hyprpaper::{FitMode, Hyprpaper, Unload};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let mut hyprpaper = Hyprpaper::new()?;
+//!     let path = "/home/user/wallpapers/mountains.png";
+//!     hyprpaper.preload(path)?;
+//!     hyprpaper.wallpaper(Some("DP-1"), path, FitMode::Cover)?;
+//!     for active in hyprpaper.list_active()? {
+//!         println!("{} shows {}", active.monitor, active.path.display());
+//!     }
+//!     hyprpaper.unload(Unload::Unused)
+//! }
+//! ```
+
+use crate::request::Request;
+use crate::shared::*;
+use crate::transport::{Transport, UnixSocket};
+use std::path::{Path, PathBuf};
+
+/// This struct holds the error message hyprpaper responded to a command with,
+/// and what kind of error it is
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct HyprpaperError {
+    kind: HyprpaperErrorKind,
+    message: String,
+}
+
+/// This enum holds the kinds of errors hyprpaper responds to commands with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HyprpaperErrorKind {
+    /// The wallpaper has to be preloaded before it's shown
+    NotPreloaded,
+    /// The wallpaper file doesn't exist
+    FileNotFound,
+    /// No monitor matched the name
+    MonitorNotFound,
+    /// The command doesn't exist or its arguments are invalid
+    InvalidCommand,
+    /// An error this crate doesn't know about, see the message
+    Other,
+}
+
+impl HyprpaperError {
+    /// This method creates a error from the message hyprpaper responded with,
+    /// the kind is picked from the message
+    ///
+    /// ```rust
+    /// use hyprland::hyprpaper::{HyprpaperError, HyprpaperErrorKind};
+    /// let error = HyprpaperError::new("wallpaper failed (not preloaded)");
+    /// assert_eq!(error.kind(), HyprpaperErrorKind::NotPreloaded);
+    /// ```
+    pub fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        HyprpaperError {
+            kind: HyprpaperErrorKind::from_message(&message),
+            message,
+        }
+    }
+
+    /// This method returns the kind of the error
+    pub fn kind(&self) -> HyprpaperErrorKind {
+        self.kind
+    }
+
+    /// This method returns the message hyprpaper responded with
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl HyprpaperErrorKind {
+    fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let missing = ["not found", "no such", "doesn't exist", "does not exist"]
+            .iter()
+            .any(|missing| message.contains(missing));
+        if message.contains("not preloaded") {
+            HyprpaperErrorKind::NotPreloaded
+        } else if message.contains("monitor") && missing {
+            HyprpaperErrorKind::MonitorNotFound
+        } else if missing {
+            HyprpaperErrorKind::FileNotFound
+        } else if message.contains("invalid") || message.contains("syntax") {
+            HyprpaperErrorKind::InvalidCommand
+        } else {
+            HyprpaperErrorKind::Other
+        }
+    }
+}
+
+/// This enum holds how a wallpaper fills the monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// Scales the wallpaper to cover the monitor, cutting off what doesn't fit (the default)
+    #[default]
+    Cover,
+    /// Scales the wallpaper to fit in the monitor, with borders where it doesn't fill it
+    Contain,
+    /// Repeats the wallpaper, without scaling it
+    Tile,
+}
+
+impl FitMode {
+    fn prefix(&self) -> &'static str {
+        match self {
+            FitMode::Cover => "",
+            FitMode::Contain => "contain:",
+            FitMode::Tile => "tile:",
+        }
+    }
+}
+
+/// This enum holds which wallpapers to unload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unload {
+    /// The wallpaper at the path
+    Path(PathBuf),
+    /// Every wallpaper that isn't shown
+    Unused,
+    /// Every wallpaper
+    All,
+}
+
+/// This struct holds the wallpaper a monitor shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveWallpaper {
+    /// The name of the monitor
+    pub monitor: String,
+    /// The path of the wallpaper
+    pub path: PathBuf,
+}
+
+/// This struct sends commands to hyprpaper
+///
+/// Like [`HyprlandConnection`](crate::connection::HyprlandConnection),
+/// it's created with a [`Transport`], by default the [`UnixSocket`] of hyprpaper.
+/// Every method has a async version, with a `_async` suffix
+#[derive(Debug, Clone)]
+pub struct Hyprpaper<T: Transport = UnixSocket> {
+    transport: T,
+    buffer: Vec<u8>,
+}
+
+impl Hyprpaper {
+    /// This method creates a client of the hyprpaper running in the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        Ok(Hyprpaper::for_instance(&Instance::current()?))
+    }
+
+    /// This method creates a client of the hyprpaper running in a specific Hyprland instance
+    pub fn for_instance(instance: &Instance) -> Self {
+        let socket = UnixSocket::from_path(instance.socket_path(SocketType::Hyprpaper));
+        Hyprpaper::with_transport(socket)
+    }
+}
+
+impl<T: Transport> Hyprpaper<T> {
+    /// This method creates a client that sends commands with `transport`
+    pub fn with_transport(transport: T) -> Self {
+        Hyprpaper {
+            transport,
+            buffer: vec![],
+        }
+    }
+
+    fn send<R>(&mut self, request: Request<HyprResult<R>>) -> HyprResult<R> {
+        request.send_blocking_with(&mut self.transport, &mut self.buffer)
+    }
+
+    #[cfg(feature = "async")]
+    async fn send_async<R>(&mut self, request: Request<HyprResult<R>>) -> HyprResult<R> {
+        request
+            .send_with(&mut self.transport, &mut self.buffer)
+            .await
+    }
+
+    /// This method loads the wallpaper into memory, so it can be shown (blocking)
+    pub fn preload(&mut self, path: impl AsRef<Path>) -> HyprResult<()> {
+        self.send(preload_request(path.as_ref()))
+    }
+
+    /// This method shows the preloaded wallpaper on the monitor,
+    /// or on every monitor if it's `None` (blocking)
+    pub fn wallpaper(
+        &mut self,
+        monitor: Option<&str>,
+        path: impl AsRef<Path>,
+        mode: FitMode,
+    ) -> HyprResult<()> {
+        self.send(wallpaper_request(monitor, path.as_ref(), mode))
+    }
+
+    /// This method unloads wallpapers from memory (blocking)
+    pub fn unload(&mut self, unload: Unload) -> HyprResult<()> {
+        self.send(unload_request(&unload))
+    }
+
+    /// This method returns the paths of the preloaded wallpapers (blocking)
+    pub fn list_loaded(&mut self) -> HyprResult<Vec<PathBuf>> {
+        self.send(list_loaded_request())
+    }
+
+    /// This method returns the wallpaper every monitor shows (blocking)
+    pub fn list_active(&mut self) -> HyprResult<Vec<ActiveWallpaper>> {
+        self.send(list_active_request())
+    }
+
+    /// This method loads the wallpaper into memory, so it can be shown (async)
+    #[cfg(feature = "async")]
+    pub async fn preload_async(&mut self, path: impl AsRef<Path>) -> HyprResult<()> {
+        self.send_async(preload_request(path.as_ref())).await
+    }
+
+    /// This method shows the preloaded wallpaper on the monitor,
+    /// or on every monitor if it's `None` (async)
+    #[cfg(feature = "async")]
+    pub async fn wallpaper_async(
+        &mut self,
+        monitor: Option<&str>,
+        path: impl AsRef<Path>,
+        mode: FitMode,
+    ) -> HyprResult<()> {
+        self.send_async(wallpaper_request(monitor, path.as_ref(), mode))
+            .await
+    }
+
+    /// This method unloads wallpapers from memory (async)
+    #[cfg(feature = "async")]
+    pub async fn unload_async(&mut self, unload: Unload) -> HyprResult<()> {
+        self.send_async(unload_request(&unload)).await
+    }
+
+    /// This method returns the paths of the preloaded wallpapers (async)
+    #[cfg(feature = "async")]
+    pub async fn list_loaded_async(&mut self) -> HyprResult<Vec<PathBuf>> {
+        self.send_async(list_loaded_request()).await
+    }
+
+    /// This method returns the wallpaper every monitor shows (async)
+    #[cfg(feature = "async")]
+    pub async fn list_active_async(&mut self) -> HyprResult<Vec<ActiveWallpaper>> {
+        self.send_async(list_active_request()).await
+    }
+}
+
+/// Creates a request for a command hyprpaper answers with `ok`
+fn ok_request(command: String) -> Request<HyprResult<()>> {
+    Request::new(command, |response| match response?.trim() {
+        "ok" => Ok(()),
+        message => Err(HyprError::Hyprpaper(HyprpaperError::new(message))),
+    })
+}
+
+fn preload_request(path: &Path) -> Request<HyprResult<()>> {
+    ok_request(format!("preload {}", path.display()))
+}
+
+fn wallpaper_request(monitor: Option<&str>, path: &Path, mode: FitMode) -> Request<HyprResult<()>> {
+    let monitor = monitor.unwrap_or_default();
+    ok_request(format!(
+        "wallpaper {monitor},{}{}",
+        mode.prefix(),
+        path.display()
+    ))
+}
+
+fn unload_request(unload: &Unload) -> Request<HyprResult<()>> {
+    ok_request(match unload {
+        Unload::Path(path) => format!("unload {}", path.display()),
+        Unload::Unused => "unload unused".to_string(),
+        Unload::All => "unload all".to_string(),
+    })
+}
+
+fn list_loaded_request() -> Request<HyprResult<Vec<PathBuf>>> {
+    Request::new("listloaded", |response| {
+        Ok(lines(&response?).map(PathBuf::from).collect())
+    })
+}
+
+fn list_active_request() -> Request<HyprResult<Vec<ActiveWallpaper>>> {
+    Request::new("listactive", |response| {
+        let response = response?;
+        lines(&response)
+            .map(|line| match line.split_once(" = ") {
+                Some((monitor, path)) => Ok(ActiveWallpaper {
+                    monitor: monitor.to_string(),
+                    path: PathBuf::from(path),
+                }),
+                None => Err(HyprError::UnexpectedResponse(format!(
+                    "hyprpaper listed a active wallpaper as {line:?}"
+                ))),
+            })
+            .collect()
+    })
+}
+
+/// Returns the lines of a list, which is a message like `no wallpapers loaded` when it's empty
+fn lines(response: &str) -> impl Iterator<Item = &str> {
+    let empty = response.trim().starts_with("no wallpapers");
+    response
+        .lines()
+        .map(str::trim)
+        .filter(move |line| !empty && !line.is_empty())
+}
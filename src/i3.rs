@@ -0,0 +1,961 @@
+//! # i3 module
+//!
+//! This module provides [`I3Connection`](crate::i3::I3Connection), which answers a subset of the
+//! i3/sway IPC (`get_workspaces`, `get_outputs`, `get_tree` and `run_command`) with Hyprland's data
+//! and dispatchers, so tools written against i3ipc can be ported by swapping the connection
+//!
+//! Hyprland doesn't have containers, so the tree is flat: the root holds the outputs (monitors),
+//! which hold their workspaces, which hold their windows. The ids of windows are their addresses,
+//! so they stay the same between trees, the ids of the other nodes don't
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::i3::I3Connection;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let mut connection = I3Connection::new()?;
+//!     for workspace in connection.get_workspaces()? {
+//!         println!("{} on {}", workspace.name, workspace.output);
+//!     }
+//!     if let Some(window) = connection.get_tree()?.find_focused() {
+//!         println!("{:?} is focused", window.name);
+//!     }
+//!     for outcome in connection.run_command("workspace number 2; exec kitty")? {
+//!         if let Some(error) = outcome.error {
+//!             println!("{error}");
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::connection::HyprlandConnection;
+use crate::data::{Client, Monitor, Monitors, Transforms, Workspaces};
+use crate::dispatch::{
+    Batch, Direction, DispatchType, FullscreenType, MonitorIdentifier, WindowIdentifier,
+    WindowMove, WorkspaceIdentifier, WorkspaceIdentifierWithSpecial,
+};
+use crate::shared::*;
+use crate::transport::{Transport, UnixSocket};
+use serde::Serialize;
+
+/// This struct holds a rectangle, in logical pixels
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// The x position
+    pub x: i32,
+    /// The y position
+    pub y: i32,
+    /// The width
+    pub width: i32,
+    /// The height
+    pub height: i32,
+}
+
+/// This struct holds a workspace, like i3's `get_workspaces` returns it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// The id of the workspace, `-1` if it's a named or special workspace
+    pub num: i32,
+    /// The name of the workspace
+    pub name: String,
+    /// If it's shown on its output
+    pub visible: bool,
+    /// If it's shown on the focused output
+    pub focused: bool,
+    /// If a window in it wants attention, Hyprland can't be asked for it so it's always `false`
+    pub urgent: bool,
+    /// The rectangle of its output
+    pub rect: Rect,
+    /// The name of its output
+    pub output: String,
+}
+
+/// This struct holds a output (monitor), like i3's `get_outputs` returns it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    /// The name of the output
+    pub name: String,
+    /// If it's on, Hyprland only lists the ones that are so it's always `true`
+    pub active: bool,
+    /// If it's the primary output, Hyprland doesn't have one so it's the focused output
+    pub primary: bool,
+    /// The rectangle of the output
+    pub rect: Rect,
+    /// The name of the workspace it shows
+    pub current_workspace: Option<String>,
+}
+
+/// This enum holds the types of nodes in the tree
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeType {
+    /// The root, holding the outputs
+    Root,
+    /// A output, holding its workspaces
+    Output,
+    /// A workspace, holding its windows
+    Workspace,
+    /// A tiled window
+    Con,
+    /// A floating window
+    FloatingCon,
+}
+
+/// This struct holds a node of the tree, like i3's `get_tree` returns it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// The id of the node, the address of the window for windows
+    pub id: i64,
+    /// The name of the node, the title for windows
+    pub name: Option<String>,
+    /// What the node is
+    #[serde(rename = "type")]
+    pub nodetype: NodeType,
+    /// The rectangle of the node
+    pub rect: Rect,
+    /// If it's the focused node, a window or a workspace without windows
+    pub focused: bool,
+    /// If it wants attention, Hyprland can't be asked for it so it's always `false`
+    pub urgent: bool,
+    /// The ids of the children, the focused one first
+    pub focus: Vec<i64>,
+    /// The children, tiled windows for workspaces
+    pub nodes: Vec<Node>,
+    /// The floating windows, for workspaces
+    pub floating_nodes: Vec<Node>,
+    /// The class of the window, like sway's `app_id`
+    pub app_id: Option<String>,
+    /// The process id of the window
+    pub pid: Option<u32>,
+}
+
+impl Node {
+    /// This method returns the focused node, searching this node and its children
+    pub fn find_focused(&self) -> Option<&Node> {
+        self.find(|node| node.focused)
+    }
+
+    /// This method returns the first node, this one or one of its children, that `predicate` matches
+    pub fn find(&self, predicate: impl Fn(&Node) -> bool + Copy) -> Option<&Node> {
+        if predicate(self) {
+            return Some(self);
+        }
+        self.nodes
+            .iter()
+            .chain(&self.floating_nodes)
+            .find_map(|node| node.find(predicate))
+    }
+
+    /// This method returns the address of the window, `None` if the node isn't a window
+    pub fn address(&self) -> Option<Address> {
+        match self.nodetype {
+            NodeType::Con | NodeType::FloatingCon => Some(address_of(self.id)),
+            _ => None,
+        }
+    }
+}
+
+/// This struct holds the outcome of one command of [`run_command`](I3Connection::run_command)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CommandOutcome {
+    /// If the command ran
+    pub success: bool,
+    /// Why it didn't, if it didn't
+    pub error: Option<String>,
+}
+
+/// This struct answers i3 IPC messages with a [`HyprlandConnection`]
+///
+/// Every method has a async version, with a `_async` suffix
+#[derive(Debug, Clone)]
+pub struct I3Connection<T: Transport = UnixSocket> {
+    hyprland: HyprlandConnection<T>,
+}
+
+impl I3Connection {
+    /// This method creates a connection to the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        Ok(I3Connection::with_connection(HyprlandConnection::new()?))
+    }
+
+    /// This method creates a connection to a specific Hyprland instance
+    pub fn for_instance(instance: &Instance) -> Self {
+        I3Connection::with_connection(HyprlandConnection::for_instance(instance))
+    }
+}
+
+impl<T: Transport> I3Connection<T> {
+    /// This method creates a connection that sends commands with `hyprland`
+    pub fn with_connection(hyprland: HyprlandConnection<T>) -> Self {
+        I3Connection { hyprland }
+    }
+
+    /// This method returns the workspaces (blocking)
+    pub fn get_workspaces(&mut self) -> HyprResult<Vec<Workspace>> {
+        let monitors = self.hyprland.get_monitors()?;
+        let workspaces = self.hyprland.get_workspaces()?;
+        Ok(workspaces_of(&monitors, workspaces))
+    }
+
+    /// This method returns the outputs (blocking)
+    pub fn get_outputs(&mut self) -> HyprResult<Vec<Output>> {
+        Ok(outputs_of(self.hyprland.get_monitors()?))
+    }
+
+    /// This method returns the tree of outputs, workspaces and windows (blocking)
+    pub fn get_tree(&mut self) -> HyprResult<Node> {
+        let monitors = self.hyprland.get_monitors()?;
+        let workspaces = self.hyprland.get_workspaces()?;
+        let clients = self.hyprland.get_clients()?;
+        let active = self.hyprland.get_active_window()?.into_client();
+        Ok(tree_of(monitors, workspaces, clients, active))
+    }
+
+    /// This method runs i3 commands, separated by `;` or `,`, and returns the outcome of each (blocking)
+    ///
+    /// These commands are supported, the ones with a window also take criteria
+    /// (one of `con_id`, `class`, `app_id`, `title` or `pid`, like `[app_id="firefox"] focus`):
+    /// - `exec [--no-startup-id] <command>`
+    /// - `workspace [number] <name>|next|prev|next_on_output|prev_on_output`
+    /// - `move [container|window] [to] workspace [number] <name>` (with a window)
+    /// - `move [container|window] [to] output <name>|left|right|up|down`
+    /// - `move left|right|up|down`
+    /// - `focus` (with a window), `focus left|right|up|down`, `focus output <name>`
+    /// - `kill` (with a window)
+    /// - `fullscreen [toggle]`, `floating toggle`
+    /// - `reload`, `exit` and `nop`
+    pub fn run_command(&mut self, commands: &str) -> HyprResult<Vec<CommandOutcome>> {
+        let (batch, sent) = batch_of(parse_commands(commands));
+        let results = if batch.is_empty() {
+            vec![]
+        } else {
+            self.hyprland.send_batch(batch)?
+        };
+        Ok(outcomes_of(sent, results))
+    }
+
+    /// This method returns the workspaces (async)
+    #[cfg(feature = "async")]
+    pub async fn get_workspaces_async(&mut self) -> HyprResult<Vec<Workspace>> {
+        let monitors = self.hyprland.get_monitors_async().await?;
+        let workspaces = self.hyprland.get_workspaces_async().await?;
+        Ok(workspaces_of(&monitors, workspaces))
+    }
+
+    /// This method returns the outputs (async)
+    #[cfg(feature = "async")]
+    pub async fn get_outputs_async(&mut self) -> HyprResult<Vec<Output>> {
+        Ok(outputs_of(self.hyprland.get_monitors_async().await?))
+    }
+
+    /// This method returns the tree of outputs, workspaces and windows (async)
+    #[cfg(feature = "async")]
+    pub async fn get_tree_async(&mut self) -> HyprResult<Node> {
+        let monitors = self.hyprland.get_monitors_async().await?;
+        let workspaces = self.hyprland.get_workspaces_async().await?;
+        let clients = self.hyprland.get_clients_async().await?;
+        let active = self.hyprland.get_active_window_async().await?.into_client();
+        Ok(tree_of(monitors, workspaces, clients, active))
+    }
+
+    /// This method runs i3 commands, separated by `;` or `,`, and returns the outcome of each (async)
+    ///
+    /// See [`run_command`](Self::run_command) for the commands that are supported
+    #[cfg(feature = "async")]
+    pub async fn run_command_async(&mut self, commands: &str) -> HyprResult<Vec<CommandOutcome>> {
+        let (batch, sent) = batch_of(parse_commands(commands));
+        let results = if batch.is_empty() {
+            vec![]
+        } else {
+            self.hyprland.send_batch_async(batch).await?
+        };
+        Ok(outcomes_of(sent, results))
+    }
+}
+
+/// Returns the id of a window, its address as a number
+fn id_of(address: &Address) -> i64 {
+    let address = address.to_string();
+    u64::from_str_radix(address.trim_start_matches("0x"), 16).unwrap_or_default() as i64
+}
+
+/// Returns the address of a window out of its id
+fn address_of(id: i64) -> Address {
+    Address::new(format!("{:x}", id as u64))
+}
+
+/// Returns the rectangle of the monitor in logical pixels, turned when it's rotated
+fn rect_of(monitor: &Monitor) -> Rect {
+    let scale = if monitor.scale > 0.0 {
+        monitor.scale
+    } else {
+        1.0
+    };
+    let width = (f32::from(monitor.width) / scale).round() as i32;
+    let height = (f32::from(monitor.height) / scale).round() as i32;
+    let turned = matches!(
+        monitor.transform,
+        Transforms::Normal90
+            | Transforms::Normal270
+            | Transforms::Flipped90
+            | Transforms::Flipped270
+    );
+    let (width, height) = if turned {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    Rect {
+        x: monitor.x,
+        y: monitor.y,
+        width,
+        height,
+    }
+}
+
+fn workspaces_of(monitors: &Monitors, workspaces: Workspaces) -> Vec<Workspace> {
+    workspaces
+        .into_iter()
+        .map(|workspace| {
+            let monitor = monitors.iter().find(|mon| mon.name == workspace.monitor);
+            let visible = monitor.is_some_and(|mon| mon.active_workspace.name == workspace.name);
+            Workspace {
+                num: match workspace.id {
+                    WorkspaceType::Regular(id) => id,
+                    _ => -1,
+                },
+                visible,
+                focused: visible && monitor.is_some_and(|mon| mon.focused),
+                urgent: false,
+                rect: monitor.map(rect_of).unwrap_or_default(),
+                name: workspace.name,
+                output: workspace.monitor,
+            }
+        })
+        .collect()
+}
+
+fn outputs_of(monitors: Monitors) -> Vec<Output> {
+    monitors
+        .into_iter()
+        .map(|monitor| Output {
+            active: true,
+            primary: monitor.focused,
+            rect: rect_of(&monitor),
+            current_workspace: Some(monitor.active_workspace.name.clone()),
+            name: monitor.name,
+        })
+        .collect()
+}
+
+fn tree_of(
+    monitors: Monitors,
+    workspaces: Workspaces,
+    clients: Vec<Client>,
+    active: Option<Client>,
+) -> Node {
+    let active = active.map(|client| client.address);
+    let mut next_id = 0;
+    let mut new_id = || {
+        next_id += 1;
+        next_id
+    };
+    let root_id = new_id();
+    let outputs: Vec<Node> = monitors
+        .iter()
+        .map(|monitor| {
+            let rect = rect_of(monitor);
+            let output_id = new_id();
+            let mut children: Vec<(Node, bool)> = workspaces
+                .iter()
+                .filter(|workspace| workspace.monitor == monitor.name)
+                .map(|workspace| {
+                    let windows = clients
+                        .iter()
+                        .filter(|client| client.workspace.name == workspace.name);
+                    let (floating, tiled): (Vec<&Client>, Vec<&Client>) =
+                        windows.partition(|client| client.floating);
+                    let window_node = |client: &Client| Node {
+                        id: id_of(&client.address),
+                        name: Some(client.title.clone()),
+                        nodetype: if client.floating {
+                            NodeType::FloatingCon
+                        } else {
+                            NodeType::Con
+                        },
+                        rect: Rect {
+                            x: client.at.0.into(),
+                            y: client.at.1.into(),
+                            width: client.size.0.into(),
+                            height: client.size.1.into(),
+                        },
+                        focused: active.as_ref() == Some(&client.address),
+                        urgent: false,
+                        focus: vec![],
+                        nodes: vec![],
+                        floating_nodes: vec![],
+                        app_id: Some(client.class.clone()),
+                        pid: Some(client.pid),
+                    };
+                    let nodes: Vec<Node> = tiled.into_iter().map(window_node).collect();
+                    let floating_nodes: Vec<Node> = floating.into_iter().map(window_node).collect();
+                    let shown = monitor.active_workspace.name == workspace.name;
+                    let node = Node {
+                        id: new_id(),
+                        name: Some(workspace.name.clone()),
+                        nodetype: NodeType::Workspace,
+                        rect,
+                        focused: active.is_none() && shown && monitor.focused,
+                        urgent: false,
+                        focus: focus_order(nodes.iter().chain(&floating_nodes)),
+                        nodes,
+                        floating_nodes,
+                        app_id: None,
+                        pid: None,
+                    };
+                    (node, shown)
+                })
+                .collect();
+            // the shown workspace is focused first
+            children.sort_by_key(|(_, shown)| !*shown);
+            let children: Vec<Node> = children.into_iter().map(|(node, _)| node).collect();
+            Node {
+                id: output_id,
+                name: Some(monitor.name.clone()),
+                nodetype: NodeType::Output,
+                rect,
+                focused: false,
+                urgent: false,
+                focus: children.iter().map(|node| node.id).collect(),
+                nodes: children,
+                floating_nodes: vec![],
+                app_id: None,
+                pid: None,
+            }
+        })
+        .collect();
+    let mut focus: Vec<i64> = outputs.iter().map(|node| node.id).collect();
+    let focused = monitors.iter().position(|monitor| monitor.focused);
+    if let Some(index) = focused {
+        let id = focus.remove(index);
+        focus.insert(0, id);
+    }
+    Node {
+        id: root_id,
+        name: Some("root".to_string()),
+        nodetype: NodeType::Root,
+        rect: Rect::default(),
+        focused: false,
+        urgent: false,
+        focus,
+        nodes: outputs,
+        floating_nodes: vec![],
+        app_id: None,
+        pid: None,
+    }
+}
+
+/// Returns the ids of the nodes, the focused one first
+fn focus_order<'a>(nodes: impl Iterator<Item = &'a Node>) -> Vec<i64> {
+    let mut nodes: Vec<&Node> = nodes.collect();
+    nodes.sort_by_key(|node| !node.focused);
+    nodes.into_iter().map(|node| node.id).collect()
+}
+
+/// What a i3 command does in Hyprland
+enum Action {
+    Dispatch(DispatchType),
+    Command(&'static str),
+    Nothing,
+}
+
+/// A workspace like i3 commands name it
+enum WorkspaceArg {
+    Id(WorkspaceId),
+    Name(String),
+    Relative { next: bool, on_output: bool },
+}
+
+fn parse_commands(commands: &str) -> Vec<Result<Action, String>> {
+    let mut parsed = vec![];
+    for group in split_unquoted(commands, ';') {
+        let group = group.trim();
+        if group.is_empty() {
+            continue;
+        }
+        let (window, rest) = match parse_criteria(group) {
+            Ok(criteria) => criteria,
+            Err(error) => {
+                parsed.push(Err(error));
+                continue;
+            }
+        };
+        for command in split_unquoted(rest, ',') {
+            if !command.trim().is_empty() {
+                parsed.push(parse_command(&command, window.clone()));
+            }
+        }
+    }
+    parsed
+}
+
+/// Returns the window the criteria in front of the commands match, and the commands after them
+fn parse_criteria(group: &str) -> Result<(Option<WindowIdentifier>, &str), String> {
+    let Some(inner) = group.strip_prefix('[') else {
+        return Ok((None, group));
+    };
+    let end = split_unquoted(inner, ']')
+        .first()
+        .map(String::len)
+        .filter(|len| *len < inner.len())
+        .ok_or_else(|| format!("the criteria of {group:?} aren't closed"))?;
+    let criteria = words(&inner[..end]);
+    let [criterion] = criteria.as_slice() else {
+        return Err(format!(
+            "{group:?} has {} criteria, only one is supported",
+            criteria.len()
+        ));
+    };
+    let (key, value) = criterion
+        .split_once('=')
+        .ok_or_else(|| format!("the criterion {criterion:?} doesn't have a value"))?;
+    let value = value.to_string();
+    let window = match key {
+        "con_id" => WindowIdentifier::Address(address_of(
+            value
+                .parse()
+                .map_err(|_| format!("{value:?} isn't a con_id"))?,
+        )),
+        "class" | "app_id" => WindowIdentifier::ClassRegularExpression(value),
+        "title" => WindowIdentifier::Title(value),
+        "pid" => WindowIdentifier::ProcessId(
+            value
+                .parse()
+                .map_err(|_| format!("{value:?} isn't a pid"))?,
+        ),
+        key => return Err(format!("the criterion {key:?} isn't supported")),
+    };
+    Ok((Some(window), &inner[end + 1..]))
+}
+
+fn parse_command(command: &str, window: Option<WindowIdentifier>) -> Result<Action, String> {
+    let words = words(command);
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let dispatch = |dispatch_type| Ok(Action::Dispatch(dispatch_type));
+    let needs_window = |command: &str| format!("{command:?} needs criteria to know the window");
+    let no_criteria = |window: &Option<WindowIdentifier>| match window {
+        Some(_) => Err(format!("{:?} doesn't support criteria", words.join(" "))),
+        None => Ok(()),
+    };
+    match words.as_slice() {
+        ["nop", ..] => Ok(Action::Nothing),
+        ["exec", ..] => {
+            no_criteria(&window)?;
+            // the command is run by a shell, so it's passed as it was written, with its quotes
+            let rest = command
+                .trim()
+                .strip_prefix("exec")
+                .unwrap_or_default()
+                .trim();
+            let rest = rest.strip_prefix("--no-startup-id").unwrap_or(rest).trim();
+            if rest.is_empty() {
+                return Err("exec needs a command".to_string());
+            }
+            dispatch(DispatchType::Exec(rest.to_string()))
+        }
+        ["workspace", rest @ ..] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::Workspace(with_special(parse_workspace(
+                rest,
+            )?)))
+        }
+        ["kill"] => match window {
+            Some(window) => dispatch(DispatchType::CloseWindow(window)),
+            None => dispatch(DispatchType::KillActiveWindow),
+        },
+        ["focus"] => match window {
+            Some(window) => dispatch(DispatchType::FocusWindow(window)),
+            None => Err(needs_window("focus")),
+        },
+        ["focus", "output", output] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::FocusMonitor(parse_output(output)))
+        }
+        ["focus", direction] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::MoveFocus(parse_direction(direction)?))
+        }
+        ["fullscreen"] | ["fullscreen", "toggle"] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::ToggleFullscreen(FullscreenType::NoParam))
+        }
+        ["floating", "toggle"] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::ToggleFloating)
+        }
+        ["move", rest @ ..] => {
+            let rest = match rest {
+                ["container" | "window", rest @ ..] => rest,
+                rest => rest,
+            };
+            let rest = match rest {
+                ["to", rest @ ..] => rest,
+                rest => rest,
+            };
+            match rest {
+                ["workspace", workspace @ ..] => {
+                    let workspace = parse_workspace(workspace)?;
+                    match window {
+                        Some(window) => dispatch(DispatchType::MoveWindowToWorkspaceSilent(
+                            with_special(workspace),
+                            window,
+                        )),
+                        None => dispatch(DispatchType::MoveFocusedWindowToWorkspaceSilent(
+                            without_special(workspace)?,
+                        )),
+                    }
+                }
+                ["output", output] => {
+                    no_criteria(&window)?;
+                    dispatch(DispatchType::MoveWindow(WindowMove::Monitor(parse_output(
+                        output,
+                    ))))
+                }
+                [direction] => {
+                    no_criteria(&window)?;
+                    dispatch(DispatchType::MoveWindow(WindowMove::Direction(
+                        parse_direction(direction)?,
+                    )))
+                }
+                _ => Err(format!("{:?} isn't a supported move", words.join(" "))),
+            }
+        }
+        ["reload"] => {
+            no_criteria(&window)?;
+            Ok(Action::Command("reload"))
+        }
+        ["exit"] => {
+            no_criteria(&window)?;
+            dispatch(DispatchType::Exit)
+        }
+        _ => Err(format!("{:?} isn't a supported command", words.join(" "))),
+    }
+}
+
+fn parse_workspace(words: &[&str]) -> Result<WorkspaceArg, String> {
+    match words {
+        ["next"] => Ok(WorkspaceArg::Relative {
+            next: true,
+            on_output: false,
+        }),
+        ["prev"] => Ok(WorkspaceArg::Relative {
+            next: false,
+            on_output: false,
+        }),
+        ["next_on_output"] => Ok(WorkspaceArg::Relative {
+            next: true,
+            on_output: true,
+        }),
+        ["prev_on_output"] => Ok(WorkspaceArg::Relative {
+            next: false,
+            on_output: true,
+        }),
+        // `number 3:web` is workspace 3, whatever its name is
+        ["number", name @ ..] if !name.is_empty() => {
+            let name = name.join(" ");
+            let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+            match digits.parse() {
+                Ok(id) => Ok(WorkspaceArg::Id(id)),
+                Err(_) => Err(format!("{name:?} doesn't start with a workspace number")),
+            }
+        }
+        [] | ["number"] => Err("the workspace is missing".to_string()),
+        name => {
+            let name = name.join(" ");
+            // Hyprland would take `-1` as the previous workspace, for i3 it's a name
+            Ok(match name.parse() {
+                Ok(id) if id >= 0 => WorkspaceArg::Id(id),
+                _ => WorkspaceArg::Name(name),
+            })
+        }
+    }
+}
+
+fn with_special(workspace: WorkspaceArg) -> WorkspaceIdentifierWithSpecial {
+    match workspace {
+        WorkspaceArg::Id(id) => WorkspaceIdentifierWithSpecial::Id(id),
+        WorkspaceArg::Name(name) if name == "special" => WorkspaceIdentifierWithSpecial::Special,
+        WorkspaceArg::Name(name) => match name.strip_prefix("special:") {
+            Some(name) => WorkspaceIdentifierWithSpecial::NamedSpecial(name.to_string()),
+            None => WorkspaceIdentifierWithSpecial::Name(name),
+        },
+        WorkspaceArg::Relative {
+            next: true,
+            on_output: false,
+        } => WorkspaceIdentifierWithSpecial::PositiveRelative(1),
+        WorkspaceArg::Relative {
+            next: false,
+            on_output: false,
+        } => WorkspaceIdentifierWithSpecial::NegativeRelative(1),
+        WorkspaceArg::Relative {
+            next: true,
+            on_output: true,
+        } => WorkspaceIdentifierWithSpecial::PositiveRelativeMonitor(1),
+        WorkspaceArg::Relative {
+            next: false,
+            on_output: true,
+        } => WorkspaceIdentifierWithSpecial::NegativeRelativeMonitor(1),
+    }
+}
+
+fn without_special(workspace: WorkspaceArg) -> Result<WorkspaceIdentifier, String> {
+    Ok(match with_special(workspace) {
+        WorkspaceIdentifierWithSpecial::Id(id) => WorkspaceIdentifier::Id(id),
+        WorkspaceIdentifierWithSpecial::Name(name) => WorkspaceIdentifier::Name(name),
+        WorkspaceIdentifierWithSpecial::PositiveRelative(int) => {
+            WorkspaceIdentifier::PositiveRelative(int)
+        }
+        WorkspaceIdentifierWithSpecial::NegativeRelative(int) => {
+            WorkspaceIdentifier::NegativeRelative(int)
+        }
+        WorkspaceIdentifierWithSpecial::PositiveRelativeMonitor(int) => {
+            WorkspaceIdentifier::PositiveRelativeMonitor(int)
+        }
+        WorkspaceIdentifierWithSpecial::NegativeRelativeMonitor(int) => {
+            WorkspaceIdentifier::NegativeRelativeMonitor(int)
+        }
+        WorkspaceIdentifierWithSpecial::Special
+        | WorkspaceIdentifierWithSpecial::NamedSpecial(_) => {
+            return Err(
+                "moving the focused window to a special workspace needs criteria".to_string(),
+            )
+        }
+    })
+}
+
+fn parse_direction(direction: &str) -> Result<Direction, String> {
+    match direction {
+        "left" => Ok(Direction::Left),
+        "right" => Ok(Direction::Right),
+        "up" => Ok(Direction::Up),
+        "down" => Ok(Direction::Down),
+        direction => Err(format!("{direction:?} isn't a direction")),
+    }
+}
+
+fn parse_output(output: &str) -> MonitorIdentifier {
+    match parse_direction(output) {
+        Ok(direction) => MonitorIdentifier::Direction(direction),
+        Err(_) => MonitorIdentifier::Name(output.to_string()),
+    }
+}
+
+/// Returns the parts of `string` between `separator`s that aren't in double quotes
+fn split_unquoted(string: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for char in string.chars() {
+        match char {
+            '"' => quoted = !quoted,
+            char if char == separator && !quoted => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(part) = parts.last_mut() {
+            part.push(char);
+        }
+    }
+    parts
+}
+
+/// Returns the words of a command, without the quotes around them
+fn words(command: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for char in command.chars() {
+        match char {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            char if char.is_whitespace() && !quoted => words.extend(word.take()),
+            char => word.get_or_insert_with(String::new).push(char),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Returns the batch of the commands that do something, and which commands are in it
+/// or why they can't run
+fn batch_of(parsed: Vec<Result<Action, String>>) -> (Batch, Vec<Result<bool, String>>) {
+    let mut batch = Batch::new();
+    let sent = parsed
+        .into_iter()
+        .map(|action| {
+            let action = action?;
+            let sends = !matches!(action, Action::Nothing);
+            batch = match action {
+                Action::Dispatch(dispatch_type) => {
+                    std::mem::take(&mut batch).dispatch(dispatch_type)
+                }
+                Action::Command(command) => std::mem::take(&mut batch).command(command),
+                Action::Nothing => std::mem::take(&mut batch),
+            };
+            Ok(sends)
+        })
+        .collect();
+    (batch, sent)
+}
+
+fn outcomes_of(
+    sent: Vec<Result<bool, String>>,
+    results: Vec<HyprResult<()>>,
+) -> Vec<CommandOutcome> {
+    let mut results = results.into_iter();
+    sent.into_iter()
+        .map(|sent| {
+            let result = match sent {
+                Ok(false) => Ok(()),
+                Ok(true) => match results.next() {
+                    Some(result) => result.map_err(|error| error.to_string()),
+                    None => Err("Hyprland didn't answer the command".to_string()),
+                },
+                Err(error) => Err(error),
+            };
+            CommandOutcome {
+                success: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns what the commands are run as, the dispatch string of a dispatcher,
+    /// `None` for the ones that can't run
+    fn parse(commands: &str) -> Vec<Option<String>> {
+        parse_commands(commands)
+            .into_iter()
+            .map(|action| match action {
+                Ok(Action::Dispatch(dispatch)) => Some(dispatch.to_string()),
+                Ok(Action::Command(command)) => Some(command.to_string()),
+                Ok(Action::Nothing) => Some("nothing".to_string()),
+                Err(_) => None,
+            })
+            .collect()
+    }
+
+    fn some(commands: &[&str]) -> Vec<Option<String>> {
+        commands
+            .iter()
+            .map(|command| Some(command.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn commands_and_groups() {
+        assert_eq!(
+            parse("workspace 3; exec --no-startup-id kitty, focus left;;nop comment"),
+            some(&[
+                "dispatch workspace 3",
+                "dispatch exec kitty",
+                "dispatch movefocus l",
+                "nothing",
+            ])
+        );
+        // separators in quotes are part of the command
+        assert_eq!(
+            parse("exec \"notify-send 'a; b, c'\""),
+            some(&["dispatch exec \"notify-send 'a; b, c'\""])
+        );
+        assert_eq!(parse("  ;  "), Vec::<Option<String>>::new());
+        assert_eq!(
+            parse("frobnicate; kill"),
+            [None, Some("dispatch killactive".to_string())]
+        );
+    }
+
+    #[test]
+    fn workspaces() {
+        assert_eq!(
+            parse(
+                "workspace next; workspace prev_on_output; workspace number 4:mail; \
+                 workspace \"my music\"; workspace -1; workspace special:scratch"
+            ),
+            some(&[
+                "dispatch workspace +1",
+                "dispatch workspace m-1",
+                "dispatch workspace 4",
+                "dispatch workspace name:my music",
+                "dispatch workspace name:-1",
+                "dispatch workspace special:scratch",
+            ])
+        );
+        assert_eq!(parse("workspace; workspace number web"), [None, None]);
+        assert_eq!(
+            parse("move container to workspace 2; move to workspace special"),
+            [Some("dispatch movetoworkspacesilent 2".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn criteria() {
+        assert_eq!(
+            parse("[con_id=255] focus, kill; [class=\"^fire fox$\"] move workspace special"),
+            some(&[
+                "dispatch focuswindow address:0xff",
+                "dispatch closewindow address:0xff",
+                "dispatch movetoworkspacesilent special,^fire fox$",
+            ])
+        );
+        assert_eq!(
+            parse("[pid=42] kill; [title=vim] focus"),
+            some(&[
+                "dispatch closewindow pid:42",
+                "dispatch focuswindow title:vim"
+            ])
+        );
+        assert_eq!(
+            parse(
+                "[con_id=1 class=a] kill; [con_mark=a] kill; [con_id=a] kill; [pid] kill; \
+                 [class=a kill; [class=a] exec kitty; focus"
+            ),
+            [None, None, None, None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn other_commands() {
+        assert_eq!(
+            parse(
+                "fullscreen toggle; floating toggle; move left; move output right; \
+                 move window to output DP-1; focus output HDMI-A-1; reload; exit"
+            ),
+            some(&[
+                "dispatch fullscreen ",
+                "dispatch togglefloating",
+                "dispatch movewindow l",
+                "dispatch movewindow mon:r",
+                "dispatch movewindow mon:DP-1",
+                "dispatch focusmonitor HDMI-A-1",
+                "reload",
+                "dispatch exit",
+            ])
+        );
+        assert_eq!(
+            parse("exec; move sideways; focus inwards"),
+            [None, None, None]
+        );
+    }
+}
@@ -3,12 +3,30 @@
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::expect_used)]
 
-#[macro_use]
-extern crate lazy_static;
+/// Logs a message with the `log` crate when the `log` feature is enabled,
+/// and as a `tracing` event when the `tracing` feature is,
+/// the arguments are still type checked without them
+macro_rules! log {
+    ($level:ident, $($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::$level!($($arg)*);
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+        #[cfg(not(any(feature = "log", feature = "tracing")))]
+        let _ = format_args!($($arg)*);
+    }};
+}
 
 /// This module provides shared things throughout the crate
 pub mod shared;
 
+/// This module holds the requests shared by the blocking and async functions
+mod request;
+
+/// This module holds the sockets and timers of the async runtime
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod runtime;
+
 /// This module provides functions for getting information on the compositor
 pub mod data;
 
@@ -17,3 +35,73 @@ pub mod event_listener;
 
 /// This module is for calling dispatchers and changing keywords
 pub mod dispatch;
+
+/// This module is for calling hyprctl control commands
+pub mod ctl;
+
+/// This module provides a handle for sending many commands to Hyprland
+pub mod connection;
+
+/// This module provides the Transport trait, for sending commands to Hyprland some other way
+pub mod transport;
+
+/// This module provides a cache of Hyprland's state, kept up to date with events
+pub mod state;
+
+/// This module provides a json feed of the state, for status bars
+pub mod bar;
+
+/// This module provides a tracker of the active submap and its binds
+pub mod submap;
+
+/// This module provides a history of the focused windows, for switching between them like alt-tab
+pub mod focus;
+
+/// This module provides scratchpads, programs kept in a special workspace
+pub mod scratchpad;
+
+/// This module provides groups of windows shown as tabs, and what tab bars do with them
+pub mod group;
+
+/// This module provides switching the active window between maximized, fullscreen and fake fullscreen
+pub mod fullscreen;
+
+/// This module provides finding which windows swallowed which, and swallowing or unswallowing them
+pub mod swallow;
+
+/// This module provides monitor profiles, detected from the connected monitors and applied in one call
+pub mod profiles;
+
+/// This module provides reading and changing the master layout, keeping its options and the current workspace the same
+pub mod master;
+
+/// This module provides the dwindle layout's messages as functions, and reading its options
+pub mod dwindle;
+
+/// This module provides a fixed amount of workspaces per monitor, numbered from 1 on every monitor
+pub mod monitor_workspaces;
+
+/// This module provides a builder for keybinds, to bind them at runtime or write them to the config
+pub mod bind;
+
+/// This module provides builders for window and layer rules, to add them at runtime or write them to the config
+pub mod rule;
+
+/// This module provides a writer and a parser for config files, built from typed values
+pub mod config;
+
+/// This module provides a client for hyprpaper, the wallpaper utility
+#[cfg(feature = "hyprpaper")]
+pub mod hyprpaper;
+
+/// This module provides locking the session and inhibiting idle, for hyprlock and hypridle
+#[cfg(feature = "session")]
+pub mod session;
+
+/// This module provides a subset of the i3/sway IPC, answered with Hyprland's data and dispatchers
+#[cfg(feature = "i3")]
+pub mod i3;
+
+/// This module provides a mock Hyprland, for testing without a compositor
+#[cfg(feature = "mock")]
+pub mod mock;
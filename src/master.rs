@@ -0,0 +1,283 @@
+//! # Master module
+//!
+//! This module provides functions for the master layout, which read its options and change them
+//! together with the current workspace, so what [`orientation`](crate::master::orientation)
+//! and [`mfact`](crate::master::mfact) return is what the workspace looks like
+//!
+//! `layoutmsg` only changes the current workspace and `getoption` only reads the options,
+//! so the setters send both the keyword and the layout message, in a single socket write
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::dispatch::MasterOrientation;
+//! use hyprland::master::{change_mfact_blocking, orientation_blocking, set_orientation_blocking};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     if orientation_blocking()? == MasterOrientation::Left {
+//!         set_orientation_blocking(MasterOrientation::Top)?;
+//!     }
+//!     let mfact = change_mfact_blocking(0.05)?;
+//!     println!("the master area takes up {}%", mfact * 100.0);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::data::{ConfigOption, Keyword, OptionValue};
+use crate::dispatch::{DispatchType, LayoutMsg, MasterLayoutMsg, MasterOrientation};
+use crate::shared::*;
+
+/// The smallest and biggest master factor Hyprland allows
+const MFACT_RANGE: (f32, f32) = (0.05, 0.95);
+
+/// This function returns if the master layout is the layout (blocking)
+pub fn is_active_blocking() -> HyprResult<bool> {
+    is_master(Keyword::get(ConfigOption::GeneralLayout)?)
+}
+
+/// This function returns if the master layout is the layout (async)
+#[cfg(feature = "async")]
+pub async fn is_active() -> HyprResult<bool> {
+    is_master(Keyword::get_async(ConfigOption::GeneralLayout).await?)
+}
+
+/// This function returns the orientation of the master area, `master:orientation` (blocking)
+pub fn orientation_blocking() -> HyprResult<MasterOrientation> {
+    parse_orientation(Keyword::get(ConfigOption::MasterOrientation)?)
+}
+
+/// This function returns the orientation of the master area, `master:orientation` (async)
+#[cfg(feature = "async")]
+pub async fn orientation() -> HyprResult<MasterOrientation> {
+    parse_orientation(Keyword::get_async(ConfigOption::MasterOrientation).await?)
+}
+
+/// This function sets the orientation of the master area,
+/// for the current workspace and the ones that weren't given their own (blocking)
+pub fn set_orientation_blocking(orientation: MasterOrientation) -> HyprResult<()> {
+    crate::dispatch::dispatch_batch_blocking(orientation_dispatches(orientation))
+}
+
+/// This function sets the orientation of the master area,
+/// for the current workspace and the ones that weren't given their own (async)
+#[cfg(feature = "async")]
+pub async fn set_orientation(orientation: MasterOrientation) -> HyprResult<()> {
+    crate::dispatch::dispatch_batch(orientation_dispatches(orientation)).await
+}
+
+/// This function returns the master factor (the part of the workspace the master area takes up),
+/// `master:mfact` (blocking)
+pub fn mfact_blocking() -> HyprResult<f32> {
+    parse_mfact(Keyword::get(ConfigOption::MasterMfact)?)
+}
+
+/// This function returns the master factor (the part of the workspace the master area takes up),
+/// `master:mfact` (async)
+#[cfg(feature = "async")]
+pub async fn mfact() -> HyprResult<f32> {
+    parse_mfact(Keyword::get_async(ConfigOption::MasterMfact).await?)
+}
+
+/// This function sets the master factor, for the current workspace and new windows (blocking)
+///
+/// Returns [`HyprError::InvalidConfig`] if it isn't between 0 and 1
+pub fn set_mfact_blocking(mfact: f32) -> HyprResult<()> {
+    crate::dispatch::dispatch_batch_blocking(mfact_dispatches(mfact)?)
+}
+
+/// This function sets the master factor, for the current workspace and new windows (async)
+///
+/// Returns [`HyprError::InvalidConfig`] if it isn't between 0 and 1
+#[cfg(feature = "async")]
+pub async fn set_mfact(mfact: f32) -> HyprResult<()> {
+    crate::dispatch::dispatch_batch(mfact_dispatches(mfact)?).await
+}
+
+/// This function changes the master factor by `delta`, and returns the new one (blocking)
+///
+/// It's kept between 0.05 and 0.95, like Hyprland does
+pub fn change_mfact_blocking(delta: f32) -> HyprResult<f32> {
+    let mfact = changed_mfact(mfact_blocking()?, delta);
+    set_mfact_blocking(mfact)?;
+    Ok(mfact)
+}
+
+/// This function changes the master factor by `delta`, and returns the new one (async)
+///
+/// It's kept between 0.05 and 0.95, like Hyprland does
+#[cfg(feature = "async")]
+pub async fn change_mfact(delta: f32) -> HyprResult<f32> {
+    let mfact = changed_mfact(mfact().await?, delta);
+    set_mfact(mfact).await?;
+    Ok(mfact)
+}
+
+/// This function adds a master window to the current workspace (blocking)
+pub fn add_master_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(MasterLayoutMsg::AddMaster))
+}
+
+/// This function adds a master window to the current workspace (async)
+#[cfg(feature = "async")]
+pub async fn add_master() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(MasterLayoutMsg::AddMaster)).await
+}
+
+/// This function removes a master window from the current workspace (blocking)
+pub fn remove_master_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(MasterLayoutMsg::RemoveMaster))
+}
+
+/// This function removes a master window from the current workspace (async)
+#[cfg(feature = "async")]
+pub async fn remove_master() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(MasterLayoutMsg::RemoveMaster)).await
+}
+
+/// This function makes the active window the master window,
+/// by swapping it with the master window (blocking)
+pub fn promote_blocking() -> HyprResult<()> {
+    crate::dispatch::dispatch_blocking(message(MasterLayoutMsg::SwapWithMaster))
+}
+
+/// This function makes the active window the master window,
+/// by swapping it with the master window (async)
+#[cfg(feature = "async")]
+pub async fn promote() -> HyprResult<()> {
+    crate::dispatch::dispatch(message(MasterLayoutMsg::SwapWithMaster)).await
+}
+
+fn message(msg: MasterLayoutMsg) -> DispatchType {
+    DispatchType::LayoutMsg(LayoutMsg::Master(msg))
+}
+
+fn orientation_dispatches(orientation: MasterOrientation) -> Vec<DispatchType> {
+    vec![
+        DispatchType::Keyword(
+            ConfigOption::MasterOrientation.into(),
+            orientation.to_string(),
+        ),
+        message(MasterLayoutMsg::Orientation(orientation)),
+    ]
+}
+
+fn mfact_dispatches(mfact: f32) -> HyprResult<Vec<DispatchType>> {
+    if !(0.0..=1.0).contains(&mfact) {
+        return Err(HyprError::InvalidConfig(format!(
+            "the master factor has to be between 0 and 1, not {mfact}"
+        )));
+    }
+    // the f32 is written as it is, through f64 it would get digits it doesn't have
+    Ok(vec![
+        DispatchType::Keyword(ConfigOption::MasterMfact.into(), mfact.to_string()),
+        message(MasterLayoutMsg::MfactExact(mfact)),
+    ])
+}
+
+fn changed_mfact(mfact: f32, delta: f32) -> f32 {
+    let (min, max) = MFACT_RANGE;
+    // rounded, so repeated changes don't drift away from the steps they're made in
+    ((mfact + delta).clamp(min, max) * 1000.0).round() / 1000.0
+}
+
+fn is_master(keyword: Keyword) -> HyprResult<bool> {
+    match keyword.value {
+        OptionValue::String(layout) => Ok(layout == "master"),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn parse_orientation(keyword: Keyword) -> HyprResult<MasterOrientation> {
+    let orientation = match &keyword.value {
+        OptionValue::String(orientation) => match orientation.as_str() {
+            "left" => Some(MasterOrientation::Left),
+            "right" => Some(MasterOrientation::Right),
+            "top" => Some(MasterOrientation::Top),
+            "bottom" => Some(MasterOrientation::Bottom),
+            "center" => Some(MasterOrientation::Center),
+            _ => None,
+        },
+        _ => None,
+    };
+    orientation.ok_or_else(|| unexpected(&keyword.option, &keyword.value))
+}
+
+fn parse_mfact(keyword: Keyword) -> HyprResult<f32> {
+    match keyword.value {
+        OptionValue::Float(mfact) => Ok(mfact as f32),
+        OptionValue::Int(mfact) => Ok(mfact as f32),
+        value => Err(unexpected(&keyword.option, &value)),
+    }
+}
+
+fn unexpected(option: &str, value: &OptionValue) -> HyprError {
+    HyprError::UnexpectedResponse(format!("{option} has the unexpected value {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword(option: &str, value: impl Into<OptionValue>) -> Keyword {
+        Keyword {
+            option: option.to_string(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn options_are_set_with_the_layout_message() -> HyprResult<()> {
+        let strings = |dispatches: Vec<DispatchType>| -> Vec<String> {
+            dispatches.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            strings(orientation_dispatches(MasterOrientation::Top)),
+            [
+                "keyword master:orientation top",
+                "dispatch layoutmsg orientationtop",
+            ]
+        );
+        assert_eq!(
+            strings(mfact_dispatches(0.6)?),
+            [
+                "keyword master:mfact 0.6",
+                "dispatch layoutmsg mfact exact 0.6"
+            ]
+        );
+        assert!(matches!(
+            mfact_dispatches(1.5),
+            Err(HyprError::InvalidConfig(_))
+        ));
+        assert_eq!(
+            message(MasterLayoutMsg::SwapWithMaster).to_string(),
+            "dispatch layoutmsg swapwithmaster"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mfact_changes_are_clamped_and_rounded() {
+        assert_eq!(changed_mfact(0.55, 0.05), 0.6);
+        assert_eq!(changed_mfact(0.55, -0.1), 0.45);
+        assert_eq!(changed_mfact(0.9, 0.1), 0.95);
+        assert_eq!(changed_mfact(0.1, -0.1), 0.05);
+    }
+
+    #[test]
+    fn options_are_parsed() -> HyprResult<()> {
+        assert!(is_master(keyword("general:layout", "master"))?);
+        assert!(!is_master(keyword("general:layout", "dwindle"))?);
+        assert_eq!(
+            parse_orientation(keyword("master:orientation", "center"))?,
+            MasterOrientation::Center
+        );
+        assert!(matches!(
+            parse_orientation(keyword("master:orientation", "diagonal")),
+            Err(HyprError::UnexpectedResponse(_))
+        ));
+        assert_eq!(parse_mfact(keyword("master:mfact", 0.55))?, 0.55);
+        assert_eq!(parse_mfact(keyword("master:mfact", 1))?, 1.0);
+        assert!(parse_mfact(keyword("master:mfact", "0.5")).is_err());
+        Ok(())
+    }
+}
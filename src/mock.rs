@@ -0,0 +1,269 @@
+//! # Mock module
+//!
+//! This module provides [`MockServer`](crate::mock::MockServer), which stands in for Hyprland's sockets,
+//! so code using this crate can be tested where no compositor runs (like in CI)
+//!
+//! It's enabled with the `mock` feature
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use hyprland::connection::HyprlandConnection;
+//! use hyprland::dispatch::DispatchType;
+//! use hyprland::mock::MockServer;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let server = MockServer::start()?;
+//!     server.reply_json("workspaces", "[]");
+//!
+//!     let mut hyprland = HyprlandConnection::for_instance(server.instance());
+//!     assert!(hyprland.get_workspaces()?.is_empty());
+//!     hyprland.dispatch(DispatchType::Exec("kitty".to_string()))?;
+//!
+//!     server.assert_dispatched("exec kitty");
+//!     Ok(())
+//! }
+//! ```
+
+use crate::shared::{Instance, SocketType};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// This struct is a mock Hyprland instance, with a command socket and a event socket
+///
+/// Commands are answered with the replies set with [`reply`](Self::reply),
+/// or with `ok` if there isn't one, and every command is recorded so tests can assert on them.
+/// Event listeners can connect to it, and receive the events sent with [`emit`](Self::emit)
+///
+/// The sockets are created where Hyprland would create them, so the [`Instance`]
+/// (see [`instance`](Self::instance)) works everywhere instances are accepted.
+/// The functions that use the instance the program runs in work with the mock server too,
+/// once `HYPRLAND_INSTANCE_SIGNATURE` is set to its signature.
+/// The sockets are removed when the server is dropped
+pub struct MockServer {
+    instance: Instance,
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    stopped: AtomicBool,
+}
+
+#[derive(Default)]
+struct State {
+    replies: HashMap<String, String>,
+    received: Vec<String>,
+    listeners: Vec<UnixStream>,
+}
+
+impl MockServer {
+    /// This method creates the sockets of a new mock instance, and starts answering on them
+    pub fn start() -> io::Result<Self> {
+        static SERVERS: AtomicUsize = AtomicUsize::new(0);
+        let signature = format!(
+            "mock_{}_{}",
+            std::process::id(),
+            SERVERS.fetch_add(1, Ordering::Relaxed)
+        );
+        let instance = Instance::from_signature(signature);
+        let dir = instance.socket_dir();
+        std::fs::create_dir_all(&dir)?;
+        let bind = |socket_type| UnixListener::bind(instance.socket_path(socket_type));
+        let (commands, events) = match (bind(SocketType::Command), bind(SocketType::Listener)) {
+            (Ok(commands), Ok(events)) => (commands, events),
+            (Err(error), _) | (_, Err(error)) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                return Err(error);
+            }
+        };
+
+        let shared = Arc::new(Shared::default());
+        let threads = vec![
+            spawn_accepting(commands, shared.clone(), |stream, shared| {
+                let _ = answer(stream, shared);
+            }),
+            spawn_accepting(events, shared.clone(), |stream, shared| {
+                shared.state().listeners.push(stream);
+            }),
+        ];
+        Ok(MockServer {
+            instance,
+            shared,
+            threads,
+        })
+    }
+
+    /// This method returns the instance of the server, to connect to it
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    /// This method sets what the server responds to a command with,
+    /// the command has to match exactly (including flags, like `j/`)
+    pub fn reply(&self, command: impl Into<String>, response: impl Into<String>) {
+        self.shared
+            .state()
+            .replies
+            .insert(command.into(), response.into());
+    }
+
+    /// This method sets the json the server responds to a data command with (like `monitors`)
+    pub fn reply_json(&self, command: &str, json: impl Into<String>) {
+        self.reply(format!("j/{command}"), json);
+    }
+
+    /// This method returns every command the server received, in order,
+    /// the commands of a batch are returned separately
+    pub fn received(&self) -> Vec<String> {
+        self.shared.state().received.clone()
+    }
+
+    /// This method forgets the commands the server received
+    pub fn clear_received(&self) {
+        self.shared.state().received.clear();
+    }
+
+    /// This method panics if the server didn't receive the command
+    pub fn assert_received(&self, command: &str) {
+        let received = self.received();
+        assert!(
+            received.iter().any(|received| received == command),
+            "the mock server didn't receive {command:?}, it received {received:?}"
+        );
+    }
+
+    /// This method panics if the dispatcher wasn't called,
+    /// `dispatch` is what comes after `dispatch ` (like `exec kitty`)
+    pub fn assert_dispatched(&self, dispatch: &str) {
+        self.assert_received(&format!("dispatch {dispatch}"));
+    }
+
+    /// This method waits until at least `count` event listeners are connected,
+    /// returns whether they connected within the timeout
+    ///
+    /// Events are only sent to the listeners connected when they're emitted,
+    /// so tests should wait for theirs to connect first
+    pub fn wait_for_listeners(&self, count: usize, timeout: Duration) -> bool {
+        let start = Instant::now();
+        loop {
+            if self.shared.state().listeners.len() >= count {
+                return true;
+            }
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// This method sends a event line (`EVENT>>DATA`) to every connected event listener
+    ///
+    /// ```rust
+    /// # fn main() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::event_listener::EventListener;
+    /// use hyprland::mock::MockServer;
+    /// use hyprland::shared::WorkspaceType;
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    ///
+    /// let server = MockServer::start()?;
+    /// let (sender, receiver) = mpsc::channel();
+    /// let instance = server.instance().clone();
+    /// std::thread::spawn(move || {
+    ///     let mut listener = EventListener::new();
+    ///     listener.set_instance(instance);
+    ///     listener.add_workspace_change_handler(move |id| {
+    ///         let _ = sender.send(id);
+    ///     });
+    ///     listener.start_listener_blocking()
+    /// });
+    ///
+    /// assert!(server.wait_for_listeners(1, Duration::from_secs(1)));
+    /// server.emit("workspace>>2");
+    /// let id = receiver.recv_timeout(Duration::from_secs(1)).ok();
+    /// assert_eq!(id, Some(WorkspaceType::Regular(2)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn emit(&self, line: &str) {
+        let line = format!("{line}\n");
+        self.shared
+            .state()
+            .listeners
+            .retain_mut(|listener| listener.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        // connecting wakes the threads up, so they see they're stopped
+        for socket_type in [SocketType::Command, SocketType::Listener] {
+            let _ = UnixStream::connect(self.instance.socket_path(socket_type));
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_dir_all(self.instance.socket_dir());
+    }
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn respond(&mut self, command: &str) -> String {
+        self.received.push(command.to_string());
+        match self.replies.get(command) {
+            Some(response) => response.clone(),
+            None => "ok".to_string(),
+        }
+    }
+}
+
+fn spawn_accepting(
+    listener: UnixListener,
+    shared: Arc<Shared>,
+    handle: fn(UnixStream, &Shared),
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if shared.stopped.load(Ordering::SeqCst) {
+                break;
+            }
+            if let Ok(stream) = stream {
+                handle(stream, &shared);
+            }
+        }
+    })
+}
+
+/// Answers a command (or a batch of them), which like Hyprland is read with a single read
+fn answer(mut stream: UnixStream, shared: &Shared) -> io::Result<()> {
+    let mut buffer = vec![0; 65536];
+    let length = stream.read(&mut buffer)?;
+    let request = String::from_utf8_lossy(&buffer[..length]);
+    let response = {
+        let mut state = shared.state();
+        match request.strip_prefix("[[BATCH]]") {
+            Some(batch) => batch
+                .split(';')
+                .map(|command| state.respond(command))
+                .collect::<Vec<_>>()
+                .join("\n\n\n"),
+            None => state.respond(&request),
+        }
+    };
+    stream.write_all(response.as_bytes())
+}
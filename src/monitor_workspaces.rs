@@ -0,0 +1,403 @@
+//! # Monitor workspaces module
+//!
+//! This module provides [`MonitorWorkspaces`](crate::monitor_workspaces::MonitorWorkspaces), which gives
+//! every monitor its own workspaces numbered from 1 (like the split-monitor-workspaces plugin),
+//! so the same keybind switches to workspace 1 of whichever monitor is focused
+//!
+//! With `count` workspaces per monitor, workspace `n` of the monitor at `index` is the workspace
+//! with the id `index * count + n`. Monitors are indexed in the order of their ids, so with
+//! 10 workspaces per monitor the second monitor has the workspaces 11 to 20
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::dispatch::CycleDirection;
+//! use hyprland::monitor_workspaces::MonitorWorkspaces;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let workspaces = MonitorWorkspaces::new(10);
+//!     // makes every workspace open on its monitor, once after the monitors are set up
+//!     workspaces.bind_to_monitors()?;
+//!     // bound to SUPER+3 and SUPER+SHIFT+3
+//!     workspaces.switch(3)?;
+//!     workspaces.move_to(3)?;
+//!     // bound to SUPER+Tab
+//!     workspaces.cycle(CycleDirection::Next)
+//! }
+//! ```
+
+use crate::data::{Keyword, Monitor, Monitors, OptionValue};
+use crate::dispatch::{
+    CycleDirection, DispatchType, WorkspaceIdentifier, WorkspaceIdentifierWithSpecial,
+};
+use crate::shared::*;
+
+/// This struct lays out a fixed amount of workspaces per monitor, see the [module](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorWorkspaces {
+    count: u8,
+}
+
+impl Default for MonitorWorkspaces {
+    fn default() -> Self {
+        MonitorWorkspaces::new(10)
+    }
+}
+
+impl MonitorWorkspaces {
+    /// This method lays out `count` workspaces per monitor, 10 is the default
+    pub fn new(count: u8) -> Self {
+        MonitorWorkspaces { count }
+    }
+
+    /// This method returns how many workspaces every monitor has
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// This method returns the id of workspace `n` (from 1) of the monitor at `index` (from 0),
+    /// `None` if `n` isn't one of the monitor's workspaces or the id is too big
+    ///
+    /// ```rust
+    /// use hyprland::monitor_workspaces::MonitorWorkspaces;
+    /// let workspaces = MonitorWorkspaces::new(10);
+    /// assert_eq!(workspaces.id(0, 3), Some(3));
+    /// assert_eq!(workspaces.id(1, 3), Some(13));
+    /// assert_eq!(workspaces.id(1, 11), None);
+    /// ```
+    pub fn id(&self, index: usize, n: u8) -> Option<WorkspaceId> {
+        if n == 0 || n > self.count {
+            return None;
+        }
+        let id = index
+            .checked_mul(self.count.into())?
+            .checked_add(n.into())?;
+        id.try_into().ok()
+    }
+
+    /// This method returns the index of the monitor the workspace belongs to, and its number
+    /// on that monitor, `None` for the id 0 and named or special workspaces
+    ///
+    /// ```rust
+    /// use hyprland::monitor_workspaces::MonitorWorkspaces;
+    /// let workspaces = MonitorWorkspaces::new(10);
+    /// assert_eq!(workspaces.logical(13), Some((1, 3)));
+    /// assert_eq!(workspaces.logical(10), Some((0, 10)));
+    /// ```
+    pub fn logical(&self, id: WorkspaceId) -> Option<(usize, u8)> {
+        if id <= 0 || self.count == 0 {
+            return None;
+        }
+        let count = WorkspaceId::from(self.count);
+        let index = (id - 1) / count;
+        // both fit, the index isn't negative and the number is at most `count`
+        Some((index as usize, (id - index * count) as u8))
+    }
+
+    /// This method returns the index of the monitor, its position when the monitors are ordered by id
+    pub fn monitor_index(monitors: &Monitors, name: &str) -> Option<usize> {
+        let mut ids: Vec<u8> = monitors.iter().map(|monitor| monitor.id).collect();
+        ids.sort_unstable();
+        let id = monitors.iter().find(|monitor| monitor.name == name)?.id;
+        ids.iter().position(|other| *other == id)
+    }
+
+    /// This method returns the id of workspace `n` of the monitor
+    ///
+    /// Returns [`HyprError::InvalidWorkspace`] if `n` isn't one of the monitor's workspaces
+    pub fn id_on(&self, monitors: &Monitors, monitor: &str, n: u8) -> HyprResult<WorkspaceId> {
+        let index = MonitorWorkspaces::monitor_index(monitors, monitor).ok_or_else(|| {
+            HyprError::InvalidWorkspace(format!("there's no monitor named {monitor:?}"))
+        })?;
+        self.id(index, n).ok_or_else(|| {
+            HyprError::InvalidWorkspace(format!(
+                "{monitor} has workspaces 1 to {}, not {n}",
+                self.count
+            ))
+        })
+    }
+
+    /// This method switches the focused monitor to its workspace `n` (blocking)
+    pub fn switch(&self, n: u8) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.switch_dispatch(&get_monitors()?, n)?)
+    }
+
+    /// This method switches the focused monitor to its workspace `n` (async)
+    #[cfg(feature = "async")]
+    pub async fn switch_async(&self, n: u8) -> HyprResult<()> {
+        let monitors = get_monitors_async().await?;
+        crate::dispatch::dispatch(self.switch_dispatch(&monitors, n)?).await
+    }
+
+    /// This method moves the active window to workspace `n` of the focused monitor,
+    /// and switches to it (blocking)
+    pub fn move_to(&self, n: u8) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.move_dispatch(&get_monitors()?, n, false)?)
+    }
+
+    /// This method moves the active window to workspace `n` of the focused monitor,
+    /// and switches to it (async)
+    #[cfg(feature = "async")]
+    pub async fn move_to_async(&self, n: u8) -> HyprResult<()> {
+        let monitors = get_monitors_async().await?;
+        crate::dispatch::dispatch(self.move_dispatch(&monitors, n, false)?).await
+    }
+
+    /// This method moves the active window to workspace `n` of the focused monitor,
+    /// without switching to it (blocking)
+    pub fn move_to_silent(&self, n: u8) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.move_dispatch(&get_monitors()?, n, true)?)
+    }
+
+    /// This method moves the active window to workspace `n` of the focused monitor,
+    /// without switching to it (async)
+    #[cfg(feature = "async")]
+    pub async fn move_to_silent_async(&self, n: u8) -> HyprResult<()> {
+        let monitors = get_monitors_async().await?;
+        crate::dispatch::dispatch(self.move_dispatch(&monitors, n, true)?).await
+    }
+
+    /// This method switches the focused monitor to its next or previous workspace,
+    /// going around after the last one (blocking)
+    pub fn cycle(&self, direction: CycleDirection) -> HyprResult<()> {
+        crate::dispatch::dispatch_blocking(self.cycle_dispatch(&get_monitors()?, direction)?)
+    }
+
+    /// This method switches the focused monitor to its next or previous workspace,
+    /// going around after the last one (async)
+    #[cfg(feature = "async")]
+    pub async fn cycle_async(&self, direction: CycleDirection) -> HyprResult<()> {
+        let monitors = get_monitors_async().await?;
+        crate::dispatch::dispatch(self.cycle_dispatch(&monitors, direction)?).await
+    }
+
+    /// This method returns the workspace rules that make every monitor's workspaces open on it,
+    /// like `11,monitor:DP-2` (the value of a `workspace` keyword)
+    pub fn rules(&self, monitors: &Monitors) -> Vec<String> {
+        let mut rules = vec![];
+        for monitor in monitors {
+            let Some(index) = MonitorWorkspaces::monitor_index(monitors, &monitor.name) else {
+                continue;
+            };
+            for n in 1..=self.count {
+                if let Some(id) = self.id(index, n) {
+                    rules.push(format!("{id},monitor:{}", monitor.name));
+                }
+            }
+        }
+        rules
+    }
+
+    /// This method sets the workspace rules that make every monitor's workspaces open on it (blocking)
+    ///
+    /// The rules last until the config is reloaded, and have to be set again when monitors
+    /// are added or removed
+    pub fn bind_to_monitors(&self) -> HyprResult<()> {
+        let rules = self.keywords(&get_monitors()?);
+        Keyword::set_many(&rules)?.into_iter().collect()
+    }
+
+    /// This method sets the workspace rules that make every monitor's workspaces open on it (async)
+    ///
+    /// See [`bind_to_monitors`](Self::bind_to_monitors)
+    #[cfg(feature = "async")]
+    pub async fn bind_to_monitors_async(&self) -> HyprResult<()> {
+        let rules = self.keywords(&get_monitors_async().await?);
+        Keyword::set_many_async(&rules).await?.into_iter().collect()
+    }
+
+    fn keywords(&self, monitors: &Monitors) -> Vec<(String, OptionValue)> {
+        self.rules(monitors)
+            .into_iter()
+            .map(|rule| ("workspace".to_string(), rule.into()))
+            .collect()
+    }
+
+    /// Returns the id of workspace `n` of the focused monitor
+    fn focused_id(&self, monitors: &Monitors, n: u8) -> HyprResult<WorkspaceId> {
+        self.id_on(monitors, &focused(monitors)?.name, n)
+    }
+
+    fn switch_dispatch(&self, monitors: &Monitors, n: u8) -> HyprResult<DispatchType> {
+        let id = self.focused_id(monitors, n)?;
+        Ok(DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Id(
+            id,
+        )))
+    }
+
+    fn move_dispatch(&self, monitors: &Monitors, n: u8, silent: bool) -> HyprResult<DispatchType> {
+        let workspace = WorkspaceIdentifier::Id(self.focused_id(monitors, n)?);
+        Ok(if silent {
+            DispatchType::MoveFocusedWindowToWorkspaceSilent(workspace)
+        } else {
+            DispatchType::MoveFocusedWindowToWorkspace(workspace)
+        })
+    }
+
+    fn cycle_dispatch(
+        &self,
+        monitors: &Monitors,
+        direction: CycleDirection,
+    ) -> HyprResult<DispatchType> {
+        let monitor = focused(monitors)?;
+        // a workspace that isn't one of the monitor's (like a named one) counts as its first
+        let current = match monitor.active_workspace.id {
+            WorkspaceType::Regular(id) => self
+                .logical(id)
+                .filter(|(index, _)| {
+                    Some(*index) == MonitorWorkspaces::monitor_index(monitors, &monitor.name)
+                })
+                .map_or(1, |(_, n)| n),
+            _ => 1,
+        };
+        let count = self.count.max(1);
+        let n = match direction {
+            CycleDirection::Next => current % count + 1,
+            CycleDirection::Previous => (current + count - 2) % count + 1,
+        };
+        self.switch_dispatch(monitors, n)
+    }
+}
+
+/// Returns the focused monitor
+fn focused(monitors: &Monitors) -> HyprResult<&Monitor> {
+    monitors
+        .iter()
+        .find(|monitor| monitor.focused)
+        .ok_or_else(|| HyprError::UnexpectedResponse("no monitor is focused".to_string()))
+}
+
+fn get_monitors() -> HyprResult<Monitors> {
+    crate::data::blocking::get_monitors()
+}
+
+#[cfg(feature = "async")]
+async fn get_monitors_async() -> HyprResult<Monitors> {
+    crate::data::asynchronous::get_monitors().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `hyprctl monitors -j`, with the ids not in the order the monitors are listed in,
+    /// DP-2 is focused and on workspace 13
+    const MONITORS: &str = r#"[
+        {"id": 2, "name": "HDMI-A-1", "width": 1920, "height": 1080, "refreshRate": 60.0,
+         "x": 4480, "y": 0, "activeWorkspace": {"id": 21, "name": "21"},
+         "reserved": [0, 0, 0, 0], "scale": 1.0, "transform": 0, "focused": false},
+        {"id": 0, "name": "eDP-1", "width": 1920, "height": 1080, "refreshRate": 60.0,
+         "x": 0, "y": 0, "activeWorkspace": {"id": -1337, "name": "coding"},
+         "reserved": [0, 0, 0, 0], "scale": 1.0, "transform": 0, "focused": false},
+        {"id": 1, "name": "DP-2", "width": 2560, "height": 1440, "refreshRate": 144.0,
+         "x": 1920, "y": 0, "activeWorkspace": {"id": 13, "name": "13"},
+         "reserved": [0, 0, 0, 0], "scale": 1.0, "transform": 0, "focused": true}
+    ]"#;
+
+    fn monitors() -> HyprResult<Monitors> {
+        Ok(serde_json::from_str(MONITORS)?)
+    }
+
+    #[test]
+    fn ids_and_logical_workspaces() {
+        let workspaces = MonitorWorkspaces::new(10);
+        assert_eq!(workspaces.id(0, 1), Some(1));
+        assert_eq!(workspaces.id(2, 10), Some(30));
+        assert_eq!(workspaces.id(0, 0), None);
+        assert_eq!(workspaces.id(0, 11), None);
+        assert_eq!(workspaces.id(usize::MAX, 1), None);
+        assert_eq!(workspaces.id(i32::MAX as usize / 10, 10), None);
+        for index in 0..5 {
+            for n in 1..=10 {
+                let id = workspaces.id(index, n);
+                assert_eq!(id.and_then(|id| workspaces.logical(id)), Some((index, n)));
+            }
+        }
+        assert_eq!(workspaces.logical(0), None);
+        assert_eq!(workspaces.logical(-1337), None);
+        assert_eq!(workspaces.logical(-99), None);
+        assert_eq!(MonitorWorkspaces::new(0).logical(1), None);
+        assert_eq!(MonitorWorkspaces::new(0).id(0, 1), None);
+    }
+
+    #[test]
+    fn monitors_are_indexed_by_id() -> HyprResult<()> {
+        let monitors = monitors()?;
+        let index = |name| MonitorWorkspaces::monitor_index(&monitors, name);
+        assert_eq!(index("eDP-1"), Some(0));
+        assert_eq!(index("DP-2"), Some(1));
+        assert_eq!(index("HDMI-A-1"), Some(2));
+        assert_eq!(index("DP-3"), None);
+
+        let workspaces = MonitorWorkspaces::new(3);
+        assert_eq!(workspaces.id_on(&monitors, "HDMI-A-1", 2)?, 8);
+        assert!(matches!(
+            workspaces.id_on(&monitors, "HDMI-A-1", 4),
+            Err(HyprError::InvalidWorkspace(_))
+        ));
+        assert!(matches!(
+            workspaces.id_on(&monitors, "DP-3", 1),
+            Err(HyprError::InvalidWorkspace(_))
+        ));
+        assert_eq!(
+            workspaces.rules(&monitors),
+            [
+                "7,monitor:HDMI-A-1",
+                "8,monitor:HDMI-A-1",
+                "9,monitor:HDMI-A-1",
+                "1,monitor:eDP-1",
+                "2,monitor:eDP-1",
+                "3,monitor:eDP-1",
+                "4,monitor:DP-2",
+                "5,monitor:DP-2",
+                "6,monitor:DP-2",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dispatches_on_the_focused_monitor() -> HyprResult<()> {
+        let mut monitors = monitors()?;
+        let workspaces = MonitorWorkspaces::new(10);
+        let dispatch = |dispatch: HyprResult<DispatchType>| dispatch.map(|it| it.to_string());
+        assert_eq!(
+            dispatch(workspaces.switch_dispatch(&monitors, 5))?,
+            "dispatch workspace 15"
+        );
+        assert_eq!(
+            dispatch(workspaces.move_dispatch(&monitors, 5, true))?,
+            "dispatch movetoworkspacesilent 15"
+        );
+        assert_eq!(
+            dispatch(workspaces.cycle_dispatch(&monitors, CycleDirection::Next))?,
+            "dispatch workspace 14"
+        );
+        assert_eq!(
+            dispatch(workspaces.cycle_dispatch(&monitors, CycleDirection::Previous))?,
+            "dispatch workspace 12"
+        );
+
+        // a named workspace counts as the first, the cycle wraps around
+        for monitor in &mut monitors {
+            monitor.focused = monitor.name == "eDP-1";
+        }
+        assert_eq!(
+            dispatch(workspaces.cycle_dispatch(&monitors, CycleDirection::Previous))?,
+            "dispatch workspace 10"
+        );
+        assert_eq!(
+            dispatch(workspaces.cycle_dispatch(&monitors, CycleDirection::Next))?,
+            "dispatch workspace 2"
+        );
+
+        for monitor in &mut monitors {
+            monitor.focused = false;
+        }
+        assert!(matches!(
+            workspaces.switch_dispatch(&monitors, 1),
+            Err(HyprError::UnexpectedResponse(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,585 @@
+//! # Profiles module
+//!
+//! This module provides [`Profiles`](crate::profiles::Profiles), named monitor configurations
+//! (like laptop only, docked or presenting) that are detected from the monitors that are connected,
+//! and applied in one call, for switching between them when a monitor is plugged in
+//!
+//! Monitors are found by their [`Output`](crate::profiles::Output), their description or serial
+//! stay the same when they're plugged into another port, unlike their name
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::profiles::{Mode, MonitorSettings, Output, Profile, Profiles};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let laptop = Output::Name("eDP-1".to_string());
+//!     let desk = Output::Description("Dell Inc. DELL U2720Q".to_string());
+//!     let profiles = Profiles::new()
+//!         .profile(Profile::new("laptop").monitor(MonitorSettings::new(laptop.clone()).scale(1.5)))
+//!         .profile(
+//!             Profile::new("docked")
+//!                 .monitor(MonitorSettings::new(laptop).disabled())
+//!                 .monitor(
+//!                     MonitorSettings::new(desk)
+//!                         .mode(Mode::Exact(3840, 2160, Some(60.0)))
+//!                         .position(0, 0)
+//!                         .scale(2.0)
+//!                         .workspaces(1..=5),
+//!                 ),
+//!         );
+//!     // called on startup, and when a monitor is added or removed
+//!     if let Some(profile) = profiles.apply_detected()? {
+//!         println!("applied {}", profile.name());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::data::{Keyword, Monitor, Monitors, OptionValue, Transforms, Workspaces};
+use crate::dispatch::{DispatchType, MonitorIdentifier, WorkspaceIdentifier};
+use crate::shared::*;
+use std::fmt;
+
+/// This enum holds how a monitor is found
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Output {
+    /// The name of the port it's plugged into (like `DP-1`)
+    Name(String),
+    /// The start of its description (like `Dell Inc. DELL U2720Q`), see [`Monitor::description`]
+    Description(String),
+    /// Its serial number
+    Serial(String),
+}
+
+impl Output {
+    /// This method returns if the monitor is this output
+    pub fn matches(&self, monitor: &Monitor) -> bool {
+        match self {
+            Output::Name(name) => monitor.name == *name,
+            Output::Description(description) => {
+                monitor.description.starts_with(description.as_str())
+            }
+            Output::Serial(serial) => !serial.is_empty() && monitor.serial == *serial,
+        }
+    }
+
+    /// Returns how the output is named in rules, `None` for a serial of a monitor that isn't connected
+    fn selector(&self, monitors: &Monitors) -> Option<String> {
+        match self {
+            Output::Name(name) => Some(name.clone()),
+            Output::Description(description) => Some(format!("desc:{description}")),
+            // Hyprland's rules don't have serials, so the name it's connected with is used
+            Output::Serial(_) => monitors
+                .iter()
+                .find(|monitor| self.matches(monitor))
+                .map(|monitor| monitor.name.clone()),
+        }
+    }
+}
+
+/// This enum holds the resolution and refresh rate of a monitor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// The mode the monitor prefers
+    Preferred,
+    /// The highest resolution
+    HighRes,
+    /// The highest refresh rate
+    HighRr,
+    /// The width and height (in pixels), and the refresh rate (in hertz)
+    Exact(u16, u16, Option<f32>),
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Preferred => f.write_str("preferred"),
+            Mode::HighRes => f.write_str("highres"),
+            Mode::HighRr => f.write_str("highrr"),
+            Mode::Exact(width, height, None) => write!(f, "{width}x{height}"),
+            Mode::Exact(width, height, Some(rate)) => write!(f, "{width}x{height}@{rate}"),
+        }
+    }
+}
+
+/// This struct holds the settings of a monitor in a profile
+///
+/// By default the monitor gets its preferred mode, is placed automatically, and isn't scaled or rotated
+#[derive(Debug, Clone)]
+pub struct MonitorSettings {
+    output: Output,
+    mode: Mode,
+    position: Option<(i32, i32)>,
+    scale: f32,
+    transform: Transforms,
+    enabled: bool,
+    workspaces: Vec<WorkspaceId>,
+}
+
+impl MonitorSettings {
+    /// This method creates the settings of the monitor, with the defaults
+    pub fn new(output: Output) -> Self {
+        MonitorSettings {
+            output,
+            mode: Mode::Preferred,
+            position: None,
+            scale: 1.0,
+            transform: Transforms::Normal,
+            enabled: true,
+            workspaces: vec![],
+        }
+    }
+
+    /// This method sets the resolution and refresh rate
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// This method sets the position (in logical pixels), instead of placing it automatically
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// This method sets the scale
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// This method sets the transform (rotation and flip)
+    pub fn transform(mut self, transform: Transforms) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// This method turns the monitor off, the profile still applies when it's connected
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    /// This method makes the workspaces open on the monitor,
+    /// and moves them to it when the profile is applied
+    pub fn workspaces(mut self, workspaces: impl IntoIterator<Item = WorkspaceId>) -> Self {
+        self.workspaces.extend(workspaces);
+        self
+    }
+
+    /// This method returns how the monitor is found
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// This method returns if the monitor is turned on
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the value of the `monitor` keyword
+    fn rule(&self, selector: &str) -> String {
+        if !self.enabled {
+            return format!("{selector},disable");
+        }
+        let position = match self.position {
+            Some((x, y)) => format!("{x}x{y}"),
+            None => "auto".to_string(),
+        };
+        let mut rule = format!("{selector},{},{position},{}", self.mode, self.scale);
+        let transform = self.transform.clone() as u8;
+        if transform != 0 {
+            rule.push_str(&format!(",transform,{transform}"));
+        }
+        rule
+    }
+}
+
+/// This struct holds a named configuration of monitors
+#[derive(Debug, Clone)]
+pub struct Profile {
+    name: String,
+    monitors: Vec<MonitorSettings>,
+}
+
+impl Profile {
+    /// This method creates a profile without monitors
+    pub fn new(name: impl Into<String>) -> Self {
+        Profile {
+            name: name.into(),
+            monitors: vec![],
+        }
+    }
+
+    /// This method adds the settings of a monitor
+    pub fn monitor(mut self, settings: MonitorSettings) -> Self {
+        self.monitors.push(settings);
+        self
+    }
+
+    /// This method returns the name of the profile
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// This method returns the settings of the monitors
+    pub fn monitors(&self) -> &[MonitorSettings] {
+        &self.monitors
+    }
+
+    /// This method returns if the profile is for the connected monitors,
+    /// every monitor it turns on is connected and every connected monitor is in it
+    /// (the ones it turns off don't have to be connected)
+    ///
+    /// Monitors that are turned off are connected too, so `monitors` should be the ones from
+    /// [`get_monitors_all`](crate::data::blocking::get_monitors_all), otherwise a monitor
+    /// a profile turned off looks unplugged and the profile that turns it on again never matches
+    pub fn matches(&self, monitors: &Monitors) -> bool {
+        let connected = |settings: &&MonitorSettings| {
+            monitors
+                .iter()
+                .any(|monitor| settings.output.matches(monitor))
+        };
+        let known = |monitor: &Monitor| {
+            self.monitors
+                .iter()
+                .any(|settings| settings.output.matches(monitor))
+        };
+        self.monitors
+            .iter()
+            .filter(|settings| settings.enabled)
+            .all(|settings| connected(&settings))
+            && monitors.iter().all(known)
+    }
+
+    /// This method returns the values of the `monitor` keyword that apply the profile,
+    /// one for every monitor (like `desc:Dell Inc. DELL U2720Q,3840x2160@60,0x0,2`)
+    ///
+    /// Monitors found by their serial are named by the port they're connected to,
+    /// and left out when they aren't connected (`monitors` should include the ones that are turned off)
+    pub fn rules(&self, monitors: &Monitors) -> Vec<String> {
+        self.monitors
+            .iter()
+            .filter_map(|settings| Some(settings.rule(&settings.output.selector(monitors)?)))
+            .collect()
+    }
+
+    /// This method applies the profile, by setting the `monitor` and `workspace` keywords,
+    /// then moving its workspaces to their monitors (blocking)
+    ///
+    /// The changes last until the config is reloaded
+    pub fn apply(&self) -> HyprResult<()> {
+        let keywords = self.keywords(&crate::data::blocking::get_monitors_all()?);
+        Keyword::set_many(&keywords)?
+            .into_iter()
+            .collect::<HyprResult<()>>()?;
+        // workspaces can only be moved to the monitors that are turned on now
+        let moves = self.workspace_moves(
+            &crate::data::blocking::get_monitors()?,
+            &crate::data::blocking::get_workspaces()?,
+        );
+        if moves.is_empty() {
+            return Ok(());
+        }
+        crate::dispatch::dispatch_batch_blocking(moves)
+    }
+
+    /// This method applies the profile, by setting the `monitor` and `workspace` keywords,
+    /// then moving its workspaces to their monitors (async)
+    ///
+    /// See [`apply`](Self::apply)
+    #[cfg(feature = "async")]
+    pub async fn apply_async(&self) -> HyprResult<()> {
+        let keywords = self.keywords(&crate::data::asynchronous::get_monitors_all().await?);
+        Keyword::set_many_async(&keywords)
+            .await?
+            .into_iter()
+            .collect::<HyprResult<()>>()?;
+        let moves = self.workspace_moves(
+            &crate::data::asynchronous::get_monitors().await?,
+            &crate::data::asynchronous::get_workspaces().await?,
+        );
+        if moves.is_empty() {
+            return Ok(());
+        }
+        crate::dispatch::dispatch_batch(moves).await
+    }
+
+    fn keywords(&self, monitors: &Monitors) -> Vec<(String, OptionValue)> {
+        let mut keywords: Vec<(String, OptionValue)> = self
+            .rules(monitors)
+            .into_iter()
+            .map(|rule| ("monitor".to_string(), rule.into()))
+            .collect();
+        for settings in self.monitors.iter().filter(|settings| settings.enabled) {
+            let Some(selector) = settings.output.selector(monitors) else {
+                continue;
+            };
+            for id in &settings.workspaces {
+                keywords.push((
+                    "workspace".to_string(),
+                    format!("{id},monitor:{selector}").into(),
+                ));
+            }
+        }
+        keywords
+    }
+
+    /// Returns the dispatchers that move the profile's workspaces that exist to their monitors
+    fn workspace_moves(&self, monitors: &Monitors, workspaces: &Workspaces) -> Vec<DispatchType> {
+        let mut moves = vec![];
+        for settings in self.monitors.iter().filter(|settings| settings.enabled) {
+            let Some(monitor) = monitors
+                .iter()
+                .find(|monitor| settings.output.matches(monitor))
+            else {
+                continue;
+            };
+            for id in &settings.workspaces {
+                let elsewhere = workspaces.iter().any(|workspace| {
+                    workspace.id == WorkspaceType::Regular(*id) && workspace.monitor != monitor.name
+                });
+                if elsewhere {
+                    moves.push(DispatchType::MoveWorkspaceToMonitor(
+                        WorkspaceIdentifier::Id(*id),
+                        MonitorIdentifier::Name(monitor.name.clone()),
+                    ));
+                }
+            }
+        }
+        moves
+    }
+}
+
+/// The profile is written as the config lines that apply it (`monitor=...` and `workspace=...`),
+/// one per line, monitors found by their serial are left out
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (keyword, value) in self.keywords(&vec![]) {
+            writeln!(f, "{keyword}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// This struct holds the profiles, to find the one for the connected monitors
+#[derive(Debug, Clone, Default)]
+pub struct Profiles {
+    profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// This method creates a empty list of profiles
+    pub fn new() -> Self {
+        Profiles::default()
+    }
+
+    /// This method adds a profile, profiles added first are detected first
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profiles.push(profile);
+        self
+    }
+
+    /// This method returns the profile with the name
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// This method returns all profiles
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    /// This method returns the first profile for the monitors, see [`Profile::matches`]
+    pub fn detect(&self, monitors: &Monitors) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.matches(monitors))
+    }
+
+    /// This method returns the first profile for the connected monitors (blocking)
+    pub fn detect_current(&self) -> HyprResult<Option<&Profile>> {
+        Ok(self.detect(&crate::data::blocking::get_monitors_all()?))
+    }
+
+    /// This method returns the first profile for the connected monitors (async)
+    #[cfg(feature = "async")]
+    pub async fn detect_current_async(&self) -> HyprResult<Option<&Profile>> {
+        Ok(self.detect(&crate::data::asynchronous::get_monitors_all().await?))
+    }
+
+    /// This method applies the profile for the connected monitors,
+    /// and returns it, `None` if there isn't one (blocking)
+    pub fn apply_detected(&self) -> HyprResult<Option<&Profile>> {
+        let profile = self.detect_current()?;
+        if let Some(profile) = profile {
+            profile.apply()?;
+        }
+        Ok(profile)
+    }
+
+    /// This method applies the profile for the connected monitors,
+    /// and returns it, `None` if there isn't one (async)
+    #[cfg(feature = "async")]
+    pub async fn apply_detected_async(&self) -> HyprResult<Option<&Profile>> {
+        let profile = self.detect_current_async().await?;
+        if let Some(profile) = profile {
+            profile.apply_async().await?;
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a connected monitor, named `name` with the description `description`
+    fn monitor(name: &str, description: &str, serial: &str) -> HyprResult<Monitor> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": 0, "name": name, "description": description, "serial": serial,
+            "width": 1920, "height": 1080, "refreshRate": 60.0, "x": 0, "y": 0,
+            "activeWorkspace": {"id": 1, "name": "1"}, "reserved": [0, 0, 0, 0],
+            "scale": 1.0, "transform": 0, "focused": true,
+        }))?)
+    }
+
+    /// Returns the profiles of a laptop that's used alone or docked to a monitor on the desk
+    fn profiles() -> Profiles {
+        let laptop = Output::Name("eDP-1".to_string());
+        let desk = Output::Description("Dell Inc. DELL U2720Q".to_string());
+        Profiles::new()
+            .profile(
+                Profile::new("laptop").monitor(MonitorSettings::new(laptop.clone()).scale(1.5)),
+            )
+            .profile(
+                Profile::new("docked")
+                    .monitor(MonitorSettings::new(laptop).disabled())
+                    .monitor(
+                        MonitorSettings::new(desk)
+                            .mode(Mode::Exact(3840, 2160, Some(60.0)))
+                            .position(0, 0)
+                            .scale(2.0)
+                            .transform(Transforms::Normal90)
+                            .workspaces(1..=2),
+                    ),
+            )
+    }
+
+    #[test]
+    fn outputs_are_found_by_name_description_or_serial() -> HyprResult<()> {
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        assert!(Output::Name("DP-1".to_string()).matches(&desk));
+        assert!(!Output::Name("DP-2".to_string()).matches(&desk));
+        assert!(Output::Description("Dell Inc. DELL U2720Q".to_string()).matches(&desk));
+        assert!(!Output::Description("DELL U2720Q".to_string()).matches(&desk));
+        assert!(Output::Serial("1234ABC".to_string()).matches(&desk));
+        // monitors without a serial report a empty one
+        let laptop = monitor("eDP-1", "BOE 0x0BCA", "")?;
+        assert!(!Output::Serial(String::new()).matches(&laptop));
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_are_detected_from_the_connected_monitors() -> HyprResult<()> {
+        let profiles = profiles();
+        let laptop = monitor("eDP-1", "BOE 0x0BCA", "")?;
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        let detected = |monitors: Monitors| profiles.detect(&monitors).map(Profile::name);
+        assert_eq!(detected(vec![laptop.clone()]), Some("laptop"));
+        assert_eq!(detected(vec![desk.clone()]), Some("docked"));
+        assert_eq!(detected(vec![laptop.clone(), desk]), Some("docked"));
+        let tv = monitor("HDMI-A-1", "LG Electronics LG TV", "")?;
+        assert_eq!(detected(vec![laptop, tv]), None);
+        assert!(profiles.get("docked").is_some() && profiles.get("tv").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_switch_when_docking_and_undocking() -> HyprResult<()> {
+        let profiles = profiles();
+        let laptop = monitor("eDP-1", "BOE 0x0BCA", "")?;
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        let turned_off = Monitor {
+            disabled: true,
+            ..laptop.clone()
+        };
+        let detected = |monitors: &Monitors| profiles.detect(monitors).map(Profile::name);
+        let docked = vec![turned_off.clone(), desk];
+        assert_eq!(detected(&docked), Some("docked"));
+        // undocking leaves the laptop's monitor turned off
+        let undocked = vec![turned_off];
+        assert_eq!(detected(&undocked), Some("laptop"));
+        let rules = profiles
+            .detect(&undocked)
+            .map(|profile| profile.rules(&undocked));
+        assert_eq!(rules, Some(vec!["eDP-1,preferred,auto,1.5".to_string()]));
+        assert_eq!(detected(&vec![laptop]), Some("laptop"));
+        Ok(())
+    }
+
+    #[test]
+    fn monitors_found_by_serial_are_turned_off() -> HyprResult<()> {
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        let desk = Monitor {
+            disabled: true,
+            ..desk
+        };
+        let profile = Profile::new("laptop")
+            .monitor(MonitorSettings::new(Output::Serial("1234ABC".to_string())).disabled());
+        assert_eq!(profile.rules(&vec![desk]), ["DP-1,disable"]);
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_render_as_monitor_rules() -> HyprResult<()> {
+        let profiles = profiles();
+        let docked = profiles.get("docked");
+        assert_eq!(
+            docked.map(ToString::to_string),
+            Some(
+                "monitor=eDP-1,disable\n\
+                 monitor=desc:Dell Inc. DELL U2720Q,3840x2160@60,0x0,2,transform,1\n\
+                 workspace=1,monitor:desc:Dell Inc. DELL U2720Q\n\
+                 workspace=2,monitor:desc:Dell Inc. DELL U2720Q\n"
+                    .to_string()
+            )
+        );
+        let projector = Profile::new("presenting").monitor(
+            MonitorSettings::new(Output::Serial("1234ABC".to_string())).mode(Mode::HighRr),
+        );
+        assert!(projector.rules(&vec![]).is_empty());
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        assert_eq!(projector.rules(&vec![desk]), ["DP-1,highrr,auto,1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn workspaces_on_other_monitors_are_moved() -> HyprResult<()> {
+        let workspace = |id: u8, monitor: &str| {
+            format!(
+                r#"{{"id": {id}, "name": "{id}", "monitor": "{monitor}", "monitorID": 0,
+                    "windows": 1, "hasfullscreen": false, "lastwindow": "0x0",
+                    "lastwindowtitle": ""}}"#
+            )
+        };
+        let workspaces: Workspaces = serde_json::from_str(&format!(
+            "[{}, {}, {}]",
+            workspace(1, "DP-1"),
+            workspace(2, "eDP-1"),
+            workspace(3, "eDP-1"),
+        ))?;
+        let docked = profiles().profiles[1].clone();
+        let desk = monitor("DP-1", "Dell Inc. DELL U2720Q 1234ABC", "1234ABC")?;
+        let moves = docked.workspace_moves(&vec![desk], &workspaces);
+        assert!(matches!(
+            &moves[..],
+            [DispatchType::MoveWorkspaceToMonitor(
+                WorkspaceIdentifier::Id(2),
+                MonitorIdentifier::Name(name),
+            )] if name == "DP-1"
+        ));
+        Ok(())
+    }
+}
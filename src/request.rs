@@ -0,0 +1,81 @@
+//! # Request module
+//!
+//! This module holds the requests sent to the command socket, without doing any io.
+//! A request is the command to send and how its response is parsed, the blocking and async
+//! functions only differ in how they send it, so the two can't drift apart
+
+use crate::shared::*;
+use crate::transport::{Transport, UnixSocket};
+
+type Parser<T> = Box<dyn FnOnce(HyprResult<String>) -> T + Send>;
+
+/// This pub(crate) struct holds a command and how to parse the response to it
+pub(crate) struct Request<T> {
+    command: String,
+    parse: Parser<T>,
+}
+
+impl<T> Request<T> {
+    /// Creates a request, `parse` receives the response (or the socket error)
+    pub(crate) fn new(
+        command: impl Into<String>,
+        parse: impl FnOnce(HyprResult<String>) -> T + Send + 'static,
+    ) -> Self {
+        Request {
+            command: command.into(),
+            parse: Box::new(parse),
+        }
+    }
+
+    /// Parses a response to the request, without sending it
+    fn parse(self, response: HyprResult<String>) -> T {
+        (self.parse)(response)
+    }
+}
+
+impl<T> Request<HyprResult<T>> {
+    /// Sends the request and parses the response (blocking)
+    pub(crate) fn send_blocking(self) -> HyprResult<T> {
+        self.send_blocking_with(&mut UnixSocket::new()?, &mut vec![])
+    }
+
+    /// Sends the request with `transport` and parses the response, reading it into `buffer` (blocking)
+    pub(crate) fn send_blocking_with(
+        self,
+        transport: &mut impl Transport,
+        buffer: &mut Vec<u8>,
+    ) -> HyprResult<T> {
+        let response = transport
+            .send(self.command.as_bytes(), buffer)
+            .and_then(|()| decode_response(buffer));
+        self.parse(response)
+    }
+
+    /// Sends the request and parses the response (async)
+    #[cfg(feature = "async")]
+    pub(crate) async fn send(self) -> HyprResult<T> {
+        self.send_with(&mut UnixSocket::new()?, &mut vec![]).await
+    }
+
+    /// Sends the request with `transport` and parses the response, reading it into `buffer` (async)
+    #[cfg(feature = "async")]
+    pub(crate) async fn send_with(
+        self,
+        transport: &mut impl Transport,
+        buffer: &mut Vec<u8>,
+    ) -> HyprResult<T> {
+        let response = transport
+            .send_async(self.command.as_bytes(), buffer)
+            .await
+            .and_then(|()| decode_response(buffer));
+        self.parse(response)
+    }
+}
+
+impl Request<HyprResult<()>> {
+    /// Creates a request for a command Hyprland answers with `ok`,
+    /// any other response is returned as [`HyprError::Command`]
+    pub(crate) fn ok(command: impl Into<String>) -> Self {
+        Request::new(command, |output| check_ok(&output?))
+    }
+}
@@ -0,0 +1,523 @@
+//! # Rule module
+//!
+//! This module provides [`WindowRule`](crate::rule::WindowRule) and [`LayerRule`](crate::rule::LayerRule),
+//! builders for `windowrulev2` and `layerrule` rules that are added while Hyprland runs
+//! or written to the config
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::rule::{LayerRule, LayerRuleEffect, Length, WindowRule, WindowRuleEffect};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let rule = WindowRule::new()
+//!         .effect(WindowRuleEffect::Float)
+//!         .effect(WindowRuleEffect::Size(Length::Percent(50.0), Length::Percent(50.0)))
+//!         .class("pavucontrol");
+//!     // windowrulev2 = float, class:^(pavucontrol)$
+//!     // windowrulev2 = size 50% 50%, class:^(pavucontrol)$
+//!     print!("{}", rule.to_config()?);
+//!     rule.apply()?;
+//!
+//!     // layerrule = blur, ^(waybar)$
+//!     LayerRule::new().effect(LayerRuleEffect::Blur).namespace("waybar").apply()
+//! }
+//! ```
+
+use crate::data::{Keyword, OptionValue};
+use crate::dispatch::{Tag, WorkspaceIdentifierWithSpecial};
+use crate::shared::{HyprError, HyprResult, WorkspaceId};
+use std::fmt;
+
+/// This enum holds a size or position in a rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// In pixels
+    Pixels(i32),
+    /// In percent of the monitor
+    Percent(f32),
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Length::Pixels(pixels) => write!(f, "{pixels}"),
+            Length::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+/// This enum holds when a window stops the session from going idle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleInhibitMode {
+    /// Never, which overrides the rules before it
+    None,
+    /// Always, while the window is open
+    Always,
+    /// While the window is focused
+    Focus,
+    /// While the window is fullscreen
+    Fullscreen,
+}
+
+impl fmt::Display for IdleInhibitMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            IdleInhibitMode::None => "none",
+            IdleInhibitMode::Always => "always",
+            IdleInhibitMode::Focus => "focus",
+            IdleInhibitMode::Fullscreen => "fullscreen",
+        })
+    }
+}
+
+/// This enum holds what a window rule does to the windows it matches
+pub enum WindowRuleEffect {
+    /// Makes the window floating
+    Float,
+    /// Makes the window tiled
+    Tile,
+    /// Makes the window fullscreen
+    Fullscreen,
+    /// Maximizes the window
+    Maximize,
+    /// Pins the window (shows it on every workspace), only for floating windows
+    Pin,
+    /// Centers the window on the monitor, only for floating windows
+    Center,
+    /// Sets the size of the window (width and height), only for floating windows
+    Size(Length, Length),
+    /// Moves the window (x and y, relative to the monitor), only for floating windows
+    Move(Length, Length),
+    /// Sets the opacity of the window, from 0 to 1
+    Opacity(f32),
+    /// Opens the window on the workspace, `true` to not switch to it (silent)
+    Workspace(WorkspaceIdentifierWithSpecial, bool),
+    /// Stops the window from getting focus
+    NoFocus,
+    /// Disables the animations of the window
+    NoAnim,
+    /// Disables the blur of the window
+    NoBlur,
+    /// Disables the border of the window
+    NoBorder,
+    /// Disables the shadow of the window
+    NoShadow,
+    /// Disables dimming the window when it's inactive
+    NoDim,
+    /// Stops the window from swallowing the terminal it was started from
+    NoSwallow,
+    /// Sets the rounding of the window corners (in pixels)
+    Rounding(i32),
+    /// Sets the size of the window border (in pixels)
+    BorderSize(i32),
+    /// Applies a tag to the window
+    Tag(Tag),
+    /// Stops the session from going idle (so hypridle doesn't lock it) while the window is open
+    IdleInhibit(IdleInhibitMode),
+}
+
+impl fmt::Display for WindowRuleEffect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowRuleEffect::Float => f.write_str("float"),
+            WindowRuleEffect::Tile => f.write_str("tile"),
+            WindowRuleEffect::Fullscreen => f.write_str("fullscreen"),
+            WindowRuleEffect::Maximize => f.write_str("maximize"),
+            WindowRuleEffect::Pin => f.write_str("pin"),
+            WindowRuleEffect::Center => f.write_str("center"),
+            WindowRuleEffect::Size(width, height) => write!(f, "size {width} {height}"),
+            WindowRuleEffect::Move(x, y) => write!(f, "move {x} {y}"),
+            WindowRuleEffect::Opacity(opacity) => write!(f, "opacity {opacity}"),
+            WindowRuleEffect::Workspace(workspace, silent) => {
+                write!(f, "workspace {workspace}")?;
+                if *silent {
+                    f.write_str(" silent")?;
+                }
+                Ok(())
+            }
+            WindowRuleEffect::NoFocus => f.write_str("nofocus"),
+            WindowRuleEffect::NoAnim => f.write_str("noanim"),
+            WindowRuleEffect::NoBlur => f.write_str("noblur"),
+            WindowRuleEffect::NoBorder => f.write_str("noborder"),
+            WindowRuleEffect::NoShadow => f.write_str("noshadow"),
+            WindowRuleEffect::NoDim => f.write_str("nodim"),
+            WindowRuleEffect::NoSwallow => f.write_str("noswallow"),
+            WindowRuleEffect::Rounding(rounding) => write!(f, "rounding {rounding}"),
+            WindowRuleEffect::BorderSize(size) => write!(f, "bordersize {size}"),
+            WindowRuleEffect::Tag(tag) => match tag {
+                Tag::Set(tag) => write!(f, "tag +{tag}"),
+                Tag::Unset(tag) => write!(f, "tag -{tag}"),
+                Tag::Toggle(tag) => write!(f, "tag {tag}"),
+            },
+            WindowRuleEffect::IdleInhibit(mode) => write!(f, "idleinhibit {mode}"),
+        }
+    }
+}
+
+/// This struct holds a window rule (`windowrulev2`), its effects are applied to the windows
+/// that match every field of it
+///
+/// The methods that match text exactly (like [`class`](Self::class)) escape it,
+/// the `_matches` ones take a regular expression. Hyprland takes a single effect per rule,
+/// so a rule with multiple effects is written as one rule per effect
+#[derive(Default)]
+pub struct WindowRule {
+    effects: Vec<WindowRuleEffect>,
+    fields: Vec<String>,
+}
+
+impl WindowRule {
+    /// This method creates a rule without effects that matches nothing,
+    /// at least one effect and one field have to be added
+    pub fn new() -> Self {
+        WindowRule::default()
+    }
+
+    /// This method adds a effect
+    pub fn effect(mut self, effect: WindowRuleEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    fn field(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.fields.push(format!("{name}:{value}"));
+        self
+    }
+
+    /// This method matches windows with exactly the class
+    pub fn class(self, class: &str) -> Self {
+        self.field("class", exactly(class))
+    }
+
+    /// This method matches windows of which the class matches the regular expression
+    pub fn class_matches(self, regex: &str) -> Self {
+        self.field("class", regex)
+    }
+
+    /// This method matches windows with exactly the title
+    pub fn title(self, title: &str) -> Self {
+        self.field("title", exactly(title))
+    }
+
+    /// This method matches windows of which the title matches the regular expression
+    pub fn title_matches(self, regex: &str) -> Self {
+        self.field("title", regex)
+    }
+
+    /// This method matches windows that had exactly the class when they opened
+    pub fn initial_class(self, class: &str) -> Self {
+        self.field("initialClass", exactly(class))
+    }
+
+    /// This method matches windows of which the class they opened with matches the regular expression
+    pub fn initial_class_matches(self, regex: &str) -> Self {
+        self.field("initialClass", regex)
+    }
+
+    /// This method matches windows that had exactly the title when they opened
+    pub fn initial_title(self, title: &str) -> Self {
+        self.field("initialTitle", exactly(title))
+    }
+
+    /// This method matches windows of which the title they opened with matches the regular expression
+    pub fn initial_title_matches(self, regex: &str) -> Self {
+        self.field("initialTitle", regex)
+    }
+
+    /// This method matches windows with the tag
+    pub fn tag(self, tag: &str) -> Self {
+        self.field("tag", tag)
+    }
+
+    /// This method matches floating (`true`) or tiled (`false`) windows
+    pub fn floating(self, floating: bool) -> Self {
+        self.field("floating", u8::from(floating))
+    }
+
+    /// This method matches windows running under XWayland (`true`) or not (`false`)
+    pub fn xwayland(self, xwayland: bool) -> Self {
+        self.field("xwayland", u8::from(xwayland))
+    }
+
+    /// This method matches windows on the regular workspace with the id
+    pub fn on_workspace(self, id: WorkspaceId) -> Self {
+        self.field("workspace", id)
+    }
+
+    /// This method matches windows on the workspace with the name
+    pub fn on_workspace_named(self, name: &str) -> Self {
+        self.field("workspace", format_args!("name:{name}"))
+    }
+
+    /// This method returns the value of the `windowrulev2` keyword for every effect
+    /// (like `float, class:^(kitty)$`)
+    ///
+    /// Returns [`HyprError::InvalidRule`] if the rule has no effects or fields,
+    /// or if something in it can't be written in a rule (like a newline)
+    pub fn rules(&self) -> HyprResult<Vec<String>> {
+        rules(&self.effects, &self.fields)
+    }
+
+    /// This method returns the config lines that create the rule, one per effect
+    pub fn to_config(&self) -> HyprResult<String> {
+        to_config("windowrulev2", &self.rules()?)
+    }
+
+    /// This method adds the rule, until the config is reloaded (blocking)
+    pub fn apply(&self) -> HyprResult<()> {
+        Keyword::set_many(&keywords("windowrulev2", self.rules()?))?
+            .into_iter()
+            .collect()
+    }
+
+    /// This method adds the rule, until the config is reloaded (async)
+    #[cfg(feature = "async")]
+    pub async fn apply_async(&self) -> HyprResult<()> {
+        Keyword::set_many_async(&keywords("windowrulev2", self.rules()?))
+            .await?
+            .into_iter()
+            .collect()
+    }
+}
+
+/// This enum holds what a layer rule does to the layers (like bars and notifications) it matches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayerRuleEffect {
+    /// Blurs what's behind the layer
+    Blur,
+    /// Makes blur ignore the fully transparent parts of the layer
+    IgnoreZero,
+    /// Makes blur ignore the parts of the layer that are less opaque than the value, from 0 to 1
+    IgnoreAlpha(f32),
+    /// Disables the animations of the layer
+    NoAnim,
+    /// Dims everything behind the layer
+    DimAround,
+    /// Sets if the blur of the layer shows only the wallpaper (`true`) or the windows too
+    Xray(bool),
+}
+
+impl fmt::Display for LayerRuleEffect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayerRuleEffect::Blur => f.write_str("blur"),
+            LayerRuleEffect::IgnoreZero => f.write_str("ignorezero"),
+            LayerRuleEffect::IgnoreAlpha(alpha) => write!(f, "ignorealpha {alpha}"),
+            LayerRuleEffect::NoAnim => f.write_str("noanim"),
+            LayerRuleEffect::DimAround => f.write_str("dimaround"),
+            LayerRuleEffect::Xray(xray) => write!(f, "xray {}", u8::from(*xray)),
+        }
+    }
+}
+
+/// This struct holds a layer rule (`layerrule`), its effects are applied to the layers
+/// of which the namespace matches
+///
+/// Like [`WindowRule`], a rule with multiple effects is written as one rule per effect
+#[derive(Debug, Clone, Default)]
+pub struct LayerRule {
+    effects: Vec<LayerRuleEffect>,
+    namespace: Option<String>,
+}
+
+impl LayerRule {
+    /// This method creates a rule without effects that matches nothing,
+    /// at least one effect and a namespace have to be added
+    pub fn new() -> Self {
+        LayerRule::default()
+    }
+
+    /// This method adds a effect
+    pub fn effect(mut self, effect: LayerRuleEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// This method matches layers with exactly the namespace (like `waybar`),
+    /// replacing the namespace set before
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(exactly(namespace));
+        self
+    }
+
+    /// This method matches layers of which the namespace matches the regular expression,
+    /// replacing the namespace set before
+    pub fn namespace_matches(mut self, regex: &str) -> Self {
+        self.namespace = Some(regex.to_string());
+        self
+    }
+
+    /// This method returns the value of the `layerrule` keyword for every effect
+    /// (like `blur, ^(waybar)$`)
+    ///
+    /// Returns [`HyprError::InvalidRule`] if the rule has no effects or namespace,
+    /// or if something in it can't be written in a rule (like a newline)
+    pub fn rules(&self) -> HyprResult<Vec<String>> {
+        let namespace: Vec<String> = self.namespace.iter().cloned().collect();
+        rules(&self.effects, &namespace)
+    }
+
+    /// This method returns the config lines that create the rule, one per effect
+    pub fn to_config(&self) -> HyprResult<String> {
+        to_config("layerrule", &self.rules()?)
+    }
+
+    /// This method adds the rule, until the config is reloaded (blocking)
+    pub fn apply(&self) -> HyprResult<()> {
+        Keyword::set_many(&keywords("layerrule", self.rules()?))?
+            .into_iter()
+            .collect()
+    }
+
+    /// This method adds the rule, until the config is reloaded (async)
+    #[cfg(feature = "async")]
+    pub async fn apply_async(&self) -> HyprResult<()> {
+        Keyword::set_many_async(&keywords("layerrule", self.rules()?))
+            .await?
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Returns a regular expression that matches exactly the text
+fn exactly(text: &str) -> String {
+    let mut regex = String::from("^(");
+    for char in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(char) {
+            regex.push('\\');
+        }
+        regex.push(char);
+    }
+    regex.push_str(")$");
+    regex
+}
+
+/// Joins every effect with the fields, checking they can be written in a rule
+fn rules(effects: &[impl fmt::Display], fields: &[String]) -> HyprResult<Vec<String>> {
+    if effects.is_empty() {
+        return Err(HyprError::InvalidRule(
+            "the rule has no effects".to_string(),
+        ));
+    }
+    if fields.is_empty() {
+        return Err(HyprError::InvalidRule(
+            "the rule matches nothing".to_string(),
+        ));
+    }
+    let fields = fields.join(", ");
+    effects
+        .iter()
+        .map(|effect| {
+            let rule = format!("{effect}, {fields}");
+            match rule.contains(['\n', '\r']) {
+                true => Err(HyprError::InvalidRule(format!(
+                    "rules can't contain newlines: {rule:?}"
+                ))),
+                false => Ok(rule),
+            }
+        })
+        .collect()
+}
+
+fn to_config(keyword: &str, rules: &[String]) -> HyprResult<String> {
+    Ok(rules
+        .iter()
+        .map(|rule| format!("{keyword} = {rule}\n"))
+        .collect())
+}
+
+fn keywords(keyword: &str, rules: Vec<String>) -> Vec<(String, OptionValue)> {
+    rules
+        .into_iter()
+        .map(|rule| (keyword.to_string(), OptionValue::String(rule)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_rules_have_a_line_per_effect() -> HyprResult<()> {
+        let rule = WindowRule::new()
+            .effect(WindowRuleEffect::Float)
+            .effect(WindowRuleEffect::Size(
+                Length::Percent(50.0),
+                Length::Pixels(600),
+            ))
+            .class("pavucontrol")
+            .floating(false);
+        assert_eq!(
+            rule.to_config()?,
+            "windowrulev2 = float, class:^(pavucontrol)$, floating:0\n\
+             windowrulev2 = size 50% 600, class:^(pavucontrol)$, floating:0\n"
+        );
+        let rule = WindowRule::new()
+            .effect(WindowRuleEffect::Workspace(
+                WorkspaceIdentifierWithSpecial::Id(3),
+                true,
+            ))
+            .effect(WindowRuleEffect::Tag(Tag::Set("chat".to_string())))
+            .title_matches("^(Discord|Element)")
+            .on_workspace_named("coding");
+        assert_eq!(
+            rule.rules()?,
+            [
+                "workspace 3 silent, title:^(Discord|Element), workspace:name:coding",
+                "tag +chat, title:^(Discord|Element), workspace:name:coding",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exact_matches_are_escaped() -> HyprResult<()> {
+        let rule = WindowRule::new()
+            .effect(WindowRuleEffect::Opacity(0.9))
+            .title("nvim (~/src) [+]");
+        assert_eq!(
+            rule.rules()?,
+            [r"opacity 0.9, title:^(nvim \(~/src\) \[\+\])$"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_rules() {
+        for rule in [
+            WindowRule::new().class("kitty"),
+            WindowRule::new().effect(WindowRuleEffect::Float),
+            WindowRule::new()
+                .effect(WindowRuleEffect::Float)
+                .title("a\nb"),
+        ] {
+            assert!(matches!(rule.rules(), Err(HyprError::InvalidRule(_))));
+        }
+    }
+
+    #[test]
+    fn layer_rules_match_the_namespace() -> HyprResult<()> {
+        let rule = LayerRule::new()
+            .effect(LayerRuleEffect::Blur)
+            .effect(LayerRuleEffect::IgnoreAlpha(0.5))
+            .effect(LayerRuleEffect::Xray(false))
+            .namespace("waybar");
+        assert_eq!(
+            rule.to_config()?,
+            "layerrule = blur, ^(waybar)$\n\
+             layerrule = ignorealpha 0.5, ^(waybar)$\n\
+             layerrule = xray 0, ^(waybar)$\n"
+        );
+        let rule = LayerRule::new()
+            .effect(LayerRuleEffect::NoAnim)
+            .namespace("waybar")
+            .namespace_matches("^(rofi|wofi)$");
+        assert_eq!(rule.rules()?, ["noanim, ^(rofi|wofi)$"]);
+        assert!(matches!(
+            LayerRule::new().effect(LayerRuleEffect::Blur).rules(),
+            Err(HyprError::InvalidRule(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+//! # Runtime module
+//!
+//! This module holds what the async functions and listeners need from a async runtime,
+//! which is the unix sockets, timers and reading the event socket line by line.
+//! It's tokio with the `tokio` feature, and async-io with the `async-io` feature
+//! (which works with any executor), tokio is used if both are enabled
+
+use std::future::Future;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// This pub(crate) type is a unix socket of the runtime
+#[cfg(feature = "tokio")]
+pub(crate) type UnixStream = tokio::net::UnixStream;
+
+/// This pub(crate) type is a unix socket of the runtime
+#[cfg(not(feature = "tokio"))]
+pub(crate) type UnixStream = async_io::Async<std::os::unix::net::UnixStream>;
+
+/// This pub(crate) function connects to the unix socket at `path`
+pub(crate) async fn connect(path: &str) -> io::Result<UnixStream> {
+    UnixStream::connect(path).await
+}
+
+/// This pub(crate) function writes all of `bytes` to the socket
+pub(crate) async fn write_all(stream: &mut UnixStream, bytes: &[u8]) -> io::Result<()> {
+    #[cfg(not(feature = "tokio"))]
+    use futures_lite::AsyncWriteExt;
+    #[cfg(feature = "tokio")]
+    use tokio::io::AsyncWriteExt;
+    stream.write_all(bytes).await
+}
+
+/// This pub(crate) function reads the socket until it's closed, returning how many bytes were read
+pub(crate) async fn read_to_end(
+    stream: &mut UnixStream,
+    buffer: &mut Vec<u8>,
+) -> io::Result<usize> {
+    #[cfg(not(feature = "tokio"))]
+    use futures_lite::AsyncReadExt;
+    #[cfg(feature = "tokio")]
+    use tokio::io::AsyncReadExt;
+    stream.read_to_end(buffer).await
+}
+
+/// This pub(crate) function waits for `duration`
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(not(feature = "tokio"))]
+    async_io::Timer::after(duration).await;
+}
+
+/// This pub(crate) function waits until `deadline`
+pub(crate) async fn sleep_until(deadline: Instant) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep_until(deadline.into()).await;
+    #[cfg(not(feature = "tokio"))]
+    async_io::Timer::at(deadline).await;
+}
+
+/// This pub(crate) function runs `future` for up to `duration`, `None` if it took longer
+pub(crate) async fn timeout<T>(duration: Duration, future: impl Future<Output = T>) -> Option<T> {
+    #[cfg(feature = "tokio")]
+    {
+        tokio::time::timeout(duration, future).await.ok()
+    }
+    #[cfg(not(feature = "tokio"))]
+    {
+        let elapsed = async {
+            sleep(duration).await;
+            None
+        };
+        futures_lite::future::or(async { Some(future.await) }, elapsed).await
+    }
+}
+
+/// This pub(crate) struct reads the lines of the event socket
+///
+/// Waiting for the next line can be cancelled (like when the listener is stopped),
+/// without losing the part of the line that was read already
+#[cfg(feature = "tokio")]
+pub(crate) struct Lines(tokio::io::Lines<tokio::io::BufReader<UnixStream>>);
+
+/// This pub(crate) struct reads the lines of the event socket
+///
+/// Waiting for the next line can be cancelled (like when the listener is stopped),
+/// without losing the part of the line that was read already
+#[cfg(not(feature = "tokio"))]
+pub(crate) struct Lines {
+    reader: futures_lite::io::BufReader<UnixStream>,
+    /// What was read of the line that isn't finished yet
+    line: Vec<u8>,
+}
+
+impl Lines {
+    /// Creates a reader for the lines of the socket
+    pub(crate) fn new(stream: UnixStream) -> Self {
+        #[cfg(feature = "tokio")]
+        {
+            use tokio::io::AsyncBufReadExt;
+            Lines(tokio::io::BufReader::new(stream).lines())
+        }
+        #[cfg(not(feature = "tokio"))]
+        {
+            Lines {
+                reader: futures_lite::io::BufReader::new(stream),
+                line: vec![],
+            }
+        }
+    }
+
+    /// Returns the next line without the line ending, `None` once the socket is closed
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn next_line(&mut self) -> io::Result<Option<String>> {
+        self.0.next_line().await
+    }
+
+    /// Returns the next line without the line ending, `None` once the socket is closed
+    #[cfg(not(feature = "tokio"))]
+    pub(crate) async fn next_line(&mut self) -> io::Result<Option<String>> {
+        use futures_lite::AsyncBufRead;
+        use std::pin::Pin;
+        use std::task::{ready, Poll};
+        std::future::poll_fn(|cx| loop {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                // a unfinished line at the end is still a line
+                return Poll::Ready(match self.line.is_empty() {
+                    true => Ok(None),
+                    false => take_line(&mut self.line).map(Some),
+                });
+            }
+            match available.iter().position(|byte| *byte == b'\n') {
+                Some(end) => {
+                    self.line.extend_from_slice(&available[..end]);
+                    Pin::new(&mut self.reader).consume(end + 1);
+                    return Poll::Ready(take_line(&mut self.line).map(Some));
+                }
+                None => {
+                    let read = available.len();
+                    self.line.extend_from_slice(available);
+                    Pin::new(&mut self.reader).consume(read);
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// Returns the line that was read, without a `\r` at the end (like tokio's lines)
+#[cfg(not(feature = "tokio"))]
+fn take_line(line: &mut Vec<u8>) -> io::Result<String> {
+    let mut line = std::mem::take(line);
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
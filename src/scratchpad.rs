@@ -0,0 +1,142 @@
+//! # Scratchpad module
+//!
+//! This module provides [`Scratchpad`](crate::scratchpad::Scratchpad), a program kept in a named
+//! special workspace, that's shown and hidden with a single keybind
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::scratchpad::Scratchpad;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     // bound to a key with `bind = SUPER, grave, exec, my-scratchpad`
+//!     Scratchpad::new("term", "kitty --class scratch-term", "scratch-term").toggle()
+//! }
+//! ```
+
+use crate::data::Clients;
+use crate::dispatch::{DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial};
+use crate::shared::HyprResult;
+
+/// This struct is a program kept in the special workspace `special:<name>`
+///
+/// Its window is found by its class, so the command should start it with a class
+/// no other window has (most terminals take a `--class` argument)
+#[derive(Debug, Clone)]
+pub struct Scratchpad {
+    name: String,
+    command: String,
+    class: String,
+}
+
+impl Scratchpad {
+    /// This method creates a scratchpad, `command` is what starts it
+    /// and `class` is the class of its window
+    pub fn new(
+        name: impl Into<String>,
+        command: impl Into<String>,
+        class: impl Into<String>,
+    ) -> Self {
+        Scratchpad {
+            name: name.into(),
+            command: command.into(),
+            class: class.into(),
+        }
+    }
+
+    /// This method returns the name of the special workspace
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// This method shows the scratchpad on the focused monitor, or hides it if it's shown (blocking)
+    ///
+    /// If the window isn't open the command is run, and the window opens in the shown scratchpad.
+    /// If the window was moved to another workspace, it's moved back first
+    pub fn toggle(&self) -> HyprResult<()> {
+        use crate::data::blocking::get_clients;
+        use crate::dispatch::dispatch_batch_blocking;
+        dispatch_batch_blocking(self.toggle_dispatches(&get_clients()?))
+    }
+
+    /// This method shows the scratchpad on the focused monitor, or hides it if it's shown (async)
+    ///
+    /// See [`toggle`](Self::toggle)
+    #[cfg(feature = "async")]
+    pub async fn toggle_async(&self) -> HyprResult<()> {
+        use crate::data::asynchronous::get_clients;
+        use crate::dispatch::dispatch_batch;
+        dispatch_batch(self.toggle_dispatches(&get_clients().await?)).await
+    }
+
+    fn toggle_dispatches(&self, clients: &Clients) -> Vec<DispatchType> {
+        let workspace = WorkspaceIdentifierWithSpecial::NamedSpecial(self.name.clone());
+        let toggle = DispatchType::ToggleNamedSpecialWorkspace(self.name.clone());
+        match clients.iter().find(|client| client.class == self.class) {
+            None => vec![
+                // the workspace is shown after, as a window opening there doesn't show it
+                DispatchType::Exec(format!("[workspace {workspace} silent] {}", self.command)),
+                toggle,
+            ],
+            Some(client) if client.workspace.name != workspace.to_string() => vec![
+                DispatchType::MoveWindowToWorkspaceSilent(
+                    workspace,
+                    WindowIdentifier::Address(client.address.clone()),
+                ),
+                toggle,
+            ],
+            Some(_) => vec![toggle],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the dispatchers toggling the scratchpad sends, with kitty on the workspace
+    fn dispatches(scratchpad: &Scratchpad, workspace: &str) -> HyprResult<Vec<String>> {
+        let clients: Clients = serde_json::from_str(&format!(
+            r#"[{{"address": "0x5581b1f5c9a0", "at": [0, 0], "size": [2560, 1440],
+                 "workspace": {{"id": -98, "name": "{workspace}"}}, "floating": true,
+                 "monitor": 0, "class": "scratch-term", "title": "kitty", "pid": 1234,
+                 "xwayland": false}}]"#
+        ))?;
+        let dispatches = scratchpad.toggle_dispatches(&clients);
+        Ok(dispatches.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn the_program_is_started_in_the_workspace() {
+        let scratchpad = Scratchpad::new("term", "kitty --class scratch-term", "scratch-term");
+        assert_eq!(scratchpad.name(), "term");
+        let dispatches = scratchpad.toggle_dispatches(&vec![]);
+        assert_eq!(
+            dispatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "dispatch exec [workspace special:term silent] kitty --class scratch-term",
+                "dispatch togglespecialworkspace term",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_window_is_moved_back_to_the_workspace() -> HyprResult<()> {
+        let scratchpad = Scratchpad::new("term", "kitty --class scratch-term", "scratch-term");
+        assert_eq!(
+            dispatches(&scratchpad, "special:term")?,
+            ["dispatch togglespecialworkspace term"]
+        );
+        assert_eq!(
+            dispatches(&scratchpad, "special:music")?,
+            [
+                "dispatch movetoworkspacesilent special:term,address:0x5581b1f5c9a0",
+                "dispatch togglespecialworkspace term",
+            ]
+        );
+        Ok(())
+    }
+}
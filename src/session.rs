@@ -0,0 +1,138 @@
+//! # Session module
+//!
+//! This module provides [`Locker`](crate::session::Locker), for locking the session with hyprlock
+//! and checking if it's locked, and [`inhibit_idle_blocking`](crate::session::inhibit_idle_blocking),
+//! for keeping hypridle from locking it while a program runs
+//!
+//! hypridle and hyprlock don't have sockets, so the session is locked by running a command
+//! in Hyprland, and it's locked while the locker's process runs.
+//! Idle is inhibited with a `idleinhibit` window rule, which hypridle respects
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::rule::IdleInhibitMode;
+//! use hyprland::session::{inhibit_idle_blocking, Locker};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     // don't lock while a video plays fullscreen
+//!     inhibit_idle_blocking("mpv", IdleInhibitMode::Fullscreen)?;
+//!
+//!     // lock through logind, so hypridle's `lock_cmd` and `before_sleep_cmd` run
+//!     let locker = Locker::loginctl();
+//!     if !locker.is_locked()? {
+//!         locker.lock()?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::dispatch::DispatchType;
+use crate::rule::{IdleInhibitMode, WindowRule, WindowRuleEffect};
+use crate::shared::HyprResult;
+use std::io;
+
+/// This struct locks the session, with a command that starts a locker (like hyprlock)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locker {
+    command: String,
+    process: String,
+}
+
+impl Locker {
+    /// This method creates a locker that runs `command`, and of which the process is named `process`
+    ///
+    /// Process names are cut off after 15 characters, like in `/proc/<pid>/comm`
+    pub fn new(command: impl Into<String>, process: impl Into<String>) -> Self {
+        Locker {
+            command: command.into(),
+            process: process.into(),
+        }
+    }
+
+    /// This method creates a locker that runs hyprlock
+    pub fn hyprlock() -> Self {
+        Locker::new("hyprlock", "hyprlock")
+    }
+
+    /// This method creates a locker that asks logind to lock the session (`loginctl lock-session`),
+    /// which makes hypridle run its `lock_cmd`, expected to start hyprlock
+    pub fn loginctl() -> Self {
+        Locker::new("loginctl lock-session", "hyprlock")
+    }
+
+    /// This method returns if the session is locked, by checking if the locker's process runs
+    pub fn is_locked(&self) -> HyprResult<bool> {
+        let name: String = self.process.chars().take(15).collect();
+        for entry in std::fs::read_dir("/proc")? {
+            let entry = entry?;
+            let is_process = entry.file_name().to_string_lossy().parse::<u32>().is_ok();
+            if !is_process {
+                continue;
+            }
+            // processes that exited since /proc was read are skipped
+            match std::fs::read_to_string(entry.path().join("comm")) {
+                Ok(comm) if comm.trim_end() == name => return Ok(true),
+                Ok(_) => {}
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied
+                    ) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(false)
+    }
+
+    /// This method locks the session, unless it's locked (blocking)
+    ///
+    /// Returns `false` if it was already locked
+    pub fn lock(&self) -> HyprResult<bool> {
+        if self.is_locked()? {
+            return Ok(false);
+        }
+        crate::dispatch::dispatch_blocking(self.lock_dispatch())?;
+        Ok(true)
+    }
+
+    /// This method locks the session, unless it's locked (async)
+    ///
+    /// Returns `false` if it was already locked
+    #[cfg(feature = "async")]
+    pub async fn lock_async(&self) -> HyprResult<bool> {
+        if self.is_locked()? {
+            return Ok(false);
+        }
+        crate::dispatch::dispatch(self.lock_dispatch()).await?;
+        Ok(true)
+    }
+
+    /// Returns the dispatch that runs the command, as a child of Hyprland so it has its environment
+    fn lock_dispatch(&self) -> DispatchType {
+        DispatchType::Exec(self.command.clone())
+    }
+}
+
+/// This function keeps the session from going idle while a window with exactly the class is open,
+/// focused or fullscreen (blocking)
+///
+/// It adds a window rule, which lasts until the config is reloaded
+pub fn inhibit_idle_blocking(class: &str, mode: IdleInhibitMode) -> HyprResult<()> {
+    inhibit_rule(class, mode).apply()
+}
+
+/// This function keeps the session from going idle while a window with exactly the class is open,
+/// focused or fullscreen (async)
+///
+/// It adds a window rule, which lasts until the config is reloaded
+#[cfg(feature = "async")]
+pub async fn inhibit_idle(class: &str, mode: IdleInhibitMode) -> HyprResult<()> {
+    inhibit_rule(class, mode).apply_async().await
+}
+
+fn inhibit_rule(class: &str, mode: IdleInhibitMode) -> WindowRule {
+    WindowRule::new()
+        .effect(WindowRuleEffect::IdleInhibit(mode))
+        .class(class)
+}
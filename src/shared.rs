@@ -2,17 +2,211 @@
 //!
 //! This module provides shared private and public functions, structs, enum, and types
 use serde::{Deserialize, Deserializer, Serialize};
-use std::env::{var, VarError};
+use std::env::{var, var_os, VarError};
+use std::path::PathBuf;
+use std::time::Duration;
 use std::{fmt, io};
 
+/// This enum holds the errors that can occur when talking to Hyprland
+#[derive(Debug, thiserror::Error)]
+pub enum HyprError {
+    /// Hyprland's socket couldn't be found, usually because Hyprland isn't running
+    #[error("couldn't find Hyprland's socket ({0}), is Hyprland running?")]
+    SocketNotFound(String),
+    /// A error occured while reading from or writing to a socket
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    /// The response couldn't be deserialized
+    #[error("couldn't deserialize the response: {0}")]
+    Serde(#[from] serde_json::Error),
+    /// The response isn't valid utf8
+    #[error("the response isn't valid utf8: {0}")]
+    NotUtf8(#[from] std::string::FromUtf8Error),
+    /// A address isn't valid hex
+    #[error("the address isn't valid hex: {0}")]
+    InvalidAddress(#[from] hex::FromHexError),
+    /// Hyprland returned a workspace id this crate doesn't know about
+    #[error("unrecognised workspace id: {0}")]
+    InvalidWorkspaceId(WorkspaceId),
+    /// Hyprland responded with something that wasn't expected
+    #[error("Hyprland returned a unexpected response: {0}")]
+    UnexpectedResponse(String),
+    /// Hyprland couldn't run a command (or dispatcher)
+    #[error("Hyprland couldn't run the command: {0}")]
+    Command(CommandError),
+    /// Hyprland didn't accept or answer a command in time
+    #[error("Hyprland didn't respond within {0:?}")]
+    Timeout(Duration),
+    /// The deadline of the command passed before it finished
+    #[error("the deadline of the command passed")]
+    DeadlineExceeded,
+    /// The command was cancelled with a [`CancellationToken`]
+    #[error("the command was cancelled")]
+    Cancelled,
+    /// A rule can't be written the way it was built
+    #[error("invalid rule: {0}")]
+    InvalidRule(String),
+    /// A config can't be written the way it was built
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+    /// A workspace doesn't exist in the way workspaces are laid out
+    #[error("invalid workspace: {0}")]
+    InvalidWorkspace(String),
+    /// A argument of a command can't be sent the way it is
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    /// A regular expression isn't valid
+    #[cfg(feature = "regex")]
+    #[error("invalid regular expression: {0}")]
+    Regex(#[from] regex::Error),
+    /// hyprpaper couldn't run a command
+    #[cfg(feature = "hyprpaper")]
+    #[error("hyprpaper couldn't run the command: {0}")]
+    Hyprpaper(crate::hyprpaper::HyprpaperError),
+}
+
+/// This struct holds the error message Hyprland responded to a command with,
+/// and what kind of error it is
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct CommandError {
+    kind: CommandErrorKind,
+    message: String,
+}
+
+/// This enum holds the kinds of errors Hyprland responds to commands with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandErrorKind {
+    /// The dispatcher doesn't exist
+    InvalidDispatcher,
+    /// The command doesn't exist
+    UnknownRequest,
+    /// The config option (keyword) doesn't exist
+    UnknownOption,
+    /// No window matched the window identifier
+    WindowNotFound,
+    /// No workspace matched the workspace identifier
+    WorkspaceNotFound,
+    /// No monitor matched the monitor identifier
+    MonitorNotFound,
+    /// An argument is missing or invalid
+    InvalidArgument,
+    /// An error this crate doesn't know about, see the message
+    Other,
+}
+
+impl CommandError {
+    /// This method creates a error from the message Hyprland responded with,
+    /// the kind is picked from the message
+    ///
+    /// ```rust
+    /// use hyprland::shared::{CommandError, CommandErrorKind};
+    /// let error = CommandError::new("Invalid dispatcher");
+    /// assert_eq!(error.kind(), CommandErrorKind::InvalidDispatcher);
+    /// ```
+    pub fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        CommandError {
+            kind: CommandErrorKind::from_message(&message),
+            message,
+        }
+    }
+
+    /// This method returns the kind of the error
+    pub fn kind(&self) -> CommandErrorKind {
+        self.kind
+    }
+
+    /// This method returns the message Hyprland responded with
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl CommandErrorKind {
+    fn from_message(message: &str) -> Self {
+        let message = message.trim().to_lowercase();
+        // dispatchers start their errors with their name, like `focusWindow: no window`
+        let reason = match message.split_once(": ") {
+            Some((name, reason)) if !name.contains(' ') => reason,
+            _ => message.as_str(),
+        };
+        let not_found = |item: &str| {
+            [
+                format!("{item} not found"),
+                format!("no such {item}"),
+                format!("no {item} found"),
+                format!("{item} doesn't exist"),
+                format!("{item} does not exist"),
+            ]
+            .iter()
+            .any(|missing| reason.contains(missing.as_str()))
+                || reason == format!("no {item}")
+        };
+        let words: Vec<&str> = reason
+            .split(|char: char| !char.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        if reason.starts_with("invalid dispatcher") {
+            CommandErrorKind::InvalidDispatcher
+        } else if reason.starts_with("unknown request") {
+            CommandErrorKind::UnknownRequest
+        } else if not_found("option")
+            || not_found("keyword")
+            || (reason.starts_with("config option") && reason.contains("does not exist"))
+        {
+            CommandErrorKind::UnknownOption
+        } else if not_found("window") {
+            CommandErrorKind::WindowNotFound
+        } else if not_found("workspace") {
+            CommandErrorKind::WorkspaceNotFound
+        } else if not_found("monitor") {
+            CommandErrorKind::MonitorNotFound
+        } else if words.first() == Some(&"invalid")
+            || words.iter().any(|word| {
+                matches!(
+                    *word,
+                    "arg" | "args" | "argument" | "arguments" | "param" | "params"
+                )
+            })
+        {
+            CommandErrorKind::InvalidArgument
+        } else {
+            CommandErrorKind::Other
+        }
+    }
+}
+
+impl From<HyprError> for io::Error {
+    fn from(error: HyprError) -> Self {
+        match error {
+            HyprError::Io(error) => error,
+            HyprError::SocketNotFound(_) => io::Error::new(io::ErrorKind::NotFound, error),
+            HyprError::Timeout(_) => io::Error::new(io::ErrorKind::TimedOut, error),
+            HyprError::DeadlineExceeded => io::Error::new(io::ErrorKind::TimedOut, error),
+            HyprError::Cancelled => io::Error::new(io::ErrorKind::Interrupted, error),
+            HyprError::InvalidRule(_) => io::Error::new(io::ErrorKind::InvalidInput, error),
+            HyprError::InvalidConfig(_) => io::Error::new(io::ErrorKind::InvalidInput, error),
+            HyprError::InvalidWorkspace(_) => io::Error::new(io::ErrorKind::InvalidInput, error),
+            HyprError::InvalidArgument(_) => io::Error::new(io::ErrorKind::InvalidInput, error),
+            error => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// This type is the result of everything that talks to Hyprland
+pub type HyprResult<T> = Result<T, HyprError>;
+
 /// The address struct holds a address as a tuple with a single value
 /// and has methods to reveal the address in different data formats
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
 pub struct Address(String);
 
 /// This type provides the id used to identify workspaces
 /// > its a type because it might change at some point
-pub type WorkspaceId = u8;
+///
+/// Named and special workspaces have negative ids
+pub type WorkspaceId = i32;
 
 /// This enum holds workspace data
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -25,17 +219,34 @@ pub enum WorkspaceType {
     ),
     /// The special workspace
     Special,
+    /// A named workspace
+    Named(
+        /// The workspace name
+        String,
+    ),
+}
+
+impl WorkspaceType {
+    /// Returns the workspace for the id and name Hyprland gives it,
+    /// workspaces with a negative id that isn't special are named
+    pub(crate) fn from_id_and_name(id: WorkspaceId, name: &str) -> Self {
+        match id {
+            _ if name == "special" || name.starts_with("special:") => WorkspaceType::Special,
+            0.. => WorkspaceType::Regular(id),
+            _ => WorkspaceType::Named(name.to_string()),
+        }
+    }
 }
 
-impl From<i8> for WorkspaceType {
-    fn from(int: i8) -> Self {
+impl TryFrom<WorkspaceId> for WorkspaceType {
+    type Error = HyprError;
+
+    /// Named workspaces can't be told apart by their id, use their name for them
+    fn try_from(int: WorkspaceId) -> HyprResult<Self> {
         match int {
-            -99 => WorkspaceType::Special,
-            0.. => WorkspaceType::Regular(match int.try_into() {
-                Ok(num) => num,
-                Err(e) => panic!("Issue with parsing id (i8) as u8: {e}"),
-            }),
-            _ => panic!("Unrecognised id"),
+            -99 => Ok(WorkspaceType::Special),
+            0.. => Ok(WorkspaceType::Regular(int)),
+            _ => Err(HyprError::InvalidWorkspaceId(int)),
         }
     }
 }
@@ -47,69 +258,938 @@ impl fmt::Display for Address {
 }
 
 impl Address {
+    /// This method creates a new address from a string,
+    /// adding the `0x` prefix used by hyprctl if it's missing
+    pub fn new<T: ToString>(string: T) -> Self {
+        let string = string.to_string();
+        if string.starts_with("0x") {
+            Address(string)
+        } else {
+            Address(format!("0x{string}"))
+        }
+    }
     /// This method returns a vector of bytes
-    pub fn as_vec(self) -> Vec<u8> {
+    pub fn as_vec(self) -> HyprResult<Vec<u8>> {
         let Address(value) = self;
-        match hex::decode(value.trim_start_matches("0x")) {
-            Ok(value) => value,
-            Err(error) => panic!("A error has occured while parsing string as hex: {}", error),
+        Ok(hex::decode(value.trim_start_matches("0x"))?)
+    }
+}
+
+/// This struct holds a RGBA color
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    /// The red channel
+    pub red: u8,
+    /// The green channel
+    pub green: u8,
+    /// The blue channel
+    pub blue: u8,
+    /// The alpha channel
+    pub alpha: u8,
+}
+
+impl Color {
+    /// This method creates a new color from its channels
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// This method creates a new color from a integer in the `0xAARRGGBB` format,
+    /// which is how Hyprland stores colors
+    pub fn from_argb(argb: u32) -> Self {
+        let [alpha, red, green, blue] = argb.to_be_bytes();
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rgba({:02x}{:02x}{:02x}{:02x})",
+            self.red, self.green, self.blue, self.alpha
+        )
+    }
+}
+
+/// The capacity the buffer responses are read into starts with
+const RESPONSE_BUFFER_SIZE: usize = 8192;
+
+/// How long to wait for Hyprland to accept or answer a command, unless configured otherwise
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// This struct configures retrying commands that couldn't be delivered,
+/// like in the moment Hyprland is restarting
+///
+/// Only failing to connect is retried (the socket missing or refusing connections),
+/// as a command that reached Hyprland might have been ran already.
+/// After every failed attempt the delay doubles, up to `max_delay`
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    /// How many attempts to make in total
+    pub max_attempts: u32,
+    /// The delay before the second attempt
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts
+    pub max_delay: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(1),
         }
     }
 }
 
-/// This pub(crate) function is used to write a value to a socket and to get the response
-pub(crate) async fn write_to_socket(path: String, content: &[u8]) -> io::Result<String> {
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
-    use tokio::net::UnixStream;
-    let mut stream = UnixStream::connect(path).await?;
+impl Retry {
+    /// Returns how long to wait before the next attempt after `attempts` failed ones with `error`,
+    /// or `None` if it shouldn't be retried
+    fn delay(&self, attempts: u32, error: &HyprError) -> Option<Duration> {
+        let transient = match error {
+            HyprError::SocketNotFound(_) => true,
+            HyprError::Io(error) => error.kind() == io::ErrorKind::WouldBlock,
+            _ => false,
+        };
+        if !transient || attempts >= self.max_attempts {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        Some(
+            self.initial_delay
+                .saturating_mul(factor)
+                .min(self.max_delay),
+        )
+    }
+}
+
+/// This struct cancels the commands sent by the connections it's given to,
+/// commands that are running return [`HyprError::Cancelled`] right away,
+/// and the ones sent after it's cancelled aren't sent at all
+///
+/// It's cheap to clone, and every clone cancels the same commands
+///
+/// ```rust, no_run
+/// # async fn function() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::connection::HyprlandConnection;
+/// use hyprland::shared::CancellationToken;
+/// let token = CancellationToken::new();
+/// let mut hyprland = HyprlandConnection::new()?;
+/// hyprland.set_cancellation(Some(token.clone()));
+/// // on shutdown, from anywhere
+/// token.cancel();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: std::sync::Arc<Cancellation>,
+}
 
-    stream.write_all(content).await?;
-    let mut response = [0; 8192];
-    let num_read = stream.read(&mut response).await?;
-    let response = &response[..num_read];
-    Ok(match String::from_utf8(response.to_vec()) {
-        Ok(str) => str,
-        Err(error) => panic!("an error has occured while parsing bytes as utf8: {error:#?}"),
-    })
+#[derive(Debug, Default)]
+struct Cancellation {
+    cancelled: std::sync::atomic::AtomicBool,
+    /// The commands that are running
+    wakers: Wakers,
 }
 
-/// This pub(crate) function is used to write a value to a socket and to get the response
-pub(crate) fn write_to_socket_sync(path: String, content: &[u8]) -> io::Result<String> {
+impl CancellationToken {
+    /// This method creates a token that isn't cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This method cancels the commands, a cancelled token can't be reset
+    pub fn cancel(&self) {
+        use std::sync::atomic::Ordering;
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.wakers.wake_all();
+    }
+
+    /// This method returns `true` if the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner
+            .cancelled
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Runs `future`, unless the token is cancelled first
+    #[cfg(feature = "async")]
+    async fn run<T>(
+        &self,
+        future: impl std::future::Future<Output = HyprResult<T>>,
+    ) -> HyprResult<T> {
+        use std::future::Future;
+        use std::task::Poll;
+        let mut future = std::pin::pin!(future);
+        let mut cancelled = std::pin::pin!(self.inner.wakers.wait_until(|| self.is_cancelled()));
+        std::future::poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(HyprError::Cancelled));
+            }
+            future.as_mut().poll(cx)
+        })
+        .await
+    }
+}
+
+/// This pub(crate) struct holds how commands are sent
+#[derive(Debug, Clone)]
+pub(crate) struct SocketOptions {
+    /// How long writing and reading may take, `None` to wait forever
+    pub timeout: Option<Duration>,
+    /// How to retry commands that couldn't be delivered, `None` to not retry
+    pub retry: Option<Retry>,
+    /// When every command has to be finished by, including its retries
+    pub deadline: Option<std::time::Instant>,
+    /// Cancels the commands
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        SocketOptions {
+            timeout: Some(DEFAULT_TIMEOUT),
+            retry: None,
+            deadline: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl SocketOptions {
+    /// Returns how long the next attempt may take, which is the timeout shortened to the deadline,
+    /// or a error if the deadline passed or the command was cancelled
+    fn attempt_timeout(&self) -> HyprResult<Option<Duration>> {
+        if let Some(cancellation) = &self.cancellation {
+            if cancellation.is_cancelled() {
+                return Err(HyprError::Cancelled);
+            }
+        }
+        match self.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+                if remaining.is_zero() {
+                    return Err(HyprError::DeadlineExceeded);
+                }
+                Ok(Some(
+                    self.timeout
+                        .map_or(remaining, |timeout| timeout.min(remaining)),
+                ))
+            }
+            None => Ok(self.timeout),
+        }
+    }
+
+    /// Turns a attempt that timed out because it was shortened to the deadline
+    /// into [`HyprError::DeadlineExceeded`]
+    fn attempt_error(&self, error: HyprError) -> HyprError {
+        let passed = self
+            .deadline
+            .is_some_and(|deadline| deadline <= std::time::Instant::now());
+        match error {
+            HyprError::Timeout(_) if passed => HyprError::DeadlineExceeded,
+            error => error,
+        }
+    }
+}
+
+/// This pub(crate) function is used to write a value to a socket and to read the response
+/// into `buffer`
+///
+/// The whole exchange has to finish within the timeout (if set)
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) async fn write_to_socket(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    options: &SocketOptions,
+) -> HyprResult<()> {
+    match &options.cancellation {
+        Some(cancellation) => {
+            cancellation
+                .run(write_to_socket_attempts(path, content, buffer, options))
+                .await
+        }
+        None => write_to_socket_attempts(path, content, buffer, options).await,
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+async fn write_to_socket_attempts(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    options: &SocketOptions,
+) -> HyprResult<()> {
+    let attempts = async {
+        log!(
+            debug,
+            "sending {:?} to {path}",
+            String::from_utf8_lossy(content)
+        );
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let timeout = options.attempt_timeout()?;
+            let error = match exchange(path, content, buffer, timeout).await {
+                Ok(()) => return Ok(()),
+                Err(error) => options.attempt_error(error),
+            };
+            match options
+                .retry
+                .and_then(|retry| retry.delay(attempts, &error))
+            {
+                Some(delay) => {
+                    log!(debug, "retrying in {delay:?} after: {error}");
+                    crate::runtime::sleep(delay).await
+                }
+                None => return Err(error),
+            }
+        }
+    };
+    #[cfg(feature = "tracing")]
+    let attempts = tracing::Instrument::instrument(attempts, command_span(path, content));
+    attempts.await
+}
+
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+async fn exchange(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    timeout: Option<Duration>,
+) -> HyprResult<()> {
+    let exchange = async {
+        let connect = crate::runtime::connect(path);
+        #[cfg(feature = "tracing")]
+        let connect = tracing::Instrument::instrument(connect, tracing::trace_span!("connect"));
+        let mut stream = connect.await.map_err(|error| connect_error(path, error))?;
+        log!(trace, "connected to {path}");
+
+        crate::runtime::write_all(&mut stream, content).await?;
+        read_response(&mut stream, buffer).await
+    };
+    match timeout {
+        Some(timeout) => match crate::runtime::timeout(timeout, exchange).await {
+            Some(response) => response,
+            None => {
+                log!(debug, "no response from {path} within {timeout:?}");
+                Err(HyprError::Timeout(timeout))
+            }
+        },
+        None => exchange.await,
+    }
+}
+
+/// This pub(crate) function is used to write a value to a socket and to read the response
+/// into `buffer`
+///
+/// Without tokio or async-io the socket is used from its own thread, so this works with any executor.
+/// If the command is cancelled the thread finishes on its own, within the timeout
+#[cfg(all(feature = "async", not(any(feature = "tokio", feature = "async-io"))))]
+pub(crate) async fn write_to_socket(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    options: &SocketOptions,
+) -> HyprResult<()> {
+    let path = path.to_string();
+    let content = content.to_vec();
+    let mut thread_buffer = std::mem::take(buffer);
+    let thread_options = options.clone();
+    // the thread's spans are inside the span the command is sent from
+    #[cfg(feature = "tracing")]
+    let span = tracing::Span::current();
+    let exchange = async {
+        let (response, thread_buffer) = unblock(move || {
+            #[cfg(feature = "tracing")]
+            let _entered = span.entered();
+            let response =
+                write_to_socket_sync(&path, &content, &mut thread_buffer, &thread_options);
+            (response, thread_buffer)
+        })?
+        .await;
+        *buffer = thread_buffer;
+        response
+    };
+    match &options.cancellation {
+        Some(cancellation) => cancellation.run(exchange).await,
+        None => exchange.await,
+    }
+}
+
+/// This pub(crate) function is used to write a value to a socket and to read the response
+/// into `buffer`
+///
+/// Writing and reading each have to finish within the timeout (if set)
+pub(crate) fn write_to_socket_sync(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    options: &SocketOptions,
+) -> HyprResult<()> {
+    #[cfg(feature = "tracing")]
+    let _entered = command_span(path, content).entered();
+    log!(
+        debug,
+        "sending {:?} to {path}",
+        String::from_utf8_lossy(content)
+    );
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let timeout = options.attempt_timeout()?;
+        let error = match exchange_sync(path, content, buffer, timeout) {
+            Ok(()) => return Ok(()),
+            Err(error) => options.attempt_error(error),
+        };
+        match options
+            .retry
+            .and_then(|retry| retry.delay(attempts, &error))
+        {
+            Some(delay) => {
+                log!(debug, "retrying in {delay:?} after: {error}");
+                std::thread::sleep(delay)
+            }
+            None => return Err(error),
+        }
+    }
+}
+
+fn exchange_sync(
+    path: &str,
+    content: &[u8],
+    buffer: &mut Vec<u8>,
+    timeout: Option<Duration>,
+) -> HyprResult<()> {
     use io::prelude::*;
     use std::os::unix::net::UnixStream;
-    let mut stream = UnixStream::connect(path)?;
+    let connect = || UnixStream::connect(path);
+    #[cfg(feature = "tracing")]
+    let connect = || tracing::trace_span!("connect").in_scope(connect);
+    let mut stream = connect().map_err(|error| connect_error(path, error))?;
+    log!(trace, "connected to {path}");
+    stream.set_write_timeout(timeout)?;
+    stream.set_read_timeout(timeout)?;
+
+    let timed_out = |error: io::Error| match (error.kind(), timeout) {
+        (io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut, Some(timeout)) => {
+            log!(debug, "no response from {path} within {timeout:?}");
+            HyprError::Timeout(timeout)
+        }
+        _ => HyprError::Io(error),
+    };
+    stream.write_all(content).map_err(timed_out)?;
+    read_response_sync(&mut stream, buffer).map_err(timed_out)
+}
+
+/// This function reads a whole response into `buffer`,
+/// Hyprland closes the socket after answering, so it's read until the end
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+async fn read_response(
+    stream: &mut crate::runtime::UnixStream,
+    buffer: &mut Vec<u8>,
+) -> HyprResult<()> {
+    buffer.clear();
+    buffer.reserve(RESPONSE_BUFFER_SIZE);
+    let read = crate::runtime::read_to_end(stream, buffer);
+    #[cfg(feature = "tracing")]
+    let span = read_span();
+    #[cfg(feature = "tracing")]
+    let read = tracing::Instrument::instrument(read, span.clone());
+    let bytes = read.await?;
+    #[cfg(feature = "tracing")]
+    span.record("bytes", bytes);
+    log!(trace, "read {bytes} bytes");
+    Ok(())
+}
+
+/// This function reads a whole response into `buffer`,
+/// Hyprland closes the socket after answering, so it's read until the end
+fn read_response_sync(stream: &mut impl io::Read, buffer: &mut Vec<u8>) -> io::Result<()> {
+    #[cfg(feature = "tracing")]
+    let span = read_span().entered();
+    buffer.clear();
+    buffer.reserve(RESPONSE_BUFFER_SIZE);
+    let bytes = stream.read_to_end(buffer)?;
+    #[cfg(feature = "tracing")]
+    span.record("bytes", bytes);
+    log!(trace, "read {bytes} bytes");
+    Ok(())
+}
+
+/// Returns the span a command is sent in, the ones for connecting and reading are inside it
+#[cfg(feature = "tracing")]
+fn command_span(path: &str, content: &[u8]) -> tracing::Span {
+    let command = String::from_utf8_lossy(content);
+    tracing::debug_span!("hyprland_command", path, command = %command)
+}
+
+/// Returns the span a response is read in, with the bytes read once it's read
+#[cfg(feature = "tracing")]
+fn read_span() -> tracing::Span {
+    tracing::trace_span!("read", bytes = tracing::field::Empty)
+}
+
+/// This pub(crate) function turns the bytes Hyprland responded with into a string
+pub(crate) fn decode_response(response: &[u8]) -> HyprResult<String> {
+    Ok(String::from_utf8(response.to_vec())?)
+}
+
+/// This pub(crate) function turns a error from connecting to a socket into a [`HyprError`],
+/// a missing socket becomes [`HyprError::SocketNotFound`] (and its location is resolved again)
+pub(crate) fn connect_error(path: &str, error: io::Error) -> HyprError {
+    log!(debug, "couldn't connect to {path}: {error}");
+    match error.kind() {
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => {
+            forget_socket_dir(path);
+            HyprError::SocketNotFound(path.to_string())
+        }
+        _ => HyprError::Io(error),
+    }
+}
+
+/// This pub(crate) struct holds the wakers of the futures waiting for something to happen,
+/// a waker is removed once its future finishes or is dropped
+#[derive(Debug, Default)]
+pub(crate) struct Wakers {
+    /// The wakers, by the id they were registered with
+    wakers: std::sync::Mutex<std::collections::HashMap<u64, std::task::Waker>>,
+    #[cfg(feature = "async")]
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+impl Wakers {
+    /// Wakes every future that's waiting
+    pub(crate) fn wake_all(&self) {
+        for waker in std::mem::take(&mut *self.lock()).into_values() {
+            waker.wake();
+        }
+    }
+
+    /// Waits until `done` returns `true`, it's checked again every time the wakers are woken
+    #[cfg(feature = "async")]
+    pub(crate) async fn wait_until(&self, done: impl Fn() -> bool) {
+        use std::sync::atomic::Ordering;
+        use std::task::Poll;
+        let registration = WakerRegistration {
+            wakers: self,
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+        };
+        std::future::poll_fn(|cx| {
+            if done() {
+                return Poll::Ready(());
+            }
+            {
+                let mut wakers = self.lock();
+                let registered = wakers.get(&registration.id);
+                if !registered.is_some_and(|waker| waker.will_wake(cx.waker())) {
+                    wakers.insert(registration.id, cx.waker().clone());
+                }
+            }
+            // checked again, in case it happened before the waker was added
+            match done() {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, std::collections::HashMap<u64, std::task::Waker>> {
+        self.wakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Removes the waker of a future from the wakers, when it finishes or is dropped
+#[cfg(feature = "async")]
+struct WakerRegistration<'a> {
+    wakers: &'a Wakers,
+    id: u64,
+}
+
+#[cfg(feature = "async")]
+impl Drop for WakerRegistration<'_> {
+    fn drop(&mut self) {
+        self.wakers.lock().remove(&self.id);
+    }
+}
+
+/// This pub(crate) function runs a blocking function on a new thread,
+/// the returned future completes with its result
+#[cfg(all(feature = "async", not(any(feature = "tokio", feature = "async-io"))))]
+pub(crate) fn unblock<T: Send + 'static>(
+    f: impl FnOnce() -> T + Send + 'static,
+) -> io::Result<Unblock<T>> {
+    use std::sync::{Arc, Mutex};
+    let shared = Arc::new(Mutex::new(UnblockState {
+        result: None,
+        waker: None,
+    }));
+    let thread_shared = shared.clone();
+    std::thread::Builder::new()
+        .name("hyprland-ipc".to_string())
+        .spawn(move || {
+            let result = f();
+            let mut state = match thread_shared.lock() {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        })?;
+    Ok(Unblock { shared })
+}
+
+/// This pub(crate) struct is the future returned by [`unblock`]
+#[cfg(all(feature = "async", not(any(feature = "tokio", feature = "async-io"))))]
+pub(crate) struct Unblock<T> {
+    shared: std::sync::Arc<std::sync::Mutex<UnblockState<T>>>,
+}
+
+#[cfg(all(feature = "async", not(any(feature = "tokio", feature = "async-io"))))]
+struct UnblockState<T> {
+    result: Option<T>,
+    waker: Option<std::task::Waker>,
+}
 
-    stream.write_all(content)?;
-    let mut response = [0; 8192];
-    let num_read = stream.read(&mut response)?;
-    let response = &response[..num_read];
-    Ok(match String::from_utf8(response.to_vec()) {
-        Ok(str) => str,
-        Err(error) => panic!("an error has occured while parsing bytes as utf8: {error:#?}"),
-    })
+#[cfg(all(feature = "async", not(any(feature = "tokio", feature = "async-io"))))]
+impl<T> std::future::Future for Unblock<T> {
+    type Output = T;
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<T> {
+        let mut state = match self.shared.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match state.result.take() {
+            Some(result) => std::task::Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                std::task::Poll::Pending
+            }
+        }
+    }
+}
+
+/// This pub(crate) function splits the response to a `[[BATCH]]` command into one response per command,
+/// always `count` of them, a command without a response gets [`HyprError::UnexpectedResponse`]
+///
+/// Newer versions of Hyprland delimit the responses, older versions concatenate them,
+/// in which case the `ok`s are split off the start, up to the first command that returned something else.
+/// That command gets the rest of the response, as the ones after it can't be told apart from it
+pub(crate) fn split_batch_response(response: &str, count: usize) -> Vec<HyprResult<String>> {
+    let mut responses: Vec<HyprResult<String>> = Vec::with_capacity(count);
+    if response.contains("\n\n\n") {
+        let mut parts: Vec<&str> = response.split("\n\n\n").collect();
+        // a trailing delimiter leaves a empty part after the last response
+        while parts.len() > count && parts.last().is_some_and(|part| part.trim().is_empty()) {
+            parts.pop();
+        }
+        if parts.len() > count {
+            log!(
+                debug,
+                "expected {count} responses to a batch, got {}",
+                parts.len()
+            );
+        }
+        responses.extend(
+            parts
+                .into_iter()
+                .take(count)
+                .map(|part| Ok(part.to_string())),
+        );
+    } else {
+        let mut rest = response;
+        while responses.len() < count && !rest.is_empty() {
+            match rest.strip_prefix("ok") {
+                Some(after) => {
+                    responses.push(Ok("ok".to_string()));
+                    rest = after;
+                }
+                None => {
+                    responses.push(Ok(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+    }
+    while responses.len() < count {
+        let index = responses.len();
+        responses.push(Err(HyprError::UnexpectedResponse(format!(
+            "there's no response to command {} of the batch",
+            index + 1
+        ))));
+    }
+    responses
+}
+
+/// This pub(crate) function writes commands in Hyprland's batch syntax,
+/// into a single string instead of one per command
+///
+/// Returns [`HyprError::InvalidArgument`] if a command contains a `;`,
+/// which Hyprland would take as the start of another command
+pub(crate) fn batch_command<T: fmt::Display>(
+    commands: impl IntoIterator<Item = T>,
+) -> HyprResult<String> {
+    use fmt::Write;
+    let mut batch = String::from("[[BATCH]]");
+    for (index, command) in commands.into_iter().enumerate() {
+        if index > 0 {
+            batch.push(';');
+        }
+        let start = batch.len();
+        // writing to a string can't fail
+        let _ = write!(batch, "{command}");
+        if batch[start..].contains(';') {
+            return Err(HyprError::InvalidArgument(format!(
+                "{:?} contains a `;`, which would split it into two commands of the batch",
+                &batch[start..]
+            )));
+        }
+    }
+    Ok(batch)
+}
+
+/// This pub(crate) function turns the response to a `[[BATCH]]` command into one result per command,
+/// every command is expected to return `ok`
+pub(crate) fn batch_results(response: &str, count: usize) -> Vec<HyprResult<()>> {
+    split_batch_response(response, count)
+        .into_iter()
+        .map(|res| res.and_then(|res| check_ok(&res)))
+        .collect()
+}
+
+/// This pub(crate) function checks the response to a command Hyprland answers with `ok`,
+/// anything else is the message of the error it ran into
+pub(crate) fn check_ok(response: &str) -> HyprResult<()> {
+    match response.trim() {
+        "ok" => Ok(()),
+        msg => Err(HyprError::Command(CommandError::new(msg))),
+    }
 }
 
 /// This pub(crate) enum holds the different sockets that Hyprland has
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum SocketType {
     /// The socket used to send commands to Hyprland (AKA `.socket.sock`)
     Command,
     /// The socket used to listen for events (AKA `.socket2.sock`)
     Listener,
+    /// The socket of hyprpaper, the wallpaper utility (AKA `.hyprpaper.sock`)
+    #[cfg(feature = "hyprpaper")]
+    Hyprpaper,
 }
-/// This pub(crate) function gets the Hyprland socket path
-pub(crate) fn get_socket_path(socket_type: SocketType) -> String {
-    let hypr_instance_sig = match var("HYPRLAND_INSTANCE_SIGNATURE") {
-        Ok(var) => var,
-        Err(VarError::NotPresent) => panic!("Is hyprland running?"),
-        Err(VarError::NotUnicode(_)) => panic!("wtf no unicode?"),
-    };
 
-    let socket_name = match socket_type {
-        SocketType::Command => ".socket.sock",
-        SocketType::Listener => ".socket2.sock",
-    };
+/// This struct identifies a Hyprland instance, by the signature Hyprland sets
+/// `HYPRLAND_INSTANCE_SIGNATURE` to for the programs it runs
+///
+/// It's used to talk to a instance from outside of it, like from a cron job or over ssh,
+/// or to manage multiple instances at once
+///
+/// The sockets are looked for in `$XDG_RUNTIME_DIR/hypr/<signature>` (used by newer versions
+/// of Hyprland) and then in `/tmp/hypr/<signature>`, unless the directory is set with
+/// [`from_socket_dir`](Self::from_socket_dir)
+///
+/// ```rust, no_run
+/// # fn main() -> hyprland::shared::HyprResult<()> {
+/// use hyprland::connection::HyprlandConnection;
+/// use hyprland::shared::Instance;
+/// for instance in Instance::list()? {
+///     let monitors = HyprlandConnection::for_instance(&instance).get_monitors()?;
+///     println!("{} has {} monitors", instance.signature(), monitors.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Instance {
+    signature: String,
+    socket_dir: Option<PathBuf>,
+}
 
-    format!("/tmp/hypr/{hypr_instance_sig}/{socket_name}")
+impl Instance {
+    /// This method returns the instance this program runs in, from `HYPRLAND_INSTANCE_SIGNATURE`
+    pub fn current() -> HyprResult<Self> {
+        match var("HYPRLAND_INSTANCE_SIGNATURE") {
+            Ok(signature) => Ok(Instance::from_signature(signature)),
+            Err(VarError::NotPresent) => Err(HyprError::SocketNotFound(
+                "HYPRLAND_INSTANCE_SIGNATURE isn't set".to_string(),
+            )),
+            Err(VarError::NotUnicode(_)) => Err(HyprError::SocketNotFound(
+                "HYPRLAND_INSTANCE_SIGNATURE isn't valid unicode".to_string(),
+            )),
+        }
+    }
+
+    /// This method creates a instance from its signature
+    pub fn from_signature(signature: impl Into<String>) -> Self {
+        Instance {
+            signature: signature.into(),
+            socket_dir: None,
+        }
+    }
+
+    /// This method creates a instance from the directory its sockets are in,
+    /// for when they aren't in one of the default locations
+    pub fn from_socket_dir(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Instance {
+            signature: match dir.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => String::new(),
+            },
+            socket_dir: Some(dir),
+        }
+    }
+
+    /// This method returns every instance that has a command socket,
+    /// instances that crashed can leave their socket behind
+    pub fn list() -> HyprResult<Vec<Self>> {
+        let mut instances: Vec<Instance> = vec![];
+        for root in socket_roots() {
+            let entries = match std::fs::read_dir(&root) {
+                Ok(entries) => entries,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+            for entry in entries {
+                let dir = entry?.path();
+                let instance = Instance::from_socket_dir(&dir);
+                let known = instances
+                    .iter()
+                    .any(|known| known.signature == instance.signature);
+                if !known && dir.join(SocketType::Command.file_name()).exists() {
+                    instances.push(instance);
+                }
+            }
+        }
+        Ok(instances)
+    }
+
+    /// This method returns the signature of the instance
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// This method returns the directory the sockets of the instance are in,
+    /// the first default location that exists if it wasn't set
+    ///
+    /// The location is resolved once per signature, and again after connecting to it failed
+    pub fn socket_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.socket_dir {
+            return dir.clone();
+        }
+        if let Some(dir) = socket_dirs().get(&self.signature) {
+            return dir.clone();
+        }
+        let dir = self.resolve_socket_dir();
+        socket_dirs().insert(self.signature.clone(), dir.clone());
+        dir
+    }
+
+    fn resolve_socket_dir(&self) -> PathBuf {
+        let legacy_dir = PathBuf::from(LEGACY_SOCKET_ROOT).join(&self.signature);
+        match var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+            Some(runtime_dir) => {
+                let dir = PathBuf::from(runtime_dir)
+                    .join("hypr")
+                    .join(&self.signature);
+                if dir.exists() || !legacy_dir.exists() {
+                    dir
+                } else {
+                    legacy_dir
+                }
+            }
+            None => legacy_dir,
+        }
+    }
+
+    /// This pub(crate) method returns the path of one of the sockets of the instance
+    pub(crate) fn socket_path(&self, socket_type: SocketType) -> String {
+        self.socket_dir()
+            .join(socket_type.file_name())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// This function returns the socket directories resolved so far, by instance signature
+fn socket_dirs() -> std::sync::MutexGuard<'static, std::collections::HashMap<String, PathBuf>> {
+    static SOCKET_DIRS: std::sync::OnceLock<
+        std::sync::Mutex<std::collections::HashMap<String, PathBuf>>,
+    > = std::sync::OnceLock::new();
+    SOCKET_DIRS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// This function forgets the resolved socket directory the socket is in,
+/// so it's resolved again the next time it's needed
+fn forget_socket_dir(socket_path: &str) {
+    if let Some(dir) = std::path::Path::new(socket_path).parent() {
+        socket_dirs().retain(|_, resolved| resolved != dir);
+    }
+}
+
+/// This function returns the directories Hyprland creates the socket directories of its instances in,
+/// newer versions first
+fn socket_roots() -> Vec<PathBuf> {
+    let mut roots = vec![];
+    if let Some(runtime_dir) = var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        roots.push(PathBuf::from(runtime_dir).join("hypr"));
+    }
+    roots.push(PathBuf::from(LEGACY_SOCKET_ROOT));
+    roots
+}
+
+/// The directory older versions of Hyprland create the socket directories in
+const LEGACY_SOCKET_ROOT: &str = "/tmp/hypr";
+
+impl SocketType {
+    fn file_name(&self) -> &'static str {
+        match self {
+            SocketType::Command => ".socket.sock",
+            SocketType::Listener => ".socket2.sock",
+            #[cfg(feature = "hyprpaper")]
+            SocketType::Hyprpaper => ".hyprpaper.sock",
+        }
+    }
+}
+
+/// This pub(crate) function gets the Hyprland socket path of the instance this program runs in
+pub(crate) fn get_socket_path(socket_type: SocketType) -> HyprResult<String> {
+    Ok(Instance::current()?.socket_path(socket_type))
+}
+
+/// This pub(crate) function gets the socket path of `instance`,
+/// or of the instance this program runs in if it's `None`
+pub(crate) fn instance_socket_path(
+    instance: Option<&Instance>,
+    socket_type: SocketType,
+) -> HyprResult<String> {
+    match instance {
+        Some(instance) => Ok(instance.socket_path(socket_type)),
+        None => get_socket_path(socket_type),
+    }
 }
 
 pub(crate) fn object_empty_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -135,19 +1215,167 @@ where
     }
 }
 
-pub(crate) fn de_work_id<'de, D>(deserializer: D) -> Result<WorkspaceType, D::Error>
+/// Deserializes a address, `0x0` (which Hyprland uses for no window) as `None`
+pub(crate) fn de_optional_address<'de, D>(deserializer: D) -> Result<Option<Address>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    #[derive(Deserialize, Debug)]
-    #[serde(untagged)]
-    enum Aux {
-        Reg(u8),
-        Special(i8),
+    let address: Option<Address> = Deserialize::deserialize(deserializer)?;
+    Ok(address.filter(|Address(value)| value != "0x0"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_error_kinds() {
+        let replies = [
+            ("Invalid dispatcher", CommandErrorKind::InvalidDispatcher),
+            (
+                "Invalid dispatcher, requested \"foo\" does not exist",
+                CommandErrorKind::InvalidDispatcher,
+            ),
+            ("unknown request", CommandErrorKind::UnknownRequest),
+            (
+                "config option <general:foo> does not exist.",
+                CommandErrorKind::UnknownOption,
+            ),
+            ("no such option", CommandErrorKind::UnknownOption),
+            ("Window not found", CommandErrorKind::WindowNotFound),
+            ("focusWindow: no window", CommandErrorKind::WindowNotFound),
+            ("No such workspace", CommandErrorKind::WorkspaceNotFound),
+            ("Monitor not found", CommandErrorKind::MonitorNotFound),
+            ("invalid window rule", CommandErrorKind::InvalidArgument),
+            ("Invalid workspace", CommandErrorKind::InvalidArgument),
+            (
+                "resizeActive: invalid size",
+                CommandErrorKind::InvalidArgument,
+            ),
+            ("not enough args", CommandErrorKind::InvalidArgument),
+            ("the window is too large", CommandErrorKind::Other),
+            ("Couldn't set the cursor theme", CommandErrorKind::Other),
+        ];
+        for (reply, kind) in replies {
+            assert_eq!(CommandError::new(reply).kind(), kind, "{reply:?}");
+        }
     }
 
-    match Deserialize::deserialize(deserializer)? {
-        Aux::Special(_) => Ok(WorkspaceType::Special),
-        Aux::Reg(int) => Ok(WorkspaceType::Regular(int)),
+    /// Returns the responses, with the errors as `None`
+    fn split(response: &str, count: usize) -> Vec<Option<String>> {
+        split_batch_response(response, count)
+            .into_iter()
+            .map(Result::ok)
+            .collect()
+    }
+
+    #[test]
+    fn delimited_batch_responses() {
+        let response = "ok\n\n\n{\"option\": \"general:gaps_in\"}\n\n\nok";
+        assert_eq!(
+            split(response, 3),
+            [
+                Some("ok".to_string()),
+                Some("{\"option\": \"general:gaps_in\"}".to_string()),
+                Some("ok".to_string()),
+            ]
+        );
+        // the trailing delimiter newer versions write after the last response
+        assert_eq!(
+            split("ok\n\n\nok\n\n\n", 2),
+            [Some("ok".to_string()), Some("ok".to_string())]
+        );
+        assert_eq!(split("ok\n\n\nok\n\n\nok", 2).len(), 2);
+        assert_eq!(
+            split("ok\n\n\nok", 3),
+            [Some("ok".to_string()), Some("ok".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn concatenated_batch_responses() {
+        assert_eq!(
+            split("okokok", 3),
+            [
+                Some("ok".to_string()),
+                Some("ok".to_string()),
+                Some("ok".to_string()),
+            ]
+        );
+        // the command that failed gets the rest of the response
+        assert_eq!(
+            split("okWindow not foundok", 3),
+            [
+                Some("ok".to_string()),
+                Some("Window not foundok".to_string()),
+                None,
+            ]
+        );
+        assert_eq!(split("", 2), [None, None]);
+    }
+
+    #[test]
+    fn batch_results_of_failed_commands() {
+        let results = batch_results("ok\n\n\nInvalid dispatcher", 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            &results[1],
+            Err(HyprError::Command(error)) if error.kind() == CommandErrorKind::InvalidDispatcher
+        ));
+    }
+
+    #[test]
+    fn batch_commands() -> HyprResult<()> {
+        assert_eq!(
+            batch_command(["dispatch exec kitty", "keyword general:gaps_in 5"])?,
+            "[[BATCH]]dispatch exec kitty;keyword general:gaps_in 5"
+        );
+        assert_eq!(batch_command(Vec::<String>::new())?, "[[BATCH]]");
+        assert!(matches!(
+            batch_command(["dispatch exec kitty", "dispatch exec sh -c 'a; b'"]),
+            Err(HyprError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn attempts_are_limited_by_the_deadline() {
+        let now = std::time::Instant::now();
+        let options = |deadline| SocketOptions {
+            deadline: Some(deadline),
+            ..SocketOptions::default()
+        };
+        let remaining = options(now + Duration::from_secs(60)).attempt_timeout();
+        assert!(matches!(remaining, Ok(Some(timeout)) if timeout == DEFAULT_TIMEOUT));
+        let remaining = options(now + Duration::from_millis(200)).attempt_timeout();
+        assert!(matches!(remaining, Ok(Some(timeout)) if timeout <= Duration::from_millis(200)));
+        assert!(matches!(
+            options(now).attempt_timeout(),
+            Err(HyprError::DeadlineExceeded)
+        ));
+        let timed_out = HyprError::Timeout(Duration::from_millis(200));
+        assert!(matches!(
+            options(now).attempt_error(timed_out),
+            HyprError::DeadlineExceeded
+        ));
+        let timed_out = HyprError::Timeout(DEFAULT_TIMEOUT);
+        assert!(matches!(
+            options(now + Duration::from_secs(60)).attempt_error(timed_out),
+            HyprError::Timeout(_)
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn cancellation_token_forgets_finished_commands() {
+        let token = CancellationToken::new();
+        for _ in 0..3 {
+            assert!(token.run(async { Ok(()) }).await.is_ok());
+        }
+        // a command that never finishes, dropped when the timeout runs out
+        let pending = token.run(std::future::pending::<HyprResult<()>>());
+        let timeout = tokio::time::timeout(std::time::Duration::from_millis(10), pending);
+        assert!(timeout.await.is_err());
+        assert!(token.inner.wakers.lock().is_empty());
     }
 }
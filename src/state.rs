@@ -0,0 +1,726 @@
+//! # State module
+//!
+//! This module provides [`HyprlandState`](crate::state::HyprlandState), a cache of the monitors,
+//! workspaces and windows of a Hyprland instance that is kept up to date with the event socket
+//!
+//! The data is fetched once, after that every getter is a cheap synchronous read,
+//! which is what status bars and widgets that redraw often need.
+//! With the `tokio` feature, the values widgets usually show can also be watched for changes
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::state::HyprlandState;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let state = HyprlandState::new()?;
+//!     loop {
+//!         if let Some(workspace) = state.active_workspace() {
+//!             println!("{} ({} windows)", workspace.name, workspace.windows);
+//!         }
+//!         std::thread::sleep(std::time::Duration::from_millis(250));
+//!     }
+//! }
+//! ```
+
+use crate::connection::HyprlandConnection;
+use crate::data::*;
+use crate::event_listener::{parse_event, Event, WindowOpenEventData};
+use crate::shared::*;
+use std::io::{self, BufRead};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use {std::collections::HashMap, tokio::sync::watch};
+
+/// How long to wait between attempts to reconnect to the event socket
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// This struct holds the monitors, workspaces and windows of a Hyprland instance,
+/// kept up to date from the event socket by a background thread
+///
+/// It's created with the data fetched from Hyprland, after that only events are read,
+/// no commands are sent (except for fetching the monitors again when one is added,
+/// as the event doesn't hold its size and position). The events don't hold the size,
+/// position and pid of windows either, so those are 0 for windows opened after it was created,
+/// [`refresh`](Self::refresh) fetches everything again.
+///
+/// If the event socket is closed, like when Hyprland restarts, the cache reconnects
+/// and fetches the data again, [`is_synced`](Self::is_synced) is `false` until it did.
+/// Dropping it stops the background thread
+pub struct HyprlandState {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    instance: Instance,
+    snapshot: RwLock<Snapshot>,
+    synced: AtomicBool,
+    stopped: AtomicBool,
+    /// A clone of the event socket, shut down to stop the thread reading it
+    socket: Mutex<Option<UnixStream>>,
+    /// How many times the data was updated, `changed` is notified when it is
+    generation: Mutex<u64>,
+    changed: Condvar,
+    #[cfg(feature = "tokio")]
+    watches: Watches,
+}
+
+/// The senders of the values that can be watched, they're sent whenever they change
+#[cfg(feature = "tokio")]
+struct Watches {
+    active_window: watch::Sender<Option<Client>>,
+    active_workspaces: watch::Sender<HashMap<String, Workspace>>,
+    submap: watch::Sender<String>,
+    generation: watch::Sender<u64>,
+}
+
+struct Snapshot {
+    monitors: Monitors,
+    workspaces: Workspaces,
+    clients: Clients,
+    active_window: Option<Address>,
+    submap: String,
+    /// The names of the workspaces with a urgent window, until they're shown
+    urgent: Vec<String>,
+    /// The addresses of the clients, the most recently focused first
+    focus_history: Vec<Address>,
+}
+
+impl HyprlandState {
+    /// This method fetches the data of the Hyprland instance this program runs in,
+    /// and starts keeping it up to date
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        HyprlandState::for_instance(&Instance::current()?)
+    }
+
+    /// This method fetches the data of a specific Hyprland instance,
+    /// and starts keeping it up to date
+    pub fn for_instance(instance: &Instance) -> HyprResult<Self> {
+        // connecting first means the events sent while fetching aren't missed
+        let socket = connect(instance)?;
+        let snapshot = Snapshot::fetch(instance)?;
+        let shared = Arc::new(Shared {
+            instance: instance.clone(),
+            #[cfg(feature = "tokio")]
+            watches: Watches::new(&snapshot),
+            snapshot: RwLock::new(snapshot),
+            synced: AtomicBool::new(true),
+            stopped: AtomicBool::new(false),
+            socket: Mutex::new(None),
+            generation: Mutex::new(0),
+            changed: Condvar::new(),
+        });
+        let thread_shared = shared.clone();
+        std::thread::spawn(move || thread_shared.run(socket));
+        Ok(HyprlandState { shared })
+    }
+
+    /// This pub(crate) method creates a state without any data, that isn't kept up to date,
+    /// for tests to fill with [`apply`](Self::apply)
+    #[cfg(test)]
+    pub(crate) fn empty() -> Self {
+        let snapshot = Snapshot {
+            monitors: vec![],
+            workspaces: vec![],
+            clients: vec![],
+            active_window: None,
+            submap: String::new(),
+            urgent: vec![],
+            focus_history: vec![],
+        };
+        HyprlandState {
+            shared: Arc::new(Shared {
+                instance: Instance::from_signature("test"),
+                #[cfg(feature = "tokio")]
+                watches: Watches::new(&snapshot),
+                snapshot: RwLock::new(snapshot),
+                synced: AtomicBool::new(true),
+                stopped: AtomicBool::new(false),
+                socket: Mutex::new(None),
+                generation: Mutex::new(0),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// This pub(crate) method updates the data with a event, like the background thread does
+    #[cfg(test)]
+    pub(crate) fn apply(&self, event: &Event) {
+        self.shared.update(|snapshot| snapshot.apply(event));
+    }
+
+    /// This method returns all monitors
+    pub fn monitors(&self) -> Monitors {
+        self.shared.read().monitors.clone()
+    }
+
+    /// This method returns the focused monitor
+    pub fn active_monitor(&self) -> Option<Monitor> {
+        self.shared.read().focused_monitor().cloned()
+    }
+
+    /// This method returns all workspaces
+    pub fn workspaces(&self) -> Workspaces {
+        self.shared.read().workspaces.clone()
+    }
+
+    /// This method returns the active workspace of the focused monitor
+    pub fn active_workspace(&self) -> Option<Workspace> {
+        let snapshot = self.shared.read();
+        let name = &snapshot.focused_monitor()?.active_workspace.name;
+        snapshot.workspace(name).cloned()
+    }
+
+    /// This method returns all clients/windows
+    pub fn clients(&self) -> Clients {
+        self.shared.read().clients.clone()
+    }
+
+    /// This method returns the client/window with the address
+    pub fn client(&self, address: &Address) -> Option<Client> {
+        self.shared.read().client(address).cloned()
+    }
+
+    /// This method returns the active window, `None` if no window is focused
+    pub fn active_window(&self) -> Option<Client> {
+        self.shared.read().active_client()
+    }
+
+    /// This method returns the active submap, empty if it's the default one
+    ///
+    /// Hyprland can't be asked for it, so it's only known after it changed once
+    pub fn submap(&self) -> String {
+        self.shared.read().submap.clone()
+    }
+
+    /// This method returns the names of the workspaces with a window that wants attention (is urgent),
+    /// until they're shown or the window is focused
+    pub fn urgent_workspaces(&self) -> Vec<String> {
+        self.shared.read().urgent.clone()
+    }
+
+    /// This method returns the addresses of all clients/windows, the most recently focused first
+    ///
+    /// Hyprland can't be asked for the order, so the windows that weren't focused since the state
+    /// was created are in the order Hyprland lists them in, after the active window
+    pub fn focus_history(&self) -> Vec<Address> {
+        self.shared.read().focus_history.clone()
+    }
+
+    /// This method returns if the data is up to date,
+    /// `false` while reconnecting to the event socket
+    pub fn is_synced(&self) -> bool {
+        self.shared.synced.load(Ordering::SeqCst)
+    }
+
+    /// This method fetches all the data again (blocking)
+    pub fn refresh(&self) -> HyprResult<()> {
+        let snapshot = Snapshot::fetch(&self.shared.instance)?;
+        self.shared.update(|current| current.replace(snapshot));
+        Ok(())
+    }
+
+    /// This pub(crate) method returns how many times the data was updated
+    pub(crate) fn update_count(&self) -> u64 {
+        *self.shared.generation()
+    }
+
+    /// This pub(crate) method waits until the data was updated more than `seen` times,
+    /// and returns how many times it was
+    pub(crate) fn wait_for_update(&self, seen: u64) -> u64 {
+        let generation = self
+            .shared
+            .changed
+            .wait_while(self.shared.generation(), |generation| *generation <= seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        *generation
+    }
+
+    /// This pub(crate) method returns a receiver that's marked as changed whenever the data is updated
+    #[cfg(feature = "tokio")]
+    pub(crate) fn watch_updates(&self) -> watch::Receiver<u64> {
+        self.shared.watches.generation.subscribe()
+    }
+
+    /// This method returns a receiver of the active window, `None` if no window is focused
+    ///
+    /// It's marked as changed whenever the window or its data (like its title) changes
+    ///
+    /// ```rust, no_run
+    /// # async fn function() -> hyprland::shared::HyprResult<()> {
+    /// use hyprland::state::HyprlandState;
+    /// let state = HyprlandState::new()?;
+    /// let mut active_window = state.watch_active_window();
+    /// while active_window.changed().await.is_ok() {
+    ///     match &*active_window.borrow_and_update() {
+    ///         Some(window) => println!("{}", window.title),
+    ///         None => println!("no window is focused"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn watch_active_window(&self) -> watch::Receiver<Option<Client>> {
+        self.shared.watches.active_window.subscribe()
+    }
+
+    /// This method returns a receiver of the active workspace of every monitor,
+    /// by the name of the monitor
+    ///
+    /// It's marked as changed whenever a active workspace or its data (like its window count) changes
+    #[cfg(feature = "tokio")]
+    pub fn watch_active_workspaces(&self) -> watch::Receiver<HashMap<String, Workspace>> {
+        self.shared.watches.active_workspaces.subscribe()
+    }
+
+    /// This method returns a receiver of the active submap, see [`submap`](Self::submap)
+    #[cfg(feature = "tokio")]
+    pub fn watch_submap(&self) -> watch::Receiver<String> {
+        self.shared.watches.submap.subscribe()
+    }
+}
+
+impl Drop for HyprlandState {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        if let Some(socket) = self.shared.socket().as_ref() {
+            // the thread sees this as the socket being closed
+            let _ = socket.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+fn connect(instance: &Instance) -> HyprResult<UnixStream> {
+    let socket_path = instance.socket_path(SocketType::Listener);
+    UnixStream::connect(&socket_path).map_err(|error| connect_error(&socket_path, error))
+}
+
+impl Shared {
+    fn read(&self) -> RwLockReadGuard<'_, Snapshot> {
+        self.snapshot.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Snapshot> {
+        self.snapshot
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn generation(&self) -> std::sync::MutexGuard<'_, u64> {
+        self.generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Changes the data, and sends the watched values that changed
+    fn update(&self, update: impl FnOnce(&mut Snapshot)) {
+        let mut snapshot = self.write();
+        update(&mut snapshot);
+        #[cfg(feature = "tokio")]
+        self.watches.send(&snapshot);
+        drop(snapshot);
+        let mut generation = self.generation();
+        *generation += 1;
+        self.changed.notify_all();
+        #[cfg(feature = "tokio")]
+        self.watches.generation.send_replace(*generation);
+    }
+
+    fn socket(&self) -> std::sync::MutexGuard<'_, Option<UnixStream>> {
+        self.socket.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Applies the events from the socket, reconnecting when it's closed, until stopped
+    fn run(&self, mut socket: UnixStream) {
+        loop {
+            *self.socket() = socket.try_clone().ok();
+            if self.is_stopped() {
+                return;
+            }
+            if let Err(error) = self.follow(socket) {
+                log!(debug, "the event socket failed: {error}");
+            }
+            self.synced.store(false, Ordering::SeqCst);
+            *self.socket() = None;
+
+            socket = loop {
+                if self.is_stopped() {
+                    return;
+                }
+                std::thread::sleep(RECONNECT_DELAY);
+                let Ok(socket) = connect(&self.instance) else {
+                    continue;
+                };
+                match Snapshot::fetch(&self.instance) {
+                    Ok(snapshot) => {
+                        self.update(|current| current.replace(snapshot));
+                        self.synced.store(true, Ordering::SeqCst);
+                        break socket;
+                    }
+                    Err(error) => log!(debug, "couldn't fetch the state again: {error}"),
+                }
+            };
+        }
+    }
+
+    fn follow(&self, socket: UnixStream) -> io::Result<()> {
+        for line in io::BufReader::new(socket).lines() {
+            let event = match parse_event(&line?, &None) {
+                Ok(Some(event)) => event,
+                // a event that can't be parsed doesn't change the data this holds
+                Ok(None) | Err(_) => continue,
+            };
+            if let Event::MonitorAddedV2(_) = event {
+                let monitors = HyprlandConnection::for_instance(&self.instance).get_monitors();
+                match monitors {
+                    Ok(monitors) => self.update(|snapshot| snapshot.monitors = monitors),
+                    Err(error) => log!(debug, "couldn't fetch the monitors: {error}"),
+                }
+            }
+            self.update(|snapshot| snapshot.apply(&event));
+        }
+        Ok(())
+    }
+}
+
+impl Snapshot {
+    fn fetch(instance: &Instance) -> HyprResult<Self> {
+        let mut hyprland = HyprlandConnection::for_instance(instance);
+        let mut snapshot = Snapshot {
+            monitors: hyprland.get_monitors()?,
+            workspaces: hyprland.get_workspaces()?,
+            clients: hyprland.get_clients()?,
+            active_window: hyprland
+                .get_active_window()?
+                .into_client()
+                .map(|client| client.address),
+            submap: String::new(),
+            urgent: vec![],
+            focus_history: vec![],
+        };
+        snapshot.keep_focus_history(&[]);
+        Ok(snapshot)
+    }
+
+    /// Replaces the data with a fetched snapshot, keeping what Hyprland can't be asked for
+    /// (the submap, the workspaces with urgent windows and the focus history)
+    fn replace(&mut self, mut snapshot: Snapshot) {
+        snapshot.submap = std::mem::take(&mut self.submap);
+        snapshot.urgent = std::mem::take(&mut self.urgent);
+        snapshot.keep_focus_history(&self.focus_history);
+        *self = snapshot;
+    }
+
+    /// Sets the focus history to the clients in `history` in its order, then the active window
+    /// in front and the other clients at the end
+    fn keep_focus_history(&mut self, history: &[Address]) {
+        let mut order: Vec<Address> = history
+            .iter()
+            .filter(|address| self.client(address).is_some())
+            .cloned()
+            .collect();
+        for client in &self.clients {
+            if !order.contains(&client.address) {
+                order.push(client.address.clone());
+            }
+        }
+        self.focus_history = order;
+        if let Some(client) = self.active_client() {
+            self.focused(client.address);
+        }
+    }
+
+    /// Moves the client to the front of the focus history
+    fn focused(&mut self, address: Address) {
+        self.focus_history.retain(|focused| *focused != address);
+        self.focus_history.insert(0, address);
+    }
+
+    fn focused_monitor(&self) -> Option<&Monitor> {
+        self.monitors.iter().find(|monitor| monitor.focused)
+    }
+
+    fn workspace(&self, name: &str) -> Option<&Workspace> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.name == name)
+    }
+
+    fn workspace_mut(&mut self, name: &str) -> Option<&mut Workspace> {
+        self.workspaces
+            .iter_mut()
+            .find(|workspace| workspace.name == name)
+    }
+
+    fn client(&self, address: &Address) -> Option<&Client> {
+        self.clients
+            .iter()
+            .find(|client| &client.address == address)
+    }
+
+    fn active_client(&self) -> Option<Client> {
+        self.client(self.active_window.as_ref()?).cloned()
+    }
+
+    fn client_mut(&mut self, address: &Address) -> Option<&mut Client> {
+        self.clients
+            .iter_mut()
+            .find(|client| &client.address == address)
+    }
+
+    /// Returns the id of the monitor the workspace is on, or of the focused one if it isn't known
+    fn monitor_id(&self, workspace: &str) -> u8 {
+        let monitor = match self.workspace(workspace) {
+            Some(workspace) => self
+                .monitors
+                .iter()
+                .find(|monitor| monitor.name == workspace.monitor),
+            None => self.focused_monitor(),
+        };
+        monitor.map_or(0, |monitor| monitor.id)
+    }
+
+    fn count_window(&mut self, workspace: &str, opened: bool) {
+        if let Some(workspace) = self.workspace_mut(workspace) {
+            workspace.windows = match opened {
+                true => workspace.windows.saturating_add(1),
+                false => workspace.windows.saturating_sub(1),
+            };
+        }
+    }
+
+    /// Updates the data with a event, applying a event twice changes nothing
+    /// (the events sent while fetching the data are already in it)
+    ///
+    /// Hyprland sends a (v1) event and a v2 event for most changes, only one of them is used
+    fn apply(&mut self, event: &Event) {
+        match event {
+            Event::WorkspaceChangedV2(data) => {
+                let workspace = basic_workspace(data.id, &data.name);
+                self.urgent.retain(|name| *name != data.name);
+                if let Some(monitor) = self.monitors.iter_mut().find(|monitor| monitor.focused) {
+                    monitor.active_workspace = workspace;
+                    let monitor = monitor.name.clone();
+                    if let Some(workspace) = self.workspace_mut(&data.name) {
+                        workspace.monitor = monitor;
+                    }
+                }
+            }
+            Event::WorkspaceAddedV2(data) if self.workspace(&data.name).is_none() => {
+                let monitor = self.focused_monitor().map(|monitor| monitor.name.clone());
+                self.workspaces.push(Workspace {
+                    id: basic_workspace(data.id, &data.name).id,
+                    name: data.name.clone(),
+                    monitor: monitor.unwrap_or_default(),
+                    windows: 0,
+                    fullscreen: false,
+                });
+            }
+            Event::WorkspaceDeletedV2(data) => {
+                self.workspaces
+                    .retain(|workspace| workspace.name != data.name);
+                self.urgent.retain(|name| *name != data.name);
+            }
+            Event::WorkspaceMovedV2(data) => {
+                if let Some(workspace) = self.workspace_mut(&data.name) {
+                    workspace.monitor = data.monitor.clone();
+                }
+            }
+            Event::WorkspaceRenamed(data) => {
+                let id = basic_workspace(data.id, &data.name).id;
+                for workspace in self.workspaces.iter_mut().filter(|w| w.id == id) {
+                    workspace.name = data.name.clone();
+                }
+                for monitor in &mut self.monitors {
+                    if monitor.active_workspace.id == id {
+                        monitor.active_workspace.name = data.name.clone();
+                    }
+                }
+                for client in &mut self.clients {
+                    if client.workspace.id == id {
+                        client.workspace.name = data.name.clone();
+                    }
+                }
+            }
+            Event::ActiveMonitorChangedV2(data) => {
+                let id = basic_workspace(data.workspace_id, "").id;
+                let workspace = self
+                    .workspaces
+                    .iter()
+                    .find(|workspace| workspace.id == id)
+                    .map(|workspace| basic_workspace(data.workspace_id, &workspace.name));
+                for monitor in &mut self.monitors {
+                    monitor.focused = monitor.name == data.monitor;
+                    if let (true, Some(workspace)) = (monitor.focused, &workspace) {
+                        monitor.active_workspace = workspace.clone();
+                    }
+                }
+            }
+            Event::ActiveWindowChangedV2(address) => {
+                self.active_window = address.clone();
+                if let Some(client) = self.active_client() {
+                    self.urgent.retain(|name| *name != client.workspace.name);
+                    self.focused(client.address);
+                }
+            }
+            Event::Urgent(address) => {
+                let name = match self.client(address) {
+                    Some(client) => client.workspace.name.clone(),
+                    None => return,
+                };
+                let shown = self
+                    .focused_monitor()
+                    .is_some_and(|monitor| monitor.active_workspace.name == name);
+                if !shown && !self.urgent.contains(&name) {
+                    self.urgent.push(name);
+                }
+            }
+            Event::FullscreenStateChanged(fullscreen) => {
+                let name = self
+                    .focused_monitor()
+                    .map(|monitor| monitor.active_workspace.name.clone());
+                if let Some(workspace) = name.and_then(|name| self.workspace_mut(&name)) {
+                    workspace.fullscreen = *fullscreen;
+                }
+            }
+            Event::MonitorRemovedV2(data) => self.monitors.retain(|mon| mon.name != data.name),
+            Event::WindowOpened(data) => self.window_opened(data),
+            Event::WindowClosed(address) => {
+                if let Some(index) = self.clients.iter().position(|c| &c.address == address) {
+                    let client = self.clients.remove(index);
+                    self.count_window(&client.workspace.name, false);
+                }
+                if self.active_window.as_ref() == Some(address) {
+                    self.active_window = None;
+                }
+                self.focus_history.retain(|focused| focused != address);
+            }
+            Event::WindowMovedV2(data) => {
+                let old = match self.client(&data.address) {
+                    Some(client) if client.workspace.name != data.workspace_name => {
+                        client.workspace.name.clone()
+                    }
+                    _ => return,
+                };
+                self.count_window(&old, false);
+                self.count_window(&data.workspace_name, true);
+                let monitor = self.monitor_id(&data.workspace_name);
+                if let Some(client) = self.client_mut(&data.address) {
+                    client.workspace = basic_workspace(data.workspace_id, &data.workspace_name);
+                    client.monitor = monitor;
+                }
+            }
+            Event::FloatStateChanged(data) => {
+                if let Some(client) = self.client_mut(&data.address) {
+                    client.floating = data.floating;
+                }
+            }
+            Event::WindowTitleChangedV2(data) => {
+                if let Some(client) = self.client_mut(&data.address) {
+                    client.title = data.title.clone();
+                }
+            }
+            Event::SubmapChanged(submap) => self.submap = submap.clone(),
+            _ => {}
+        }
+    }
+
+    fn window_opened(&mut self, data: &WindowOpenEventData) {
+        if self.client(&data.address).is_some() {
+            return;
+        }
+        let name = &data.workspace_name;
+        let workspace = match self.workspace(name) {
+            Some(workspace) => WorkspaceBasic {
+                id: workspace.id.clone(),
+                name: workspace.name.clone(),
+            },
+            None => WorkspaceBasic {
+                id: data.workspace.clone(),
+                name: name.clone(),
+            },
+        };
+        self.count_window(name, true);
+        let monitor = self.monitor_id(name);
+        self.clients.push(Client {
+            address: data.address.clone(),
+            at: (0, 0),
+            size: (0, 0),
+            workspace,
+            floating: false,
+            monitor,
+            class: data.class.clone(),
+            title: data.title.clone(),
+            pid: 0,
+            xwayland: false,
+            tags: vec![],
+            grouped: vec![],
+            fullscreen: FullscreenMode::None,
+            fullscreen_client: FullscreenMode::None,
+            hidden: false,
+            swallowing: None,
+        });
+        self.focus_history.push(data.address.clone());
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Watches {
+    fn new(snapshot: &Snapshot) -> Self {
+        Watches {
+            active_window: watch::channel(snapshot.active_client()).0,
+            active_workspaces: watch::channel(active_workspaces(snapshot)).0,
+            submap: watch::channel(snapshot.submap.clone()).0,
+            generation: watch::channel(0).0,
+        }
+    }
+
+    fn send(&self, snapshot: &Snapshot) {
+        send_changed(&self.active_window, snapshot.active_client());
+        send_changed(&self.active_workspaces, active_workspaces(snapshot));
+        send_changed(&self.submap, snapshot.submap.clone());
+    }
+}
+
+/// Returns the active workspace of every monitor, by the name of the monitor
+#[cfg(feature = "tokio")]
+fn active_workspaces(snapshot: &Snapshot) -> HashMap<String, Workspace> {
+    snapshot
+        .monitors
+        .iter()
+        .filter_map(|monitor| {
+            let workspace = snapshot.workspace(&monitor.active_workspace.name)?;
+            Some((monitor.name.clone(), workspace.clone()))
+        })
+        .collect()
+}
+
+/// Sends the value if it's different from the current one, receivers only see real changes
+#[cfg(feature = "tokio")]
+fn send_changed<T: PartialEq>(sender: &watch::Sender<T>, value: T) {
+    sender.send_if_modified(|current| {
+        let changed = *current != value;
+        if changed {
+            *current = value;
+        }
+        changed
+    });
+}
+
+/// Returns the workspace for the id and name of a v2 event
+fn basic_workspace(id: WorkspaceId, name: &str) -> WorkspaceBasic {
+    WorkspaceBasic {
+        id: WorkspaceType::from_id_and_name(id, name),
+        name: name.to_string(),
+    }
+}
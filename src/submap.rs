@@ -0,0 +1,124 @@
+//! # Submap module
+//!
+//! This module provides [`SubmapTracker`](crate::submap::SubmapTracker), which follows the active submap
+//! and knows the binds in it, for showing which keys do what while a submap is active
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::submap::SubmapTracker;
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     let tracker = SubmapTracker::new()?;
+//!     let mut submap = tracker.submap();
+//!     loop {
+//!         submap = tracker.wait_for_change(&submap);
+//!         for bind in tracker.binds() {
+//!             println!("{}: {} {}", bind.key, bind.dispatcher, bind.arg);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::connection::HyprlandConnection;
+use crate::data::Binds;
+use crate::shared::*;
+use crate::state::HyprlandState;
+use std::sync::{PoisonError, RwLock};
+
+/// This struct follows the active submap with a [`HyprlandState`], and holds the binds of every submap
+///
+/// The binds are fetched once, [`refresh_binds`](Self::refresh_binds) fetches them again
+/// (like after the config was reloaded)
+pub struct SubmapTracker {
+    instance: Instance,
+    state: HyprlandState,
+    binds: RwLock<Binds>,
+}
+
+impl SubmapTracker {
+    /// This method starts following the submap of the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        SubmapTracker::for_instance(&Instance::current()?)
+    }
+
+    /// This method starts following the submap of a specific Hyprland instance
+    pub fn for_instance(instance: &Instance) -> HyprResult<Self> {
+        let state = HyprlandState::for_instance(instance)?;
+        let binds = HyprlandConnection::for_instance(instance).get_binds()?;
+        Ok(SubmapTracker {
+            instance: instance.clone(),
+            state,
+            binds: RwLock::new(binds),
+        })
+    }
+
+    /// This method returns the state the submap is followed with
+    pub fn state(&self) -> &HyprlandState {
+        &self.state
+    }
+
+    /// This method returns the active submap, empty if it's the default one
+    ///
+    /// Hyprland can't be asked for it, so it's only known after it changed once
+    pub fn submap(&self) -> String {
+        self.state.submap()
+    }
+
+    /// This method returns the binds of the active submap
+    pub fn binds(&self) -> Binds {
+        self.binds_of(&self.submap())
+    }
+
+    /// This method returns the binds of the submap, empty for the default one
+    pub fn binds_of(&self, submap: &str) -> Binds {
+        self.binds
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|bind| bind.submap == submap)
+            .cloned()
+            .collect()
+    }
+
+    /// This method waits until the active submap isn't `submap`, and returns the new one
+    pub fn wait_for_change(&self, submap: &str) -> String {
+        loop {
+            let seen = self.state.update_count();
+            let current = self.submap();
+            if current != submap {
+                return current;
+            }
+            self.state.wait_for_update(seen);
+        }
+    }
+
+    /// This method returns a receiver of the active submap, see [`HyprlandState::watch_submap`]
+    #[cfg(feature = "tokio")]
+    pub fn watch(&self) -> tokio::sync::watch::Receiver<String> {
+        self.state.watch_submap()
+    }
+
+    /// This method fetches the binds again (blocking)
+    pub fn refresh_binds(&self) -> HyprResult<()> {
+        let binds = HyprlandConnection::for_instance(&self.instance).get_binds()?;
+        self.set_binds(binds);
+        Ok(())
+    }
+
+    /// This method fetches the binds again (async)
+    #[cfg(feature = "async")]
+    pub async fn refresh_binds_async(&self) -> HyprResult<()> {
+        let binds = HyprlandConnection::for_instance(&self.instance)
+            .get_binds_async()
+            .await?;
+        self.set_binds(binds);
+        Ok(())
+    }
+
+    fn set_binds(&self, binds: Binds) {
+        *self.binds.write().unwrap_or_else(PoisonError::into_inner) = binds;
+    }
+}
@@ -0,0 +1,172 @@
+//! # Swallow module
+//!
+//! This module provides functions for window swallowing, where a window started from a terminal
+//! hides the terminal until it's closed (with `misc:enable_swallow`). They find which window
+//! swallowed which from the [`Client`](crate::data::Client) data, and swallow or unswallow
+//! a window without toggling it the wrong way
+//!
+//! Hyprland decides if a window swallows when it opens, so windows that shouldn't swallow
+//! are set with the [`NoSwallow`](crate::rule::WindowRuleEffect::NoSwallow) window rule
+//!
+//! ## Usage
+//!
+//! ```rust, no_run
+//! use hyprland::data::blocking::get_clients;
+//! use hyprland::rule::{WindowRule, WindowRuleEffect};
+//! use hyprland::swallow::{set_swallowed_blocking, swallowed};
+//!
+//! fn main() -> hyprland::shared::HyprResult<()> {
+//!     // image viewers started from a terminal leave it shown
+//!     WindowRule::new().effect(WindowRuleEffect::NoSwallow).class("imv").apply()?;
+//!
+//!     let clients = get_clients()?;
+//!     for client in &clients {
+//!         if let Some(terminal) = swallowed(client, &clients) {
+//!             println!("{} swallowed {}", client.title, terminal.title);
+//!             // shows the terminal next to it
+//!             set_swallowed_blocking(&client.address, false)?;
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::data::{find_client, Client, Clients};
+use crate::dispatch::{DispatchType, WindowIdentifier};
+use crate::shared::*;
+
+/// This function returns the window the client swallowed, `None` if it didn't swallow one
+///
+/// The window is still returned after it's unswallowed, it can be swallowed again
+pub fn swallowed<'a>(client: &Client, clients: &'a Clients) -> Option<&'a Client> {
+    let address = client.swallowing.as_ref()?;
+    clients.iter().find(|other| other.address == *address)
+}
+
+/// This function returns the window that swallowed the client, `None` if it wasn't swallowed
+pub fn swallowed_by<'a>(client: &Client, clients: &'a Clients) -> Option<&'a Client> {
+    clients
+        .iter()
+        .find(|other| other.swallowing.as_ref() == Some(&client.address))
+}
+
+/// This function returns if the client swallowed a window, and the window is hidden
+pub fn is_swallowing(client: &Client, clients: &Clients) -> bool {
+    swallowed(client, clients).is_some_and(|swallowed| swallowed.hidden)
+}
+
+/// This function swallows the window the window swallowed when it opened,
+/// or unswallows it (shows it again) if `swallowed` is `false` (blocking)
+///
+/// The window is focused, as Hyprland only toggles swallowing for the active window.
+/// Returns `false` if it's already swallowed or unswallowed, or the window didn't swallow one
+pub fn set_swallowed_blocking(window: &Address, swallowed: bool) -> HyprResult<bool> {
+    let clients = crate::data::blocking::get_clients()?;
+    match toggle_dispatches(&clients, window, swallowed)? {
+        Some(dispatches) => crate::dispatch::dispatch_batch_blocking(dispatches).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// This function swallows the window the window swallowed when it opened,
+/// or unswallows it (shows it again) if `swallowed` is `false` (async)
+///
+/// The window is focused, as Hyprland only toggles swallowing for the active window.
+/// Returns `false` if it's already swallowed or unswallowed, or the window didn't swallow one
+#[cfg(feature = "async")]
+pub async fn set_swallowed(window: &Address, swallowed: bool) -> HyprResult<bool> {
+    let clients = crate::data::asynchronous::get_clients().await?;
+    match toggle_dispatches(&clients, window, swallowed)? {
+        Some(dispatches) => crate::dispatch::dispatch_batch(dispatches)
+            .await
+            .map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Returns the dispatchers that focus the window and toggle its swallowing,
+/// `None` if it's already how it should be
+fn toggle_dispatches(
+    clients: &Clients,
+    window: &Address,
+    swallow: bool,
+) -> HyprResult<Option<Vec<DispatchType>>> {
+    let client = find_client(clients, window)?;
+    match swallowed(client, clients) {
+        Some(swallowed) if swallowed.hidden != swallow => Ok(Some(vec![
+            DispatchType::FocusWindow(WindowIdentifier::Address(window.clone())),
+            DispatchType::ToggleSwallow,
+        ])),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns clients where mpv swallowed the terminal it was started from,
+    /// and firefox didn't swallow a window
+    fn clients(hidden: bool) -> HyprResult<Clients> {
+        let client = |address: &str, class: &str, hidden: bool, swallowing: &str| {
+            format!(
+                r#"{{"address": "{address}", "at": [0, 0], "size": [1920, 1080],
+                    "workspace": {{"id": 1, "name": "1"}}, "floating": false, "monitor": 0,
+                    "class": "{class}", "title": "", "pid": 1, "xwayland": false,
+                    "hidden": {hidden}, "swallowing": "{swallowing}"}}"#
+            )
+        };
+        let clients = [
+            client("0x1", "kitty", hidden, "0x0"),
+            client("0x2", "mpv", false, "0x1"),
+            client("0x3", "firefox", false, "0x0"),
+        ];
+        Ok(serde_json::from_str(&format!("[{}]", clients.join(", ")))?)
+    }
+
+    #[test]
+    fn swallowed_windows_are_found_both_ways() -> HyprResult<()> {
+        let clients = clients(true)?;
+        let (kitty, mpv, firefox) = (&clients[0], &clients[1], &clients[2]);
+        assert_eq!(
+            swallowed(mpv, &clients).map(|c| &c.class),
+            Some(&kitty.class)
+        );
+        assert_eq!(
+            swallowed_by(kitty, &clients).map(|c| &c.class),
+            Some(&mpv.class)
+        );
+        assert!(
+            swallowed(firefox, &clients).is_none() && swallowed_by(firefox, &clients).is_none()
+        );
+        assert!(is_swallowing(mpv, &clients));
+        // the terminal is still found after it's shown again
+        let clients = self::clients(false)?;
+        assert!(swallowed(&clients[1], &clients).is_some());
+        assert!(!is_swallowing(&clients[1], &clients));
+        Ok(())
+    }
+
+    #[test]
+    fn swallowing_is_only_toggled_when_it_changes() -> HyprResult<()> {
+        let toggle = |hidden, window: &str, swallow| -> HyprResult<Option<Vec<String>>> {
+            let dispatches = toggle_dispatches(&clients(hidden)?, &Address::new(window), swallow)?;
+            Ok(dispatches.map(|dispatches| dispatches.iter().map(ToString::to_string).collect()))
+        };
+        assert_eq!(
+            toggle(true, "0x2", false)?,
+            Some(vec![
+                "dispatch focuswindow address:0x2".to_string(),
+                "dispatch toggleswallow".to_string(),
+            ])
+        );
+        assert_eq!(toggle(true, "0x2", true)?, None);
+        assert!(toggle(false, "0x2", true)?.is_some());
+        assert_eq!(toggle(false, "0x3", true)?, None);
+        assert!(matches!(
+            toggle(false, "0x4", true),
+            Err(HyprError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+}
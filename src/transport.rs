@@ -0,0 +1,167 @@
+//! # Transport module
+//!
+//! This module provides the [`Transport`](crate::transport::Transport) trait, which is how commands reach Hyprland,
+//! and [`UnixSocket`](crate::transport::UnixSocket), the transport that talks to Hyprland's command socket
+//!
+//! A [`HyprlandConnection`](crate::connection::HyprlandConnection) can be created with any transport,
+//! like one that answers with canned responses in tests
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use hyprland::connection::HyprlandConnection;
+//! use hyprland::dispatch::DispatchType;
+//! use hyprland::shared::HyprResult;
+//! use hyprland::transport::Transport;
+//!
+//! struct AlwaysOk;
+//!
+//! impl Transport for AlwaysOk {
+//!     fn send(&mut self, _command: &[u8], response: &mut Vec<u8>) -> HyprResult<()> {
+//!         response.clear();
+//!         response.extend_from_slice(b"ok");
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn main() -> HyprResult<()> {
+//!     let mut hyprland = HyprlandConnection::with_transport(AlwaysOk);
+//!     hyprland.dispatch(DispatchType::Exec("kitty".to_string()))
+//! }
+//! ```
+
+use crate::shared::*;
+use std::time::{Duration, Instant};
+
+/// This trait sends a command to Hyprland and reads the response
+///
+/// The command is what would be written to the command socket (like `j/monitors`),
+/// and the response is written into `response`, replacing what it held.
+/// Parsing the response is left to the caller, so a transport only moves bytes
+pub trait Transport: Send {
+    /// This method sends a command and reads the response into `response` (blocking)
+    fn send(&mut self, command: &[u8], response: &mut Vec<u8>) -> HyprResult<()>;
+
+    /// This method sends a command and reads the response into `response` (async)
+    ///
+    /// By default this calls [`send`](Self::send),
+    /// which is fine for transports that don't block, like the ones used in tests
+    #[cfg(feature = "async")]
+    fn send_async(
+        &mut self,
+        command: &[u8],
+        response: &mut Vec<u8>,
+    ) -> impl std::future::Future<Output = HyprResult<()>> + Send {
+        async move { self.send(command, response) }
+    }
+}
+
+/// This struct is the transport to Hyprland's command socket
+///
+/// Hyprland closes the socket after answering, so every command opens a new connection.
+/// If the socket of a instance can't be found, its location is resolved again
+/// (and the command is sent there, if it changed)
+#[derive(Debug, Clone)]
+pub struct UnixSocket {
+    path: String,
+    instance: Option<Instance>,
+    options: SocketOptions,
+}
+
+impl UnixSocket {
+    /// This method creates a transport to the Hyprland instance this program runs in
+    ///
+    /// Returns [`HyprError::SocketNotFound`] if the program isn't running in Hyprland
+    pub fn new() -> HyprResult<Self> {
+        Ok(UnixSocket::for_instance(&Instance::current()?))
+    }
+
+    /// This method creates a transport to a specific Hyprland instance
+    pub fn for_instance(instance: &Instance) -> Self {
+        UnixSocket {
+            path: instance.socket_path(SocketType::Command),
+            instance: Some(instance.clone()),
+            options: SocketOptions::default(),
+        }
+    }
+
+    /// This method creates a transport to the command socket at `path`
+    pub fn from_path(path: impl Into<String>) -> Self {
+        UnixSocket {
+            path: path.into(),
+            instance: None,
+            options: SocketOptions::default(),
+        }
+    }
+
+    /// This method returns the path of the socket
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// This method sets how long to wait for Hyprland to accept and to answer a command,
+    /// `None` waits forever (5 seconds by default)
+    ///
+    /// A command that takes longer returns [`HyprError::Timeout`],
+    /// whether Hyprland still runs it isn't known
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.options.timeout = timeout;
+    }
+
+    /// This method makes the transport retry commands that couldn't be delivered,
+    /// like while Hyprland is restarting, `None` to not retry (the default)
+    ///
+    /// See [`Retry`] for which errors are retried
+    pub fn set_retry(&mut self, retry: Option<Retry>) {
+        self.options.retry = retry;
+    }
+
+    /// This method sets when commands have to be finished by, including their retries,
+    /// `None` to not have a deadline (the default)
+    ///
+    /// Commands that would run past it return [`HyprError::DeadlineExceeded`]
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.options.deadline = deadline;
+    }
+
+    /// This method sets the token that cancels the commands, `None` to not cancel them
+    ///
+    /// Blocking commands check it before connecting, async ones also stop waiting for Hyprland
+    pub fn set_cancellation(&mut self, cancellation: Option<CancellationToken>) {
+        self.options.cancellation = cancellation;
+    }
+
+    /// Resolves the path of the socket again, returns `true` if it changed
+    fn resolve_again(&mut self) -> bool {
+        match &self.instance {
+            Some(instance) => {
+                let path = instance.socket_path(SocketType::Command);
+                let changed = path != self.path;
+                self.path = path;
+                changed
+            }
+            None => false,
+        }
+    }
+}
+
+impl Transport for UnixSocket {
+    fn send(&mut self, command: &[u8], response: &mut Vec<u8>) -> HyprResult<()> {
+        match write_to_socket_sync(&self.path, command, response, &self.options) {
+            Err(HyprError::SocketNotFound(_)) if self.resolve_again() => {
+                write_to_socket_sync(&self.path, command, response, &self.options)
+            }
+            result => result,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn send_async(&mut self, command: &[u8], response: &mut Vec<u8>) -> HyprResult<()> {
+        match write_to_socket(&self.path, command, response, &self.options).await {
+            Err(HyprError::SocketNotFound(_)) if self.resolve_again() => {
+                write_to_socket(&self.path, command, response, &self.options).await
+            }
+            result => result,
+        }
+    }
+}
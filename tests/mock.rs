@@ -0,0 +1,469 @@
+//! Tests that drive the crate against a [`MockServer`], like code using the crate would be tested
+
+use hyprland::bar::BarState;
+use hyprland::connection::HyprlandConnection;
+use hyprland::dispatch::{DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial};
+use hyprland::event_listener::{EventListener, EventListenerMutable};
+use hyprland::mock::MockServer;
+use hyprland::shared::{Address, CommandErrorKind, HyprError, HyprResult, WorkspaceType};
+use hyprland::state::HyprlandState;
+use hyprland::submap::SubmapTracker;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+const MONITORS: &str = r#"[
+    {"id": 0, "name": "DP-1", "width": 2560, "height": 1440, "refreshRate": 144.0,
+     "x": 0, "y": 0, "activeWorkspace": {"id": 1, "name": "1"},
+     "reserved": [0, 0, 0, 0], "scale": 1.0, "transform": 0, "focused": true}
+]"#;
+
+const WORKSPACES: &str = r#"[
+    {"id": 1, "name": "1", "monitor": "DP-1", "monitorID": 0, "windows": 1,
+     "hasfullscreen": false, "lastwindow": "0x5581b1f5c9a0", "lastwindowtitle": "nvim"},
+    {"id": -1337, "name": "coding", "monitor": "DP-1", "monitorID": 0, "windows": 0,
+     "hasfullscreen": false, "lastwindow": "0x0", "lastwindowtitle": ""}
+]"#;
+
+const KITTY: &str = r#"{"address": "0x5581b1f5c9a0", "at": [0, 0], "size": [2560, 1440],
+    "workspace": {"id": 1, "name": "1"}, "floating": false, "monitor": 0,
+    "class": "kitty", "title": "nvim", "pid": 1234, "xwayland": false}"#;
+
+/// Returns a server that answers the data commands with one monitor, two workspaces
+/// and the active window
+fn server() -> HyprResult<MockServer> {
+    let server = MockServer::start()?;
+    server.reply_json("monitors", MONITORS);
+    server.reply_json("workspaces", WORKSPACES);
+    server.reply_json("clients", format!("[{KITTY}]"));
+    server.reply_json("activewindow", KITTY);
+    Ok(server)
+}
+
+/// Returns if `check` returned `true` before the timeout ran out
+fn eventually(mut check: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < TIMEOUT {
+        if check() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    false
+}
+
+#[test]
+fn connection_reads_data_and_sends_dispatchers() -> HyprResult<()> {
+    let server = server()?;
+    let mut hyprland = HyprlandConnection::for_instance(server.instance());
+
+    let workspaces = hyprland.get_workspaces()?;
+    let ids: Vec<WorkspaceType> = workspaces
+        .into_iter()
+        .map(|workspace| workspace.id)
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            WorkspaceType::Regular(1),
+            WorkspaceType::Named("coding".to_string())
+        ]
+    );
+    assert_eq!(hyprland.get_monitors()?[0].name, "DP-1");
+
+    hyprland.dispatch(DispatchType::Exec("kitty".to_string()))?;
+    hyprland.dispatch_batch(vec![
+        DispatchType::Workspace(WorkspaceIdentifierWithSpecial::Id(2)),
+        DispatchType::ToggleFloating,
+    ])?;
+    server.assert_dispatched("exec kitty");
+    assert_eq!(
+        server.received(),
+        [
+            "j/workspaces",
+            "j/monitors",
+            "dispatch exec kitty",
+            "dispatch workspace 2",
+            "dispatch togglefloating",
+        ]
+    );
+
+    // in a batch a `;` would run the rest as another command, so nothing is sent
+    server.clear_received();
+    let split = hyprland.dispatch_batch(vec![
+        DispatchType::ToggleFloating,
+        DispatchType::Exec("a; b".to_string()),
+    ]);
+    assert!(matches!(split, Err(HyprError::InvalidArgument(_))));
+    assert!(server.received().is_empty());
+
+    server.reply("dispatch focuswindow address:0x1", "Window not found");
+    let raw = hyprland.send_raw_command("dispatch focuswindow address:0x1");
+    assert_eq!(raw?, "Window not found");
+    let window = WindowIdentifier::Address(Address::new("1"));
+    let error = hyprland.dispatch(DispatchType::FocusWindow(window));
+    assert!(matches!(
+        error,
+        Err(HyprError::Command(error)) if error.kind() == CommandErrorKind::WindowNotFound
+    ));
+    Ok(())
+}
+
+#[test]
+fn event_listener_skips_malformed_events() -> HyprResult<()> {
+    let server = server()?;
+    let (sender, receiver) = mpsc::channel();
+    let (handle_sender, handle) = mpsc::channel();
+    let instance = server.instance().clone();
+    let listener = std::thread::spawn(move || {
+        let mut listener = EventListener::new();
+        listener.set_instance(instance);
+        let opened = sender.clone();
+        listener.add_window_open_handler(move |data| {
+            let _ = opened.send(format!("opened {}", data.title));
+        });
+        listener.add_raw_event_handler(move |raw| {
+            let _ = sender.send(raw.line);
+        });
+        let _ = handle_sender.send(listener.handle());
+        listener.start_listener_blocking()
+    });
+
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+    server.emit("workspacev2>>two,2");
+    server.emit("openwindow>>5581b1f5d010,1,firefox,Mozilla Firefox");
+    server.emit("fullscreen>>yes");
+    server.emit("workspace>>2");
+    let received: Vec<String> = (0..3)
+        .filter_map(|_| receiver.recv_timeout(TIMEOUT).ok())
+        .collect();
+    assert_eq!(
+        received,
+        [
+            "openwindow>>5581b1f5d010,1,firefox,Mozilla Firefox",
+            "opened Mozilla Firefox",
+            "workspace>>2",
+        ]
+    );
+
+    handle
+        .recv_timeout(TIMEOUT)
+        .map(|handle| handle.stop())
+        .ok();
+    let stopped = listener
+        .join()
+        .map_err(|_| HyprError::UnexpectedResponse("panicked".into()));
+    stopped??;
+    Ok(())
+}
+
+#[test]
+fn event_listener_has_handlers_for_the_newer_events() -> HyprResult<()> {
+    let server = server()?;
+    let (sender, receiver) = mpsc::channel();
+    let (handle_sender, handle) = mpsc::channel();
+    let instance = server.instance().clone();
+    let listener = std::thread::spawn(move || {
+        let mut listener = EventListener::new();
+        listener.set_instance(instance);
+        let send = |sender: &mpsc::Sender<String>| {
+            let sender = sender.clone();
+            move |line: String| {
+                let _ = sender.send(line);
+            }
+        };
+        let special = send(&sender);
+        listener.add_active_special_change_handler(move |data| {
+            special(format!("{:?} on {}", data.workspace_name, data.monitor));
+        });
+        let monitor = send(&sender);
+        listener.add_active_monitor_change_v2_handler(move |data| {
+            monitor(format!("{} on {}", data.workspace_id, data.monitor));
+        });
+        let layout = send(&sender);
+        listener.add_layout_change_handler(move |data| layout(data.layout_name));
+        let minimize = send(&sender);
+        listener.add_minimize_handler(move |data| {
+            minimize(format!("{} minimized {}", data.address, data.minimized));
+        });
+        let custom = send(&sender);
+        listener.add_custom_event_handler(custom);
+        let bell = send(&sender);
+        listener.add_bell_handler(move |data| bell(format!("bell {data:?}")));
+        let _ = handle_sender.send(listener.handle());
+        listener.start_listener_blocking()
+    });
+
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+    server.emit("activespecial>>special:scratch,DP-1");
+    server.emit("focusedmonv2>>DP-1,3");
+    server.emit("activelayout>>at-translated-set-2-keyboard,English (US)");
+    server.emit("minimized>>5581b1f5c9a0,1");
+    server.emit("custom>>a>>b");
+    server.emit("bell>>");
+    let received: Vec<String> = (0..6)
+        .filter_map(|_| receiver.recv_timeout(TIMEOUT).ok())
+        .collect();
+    assert_eq!(
+        received,
+        [
+            "Some(\"special:scratch\") on DP-1",
+            "3 on DP-1",
+            "English (US)",
+            "0x5581b1f5c9a0 minimized true",
+            "a>>b",
+            "bell None",
+        ]
+    );
+
+    handle
+        .recv_timeout(TIMEOUT)
+        .map(|handle| handle.stop())
+        .ok();
+    let stopped = listener
+        .join()
+        .map_err(|_| HyprError::UnexpectedResponse("panicked".into()));
+    stopped??;
+    Ok(())
+}
+
+#[test]
+fn mutable_listener_tracks_the_state_of_its_instance() -> HyprResult<()> {
+    let server = server()?;
+    let (sender, receiver) = mpsc::channel();
+    let (handle_sender, handle) = mpsc::channel();
+    let instance = server.instance().clone();
+    let listener = std::thread::spawn(move || {
+        let mut listener = EventListenerMutable::for_instance(&instance)?;
+        listener.add_workspace_change_handler(move |id, state| {
+            let _ = sender.send(format!("{id:?} on {}", state.active_monitor));
+        });
+        let _ = handle_sender.send(listener.handle());
+        listener.start_listener_blocking()
+    });
+
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+    server.emit("workspace>>2");
+    server.emit("focusedmon>>HDMI-A-1,3");
+    server.emit("workspace>>3");
+    let received: Vec<String> = (0..2)
+        .filter_map(|_| receiver.recv_timeout(TIMEOUT).ok())
+        .collect();
+    assert_eq!(received, ["Regular(2) on DP-1", "Regular(3) on HDMI-A-1"]);
+
+    handle
+        .recv_timeout(TIMEOUT)
+        .map(|handle| handle.stop())
+        .ok();
+    let stopped = listener
+        .join()
+        .map_err(|_| HyprError::UnexpectedResponse("panicked".into()));
+    stopped??;
+    Ok(())
+}
+
+#[test]
+fn state_follows_the_events() -> HyprResult<()> {
+    let server = server()?;
+    let state = HyprlandState::for_instance(server.instance())?;
+    assert_eq!(state.workspaces().len(), 2);
+    assert_eq!(
+        state.active_window().map(|client| client.class),
+        Some("kitty".to_string())
+    );
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+
+    let firefox = Address::new("5581b1f5d010");
+    server.emit("openwindow>>5581b1f5d010,coding,firefox,Mozilla Firefox");
+    server.emit("activewindowv2>>5581b1f5d010");
+    server.emit("workspacev2>>-1337,coding");
+    server.emit("submap>>resize");
+    assert!(eventually(|| state.submap() == "resize"));
+    assert_eq!(
+        state.active_window().map(|client| client.address),
+        Some(firefox.clone())
+    );
+    assert_eq!(
+        state
+            .active_workspace()
+            .map(|workspace| (workspace.name, workspace.windows)),
+        Some(("coding".to_string(), 1))
+    );
+    assert_eq!(
+        state.focus_history(),
+        [firefox.clone(), Address::new("5581b1f5c9a0")]
+    );
+
+    server.emit("windowtitlev2>>5581b1f5d010,GitHub — Mozilla Firefox");
+    server.emit("closewindow>>5581b1f5c9a0");
+    assert!(eventually(|| state.clients().len() == 1));
+    assert_eq!(
+        state.client(&firefox).map(|client| client.title),
+        Some("GitHub — Mozilla Firefox".to_string())
+    );
+    assert_eq!(state.focus_history(), [firefox]);
+    // the events don't ask Hyprland for anything
+    assert_eq!(
+        server.received(),
+        ["j/monitors", "j/workspaces", "j/clients", "j/activewindow"]
+    );
+    Ok(())
+}
+
+#[test]
+fn state_puts_opened_windows_on_their_workspace() -> HyprResult<()> {
+    let server = server()?;
+    server.reply_json(
+        "workspaces",
+        r#"[{"id": 1, "name": "1", "monitor": "DP-1", "monitorID": 0, "windows": 1,
+             "hasfullscreen": false, "lastwindow": "0x5581b1f5c9a0", "lastwindowtitle": "nvim"},
+            {"id": -98, "name": "special:scratch", "monitor": "DP-1", "monitorID": 0,
+             "windows": 0, "hasfullscreen": false, "lastwindow": "0x0", "lastwindowtitle": ""}]"#,
+    );
+    let state = HyprlandState::for_instance(server.instance())?;
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+
+    let htop = Address::new("5581b1f5d010");
+    server.emit("openwindow>>5581b1f5d010,special:scratch,kitty,htop");
+    assert!(eventually(|| state.client(&htop).is_some()));
+    assert_eq!(
+        state.client(&htop).map(|client| client.workspace.name),
+        Some("special:scratch".to_string())
+    );
+    let scratch = state
+        .workspaces()
+        .into_iter()
+        .find(|workspace| workspace.name == "special:scratch");
+    assert_eq!(scratch.map(|workspace| workspace.windows), Some(1));
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn async_listener_and_state() -> HyprResult<()> {
+    use hyprland::event_listener::AsyncEventListener;
+
+    let server = server()?;
+    let mut hyprland = HyprlandConnection::for_instance(server.instance());
+    assert_eq!(hyprland.get_clients_async().await?.len(), 1);
+    hyprland
+        .dispatch_async(DispatchType::Exec("kitty".to_string()))
+        .await?;
+    server.assert_dispatched("exec kitty");
+
+    let state = HyprlandState::for_instance(server.instance())?;
+    let mut active_window = state.watch_active_window();
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let mut listener = AsyncEventListener::new();
+    listener.set_instance(server.instance().clone());
+    listener.add_workspace_change_handler(move |workspace| {
+        let sender = sender.clone();
+        async move {
+            let _ = sender.send(workspace);
+        }
+    });
+    let handle = listener.handle();
+
+    let events = async {
+        let connected = || server.wait_for_listeners(2, Duration::ZERO);
+        while !connected() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        server.emit("activewindowv2>>,");
+        server.emit("workspace>>special:scratch");
+        let workspace = tokio::time::timeout(TIMEOUT, receiver.recv()).await;
+        let changed = tokio::time::timeout(TIMEOUT, active_window.changed()).await;
+        handle.stop();
+        (
+            workspace.ok().flatten(),
+            changed.is_ok_and(|changed| changed.is_ok()),
+        )
+    };
+    let (listened, (workspace, changed)) = tokio::join!(listener.start_listener(), events);
+    listened?;
+    assert_eq!(workspace, Some(WorkspaceType::Special));
+    assert!(changed);
+    assert!(active_window.borrow().is_none());
+    Ok(())
+}
+
+#[test]
+fn bar_state_follows_the_state() -> HyprResult<()> {
+    let server = server()?;
+    server.reply_json(
+        "workspaces",
+        r#"[
+            {"id": 3, "name": "3", "monitor": "DP-1", "monitorID": 0, "windows": 0,
+             "hasfullscreen": false, "lastwindow": "0x0", "lastwindowtitle": ""},
+            {"id": -99, "name": "special:special", "monitor": "DP-1", "monitorID": 0,
+             "windows": 1, "hasfullscreen": false, "lastwindow": "0x0", "lastwindowtitle": ""},
+            {"id": 1, "name": "1", "monitor": "DP-1", "monitorID": 0, "windows": 1,
+             "hasfullscreen": true, "lastwindow": "0x5581b1f5c9a0", "lastwindowtitle": "nvim"}
+        ]"#,
+    );
+    let state = HyprlandState::for_instance(server.instance())?;
+    let bar = BarState::of(&state);
+    assert_eq!((bar.title.as_str(), bar.class.as_str()), ("nvim", "kitty"));
+    let names: Vec<&str> = bar.workspaces.iter().map(|w| w.name.as_str()).collect();
+    assert_eq!(names, ["1", "3", "special:special"]);
+    let first = &bar.workspaces[0];
+    assert_eq!(first.id, Some(1));
+    assert!(first.active && first.focused && first.fullscreen && !first.urgent);
+    let special = &bar.workspaces[2];
+    assert_eq!(special.id, None);
+    assert!(special.special && !special.active);
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+
+    // a window on a workspace that isn't shown wants attention
+    server.emit("workspacev2>>3,3");
+    server.emit("urgent>>5581b1f5c9a0");
+    server.emit("submap>>resize");
+    assert!(eventually(|| state.submap() == "resize"));
+    let bar = BarState::of(&state);
+    assert_eq!(bar.submap, "resize");
+    assert!(bar.workspaces[0].urgent && !bar.workspaces[0].active);
+    assert!(bar.workspaces[1].active && bar.workspaces[1].focused);
+    Ok(())
+}
+
+#[test]
+fn submap_tracker_returns_the_binds_of_the_active_submap() -> HyprResult<()> {
+    let server = server()?;
+    let bind = |submap: &str, key: &str, dispatcher: &str, arg: &str| {
+        format!(
+            r#"{{"locked": false, "mouse": false, "release": false, "repeat": true,
+                "non_consuming": false, "modmask": 0, "submap": "{submap}", "key": "{key}",
+                "keycode": 0, "dispatcher": "{dispatcher}", "arg": "{arg}"}}"#
+        )
+    };
+    server.reply_json(
+        "binds",
+        format!(
+            "[{}, {}, {}]",
+            bind("", "R", "submap", "resize"),
+            bind("resize", "h", "resizeactive", "-10 0"),
+            bind("resize", "escape", "submap", "reset"),
+        ),
+    );
+    let tracker = SubmapTracker::for_instance(server.instance())?;
+    assert_eq!(tracker.submap(), "");
+    let keys = |binds: hyprland::data::Binds| -> Vec<String> {
+        binds.into_iter().map(|bind| bind.key).collect()
+    };
+    assert_eq!(keys(tracker.binds()), ["R"]);
+    assert_eq!(keys(tracker.binds_of("resize")), ["h", "escape"]);
+    assert!(server.wait_for_listeners(1, TIMEOUT));
+
+    server.emit("submap>>resize");
+    assert_eq!(tracker.wait_for_change(""), "resize");
+    assert_eq!(keys(tracker.binds()), ["h", "escape"]);
+
+    server.reply_json(
+        "binds",
+        format!("[{}]", bind("resize", "l", "resizeactive", "10 0")),
+    );
+    tracker.refresh_binds()?;
+    assert_eq!(keys(tracker.binds()), ["l"]);
+    Ok(())
+}